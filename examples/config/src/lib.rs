@@ -0,0 +1,306 @@
+//! An example of using Koto as a configuration language
+//!
+//! A config script is run in a sandboxed runtime, and the map that it exports as `config` is
+//! then extracted into typed Rust structs, with errors for missing or mis-typed fields that
+//! include the path to the field.
+
+use {
+    koto::{prelude::*, Koto, KotoError, KotoSettings},
+    std::{cell::RefCell, error::Error, fmt, rc::Rc},
+};
+
+/// The core library modules that are removed from the prelude when sandboxing is enabled
+pub const SANDBOXED_MODULES: &[&str] = &["io", "os"];
+
+/// The application's configuration, extracted from the map exported by a config script
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    /// The name of the service
+    pub name: String,
+    /// The service's server settings
+    pub server: ServerConfig,
+    /// The number of worker threads, defaulting to the host's CPU count
+    pub workers: usize,
+    /// Origins that are allowed to make requests to the service
+    pub allowed_origins: Vec<String>,
+    /// The level of detail to include in logs
+    pub log_level: String,
+}
+
+/// Server settings, extracted from the `server` map in the config
+#[derive(Clone, Debug, PartialEq)]
+pub struct ServerConfig {
+    /// The address that the server should bind to
+    pub host: String,
+    /// The port that the server should listen on
+    pub port: usize,
+    /// The number of seconds to wait before timing out requests, if provided
+    pub timeout_seconds: Option<f64>,
+}
+
+/// Information about the host application, passed to the config script's `on_load` hook
+#[derive(Clone, Debug)]
+pub struct HostInfo {
+    /// The environment that the application is running in, e.g. 'production'
+    pub environment: String,
+    /// The number of CPUs that are available to the application
+    pub cpu_count: usize,
+}
+
+impl HostInfo {
+    fn to_value(&self) -> Value {
+        let result = ValueMap::new();
+        result.add_value("environment", self.environment.as_str().into());
+        result.add_value("cpu_count", self.cpu_count.into());
+        Value::Map(result)
+    }
+}
+
+/// The errors that can be returned while loading a config
+#[derive(Debug)]
+pub enum ConfigError {
+    /// An error occurred while compiling or running the config script
+    Koto(KotoError),
+    /// A required field is missing from the config
+    MissingField {
+        /// The path to the missing field, e.g. `config.server.port`
+        path: String,
+    },
+    /// A field in the config has an unexpected type
+    UnexpectedType {
+        /// The path to the field, e.g. `config.allowed_origins[2]`
+        path: String,
+        /// A description of the expected type
+        expected: String,
+        /// The type of the value that was found
+        found: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ConfigError::*;
+
+        match &self {
+            Koto(error) => error.fmt(f),
+            MissingField { path } => write!(f, "Missing config field '{path}'"),
+            UnexpectedType {
+                path,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Expected {expected} for config field '{path}', found '{found}'"
+            ),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+impl From<KotoError> for ConfigError {
+    fn from(error: KotoError) -> Self {
+        Self::Koto(error)
+    }
+}
+
+impl From<RuntimeError> for ConfigError {
+    fn from(error: RuntimeError) -> Self {
+        Self::Koto(error.into())
+    }
+}
+
+/// Runs config scripts and extracts their exported config
+pub struct ConfigLoader {
+    koto: Koto,
+    output: Rc<RefCell<String>>,
+}
+
+impl Default for ConfigLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigLoader {
+    /// Initializes a loader with a sandboxed runtime, see [ConfigLoader::with_sandbox]
+    pub fn new() -> Self {
+        Self::with_sandbox(true)
+    }
+
+    /// Initializes a loader, optionally sandboxing the runtime
+    ///
+    /// When sandboxed, the modules in [SANDBOXED_MODULES] are removed from the runtime's prelude,
+    /// preventing the script from accessing the host's files or environment.
+    ///
+    /// The script's output is captured rather than being written to stdout,
+    /// and can be retrieved with [ConfigLoader::output].
+    pub fn with_sandbox(sandboxed: bool) -> Self {
+        let output = Rc::new(RefCell::new(String::new()));
+
+        let koto = Koto::with_settings(
+            KotoSettings {
+                run_tests: false,
+                ..Default::default()
+            }
+            .with_stdout(CapturedOutput {
+                output: output.clone(),
+            })
+            .with_stderr(CapturedOutput {
+                output: output.clone(),
+            }),
+        );
+
+        if sandboxed {
+            let mut prelude = koto.prelude().data_mut();
+            for module in SANDBOXED_MODULES {
+                prelude.remove_with_string(module);
+            }
+        }
+
+        Self { koto, output }
+    }
+
+    /// Returns the output that has been captured from the config scripts
+    pub fn output(&self) -> String {
+        self.output.borrow().clone()
+    }
+
+    /// Runs a config script, and then extracts the config that it exports
+    ///
+    /// If the script exports an `on_load` function, then it's called with the host's info.
+    /// The function should return a Map, with the map's entries replacing matching entries in the
+    /// exported config.
+    pub fn load(&mut self, script: &str, host: &HostInfo) -> Result<Config, ConfigError> {
+        self.koto.compile(script)?;
+        self.koto.run()?;
+
+        let exports = Fields::new(self.koto.exports().clone(), "");
+        let config = exports.map("config")?;
+
+        let on_load = exports.get::<Option<Value>>("on_load")?;
+        if let Some(on_load) = on_load {
+            let overrides = self
+                .koto
+                .run_function(on_load, CallArgs::Single(host.to_value()))?;
+            let overrides = extract::<ValueMap>(&overrides, "on_load()".into())?;
+
+            let mut config_data = config.map.data_mut();
+            for (key, value) in overrides.data().iter() {
+                config_data.insert(key.clone(), value.clone());
+            }
+        }
+
+        let server = config.map("server")?;
+
+        Ok(Config {
+            name: config.get("name")?,
+            server: ServerConfig {
+                host: server.get("host")?,
+                port: server.get("port")?,
+                timeout_seconds: server.get("timeout_seconds")?,
+            },
+            workers: config
+                .get::<Option<usize>>("workers")?
+                .unwrap_or(host.cpu_count),
+            allowed_origins: config.list("allowed_origins")?,
+            log_level: config.get("log_level")?,
+        })
+    }
+}
+
+// A config map, along with its path in the config, which is used when reporting errors
+struct Fields {
+    map: ValueMap,
+    path: String,
+}
+
+impl Fields {
+    fn new(map: ValueMap, path: &str) -> Self {
+        Self {
+            map,
+            path: path.into(),
+        }
+    }
+
+    fn field_path(&self, key: &str) -> String {
+        if self.path.is_empty() {
+            key.into()
+        } else {
+            format!("{}.{key}", self.path)
+        }
+    }
+
+    // Extracts a field from the map
+    //
+    // Missing fields are allowed if the type has a default value for missing values,
+    // e.g. `Option<T>`.
+    fn get<T: FromValue>(&self, key: &str) -> Result<T, ConfigError> {
+        let path = self.field_path(key);
+        let value = self.map.data().get_with_string(key).cloned();
+
+        match value {
+            Some(value) => extract(&value, path),
+            None => T::from_missing_value().ok_or(ConfigError::MissingField { path }),
+        }
+    }
+
+    fn map(&self, key: &str) -> Result<Fields, ConfigError> {
+        let map = self.get::<ValueMap>(key)?;
+        Ok(Fields::new(map, &self.field_path(key)))
+    }
+
+    fn list<T: FromValue>(&self, key: &str) -> Result<Vec<T>, ConfigError> {
+        let path = self.field_path(key);
+        let list = self.get::<ValueList>(key)?;
+        let result = list
+            .data()
+            .iter()
+            .enumerate()
+            .map(|(index, value)| extract(value, format!("{path}[{index}]")))
+            .collect();
+        result
+    }
+}
+
+fn extract<T: FromValue>(value: &Value, path: String) -> Result<T, ConfigError> {
+    T::from_value(value).ok_or_else(|| ConfigError::UnexpectedType {
+        path,
+        expected: T::type_description(),
+        found: value.type_as_string().to_string(),
+    })
+}
+
+// A KotoFile that captures the runtime's output
+#[derive(Debug)]
+struct CapturedOutput {
+    output: Rc<RefCell<String>>,
+}
+
+impl KotoFile for CapturedOutput {
+    fn id(&self) -> ValueString {
+        "_captured_output_".into()
+    }
+}
+
+impl KotoRead for CapturedOutput {}
+
+impl KotoWrite for CapturedOutput {
+    fn write(&self, bytes: &[u8]) -> Result<(), RuntimeError> {
+        self.output
+            .borrow_mut()
+            .push_str(&String::from_utf8_lossy(bytes));
+        Ok(())
+    }
+
+    fn write_line(&self, text: &str) -> Result<(), RuntimeError> {
+        let mut output = self.output.borrow_mut();
+        output.push_str(text);
+        output.push('\n');
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<(), RuntimeError> {
+        Ok(())
+    }
+}
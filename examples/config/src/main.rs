@@ -0,0 +1,35 @@
+use {
+    koto_config::{ConfigLoader, HostInfo},
+    std::{env, error::Error, fs},
+};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let script_path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| concat!(env!("CARGO_MANIFEST_DIR"), "/scripts/config.koto").into());
+    let script = fs::read_to_string(&script_path)?;
+
+    let host = HostInfo {
+        environment: env::var("APP_ENVIRONMENT").unwrap_or_else(|_| "development".into()),
+        cpu_count: env::var("APP_CPU_COUNT")
+            .ok()
+            .and_then(|count| count.parse().ok())
+            .unwrap_or(4),
+    };
+
+    let mut loader = ConfigLoader::new();
+    let result = loader.load(&script, &host);
+
+    let output = loader.output();
+    if !output.is_empty() {
+        println!("Output from '{script_path}':\n{output}");
+    }
+
+    match result {
+        Ok(config) => {
+            println!("{config:#?}");
+            Ok(())
+        }
+        Err(error) => Err(format!("Failed to load '{script_path}': {error}").into()),
+    }
+}
@@ -0,0 +1,166 @@
+use koto_config::{Config, ConfigError, ConfigLoader, HostInfo, ServerConfig};
+
+const EXAMPLE_SCRIPT: &str = include_str!("../scripts/config.koto");
+
+fn host(environment: &str) -> HostInfo {
+    HostInfo {
+        environment: environment.into(),
+        cpu_count: 8,
+    }
+}
+
+fn load(script: &str) -> Result<Config, ConfigError> {
+    ConfigLoader::new().load(script, &host("test"))
+}
+
+fn check_error_message(script: &str, expected_message: &str) {
+    match load(script) {
+        Ok(config) => panic!("Expected an error, found config: {config:?}"),
+        Err(error) => {
+            let message = error.to_string();
+            assert!(
+                message.starts_with(expected_message),
+                "Unexpected error message: {message}"
+            );
+        }
+    }
+}
+
+#[test]
+fn example_script() {
+    let mut loader = ConfigLoader::new();
+    let config = loader.load(EXAMPLE_SCRIPT, &host("development")).unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            name: "koto-service".into(),
+            server: ServerConfig {
+                host: "0.0.0.0".into(),
+                port: 8080,
+                timeout_seconds: Some(10.0),
+            },
+            workers: 4,
+            allowed_origins: vec!["https://example.com".into(), "https://koto.dev".into()],
+            log_level: "info".into(),
+        }
+    );
+
+    assert_eq!(
+        loader.output(),
+        "Configuring 'koto-service' for development\n"
+    );
+}
+
+#[test]
+fn example_script_in_production() {
+    let config = ConfigLoader::new()
+        .load(EXAMPLE_SCRIPT, &host("production"))
+        .unwrap();
+    assert_eq!(config.log_level, "warn");
+}
+
+#[test]
+fn optional_fields() {
+    let script = "
+export config =
+  name: 'minimal'
+  server: {host: 'localhost', port: 3000}
+  allowed_origins: []
+  log_level: 'debug'
+";
+    let config = load(script).unwrap();
+    assert_eq!(config.server.timeout_seconds, None);
+    // Without an on_load hook, the number of workers defaults to the host's CPU count
+    assert_eq!(config.workers, 8);
+}
+
+#[test]
+fn missing_config() {
+    check_error_message("x = 42", "Missing config field 'config'");
+}
+
+#[test]
+fn missing_field() {
+    let script = "
+export config =
+  name: 'missing port'
+  server: {host: 'localhost'}
+  allowed_origins: []
+  log_level: 'debug'
+";
+    check_error_message(script, "Missing config field 'config.server.port'");
+}
+
+#[test]
+fn mistyped_field() {
+    let script = "
+export config =
+  name: 'mistyped port'
+  server: {host: 'localhost', port: '8080'}
+  allowed_origins: []
+  log_level: 'debug'
+";
+    check_error_message(
+        script,
+        "Expected non-negative Number for config field 'config.server.port', found 'String'",
+    );
+}
+
+#[test]
+fn mistyped_list_element() {
+    let script = "
+export config =
+  name: 'mistyped origin'
+  server: {host: 'localhost', port: 8080}
+  allowed_origins: ['https://koto.dev', 42]
+  log_level: 'debug'
+";
+    check_error_message(
+        script,
+        "Expected String for config field 'config.allowed_origins[1]', found 'Int'",
+    );
+}
+
+#[test]
+fn mistyped_hook_result() {
+    let script = "
+export config =
+  name: 'mistyped hook'
+  server: {host: 'localhost', port: 8080}
+  allowed_origins: []
+  log_level: 'debug'
+export on_load = |host| host.environment
+";
+    check_error_message(
+        script,
+        "Expected Map for config field 'on_load()', found 'String'",
+    );
+}
+
+#[test]
+fn error_in_hook() {
+    let script = "
+export config = {}
+export on_load = |host| throw 'Unsupported environment: ${host.environment}'
+";
+    check_error_message(script, "Unsupported environment: test");
+}
+
+#[test]
+fn sandboxed_modules_are_unavailable() {
+    let script = "
+export config =
+  name: os.name()
+  server: {host: 'localhost', port: 8080}
+  allowed_origins: []
+  log_level: 'debug'
+";
+
+    assert!(ConfigLoader::new().load(script, &host("test")).is_err());
+
+    let config = ConfigLoader::with_sandbox(false)
+        .load(script, &host("test"))
+        .unwrap();
+    assert_eq!(config.name, std::env::consts::OS);
+}
@@ -1,6 +1,18 @@
 //! A Koto language module for working with JSON data
 
-use {koto_runtime::prelude::*, koto_serialize::SerializableValue, serde_json::Value as JsonValue};
+use {
+    koto_runtime::{core::koto::to_data, prelude::*},
+    koto_serialize::SerializableValue,
+    serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor},
+    serde_json::{ser::PrettyFormatter, Serializer, Value as JsonValue},
+    std::{fmt, io},
+};
+
+// The maximum size in bytes of the output produced by `json.to_string_pretty`
+//
+// Large indents are repeated for each level of nesting, so the output size is limited to avoid
+// allocation failures.
+const MAX_PRETTY_OUTPUT_SIZE: usize = 1 << 30;
 
 pub fn json_value_to_koto_value(value: &serde_json::Value) -> Result<Value, String> {
     let result = match value {
@@ -36,32 +48,186 @@ pub fn json_value_to_koto_value(value: &serde_json::Value) -> Result<Value, Stri
     Ok(result)
 }
 
+// A Koto value that's been deserialized from JSON
+//
+// Deserializing directly into Koto values (rather than via serde_json::Value) allows duplicate
+// keys in objects to be rejected, rather than later entries silently replacing earlier ones.
+struct JsonData(Value);
+
+impl<'de> Deserialize<'de> for JsonData {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(JsonDataVisitor).map(JsonData)
+    }
+}
+
+struct JsonDataVisitor;
+
+impl<'de> Visitor<'de> for JsonDataVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_bool<E>(self, b: bool) -> Result<Value, E> {
+        Ok(b.into())
+    }
+
+    fn visit_i64<E>(self, n: i64) -> Result<Value, E> {
+        Ok(n.into())
+    }
+
+    fn visit_u64<E>(self, n: u64) -> Result<Value, E> {
+        // Integers that don't fit in an i64 are converted to floats
+        match i64::try_from(n) {
+            Ok(n) => Ok(n.into()),
+            Err(_) => Ok((n as f64).into()),
+        }
+    }
+
+    fn visit_f64<E>(self, n: f64) -> Result<Value, E> {
+        Ok(n.into())
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<Value, E> {
+        Ok(s.into())
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut result = ValueVec::new();
+        while let Some(JsonData(element)) = seq.next_element()? {
+            result.push(element);
+        }
+        Ok(Value::List(ValueList::with_data(result)))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let result = ValueMap::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some(key) = map.next_key::<String>()? {
+            if result.data().get_with_string(&key).is_some() {
+                return Err(de::Error::custom(format!(
+                    "duplicate key '{key}' (keys in JSON objects must be unique)"
+                )));
+            }
+            let JsonData(value) = map.next_value()?;
+            result.add_value(&key, value);
+        }
+        Ok(Value::Map(result))
+    }
+}
+
 pub fn make_module() -> ValueMap {
     use Value::*;
 
     let result = ValueMap::new();
 
     result.add_fn("from_string", |vm, args| match vm.get_args(args) {
-        [Str(s)] => match serde_json::from_str(s) {
-            Ok(value) => match json_value_to_koto_value(&value) {
-                Ok(result) => Ok(result),
-                Err(e) => runtime_error!("json.from_string: Error while parsing input: {e}"),
-            },
-            Err(e) => runtime_error!(
-                "json.from_string: Error while parsing input: {}",
-                e.to_string()
-            ),
+        [Str(s)] => match serde_json::from_str::<JsonData>(s) {
+            Ok(JsonData(result)) => Ok(result),
+            Err(e) => runtime_error!("json.from_string: Error while parsing input: {e}"),
         },
         unexpected => type_error_with_slice("a String as argument", unexpected),
     });
 
     result.add_fn("to_string", |vm, args| match vm.get_args(args) {
-        [value] => match serde_json::to_string_pretty(&SerializableValue(value)) {
-            Ok(result) => Ok(result.into()),
-            Err(e) => runtime_error!("json.to_string: {e}"),
-        },
+        [value] => {
+            let data = to_json_data(value, "json.to_string")?;
+            match serde_json::to_string(&SerializableValue(&data)) {
+                Ok(result) => Ok(result.into()),
+                Err(e) => runtime_error!("json.to_string: {e}"),
+            }
+        }
         unexpected => type_error_with_slice("a Value as argument", unexpected),
     });
 
+    result.add_fn("to_string_pretty", |vm, args| {
+        let (value, indent) = match vm.get_args(args) {
+            [value] => (value, 2),
+            [value, Number(n)] if *n >= 0 => (value, usize::from(n)),
+            unexpected => {
+                return type_error_with_slice(
+                    "a Value, with an optional non-negative indent Number",
+                    unexpected,
+                )
+            }
+        };
+
+        let data = to_json_data(value, "json.to_string_pretty")?;
+
+        // The most deeply nested values are indented once for each level of nesting,
+        // and the indent string itself needs to be allocated for values without nesting.
+        match indent.checked_mul(data_depth(&data).max(1)) {
+            Some(size) if size <= MAX_PRETTY_OUTPUT_SIZE => {}
+            _ => {
+                return runtime_error!(
+                    "json.to_string_pretty: The indented output would exceed the maximum size \
+                     of {MAX_PRETTY_OUTPUT_SIZE} bytes"
+                )
+            }
+        }
+
+        let indent = " ".repeat(indent);
+        let mut result = LimitedOutput::default();
+        let mut serializer = Serializer::with_formatter(
+            &mut result,
+            PrettyFormatter::with_indent(indent.as_bytes()),
+        );
+        match serde::Serialize::serialize(&SerializableValue(&data), &mut serializer) {
+            // Safety: serde_json only produces valid UTF-8
+            Ok(_) => Ok(String::from_utf8(result.0).unwrap().into()),
+            Err(e) => runtime_error!("json.to_string_pretty: {e}"),
+        }
+    });
+
     result
 }
+
+// Returns the number of nested containers in data produced by `to_json_data`
+fn data_depth(data: &Value) -> usize {
+    match data {
+        Value::List(l) => 1 + l.data().iter().map(data_depth).max().unwrap_or(0),
+        Value::Map(m) => 1 + m.data().values().map(data_depth).max().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+// Output for the serializer that fails when the size limit would be exceeded
+#[derive(Default)]
+struct LimitedOutput(Vec<u8>);
+
+impl io::Write for LimitedOutput {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        match self.0.len().checked_add(bytes.len()) {
+            Some(size) if size <= MAX_PRETTY_OUTPUT_SIZE => {
+                self.0.extend_from_slice(bytes);
+                Ok(bytes.len())
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::OutOfMemory,
+                format!("The output exceeds the maximum size of {MAX_PRETTY_OUTPUT_SIZE} bytes"),
+            )),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// Values are converted to plain data with the same routine as koto.to_data
+fn to_json_data(value: &Value, function_name: &str) -> RuntimeResult {
+    to_data(value).map_err(|e| e.with_prefix(function_name))
+}
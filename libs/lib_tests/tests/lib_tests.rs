@@ -4,15 +4,19 @@ use {
 };
 
 fn run_script(script: &str, path: Option<PathBuf>, should_fail_at_runtime: bool) {
-    let mut koto = Koto::with_settings(KotoSettings {
-        run_tests: true,
-        ..Default::default()
-    });
+    let mut koto = Koto::with_settings(
+        KotoSettings {
+            run_tests: true,
+            ..Default::default()
+        }
+        .with_chunk_cache(ChunkCache::shared()),
+    );
     koto.set_script_path(path).unwrap();
 
     let prelude = koto.prelude();
     prelude.add_map("json", koto_json::make_module());
     prelude.add_map("random", koto_random::make_module());
+    prelude.add_map("regex", koto_regex::make_module());
     prelude.add_map("tempfile", koto_tempfile::make_module());
     prelude.add_map("toml", koto_toml::make_module());
     prelude.add_map("yaml", koto_yaml::make_module());
@@ -64,7 +68,32 @@ mod lib_tests {
 
     lib_test!(json);
     lib_test!(random);
+    lib_test!(regex);
     lib_test!(tempfile);
     lib_test!(toml);
     lib_test!(yaml);
 }
+
+mod random {
+    use super::*;
+
+    fn random_numbers(koto: &mut Koto, script: &str) -> String {
+        koto.compile(script).unwrap();
+        let result = koto.run().unwrap();
+        koto.value_to_string(result).unwrap().to_string()
+    }
+
+    #[test]
+    fn seeding_from_rust_matches_seeding_from_a_script() {
+        let mut koto = Koto::default();
+        koto.prelude().add_map("random", koto_random::make_module());
+
+        let script = "(0..5).each(|_| random.number()).to_tuple()";
+
+        koto_random::seed(42);
+        let seeded_from_rust = random_numbers(&mut koto, script);
+        let seeded_from_script = random_numbers(&mut koto, &format!("random.seed 42\n{script}"));
+
+        assert_eq!(seeded_from_rust, seeded_from_script);
+    }
+}
@@ -2,11 +2,16 @@
 
 use {
     koto_runtime::{num2, num4, prelude::*},
-    rand::{Rng, SeedableRng},
+    rand::{seq::SliceRandom, Rng, SeedableRng},
     rand_chacha::ChaCha8Rng,
     std::{cell::RefCell, rc::Rc},
 };
 
+/// Initializes the `random` module
+///
+/// The module's functions share a generator for the current thread, so seeding the generator
+/// (via `random.seed` or [seed]) affects all runtimes on the thread, including the VMs that get
+/// spawned by iterator adaptors.
 pub fn make_module() -> ValueMap {
     let result = ValueMap::new();
 
@@ -30,6 +35,10 @@ pub fn make_module() -> ValueMap {
         THREAD_RNG.with(|rng| rng.borrow_mut().gen_number())
     });
 
+    result.add_fn("number_range", |vm, args| {
+        THREAD_RNG.with(|rng| rng.borrow_mut().gen_number_range(vm.get_args(args)))
+    });
+
     result.add_fn("num2", |_, _| {
         THREAD_RNG.with(|rng| rng.borrow_mut().gen_num2())
     });
@@ -46,9 +55,21 @@ pub fn make_module() -> ValueMap {
         THREAD_RNG.with(|rng| rng.borrow_mut().seed(vm.get_args(args)))
     });
 
+    result.add_fn("shuffle", |vm, args| {
+        THREAD_RNG.with(|rng| rng.borrow_mut().shuffle(vm.get_args(args)))
+    });
+
     result
 }
 
+/// Seeds the current thread's default generator
+///
+/// This allows an embedder to make a script's random values reproducible,
+/// and is equivalent to calling `random.seed` from a script with the same seed.
+pub fn seed(seed: u64) {
+    THREAD_RNG.with(|rng| rng.borrow_mut().0 = ChaCha8Rng::seed_from_u64(seed));
+}
+
 thread_local! {
     static RNG_META: Rc<RefCell<MetaMap>> = make_rng_meta_map();
 
@@ -59,10 +80,12 @@ fn make_rng_meta_map() -> Rc<RefCell<MetaMap>> {
     MetaMapBuilder::<ChaChaRng>::new("Rng")
         .data_fn_mut("bool", |rng| rng.gen_bool())
         .data_fn_mut("number", |rng| rng.gen_number())
+        .data_fn_with_args_mut("number_range", |rng, args| rng.gen_number_range(args))
         .data_fn_mut("num2", |rng| rng.gen_num2())
         .data_fn_mut("num4", |rng| rng.gen_num4())
         .data_fn_with_args_mut("pick", |rng, args| rng.pick(args))
         .data_fn_with_args_mut("seed", |rng, args| rng.seed(args))
+        .data_fn_with_args_mut("shuffle", |rng, args| rng.shuffle(args))
         .build()
 }
 
@@ -85,6 +108,27 @@ impl ChaChaRng {
         Ok(self.0.gen::<f64>().into())
     }
 
+    fn gen_number_range(&mut self, args: &[Value]) -> RuntimeResult {
+        match args {
+            [Value::Range(r)] => {
+                let (low, high) = if r.is_ascending() {
+                    (r.start as f64, r.end as f64)
+                } else {
+                    (r.end as f64, r.start as f64)
+                };
+                let result = if r.inclusive {
+                    self.0.gen_range(low..=high)
+                } else if low < high {
+                    self.0.gen_range(low..high)
+                } else {
+                    return runtime_error!("number_range: Expected a non-empty Range");
+                };
+                Ok(result.into())
+            }
+            unexpected => type_error_with_slice("a Range as argument", unexpected),
+        }
+    }
+
     fn gen_num2(&mut self) -> RuntimeResult {
         let result = num2::Num2(self.0.gen::<f64>(), self.0.gen::<f64>());
         Ok(Value::Num2(result))
@@ -119,14 +163,14 @@ impl ChaChaRng {
                 }
             }
             [Range(r)] => {
-                let (start, end) = if r.end > r.start {
-                    (r.start, r.end)
+                let index = self.0.gen_range(0..r.len()) as isize;
+                // Descending ranges produce values counting down from the start of the range
+                let result = if r.is_ascending() {
+                    r.start + index
                 } else {
-                    (r.end, r.start)
+                    r.start - index
                 };
-                let size = end - start;
-                let index = self.0.gen_range(0..size);
-                Ok(Number((start + index).into()))
+                Ok(Number(result.into()))
             }
             [Tuple(t)] => {
                 let index = self.0.gen_range(0..t.len());
@@ -136,6 +180,16 @@ impl ChaChaRng {
         }
     }
 
+    fn shuffle(&mut self, args: &[Value]) -> RuntimeResult {
+        match args {
+            [Value::List(l)] => {
+                l.data_mut().shuffle(&mut self.0);
+                Ok(Value::List(l.clone()))
+            }
+            unexpected => type_error_with_slice("a List as argument", unexpected),
+        }
+    }
+
     fn seed(&mut self, args: &[Value]) -> RuntimeResult {
         use Value::*;
         match args {
@@ -0,0 +1,184 @@
+//! A Koto language module for working with regular expressions
+//!
+//! Matched text is returned as strings that share the input string's data.
+
+use {
+    koto_runtime::{prelude::*, ValueIteratorOutput as Output},
+    std::{borrow::Cow, cell::RefCell, rc::Rc},
+};
+
+pub fn make_module() -> ValueMap {
+    use Value::*;
+
+    let result = ValueMap::new();
+
+    result.add_fn("compile", |vm, args| match vm.get_args(args) {
+        [Str(pattern)] => match regex::Regex::new(pattern) {
+            Ok(regex) => Ok(Regex::make_external_value(regex)),
+            Err(e) => runtime_error!("regex.compile: {e}"),
+        },
+        unexpected => type_error_with_slice("a pattern String as argument", unexpected),
+    });
+
+    result
+}
+
+thread_local! {
+    static REGEX_META: Rc<RefCell<MetaMap>> = make_regex_meta_map();
+}
+
+fn make_regex_meta_map() -> Rc<RefCell<MetaMap>> {
+    use Value::*;
+
+    MetaMapBuilder::<Regex>::new("Regex")
+        .data_fn(UnaryOp::Display, |regex| {
+            Ok(format!("Regex({})", regex.0.as_str()).into())
+        })
+        .data_fn_with_args("captures", |regex, args| match args {
+            [Str(input)] => Ok(regex.captures(input)),
+            unexpected => type_error_with_slice("a String as argument", unexpected),
+        })
+        .data_fn_with_args("find", |regex, args| match args {
+            [Str(input)] => match regex.0.find(input) {
+                Some(m) => Ok(Str(input.with_bounds(m.range()).unwrap())),
+                None => Ok(Null),
+            },
+            unexpected => type_error_with_slice("a String as argument", unexpected),
+        })
+        .data_fn_with_args("find_all", |regex, args| match args {
+            [Str(input)] => Ok(Iterator(ValueIterator::new(Matches::new(
+                regex.0.clone(),
+                input.clone(),
+            )))),
+            unexpected => type_error_with_slice("a String as argument", unexpected),
+        })
+        .data_fn_with_args("is_match", |regex, args| match args {
+            [Str(input)] => Ok(regex.0.is_match(input).into()),
+            unexpected => type_error_with_slice("a String as argument", unexpected),
+        })
+        .data_fn_with_args("replace", |regex, args| match args {
+            [Str(input), Str(replacement)] => {
+                match regex.0.replace_all(input, replacement.as_str()) {
+                    // The input is returned unchanged when there are no matches
+                    Cow::Borrowed(_) => Ok(Str(input.clone())),
+                    Cow::Owned(result) => Ok(result.into()),
+                }
+            }
+            unexpected => {
+                type_error_with_slice("a String and a replacement String as arguments", unexpected)
+            }
+        })
+        .build()
+}
+
+/// The compiled regular expression returned by `regex.compile`
+#[derive(Debug)]
+struct Regex(regex::Regex);
+
+impl Regex {
+    fn make_external_value(regex: regex::Regex) -> Value {
+        let result =
+            ExternalValue::with_shared_meta_map(Self(regex), REGEX_META.with(|meta| meta.clone()));
+
+        Value::ExternalValue(result)
+    }
+
+    // Returns a map of the matched capture groups, or Null if there's no match
+    //
+    // Each group is available by index, and named groups are also available by name.
+    // Groups that didn't participate in the match are Null.
+    fn captures(&self, input: &ValueString) -> Value {
+        let captures = match self.0.captures(input) {
+            Some(captures) => captures,
+            None => return Value::Null,
+        };
+
+        let result = ValueMap::with_capacity(captures.len());
+        let mut data = result.data_mut();
+
+        for (index, (group, name)) in captures.iter().zip(self.0.capture_names()).enumerate() {
+            let text = match group {
+                Some(m) => Value::Str(input.with_bounds(m.range()).unwrap()),
+                None => Value::Null,
+            };
+            if let Some(name) = name {
+                data.insert(name.into(), text.clone());
+            }
+            data.insert(Value::from(index).into(), text);
+        }
+
+        drop(data);
+        Value::Map(result)
+    }
+}
+
+impl ExternalData for Regex {}
+
+/// An iterator that yields the successive non-overlapping matches of a regex
+///
+/// The matches share the input string's data.
+#[derive(Clone)]
+struct Matches {
+    regex: regex::Regex,
+    input: ValueString,
+    start: usize,
+    // The end of the previous match, used to avoid yielding an empty match that immediately
+    // follows a non-empty match
+    last_match_end: Option<usize>,
+}
+
+impl Matches {
+    fn new(regex: regex::Regex, input: ValueString) -> Self {
+        Self {
+            regex,
+            input,
+            start: 0,
+            last_match_end: None,
+        }
+    }
+}
+
+impl KotoIterator for Matches {
+    fn make_copy(&self) -> ValueIterator {
+        ValueIterator::new(self.clone())
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        false
+    }
+
+    fn name(&self) -> String {
+        "Matches <- Regex".into()
+    }
+}
+
+impl Iterator for Matches {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.start > self.input.len() {
+                return None;
+            }
+
+            let m = self.regex.find_at(&self.input, self.start)?;
+
+            if m.start() == m.end() {
+                // Step over the next character to avoid matching the same empty string again
+                self.start = match self.input[m.end()..].chars().next() {
+                    Some(c) => m.end() + c.len_utf8(),
+                    None => m.end() + 1,
+                };
+                if Some(m.end()) == self.last_match_end {
+                    continue;
+                }
+            } else {
+                self.start = m.end();
+            }
+
+            self.last_match_end = Some(m.end());
+            let output = Value::Str(self.input.with_bounds(m.range()).unwrap());
+            return Some(Output::Value(output));
+        }
+    }
+}
@@ -1,7 +1,16 @@
 //! A Koto language module for working with TOML data
+//!
+//! Tables are converted into Maps, and arrays are converted into Lists.
+//! TOML has a native datetime type which has no equivalent in Koto, so datetimes are converted
+//! into strings in RFC 3339 format, e.g. `1979-05-27T07:32:00Z`.
 
-use {koto_runtime::prelude::*, koto_serialize::SerializableValue, toml::Value as Toml};
+use {
+    koto_runtime::{core::koto::to_data, prelude::*},
+    koto_serialize::SerializableValue,
+    toml::Value as Toml,
+};
 
+/// Converts a TOML value into a Koto value
 pub fn toml_to_koto_value(value: &Toml) -> Result<Value, String> {
     let result = match value {
         Toml::Boolean(b) => Value::Bool(*b),
@@ -31,6 +40,7 @@ pub fn toml_to_koto_value(value: &Toml) -> Result<Value, String> {
     Ok(result)
 }
 
+/// Initializes the `toml` module
 pub fn make_module() -> ValueMap {
     use Value::*;
 
@@ -40,18 +50,26 @@ pub fn make_module() -> ValueMap {
         [Str(s)] => match toml::from_str(s) {
             Ok(toml) => match toml_to_koto_value(&toml) {
                 Ok(result) => Ok(result),
-                Err(e) => runtime_error!("Error while parsing input: {e}"),
+                Err(e) => runtime_error!("toml.from_string: Error while parsing input: {e}"),
             },
-            Err(e) => runtime_error!("Error while parsing input: {}", e.to_string()),
+            Err(e) => runtime_error!("toml.from_string: Error while parsing input: {e}"),
         },
         unexpected => type_error_with_slice("a String as argument", unexpected),
     });
 
     result.add_fn("to_string", |vm, args| match vm.get_args(args) {
-        [value] => match toml::to_string_pretty(&SerializableValue(value)) {
-            Ok(result) => Ok(result.into()),
-            Err(e) => runtime_error!("toml.to_string: {e}"),
-        },
+        [value] => {
+            // Values are converted to plain data with the same routine as koto.to_data
+            let data = to_data(value).map_err(|e| e.with_prefix("toml.to_string"))?;
+            // Converting to a TOML value first ensures that a table's values are emitted before
+            // any nested tables, regardless of the order of the Map's entries.
+            match Toml::try_from(SerializableValue(&data))
+                .and_then(|toml| toml::to_string_pretty(&toml))
+            {
+                Ok(result) => Ok(result.into()),
+                Err(e) => runtime_error!("toml.to_string: {e}"),
+            }
+        }
         unexpected => type_error_with_slice("a Value as argument", unexpected),
     });
 
@@ -1,7 +1,16 @@
 //! A Koto language module for working with YAML data
+//!
+//! Mappings are converted into Maps, and sequences are converted into Lists.
+//! Anchors and aliases are resolved while parsing, with each alias being replaced by a copy of
+//! the anchored value.
 
-use {koto_runtime::prelude::*, koto_serialize::SerializableValue, serde_yaml::Value as YamlValue};
+use {
+    koto_runtime::{core::koto::to_data, prelude::*},
+    koto_serialize::SerializableValue,
+    serde_yaml::Value as YamlValue,
+};
 
+/// Converts a YAML value into a Koto value
 pub fn yaml_value_to_koto_value(value: &serde_yaml::Value) -> Result<Value, String> {
     let result = match value {
         YamlValue::Null => Value::Null,
@@ -43,6 +52,7 @@ pub fn yaml_value_to_koto_value(value: &serde_yaml::Value) -> Result<Value, Stri
     Ok(result)
 }
 
+/// Initializes the `yaml` module
 pub fn make_module() -> ValueMap {
     use Value::*;
 
@@ -52,18 +62,22 @@ pub fn make_module() -> ValueMap {
         [Str(s)] => match serde_yaml::from_str(s) {
             Ok(value) => match yaml_value_to_koto_value(&value) {
                 Ok(result) => Ok(result),
-                Err(e) => runtime_error!("Error while parsing input: {}", e),
+                Err(e) => runtime_error!("yaml.from_string: Error while parsing input: {e}"),
             },
-            Err(e) => runtime_error!("Error while parsing input: {}", e.to_string()),
+            Err(e) => runtime_error!("yaml.from_string: Error while parsing input: {e}"),
         },
         unexpected => type_error_with_slice("a String as argument", unexpected),
     });
 
     result.add_fn("to_string", |vm, args| match vm.get_args(args) {
-        [value] => match serde_yaml::to_string(&SerializableValue(value)) {
-            Ok(result) => Ok(result.into()),
-            Err(e) => runtime_error!("yaml.to_string: {}", e),
-        },
+        [value] => {
+            // Values are converted to plain data with the same routine as koto.to_data
+            let data = to_data(value).map_err(|e| e.with_prefix("yaml.to_string"))?;
+            match serde_yaml::to_string(&SerializableValue(&data)) {
+                Ok(result) => Ok(result.into()),
+                Err(e) => runtime_error!("yaml.to_string: {e}"),
+            }
+        }
         unexpected => type_error_with_slice("a Value as argument", unexpected),
     });
 
@@ -1,5 +1,5 @@
 use {
-    crate::InstructionReader,
+    crate::{CompileWarning, InstructionReader},
     koto_parser::{ConstantPool, Span},
     std::{
         fmt::{self, Write},
@@ -12,6 +12,7 @@ use {
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct DebugInfo {
     source_map: Vec<(usize, Span)>,
+    call_spans: Vec<(usize, CallSpans)>,
     /// The source of the program that the debug info was derived from
     pub source: String,
 }
@@ -48,6 +49,62 @@ impl DebugInfo {
         }
         result
     }
+
+    /// Adds the spans of a function call's callee and arguments for the call instruction at the
+    /// given ip
+    ///
+    /// Call instructions are expected to be pushed in order of increasing ip.
+    pub fn push_call_spans(&mut self, ip: usize, spans: CallSpans) {
+        debug_assert!(self
+            .call_spans
+            .last()
+            .map_or(true, |(last_ip, _)| *last_ip < ip));
+        self.call_spans.push((ip, spans));
+    }
+
+    /// Returns the spans of a function call's callee and arguments for the call instruction at the
+    /// given ip
+    pub fn get_call_spans(&self, ip: usize) -> Option<&CallSpans> {
+        self.call_spans
+            .binary_search_by_key(&ip, |(call_ip, _)| *call_ip)
+            .ok()
+            .map(|index| &self.call_spans[index].1)
+    }
+
+    /// Returns the source text covered by the given span
+    ///
+    /// Only spans that start and end on the same line are supported,
+    /// with the span's columns treated as character offsets.
+    pub fn source_text(&self, span: Span) -> Option<&str> {
+        if span.start.line != span.end.line || span.start.column > span.end.column {
+            return None;
+        }
+
+        let line = self
+            .source
+            .lines()
+            .nth(span.start.line.checked_sub(1)? as usize)?;
+        let byte_offset = |column: u32| {
+            let char_index = column.checked_sub(1)? as usize;
+            line.char_indices()
+                .map(|(offset, _)| offset)
+                .chain(std::iter::once(line.len()))
+                .nth(char_index)
+        };
+
+        line.get(byte_offset(span.start.column)?..byte_offset(span.end.column)?)
+    }
+}
+
+/// The source spans of a function call's callee and arguments
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CallSpans {
+    /// The span of the expression that produced the called function, e.g. `x.foo` in `x.foo 42`
+    pub function: Option<Span>,
+    /// The span of the call's instance value, for calls made via a lookup, e.g. `x.foo()`
+    pub instance: Option<Span>,
+    /// The spans of the call's arguments
+    pub args: Vec<Span>,
 }
 
 /// A compiled chunk of bytecode, along with its associated constants and metadata
@@ -61,6 +118,8 @@ pub struct Chunk {
     pub source_path: Option<PathBuf>,
     /// Debug information associated with the chunk's bytecode
     pub debug_info: DebugInfo,
+    /// The warnings that were found while compiling the chunk
+    pub warnings: Vec<CompileWarning>,
 }
 
 impl Chunk {
@@ -76,6 +135,7 @@ impl Chunk {
             constants,
             source_path,
             debug_info,
+            warnings: Vec::new(),
         }
     }
 
@@ -1,9 +1,9 @@
 use {
-    crate::{DebugInfo, FunctionFlags, Op, TypeId},
+    crate::{CallSpans, DebugInfo, FunctionFlags, Op, TypeId},
     koto_parser::{
         AssignTarget, Ast, AstBinaryOp, AstFor, AstIf, AstIndex, AstNode, AstTry, AstUnaryOp,
         ConstantIndex, Function, ImportItemNode, LookupNode, MapKey, MatchArm, MetaKeyId, Node,
-        Scope, Span, StringNode, SwitchArm,
+        Position, Scope, Span, StringNode, SwitchArm,
     },
     smallvec::SmallVec,
     std::{collections::HashSet, error, fmt},
@@ -52,6 +52,11 @@ struct Loop {
     start_ip: usize,
     // Placeholders for jumps to the end of the loop, updated when the loop compilation is complete
     jump_placeholders: Vec<usize>,
+    // The number of catch points that were active when the loop started
+    //
+    // Catch points that are registered inside the loop need to be cleared when the loop is exited
+    // via `break` or `continue`.
+    catch_depth: usize,
 }
 
 enum Arg {
@@ -95,6 +100,7 @@ struct Frame {
     temporary_base: u8,
     temporary_count: u8,
     last_op: Option<Op>, // used to decide if an additional return instruction is needed
+    catch_depth: usize,  // the number of catch points that are active, see Loop::catch_depth
 }
 
 impl Frame {
@@ -574,7 +580,8 @@ impl Compiler {
             }
             Node::Function(f) => self.compile_function(result_register, f, ast)?,
             Node::NamedCall { id, args } => {
-                self.compile_named_call(result_register, *id, args, None, ast)?
+                let span = *ast.span(node.span);
+                self.compile_named_call(result_register, (*id, span), args, None, ast)?
             }
             Node::Import { from, items } => {
                 self.compile_import_expression(result_register, from, items, ast)?
@@ -637,6 +644,7 @@ impl Compiler {
                         (None, None) => {}
                     }
 
+                    self.clear_loop_catch_points();
                     self.push_op(Jump, &[]);
                     self.push_loop_jump_placeholder()?;
 
@@ -652,6 +660,7 @@ impl Compiler {
                     if let Some(result_register) = loop_result_register {
                         self.push_op(SetNull, &[result_register]);
                     }
+                    self.clear_loop_catch_points();
                     self.push_jump_back_op(JumpBack, &[], loop_start_ip);
 
                     None
@@ -801,17 +810,32 @@ impl Compiler {
             match &ast.node(*arg).node {
                 Node::List(nested_args) => {
                     let list_register = arg_index as u8;
-                    let size_op = args_size_op(nested_args, ast);
-                    self.push_op(Op::CheckType, &[list_register, TypeId::List as u8]);
-                    self.push_op(size_op, &[list_register, nested_args.len() as u8]);
-                    self.compile_unpack_nested_args(list_register, nested_args, ast)?;
+                    let function_arg = arg_index as u8;
+                    self.compile_check_nested_args(
+                        list_register,
+                        TypeId::List,
+                        nested_args,
+                        function_arg,
+                        ast,
+                    );
+                    self.compile_unpack_nested_args(list_register, nested_args, function_arg, ast)?;
                 }
                 Node::Tuple(nested_args) => {
                     let tuple_register = arg_index as u8;
-                    let size_op = args_size_op(nested_args, ast);
-                    self.push_op(Op::CheckType, &[tuple_register, TypeId::Tuple as u8]);
-                    self.push_op(size_op, &[tuple_register, nested_args.len() as u8]);
-                    self.compile_unpack_nested_args(tuple_register, nested_args, ast)?;
+                    let function_arg = arg_index as u8;
+                    self.compile_check_nested_args(
+                        tuple_register,
+                        TypeId::Tuple,
+                        nested_args,
+                        function_arg,
+                        ast,
+                    );
+                    self.compile_unpack_nested_args(
+                        tuple_register,
+                        nested_args,
+                        function_arg,
+                        ast,
+                    )?;
                 }
                 _ => {}
             }
@@ -908,10 +932,39 @@ impl Compiler {
         Ok(result)
     }
 
+    // Checks that a value matches the type and size expected by a nested args pattern
+    //
+    // `function_arg` is the index of the function argument that contains the nested args,
+    // which is used when reporting errors.
+    fn compile_check_nested_args(
+        &mut self,
+        register: u8,
+        type_id: TypeId,
+        nested_args: &[AstIndex],
+        function_arg: u8,
+        ast: &Ast,
+    ) {
+        let has_ellipsis = nested_args
+            .iter()
+            .any(|arg| matches!(&ast.node(*arg).node, Node::Ellipsis(_)));
+
+        self.push_op(Op::CheckType, &[register, type_id as u8, function_arg]);
+
+        if has_ellipsis {
+            // The ellipsis can match against zero elements, so it's excluded from the minimum size
+            let min_size = nested_args.len() as u8 - 1;
+            self.push_op(Op::CheckSizeMin, &[register, min_size, function_arg]);
+        } else {
+            let size = nested_args.len() as u8;
+            self.push_op(Op::CheckSizeEqual, &[register, size, function_arg]);
+        }
+    }
+
     fn compile_unpack_nested_args(
         &mut self,
         container_register: u8,
         args: &[AstIndex],
+        function_arg: u8,
         ast: &Ast,
     ) -> Result<(), CompilerError> {
         use Op::*;
@@ -935,20 +988,33 @@ impl Compiler {
                 }
                 Node::List(nested_args) => {
                     let list_register = self.push_register()?;
-                    let size_op = args_size_op(nested_args, ast);
                     self.push_op(TempIndex, &[list_register, container_register, arg_index]);
-                    self.push_op(CheckType, &[list_register, TypeId::List as u8]);
-                    self.push_op(size_op, &[list_register, nested_args.len() as u8]);
-                    self.compile_unpack_nested_args(list_register, nested_args, ast)?;
+                    self.compile_check_nested_args(
+                        list_register,
+                        TypeId::List,
+                        nested_args,
+                        function_arg,
+                        ast,
+                    );
+                    self.compile_unpack_nested_args(list_register, nested_args, function_arg, ast)?;
                     self.pop_register()?; // list_register
                 }
                 Node::Tuple(nested_args) => {
                     let tuple_register = self.push_register()?;
-                    let size_op = args_size_op(nested_args, ast);
                     self.push_op(TempIndex, &[tuple_register, container_register, arg_index]);
-                    self.push_op(CheckType, &[tuple_register, TypeId::Tuple as u8]);
-                    self.push_op(size_op, &[tuple_register, nested_args.len() as u8]);
-                    self.compile_unpack_nested_args(tuple_register, nested_args, ast)?;
+                    self.compile_check_nested_args(
+                        tuple_register,
+                        TypeId::Tuple,
+                        nested_args,
+                        function_arg,
+                        ast,
+                    );
+                    self.compile_unpack_nested_args(
+                        tuple_register,
+                        nested_args,
+                        function_arg,
+                        ast,
+                    )?;
                     self.pop_register()?; // tuple_register
                 }
                 Node::Ellipsis(maybe_id) if is_first_arg => {
@@ -1575,6 +1641,16 @@ impl Compiler {
             }
         };
 
+        // If there's a finally block, then errors thrown in the catch block are caught in a
+        // register so that the finally block can be run before the error is rethrown.
+        let rethrow_register = if finally_block.is_some() {
+            let register = self.push_register()?;
+            self.push_op(SetNull, &[register]);
+            Some(register)
+        } else {
+            None
+        };
+
         self.push_op(TryStart, &[catch_register]);
         // The catch block start point is defined via an offset from the current byte
         let catch_offset = self.push_offset_placeholder();
@@ -1584,7 +1660,9 @@ impl Compiler {
             _ => ResultRegister::None,
         };
 
+        self.frame_mut().catch_depth += 1;
         self.compile_node(try_result_register, try_node, ast)?;
+        self.frame_mut().catch_depth -= 1;
 
         // Clear the catch point at the end of the try block
         // - if the end of the try block has been reached then the catch block is no longer needed.
@@ -1603,24 +1681,75 @@ impl Compiler {
         //   are errors thrown in the catch block.
         self.push_op(TryEnd, &[]);
 
+        let rethrow_offset = match rethrow_register {
+            Some(rethrow_register) => {
+                self.push_op(TryStart, &[rethrow_register]);
+                Some(self.push_offset_placeholder())
+            }
+            None => None,
+        };
+
+        if rethrow_offset.is_some() {
+            self.frame_mut().catch_depth += 1;
+        }
         self.compile_node(try_result_register, catch_node, ast)?;
+        if rethrow_offset.is_some() {
+            self.frame_mut().catch_depth -= 1;
+        }
+
+        let catch_end_offset = match rethrow_offset {
+            Some(rethrow_offset) => {
+                // The end of the catch block has been reached without an error being thrown,
+                // so clear the rethrow catch point and then jump to the finally block.
+                self.push_op_without_span(TryEnd, &[]);
+                self.push_op_without_span(Jump, &[]);
+                let catch_end_offset = self.push_offset_placeholder();
+
+                // An error was thrown in the catch block, clear the rethrow catch point and then
+                // continue on to the finally block.
+                self.update_offset_placeholder(rethrow_offset)?;
+                self.push_op_without_span(TryEnd, &[]);
+
+                Some(catch_end_offset)
+            }
+            None => None,
+        };
+
         self.span_stack.pop();
 
-        if pop_catch_register {
-            self.pop_register()?;
+        self.update_offset_placeholder(finally_offset)?;
+        if let Some(catch_end_offset) = catch_end_offset {
+            self.update_offset_placeholder(catch_end_offset)?;
         }
 
-        self.update_offset_placeholder(finally_offset)?;
-        if let Some(finally_block) = finally_block {
+        let result = if let Some(finally_block) = finally_block {
             // If there's a finally block then the result of the expression is derived from there
             let finally_result_register = match result {
                 Some(result) => ResultRegister::Fixed(result.register),
                 _ => ResultRegister::None,
             };
-            self.compile_node(finally_result_register, ast.node(*finally_block), ast)
+            let finally_result =
+                self.compile_node(finally_result_register, ast.node(*finally_block), ast)?;
+
+            // Rethrow any error that was thrown in the catch block
+            if let Some(rethrow_register) = rethrow_register {
+                self.push_op_without_span(JumpIfFalse, &[rethrow_register]);
+                let rethrow_end_offset = self.push_offset_placeholder();
+                self.push_op(Throw, &[rethrow_register]);
+                self.update_offset_placeholder(rethrow_end_offset)?;
+                self.pop_register()?;
+            }
+
+            finally_result
         } else {
-            Ok(result)
+            result
+        };
+
+        if pop_catch_register {
+            self.pop_register()?;
         }
+
+        Ok(result)
     }
 
     fn compile_unary_op(
@@ -2382,6 +2511,10 @@ impl Compiler {
         // This produces a chain of temporary value registers, allowing lookup operations to access
         // parent containers when needed, e.g. calls to instance functions.
         let mut node_registers = SmallVec::<[u8; 4]>::new();
+        // The spans of the lookup chain up to each lookup node, used to provide the span of the
+        // instance value in function calls.
+        let mut node_spans = SmallVec::<[Span; 4]>::new();
+        let mut chain_start = self.span().start;
 
         // At the end of the lookup we'll pop the whole stack,
         // so we don't need to keep track of how many temporary registers we use.
@@ -2413,6 +2546,9 @@ impl Compiler {
                         .compile_node(ResultRegister::Any, ast.node(*root_node), ast)?
                         .unwrap();
                     node_registers.push(root.register);
+                    let root_span = *ast.span(ast.node(*root_node).span);
+                    chain_start = root_span.start;
+                    node_spans.push(root_span);
                 }
                 LookupNode::Id(id) => {
                     // Access by id
@@ -2428,6 +2564,7 @@ impl Compiler {
 
                     let node_register = self.push_register()?;
                     node_registers.push(node_register);
+                    node_spans.push(self.span_from(chain_start));
                     self.compile_access_id(node_register, parent_register, *id)?;
                 }
                 LookupNode::Str(ref lookup_string) => {
@@ -2445,6 +2582,7 @@ impl Compiler {
                     let node_register = self.push_register()?;
                     let key_register = self.push_register()?;
                     node_registers.push(node_register);
+                    node_spans.push(self.span_from(chain_start));
                     // TODO use compile_access_string
                     self.compile_string(
                         ResultRegister::Fixed(key_register),
@@ -2475,28 +2613,36 @@ impl Compiler {
 
                     let node_register = self.push_register()?;
                     node_registers.push(node_register);
+                    node_spans.push(self.span_from(chain_start));
                     self.push_op(Index, &[node_register, parent_register, index.register]);
                 }
                 LookupNode::Call { args, .. } => {
                     // Function call on a lookup result
 
-                    let (parent_register, function_register) = match &node_registers.as_slice() {
-                        [.., parent, function] => (Some(*parent), *function),
-                        [function] => (None, *function),
-                        [] => unreachable!(),
-                    };
+                    let (instance, function) =
+                        match (node_registers.as_slice(), node_spans.as_slice()) {
+                            ([.., parent, function], [.., parent_span, function_span]) => (
+                                Some((*parent, *parent_span)),
+                                (*function, Some(*function_span)),
+                            ),
+                            ([function], [function_span]) => {
+                                (None, (*function, Some(*function_span)))
+                            }
+                            _ => unreachable!(),
+                        };
 
                     // Not in the last node, so for the lookup chain to continue,
                     // use a temporary register for the call result.
                     let call_result_register = self.push_register()?;
                     node_registers.push(call_result_register);
+                    node_spans.push(self.span_from(chain_start));
 
                     self.compile_call(
                         ResultRegister::Fixed(call_result_register),
-                        function_register,
+                        function,
                         args,
                         None,
-                        parent_register,
+                        instance,
                         ast,
                     )?;
                 }
@@ -2567,6 +2713,7 @@ impl Compiler {
             LookupNode::Id(id) if !simple_assignment => {
                 self.compile_access_id(access_register, parent_register, *id)?;
                 node_registers.push(access_register);
+                node_spans.push(self.span_from(chain_start));
             }
             LookupNode::Str(_) if !simple_assignment => {
                 self.push_op(
@@ -2578,6 +2725,7 @@ impl Compiler {
                     ],
                 );
                 node_registers.push(access_register);
+                node_spans.push(self.span_from(chain_start));
             }
             LookupNode::Index(_) if !simple_assignment => {
                 self.push_op(
@@ -2589,33 +2737,34 @@ impl Compiler {
                     ],
                 );
                 node_registers.push(access_register);
+                node_spans.push(self.span_from(chain_start));
             }
             LookupNode::Call { args, with_parens } => {
                 if simple_assignment {
                     return compiler_error!(self, "Assigning to temporary value");
                 } else if access_assignment || piped_arg_register.is_none() || *with_parens {
-                    let (parent_register, function_register) = match &node_registers.as_slice() {
-                        [.., parent, function] => (Some(*parent), *function),
-                        [function] => (None, *function),
-                        [] => unreachable!(),
-                    };
+                    let (instance, function) =
+                        match (node_registers.as_slice(), node_spans.as_slice()) {
+                            ([.., parent, function], [.., parent_span, function_span]) => (
+                                Some((*parent, *parent_span)),
+                                (*function, Some(*function_span)),
+                            ),
+                            ([function], [function_span]) => {
+                                (None, (*function, Some(*function_span)))
+                            }
+                            _ => unreachable!(),
+                        };
 
                     let call_result_register = match chain_result_register {
                         Some(result_register) => {
                             node_registers.push(result_register);
+                            node_spans.push(self.span_from(chain_start));
                             ResultRegister::Fixed(result_register)
                         }
                         None => ResultRegister::None,
                     };
 
-                    self.compile_call(
-                        call_result_register,
-                        function_register,
-                        args,
-                        None,
-                        parent_register,
-                        ast,
-                    )?;
+                    self.compile_call(call_result_register, function, args, None, instance, ast)?;
                 }
             }
             _ => {}
@@ -2624,14 +2773,15 @@ impl Compiler {
         // Do we need to modify the accessed value?
         if access_assignment {
             let Some(rhs) = rhs else {
-                return compiler_error!(self, "compile_lookup: Missing rhs")
+                return compiler_error!(self, "compile_lookup: Missing rhs");
             };
             let Some(rhs_op) = rhs_op else {
-                return compiler_error!(self, "compile_lookup: Missing rhs_op")
+                return compiler_error!(self, "compile_lookup: Missing rhs_op");
             };
 
             self.push_op(rhs_op, &[access_register, rhs]);
             node_registers.push(access_register);
+            node_spans.push(self.span_from(chain_start));
         }
 
         // Do we need to assign a value to the last node in the lookup?
@@ -2682,10 +2832,13 @@ impl Compiler {
                 _ => Vec::new(),
             };
 
-            let (parent_register, function_register) = match &node_registers.as_slice() {
-                [.., parent, function] => (Some(*parent), *function),
-                [function] => (None, *function),
-                [] => unreachable!(),
+            let (instance, function) = match (node_registers.as_slice(), node_spans.as_slice()) {
+                ([.., parent, function], [.., parent_span, function_span]) => (
+                    Some((*parent, *parent_span)),
+                    (*function, Some(*function_span)),
+                ),
+                ([function], [function_span]) => (None, (*function, Some(*function_span))),
+                _ => unreachable!(),
             };
 
             let call_result = if let Some(result) = result {
@@ -2696,10 +2849,10 @@ impl Compiler {
 
             self.compile_call(
                 call_result,
-                function_register,
+                function,
                 &piped_call_args,
                 piped_arg_register,
-                parent_register,
+                instance,
                 ast,
             )?;
         }
@@ -2812,14 +2965,20 @@ impl Compiler {
         let result = match &rhs_node.node {
             Node::NamedCall { id, args } => self.compile_named_call(
                 call_result_register,
-                *id,
+                (*id, *ast.span(rhs_node.span)),
                 args,
                 Some(piped_value.register),
                 ast,
             ),
             Node::Id(id) => {
                 // Compile a call with the piped arg using the id to access the function
-                self.compile_named_call(result_register, *id, &[], Some(piped_value.register), ast)
+                self.compile_named_call(
+                    result_register,
+                    (*id, *ast.span(rhs_node.span)),
+                    &[],
+                    Some(piped_value.register),
+                    ast,
+                )
             }
             Node::Lookup(lookup_node) => {
                 // Compile the lookup, passing in the piped call arg, which will either be appended
@@ -2841,7 +3000,7 @@ impl Compiler {
                     .unwrap();
                 let result = self.compile_call(
                     call_result_register,
-                    function.register,
+                    (function.register, Some(*ast.span(rhs_node.span))),
                     &[],
                     Some(piped_value.register),
                     None,
@@ -2861,18 +3020,30 @@ impl Compiler {
         result
     }
 
+    // Compiles a call to a function referred to by name
+    //
+    // The span is expected to start with the function's id, e.g. the span of a NamedCall node.
     fn compile_named_call(
         &mut self,
         result_register: ResultRegister,
-        function_id: ConstantIndex,
+        (function_id, span): (ConstantIndex, Span),
         args: &[AstIndex],
         piped_arg: Option<u8>,
         ast: &Ast,
     ) -> CompileNodeResult {
+        let id_length = ast.constants().get_str(function_id).chars().count() as u32;
+        let function_span = Span {
+            start: span.start,
+            end: Position {
+                line: span.start.line,
+                column: span.start.column + id_length,
+            },
+        };
+
         if let Some(function_register) = self.frame().get_local_assigned_register(function_id) {
             self.compile_call(
                 result_register,
-                function_register,
+                (function_register, Some(function_span)),
                 args,
                 piped_arg,
                 None,
@@ -2891,7 +3062,7 @@ impl Compiler {
 
             self.compile_call(
                 call_result_register,
-                function_register,
+                (function_register, Some(function_span)),
                 args,
                 piped_arg,
                 None,
@@ -2906,17 +3077,24 @@ impl Compiler {
     fn compile_call(
         &mut self,
         result_register: ResultRegister,
-        function_register: u8,
+        function: (u8, Option<Span>),
         args: &[AstIndex],
         piped_arg: Option<u8>,
-        instance: Option<u8>,
+        instance: Option<(u8, Span)>,
         ast: &Ast,
     ) -> CompileNodeResult {
         use Op::*;
 
+        let (function_register, function_span) = function;
         let result = self.get_result_register(result_register)?;
         let stack_count = self.frame().register_stack.len();
 
+        let mut call_spans = CallSpans {
+            function: function_span,
+            instance: instance.map(|(_, span)| span),
+            args: Vec::with_capacity(args.len() + piped_arg.is_some() as usize),
+        };
+
         // The frame base is an empty register that may be used for an instance value if needed
         // (it's decided at runtime if the instance value will be used or not).
         let frame_base = self.push_register()?;
@@ -2925,13 +3103,17 @@ impl Compiler {
 
         for arg in args.iter() {
             let arg_register = self.push_register()?;
-            self.compile_node(ResultRegister::Fixed(arg_register), ast.node(*arg), ast)?;
+            let arg_node = ast.node(*arg);
+            self.compile_node(ResultRegister::Fixed(arg_register), arg_node, ast)?;
+            call_spans.args.push(*ast.span(arg_node.span));
         }
 
         if let Some(piped_arg) = piped_arg {
             arg_count += 1;
             let arg_register = self.push_register()?;
             self.push_op(Copy, &[arg_register, piped_arg]);
+            // The piped value's node isn't available here, so the call's span is used instead
+            call_spans.args.push(self.span());
         }
 
         let call_result_register = if let Some(result) = result {
@@ -2943,8 +3125,11 @@ impl Compiler {
             frame_base
         };
 
+        self.debug_info
+            .push_call_spans(self.bytes.len(), call_spans);
+
         match instance {
-            Some(instance_register) => {
+            Some((instance_register, _)) => {
                 self.push_op(
                     CallInstance,
                     &[
@@ -3630,10 +3815,12 @@ impl Compiler {
         };
 
         let loop_start_ip = self.bytes.len();
+        let catch_depth = self.frame().catch_depth;
         self.frame_mut().loop_stack.push(Loop {
             result_register: body_result_register,
             start_ip: loop_start_ip,
             jump_placeholders: vec![],
+            catch_depth,
         });
 
         match args.as_slice() {
@@ -3753,11 +3940,13 @@ impl Compiler {
         };
 
         let loop_start_ip = self.bytes.len();
+        let catch_depth = self.frame().catch_depth;
 
         self.frame_mut().loop_stack.push(Loop {
             start_ip: loop_start_ip,
             result_register: body_result_register,
             jump_placeholders: Vec::new(),
+            catch_depth,
         });
 
         if let Some((condition, negate_condition)) = condition {
@@ -3829,6 +4018,18 @@ impl Compiler {
         offset_ip
     }
 
+    // Clears any catch points that were registered inside the current loop
+    //
+    // This is needed when the loop is being exited via `break` or `continue`, otherwise the catch
+    // points would remain active after the try expressions have been left.
+    fn clear_loop_catch_points(&mut self) {
+        let frame = self.frame();
+        let loop_catch_depth = frame.loop_stack.last().map_or(0, |info| info.catch_depth);
+        for _ in loop_catch_depth..frame.catch_depth {
+            self.push_op_without_span(Op::TryEnd, &[]);
+        }
+    }
+
     fn push_loop_jump_placeholder(&mut self) -> Result<(), CompilerError> {
         let placeholder = self.push_offset_placeholder();
         match self.frame_mut().loop_stack.last_mut() {
@@ -3943,16 +4144,13 @@ impl Compiler {
     fn span(&self) -> Span {
         *self.span_stack.last().expect("Empty span stack")
     }
-}
 
-fn args_size_op(args: &[AstIndex], ast: &Ast) -> Op {
-    if args
-        .iter()
-        .any(|arg| matches!(&ast.node(*arg).node, Node::Ellipsis(_)))
-    {
-        Op::CheckSizeMin
-    } else {
-        Op::CheckSizeEqual
+    // Returns a span that starts at the given position and ends with the current span
+    fn span_from(&self, start: Position) -> Span {
+        Span {
+            start,
+            end: self.span().end,
+        }
     }
 }
 
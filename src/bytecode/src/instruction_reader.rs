@@ -419,14 +419,17 @@ pub enum Instruction {
     CheckType {
         register: u8,
         type_id: TypeId,
+        arg_index: u8,
     },
     CheckSizeEqual {
         register: u8,
         size: usize,
+        arg_index: u8,
     },
     CheckSizeMin {
         register: u8,
         size: usize,
+        arg_index: u8,
     },
     StringStart {
         register: u8,
@@ -780,15 +783,30 @@ impl fmt::Debug for Instruction {
             Debug { register, constant } => {
                 write!(f, "Debug\t\tregister: {register}\tconstant: {constant}")
             }
-            CheckType { register, type_id } => {
-                write!(f, "CheckType\tregister: {register}\ttype: {type_id:?}")
-            }
-            CheckSizeEqual { register, size } => {
-                write!(f, "CheckSizeEqual\tregister: {register}\tsize: {size}")
-            }
-            CheckSizeMin { register, size } => {
-                write!(f, "CheckSizeMin\tregister: {register}\tsize: {size}")
-            }
+            CheckType {
+                register,
+                type_id,
+                arg_index,
+            } => write!(
+                f,
+                "CheckType\tregister: {register}\ttype: {type_id:?}\targ: {arg_index}"
+            ),
+            CheckSizeEqual {
+                register,
+                size,
+                arg_index,
+            } => write!(
+                f,
+                "CheckSizeEqual\tregister: {register}\tsize: {size}\targ: {arg_index}"
+            ),
+            CheckSizeMin {
+                register,
+                size,
+                arg_index,
+            } => write!(
+                f,
+                "CheckSizeMin\tregister: {register}\tsize: {size}\targ: {arg_index}"
+            ),
             StringStart {
                 register,
                 size_hint,
@@ -1432,7 +1450,11 @@ impl Iterator for InstructionReader {
             Op::CheckType => {
                 let register = get_u8!();
                 match TypeId::from_byte(get_u8!()) {
-                    Ok(type_id) => Some(CheckType { register, type_id }),
+                    Ok(type_id) => Some(CheckType {
+                        register,
+                        type_id,
+                        arg_index: get_u8!(),
+                    }),
                     Err(byte) => Some(Error {
                         message: format!("Unexpected value for CheckType id: {byte}"),
                     }),
@@ -1441,10 +1463,12 @@ impl Iterator for InstructionReader {
             Op::CheckSizeEqual => Some(CheckSizeEqual {
                 register: get_u8!(),
                 size: get_u8!() as usize,
+                arg_index: get_u8!(),
             }),
             Op::CheckSizeMin => Some(CheckSizeMin {
                 register: get_u8!(),
                 size: get_u8!() as usize,
+                arg_index: get_u8!(),
             }),
             Op::StringStart => Some(StringStart {
                 register: get_u8!(),
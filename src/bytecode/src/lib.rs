@@ -9,11 +9,15 @@ mod compiler;
 mod instruction_reader;
 mod loader;
 mod op;
+mod script_info;
+mod warnings;
 
 pub use {
-    chunk::{Chunk, DebugInfo},
+    chunk::{CallSpans, Chunk, DebugInfo},
     compiler::{Compiler, CompilerError, CompilerSettings},
     instruction_reader::{FunctionFlags, Instruction, InstructionReader, TypeId},
-    loader::{Loader, LoaderError},
+    loader::{ChunkCache, Loader, LoaderError, ParsedScript},
     op::Op,
+    script_info::{BindingInfo, FunctionInfo, ImportInfo, ScriptInfo},
+    warnings::{CompileWarning, CompileWarningKind},
 };
@@ -1,9 +1,16 @@
 use {
-    crate::{Chunk, Compiler, CompilerError, CompilerSettings},
+    crate::{Chunk, CompileWarning, Compiler, CompilerError, CompilerSettings, ScriptInfo},
     dunce::canonicalize,
-    koto_parser::{format_error_with_excerpt, Parser, ParserError},
+    koto_parser::{format_error_with_excerpt, Ast, Parser, ParserError, Span},
     rustc_hash::FxHasher,
-    std::{collections::HashMap, error, fmt, hash::BuildHasherDefault, path::PathBuf, rc::Rc},
+    std::{
+        cell::RefCell,
+        collections::{hash_map::DefaultHasher, HashMap},
+        error, fmt,
+        hash::{BuildHasherDefault, Hash, Hasher},
+        path::{Path, PathBuf},
+        rc::Rc,
+    },
 };
 
 /// Errors that can be returned from [Loader] operations
@@ -63,6 +70,27 @@ impl LoaderError {
             _ => false,
         }
     }
+
+    /// Returns the span in the source where the error occurred
+    ///
+    /// None is returned for errors that aren't associated with the source, e.g. IO errors.
+    pub fn span(&self) -> Option<Span> {
+        match &self.error {
+            LoaderErrorType::Parser(e) => Some(e.span),
+            LoaderErrorType::Compiler(e) => Some(e.span),
+            LoaderErrorType::Io(_) => None,
+        }
+    }
+
+    /// Returns the source that was being loaded when the error occurred
+    pub fn source_code(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the path of the source that was being loaded when the error occurred
+    pub fn source_path(&self) -> Option<&Path> {
+        self.source_path.as_deref()
+    }
 }
 
 impl fmt::Display for LoaderError {
@@ -103,37 +131,201 @@ impl fmt::Display for LoaderError {
 
 impl error::Error for LoaderError {}
 
+/// A cache of compiled modules that can be shared between [Loader]s
+///
+/// Chunks are cached by the module's canonicalized path, along with a hash of the module's
+/// contents, so that modules are recompiled when their contents change.
+///
+/// Cloning the cache is cheap, with the clone sharing its data with the original.
+#[derive(Clone, Default)]
+pub struct ChunkCache {
+    chunks: Rc<RefCell<HashMap<PathBuf, CachedChunk, BuildHasherDefault<FxHasher>>>>,
+}
+
+struct CachedChunk {
+    content_hash: u64,
+    chunk: Rc<Chunk>,
+}
+
+thread_local! {
+    static SHARED_CHUNK_CACHE: ChunkCache = ChunkCache::default();
+}
+
+impl ChunkCache {
+    /// Returns the cache that's shared by all callers on the current thread
+    ///
+    /// Compiled chunks can't be sent between threads, so each thread has its own shared cache.
+    /// Chunks in the shared cache are kept until the cache is cleared or the thread exits.
+    pub fn shared() -> Self {
+        SHARED_CHUNK_CACHE.with(|cache| cache.clone())
+    }
+
+    /// Returns the number of chunks in the cache
+    pub fn len(&self) -> usize {
+        self.chunks.borrow().len()
+    }
+
+    /// Returns true if the cache contains no chunks
+    pub fn is_empty(&self) -> bool {
+        self.chunks.borrow().is_empty()
+    }
+
+    /// Removes all chunks from the cache
+    pub fn clear(&self) {
+        self.chunks.borrow_mut().clear();
+    }
+
+    // Returns the cached chunk for the path if the cached chunk's contents are up to date
+    fn get(&self, path: &Path, content_hash: u64) -> Option<Rc<Chunk>> {
+        match self.chunks.borrow().get(path) {
+            Some(cached) if cached.content_hash == content_hash => Some(cached.chunk.clone()),
+            _ => None,
+        }
+    }
+
+    fn insert(&self, path: PathBuf, content_hash: u64, chunk: Rc<Chunk>) {
+        self.chunks.borrow_mut().insert(
+            path,
+            CachedChunk {
+                content_hash,
+                chunk,
+            },
+        );
+    }
+}
+
+fn content_hash(script: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    script.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A parsed script, produced by [Loader::parse] and compiled by [Loader::compile_ast]
+///
+/// The script's source is kept alongside its [Ast], so that the compiled chunk and any compilation
+/// errors can refer back to it.
+#[derive(Debug)]
+pub struct ParsedScript {
+    ast: Ast,
+    source: String,
+    source_path: Option<PathBuf>,
+}
+
+impl ParsedScript {
+    /// Returns the script's AST
+    pub fn ast(&self) -> &Ast {
+        &self.ast
+    }
+
+    /// Returns the script's source
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the path of the script, if one was provided when parsing
+    pub fn source_path(&self) -> Option<&Path> {
+        self.source_path.as_deref()
+    }
+}
+
 /// Helper for loading, compiling, and caching Koto modules
 #[derive(Clone, Default)]
 pub struct Loader {
-    chunks: HashMap<PathBuf, Rc<Chunk>, BuildHasherDefault<FxHasher>>,
+    cache: ChunkCache,
+    // The directory of the root script, checked when a module isn't found next to the importer
+    script_dir: Option<PathBuf>,
+    // Additional directories that are searched for modules, in order
+    library_paths: Vec<PathBuf>,
 }
 
 impl Loader {
+    /// Initializes a loader that uses the provided cache for compiled modules
+    ///
+    /// Loaders that share a cache only need to compile each module once, which is useful when
+    /// running many scripts that import the same modules (e.g. a suite of tests).
+    pub fn with_shared_cache(cache: ChunkCache) -> Self {
+        Self {
+            cache,
+            ..Default::default()
+        }
+    }
+
+    /// Parses a script without compiling it
+    ///
+    /// The resulting [ParsedScript] can be inspected (e.g. by a linter), and then compiled with
+    /// [Loader::compile_ast].
+    pub fn parse(
+        &self,
+        script: &str,
+        script_path: &Option<PathBuf>,
+    ) -> Result<ParsedScript, LoaderError> {
+        match Parser::parse(script) {
+            Ok(ast) => Ok(ParsedScript {
+                ast,
+                source: script.to_string(),
+                source_path: script_path.clone(),
+            }),
+            Err(e) => Err(LoaderError::from_parser_error(
+                e,
+                script,
+                script_path.clone(),
+            )),
+        }
+    }
+
+    /// Compiles a script that was previously parsed with [Loader::parse]
+    ///
+    /// The parsed script isn't consumed, so it can be compiled again (e.g. with other settings).
+    pub fn compile_ast(
+        &mut self,
+        parsed: &ParsedScript,
+        compiler_settings: CompilerSettings,
+    ) -> Result<Rc<Chunk>, LoaderError> {
+        let ParsedScript {
+            ast,
+            source,
+            source_path,
+        } = parsed;
+
+        let repl_mode = compiler_settings.repl_mode;
+        let (bytes, mut debug_info) = match Compiler::compile(ast, compiler_settings) {
+            Ok((bytes, debug_info)) => (bytes, debug_info),
+            Err(e) => {
+                return Err(LoaderError::from_compiler_error(
+                    e,
+                    source,
+                    source_path.clone(),
+                ))
+            }
+        };
+
+        debug_info.source = source.clone();
+
+        // Warnings are skipped in the REPL, where values are assigned for later use
+        let warnings = if repl_mode {
+            Vec::new()
+        } else {
+            CompileWarning::find(ast)
+        };
+
+        let mut chunk = Chunk::new(
+            bytes,
+            ast.constants().clone(),
+            source_path.clone(),
+            debug_info,
+        );
+        chunk.warnings = warnings;
+        Ok(Rc::new(chunk))
+    }
+
     fn compile(
         &mut self,
         script: &str,
         script_path: Option<PathBuf>,
         compiler_settings: CompilerSettings,
     ) -> Result<Rc<Chunk>, LoaderError> {
-        match Parser::parse(script) {
-            Ok(ast) => {
-                let (bytes, mut debug_info) = match Compiler::compile(&ast, compiler_settings) {
-                    Ok((bytes, debug_info)) => (bytes, debug_info),
-                    Err(e) => return Err(LoaderError::from_compiler_error(e, script, script_path)),
-                };
-
-                debug_info.source = script.to_string();
-
-                Ok(Rc::new(Chunk::new(
-                    bytes,
-                    ast.consume_constants(),
-                    script_path,
-                    debug_info,
-                )))
-            }
-            Err(e) => Err(LoaderError::from_parser_error(e, script, script_path)),
-        }
+        let parsed = self.parse(script, &script_path)?;
+        self.compile_ast(&parsed, compiler_settings)
     }
 
     /// Compiles a script in REPL mode
@@ -150,14 +342,57 @@ impl Loader {
         self.compile(script, script_path.clone(), CompilerSettings::default())
     }
 
+    /// Parses a script and returns a summary of its top-level bindings and imports
+    ///
+    /// The script is parsed but not compiled, see [ScriptInfo].
+    pub fn script_info(
+        &self,
+        script: &str,
+        script_path: &Option<PathBuf>,
+    ) -> Result<ScriptInfo, LoaderError> {
+        match Parser::parse(script) {
+            Ok(ast) => Ok(ScriptInfo::from_ast(&ast)),
+            Err(e) => Err(LoaderError::from_parser_error(
+                e,
+                script,
+                script_path.clone(),
+            )),
+        }
+    }
+
+    /// Sets the directory of the root script
+    ///
+    /// Modules that can't be found next to the importing module are looked for in the root
+    /// script's directory, see [Loader::compile_module].
+    pub fn set_script_dir(&mut self, script_dir: Option<PathBuf>) {
+        self.script_dir = script_dir;
+    }
+
+    /// Adds a directory that should be searched when importing modules
+    ///
+    /// Library paths are searched in the order that they're added, after the importing module's
+    /// directory and the root script's directory.
+    pub fn add_library_path(&mut self, path: PathBuf) {
+        self.library_paths.push(path);
+    }
+
     /// Finds a module from its name, and then compiles it
+    ///
+    /// The module is looked for in the following directories, in order:
+    ///   1. The directory of the importing module (`load_from_path`), or the current working
+    ///      directory if no path is provided.
+    ///   2. The root script's directory, see [Loader::set_script_dir].
+    ///   3. The loader's library paths, see [Loader::add_library_path].
+    ///
+    /// In each directory, a file matching the name with a `.koto` extension is checked for first,
+    /// followed by a directory matching the name that contains a `main.koto` file.
     pub fn compile_module(
         &mut self,
         name: &str,
         load_from_path: Option<PathBuf>,
     ) -> Result<CompileModuleResult, LoaderError> {
         // Get either the directory of the provided path, or the current working directory
-        let search_folder = match &load_from_path {
+        let importer_dir = match &load_from_path {
             Some(path) => match canonicalize(path) {
                 Ok(canonicalized) if canonicalized.is_file() => match canonicalized.parent() {
                     Some(parent_dir) => parent_dir.to_path_buf(),
@@ -176,6 +411,14 @@ impl Loader {
             },
         };
 
+        let mut search_dirs: Vec<PathBuf> = vec![importer_dir];
+        for dir in self.script_dir.iter().chain(self.library_paths.iter()) {
+            let dir = canonicalize(dir).unwrap_or_else(|_| dir.clone());
+            if !search_dirs.contains(&dir) {
+                search_dirs.push(dir);
+            }
+        }
+
         let mut load_module_from_path = |module_path: PathBuf| {
             let module_path = module_path.canonicalize().map_err(|error| {
                 LoaderError::io_error(format!(
@@ -183,59 +426,76 @@ impl Loader {
                     module_path.to_string_lossy(),
                 ))
             })?;
-            match self.chunks.get(&module_path) {
+            let script = std::fs::read_to_string(&module_path).map_err(|_| {
+                LoaderError::io_error(format!("File not found: {}", module_path.to_string_lossy()))
+            })?;
+            let content_hash = content_hash(&script);
+
+            match self.cache.get(&module_path, content_hash) {
                 Some(chunk) => Ok(CompileModuleResult {
-                    chunk: chunk.clone(),
+                    chunk,
                     path: module_path,
                     loaded_from_cache: true,
                 }),
-                None => match std::fs::read_to_string(&module_path) {
-                    Ok(script) => {
-                        let chunk = self.compile(
-                            &script,
-                            Some(module_path.clone()),
-                            CompilerSettings::default(),
-                        )?;
-
-                        self.chunks.insert(module_path.clone(), chunk.clone());
-                        Ok(CompileModuleResult {
-                            chunk,
-                            path: module_path,
-                            loaded_from_cache: false,
-                        })
-                    }
-                    Err(_) => Err(LoaderError::io_error(format!(
-                        "File not found: {}",
-                        module_path.to_string_lossy()
-                    ))),
-                },
+                None => {
+                    let chunk = self.compile(
+                        &script,
+                        Some(module_path.clone()),
+                        CompilerSettings::default(),
+                    )?;
+
+                    self.cache
+                        .insert(module_path.clone(), content_hash, chunk.clone());
+                    Ok(CompileModuleResult {
+                        chunk,
+                        path: module_path,
+                        loaded_from_cache: false,
+                    })
+                }
             }
         };
 
         let extension = "koto";
-        let named_path = search_folder.join(name);
+        let mut tried_paths = Vec::new();
 
-        // First, check for a neighbouring file with a matching name.
-        let module_path = named_path.with_extension(extension);
-        if module_path.exists() {
-            load_module_from_path(module_path)
-        } else {
-            // Alternatively, check for a neighbouring directory with a matching name,
-            // that also contains a main file.
-            let module_path = named_path.join("main").with_extension(extension);
-            if module_path.exists() {
-                load_module_from_path(module_path)
-            } else {
-                Err(LoaderError::io_error(format!(
-                    "Unable to find module '{name}'"
-                )))
+        for search_dir in search_dirs {
+            let named_path = search_dir.join(name);
+
+            // First, check for a file with a matching name, and then check for a directory with a
+            // matching name that contains a main file.
+            for module_path in [
+                named_path.with_extension(extension),
+                named_path.join("main").with_extension(extension),
+            ] {
+                if module_path.is_file() {
+                    return load_module_from_path(module_path);
+                }
+                tried_paths.push(module_path);
             }
         }
+
+        let mut message =
+            format!("Unable to find module '{name}', the following paths were tried:");
+        for path in tried_paths {
+            message.push_str(&format!("\n  {}", path.display()));
+        }
+        Err(LoaderError::io_error(message))
     }
 
     /// Clears the compiled module cache
+    ///
+    /// If the cache is shared with other loaders (see [Loader::with_shared_cache]), then the
+    /// cache is also cleared for the other loaders.
     pub fn clear_cache(&mut self) {
-        self.chunks.clear();
+        self.cache.clear();
+    }
+
+    /// Returns a handle to the loader's compiled module cache
+    ///
+    /// The handle can be used to share the cache with other loaders,
+    /// see [Loader::with_shared_cache].
+    pub fn cache(&self) -> ChunkCache {
+        self.cache.clone()
     }
 }
 
@@ -546,25 +546,28 @@ pub enum Op {
 
     /// Throws an error if the value doesn't match the expected type
     ///
-    /// Used when matching function arguments.
+    /// Used when matching function arguments, with the index of the function argument
+    /// being included in the error message.
     ///
     /// See [TypeId](crate::TypeId) for the list of types that are checked against.
     ///
-    /// `[*value, type]`
+    /// `[*value, type, arg index]`
     CheckType,
 
     /// Throws an error if the value doesn't match the expected size
     ///
-    /// Used when matching function arguments.
+    /// Used when matching function arguments, with the index of the function argument
+    /// being included in the error message.
     ///
-    /// `[*value, size]`
+    /// `[*value, size, arg index]`
     CheckSizeEqual,
 
     /// Throws an error if the value isn't at least the expected size
     ///
-    /// Used when matching function arguments.
+    /// Used when matching function arguments, with the index of the function argument
+    /// being included in the error message.
     ///
-    /// `[*value, size]`
+    /// `[*value, size, arg index]`
     CheckSizeMin,
 
     // Unused opcodes, allowing for a direct transmutation from a byte to an Op.
@@ -0,0 +1,166 @@
+use koto_parser::{
+    Ast, AstIndex, AstString, ConstantIndex, ImportItemNode, Node, Scope, Span, StringNode,
+};
+
+/// A summary of a script's top-level bindings and imports, see [ScriptInfo::from_ast]
+///
+/// The summary is derived from the script's AST, without the script being executed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScriptInfo {
+    /// The values that are assigned at the top level of the script, in order of assignment
+    pub bindings: Vec<BindingInfo>,
+    /// The script's import expressions, in order of appearance
+    ///
+    /// Imports in nested blocks and functions are included.
+    pub imports: Vec<ImportInfo>,
+}
+
+/// A value that's assigned at the top level of a script, see [ScriptInfo]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BindingInfo {
+    /// The name of the assigned value
+    pub name: String,
+    /// The span of the assignment target
+    pub span: Span,
+    /// True if the value is assigned with `export`
+    pub exported: bool,
+    /// Information about the assigned function, if the assigned value is a function literal
+    pub function: Option<FunctionInfo>,
+}
+
+/// Information about a function that's assigned at the top level of a script
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FunctionInfo {
+    /// The number of arguments declared by the function
+    ///
+    /// A variadic function's final `...` argument is included in the count.
+    pub arg_count: usize,
+    /// True if the function's arguments end with a variadic `...` argument
+    pub is_variadic: bool,
+    /// True if the function is a generator
+    pub is_generator: bool,
+}
+
+/// An import expression, see [ScriptInfo]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportInfo {
+    /// The module that the items are imported from, if `from` was used
+    ///
+    /// The module is represented as a path of `.`-separated names, e.g. `from foo.bar import baz`
+    /// produces `["foo", "bar"]`.
+    pub from: Vec<String>,
+    /// The imported items, each represented as a path of `.`-separated names
+    ///
+    /// Expressions in interpolated import strings are represented by `{}`.
+    pub items: Vec<Vec<String>>,
+    /// The span of the import expression
+    pub span: Span,
+}
+
+impl ImportInfo {
+    /// Returns the names of the modules that the import expression depends on
+    ///
+    /// When `from` is used then the `from` module is the only dependency,
+    /// otherwise the first name in each item's path refers to a module.
+    pub fn modules(&self) -> Vec<&str> {
+        match self.from.first() {
+            Some(from) => vec![from.as_str()],
+            None => self
+                .items
+                .iter()
+                .filter_map(|item| item.first().map(|name| name.as_str()))
+                .collect(),
+        }
+    }
+}
+
+impl ScriptInfo {
+    /// Extracts a summary of the script represented by the given AST
+    pub fn from_ast(ast: &Ast) -> Self {
+        let mut result = Self::default();
+
+        if let Some(Node::MainBlock { body, .. }) = ast.entry_point().map(|entry| &entry.node) {
+            for expression in body.iter() {
+                match &ast.node(*expression).node {
+                    Node::Assign { target, expression } => {
+                        let function = match &ast.node(*expression).node {
+                            Node::Function(f) => Some(FunctionInfo {
+                                arg_count: f.args.len(),
+                                is_variadic: f.is_variadic,
+                                is_generator: f.is_generator,
+                            }),
+                            _ => None,
+                        };
+                        result.add_binding(ast, target.target_index, target.scope, function);
+                    }
+                    Node::MultiAssign { targets, .. } => {
+                        for target in targets.iter() {
+                            result.add_binding(ast, target.target_index, target.scope, None);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        for node in ast.nodes() {
+            if let Node::Import { items, from } = &node.node {
+                result.imports.push(ImportInfo {
+                    from: import_path(ast, from),
+                    items: items.iter().map(|item| import_path(ast, item)).collect(),
+                    span: *ast.span(node.span),
+                });
+            }
+        }
+
+        result
+    }
+
+    /// Returns the bindings that are exported by the script
+    pub fn exports(&self) -> impl Iterator<Item = &BindingInfo> {
+        self.bindings.iter().filter(|binding| binding.exported)
+    }
+
+    fn add_binding(
+        &mut self,
+        ast: &Ast,
+        target_index: AstIndex,
+        scope: Scope,
+        function: Option<FunctionInfo>,
+    ) {
+        let target = ast.node(target_index);
+        // Only assignments to identifiers introduce bindings,
+        // e.g. `foo.bar = 42` modifies an existing value.
+        if let Node::Id(constant_index) = &target.node {
+            self.bindings.push(BindingInfo {
+                name: constant_string(ast, *constant_index),
+                span: *ast.span(target.span),
+                exported: scope == Scope::Export,
+                function,
+            });
+        }
+    }
+}
+
+fn import_path(ast: &Ast, path: &[ImportItemNode]) -> Vec<String> {
+    path.iter()
+        .map(|item| match item {
+            ImportItemNode::Id(constant_index) => constant_string(ast, *constant_index),
+            ImportItemNode::Str(s) => import_string(ast, s),
+        })
+        .collect()
+}
+
+fn import_string(ast: &Ast, s: &AstString) -> String {
+    s.nodes
+        .iter()
+        .map(|node| match node {
+            StringNode::Literal(constant_index) => ast.constants().get_str(*constant_index),
+            StringNode::Expr(_) => "{}",
+        })
+        .collect()
+}
+
+fn constant_string(ast: &Ast, constant_index: ConstantIndex) -> String {
+    ast.constants().get_str(constant_index).to_string()
+}
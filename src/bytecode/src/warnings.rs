@@ -0,0 +1,264 @@
+use {
+    koto_parser::{
+        Ast, AstIndex, AstVisitor, ConstantIndex, ImportItemNode, MapKey, Node, Scope, Span,
+    },
+    std::{collections::HashSet, fmt},
+};
+
+/// A non-fatal issue that was found while compiling a script
+///
+/// Warnings are attached to the compiled [Chunk](crate::Chunk), see [CompileWarning::find].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompileWarning {
+    /// The kind of issue that was found
+    pub kind: CompileWarningKind,
+    /// The span of the source that the warning refers to
+    pub span: Span,
+}
+
+/// The different kinds of [CompileWarning]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CompileWarningKind {
+    /// A value is assigned to a local identifier that's never used
+    ///
+    /// Identifiers that start with `_` are ignored.
+    UnusedBinding(String),
+    /// An expression follows a `return`, `break`, `continue`, or `throw` in the same block
+    UnreachableCode,
+    /// An imported item is replaced by a later import or assignment before being used
+    ShadowedImport(String),
+}
+
+impl fmt::Display for CompileWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use CompileWarningKind::*;
+
+        match &self.kind {
+            UnusedBinding(name) => write!(f, "'{name}' is assigned but never used"),
+            UnreachableCode => write!(f, "Unreachable code"),
+            ShadowedImport(name) => {
+                write!(f, "'{name}' is imported but replaced before being used")
+            }
+        }
+    }
+}
+
+impl CompileWarning {
+    /// Finds warnings in the script represented by the given AST
+    ///
+    /// The checks are conservative, an identifier that's used anywhere in the script (e.g. in a
+    /// nested function, or in an interpolated string) prevents warnings for any of its bindings.
+    pub fn find(ast: &Ast) -> Vec<Self> {
+        let mut finder = WarningFinder {
+            used_ids: UsedIds::find(ast),
+            warnings: Vec::new(),
+        };
+        ast.visit(&mut finder);
+
+        let mut result = finder.warnings;
+        result.sort_by_key(|warning| (warning.span.start.line, warning.span.start.column));
+        result
+    }
+}
+
+// Checks each of the AST's blocks for warnings
+struct WarningFinder {
+    used_ids: UsedIds,
+    warnings: Vec<CompileWarning>,
+}
+
+impl AstVisitor for WarningFinder {
+    fn visit(&mut self, ast: &Ast, index: AstIndex) -> bool {
+        if let Node::MainBlock { body, .. } | Node::Block(body) = &ast.node(index).node {
+            check_unreachable_code(ast, body, &mut self.warnings);
+            check_unused_bindings(ast, body, &self.used_ids, &mut self.warnings);
+            check_shadowed_imports(ast, body, &mut self.warnings);
+        }
+        true
+    }
+}
+
+fn check_unreachable_code(ast: &Ast, body: &[AstIndex], result: &mut Vec<CompileWarning>) {
+    for (expression, next) in body.iter().zip(body.iter().skip(1)) {
+        if matches!(
+            ast.node(*expression).node,
+            Node::Return(_) | Node::Break(_) | Node::Continue | Node::Throw(_)
+        ) {
+            result.push(CompileWarning {
+                kind: CompileWarningKind::UnreachableCode,
+                span: *ast.span(ast.node(*next).span),
+            });
+            break;
+        }
+    }
+}
+
+fn check_unused_bindings(
+    ast: &Ast,
+    body: &[AstIndex],
+    used_ids: &UsedIds,
+    result: &mut Vec<CompileWarning>,
+) {
+    // The final expression in a block is the block's result, so its bindings are skipped.
+    let statements = match body.split_last() {
+        Some((_, statements)) => statements,
+        None => return,
+    };
+
+    for statement in statements {
+        let targets = match &ast.node(*statement).node {
+            Node::Assign { target, .. } => vec![*target],
+            Node::MultiAssign { targets, .. } => targets.clone(),
+            _ => continue,
+        };
+
+        for target in targets {
+            if target.scope != Scope::Local {
+                continue;
+            }
+            let target_node = ast.node(target.target_index);
+            if let Node::Id(id) = &target_node.node {
+                if !used_ids.contains(*id) {
+                    result.push(CompileWarning {
+                        kind: CompileWarningKind::UnusedBinding(constant_string(ast, *id)),
+                        span: *ast.span(target_node.span),
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn check_shadowed_imports(ast: &Ast, body: &[AstIndex], result: &mut Vec<CompileWarning>) {
+    // The imported names that haven't been used yet, along with the spans of their imports
+    let mut pending: Vec<(ConstantIndex, Span)> = Vec::new();
+
+    for statement in body {
+        let node = ast.node(*statement);
+        let used_ids = UsedIds::find_in(ast, *statement);
+
+        // Any use of a pending import, including in the right hand side of an assignment,
+        // means that the import isn't shadowed.
+        pending.retain(|(id, _)| !used_ids.contains(*id));
+
+        let bound_ids = match &node.node {
+            Node::Import { items, .. } => items
+                .iter()
+                .filter_map(|item| match item.last() {
+                    Some(ImportItemNode::Id(id)) => Some(*id),
+                    _ => None,
+                })
+                .collect(),
+            Node::Assign { target, .. } => {
+                assigned_id(ast, target.target_index).into_iter().collect()
+            }
+            Node::MultiAssign { targets, .. } => targets
+                .iter()
+                .filter_map(|target| assigned_id(ast, target.target_index))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        for id in bound_ids {
+            if let Some(position) = pending.iter().position(|(pending_id, _)| *pending_id == id) {
+                let (_, span) = pending.remove(position);
+                result.push(CompileWarning {
+                    kind: CompileWarningKind::ShadowedImport(constant_string(ast, id)),
+                    span,
+                });
+            }
+
+            if matches!(node.node, Node::Import { .. }) {
+                pending.push((id, *ast.span(node.span)));
+            }
+        }
+    }
+}
+
+fn assigned_id(ast: &Ast, target_index: AstIndex) -> Option<ConstantIndex> {
+    match &ast.node(target_index).node {
+        Node::Id(id) => Some(*id),
+        _ => None,
+    }
+}
+
+// The identifiers that are used in an AST or in one of its subtrees
+//
+// Assignment targets don't count as uses, but compound assignments (e.g. `x += 1`) do.
+#[derive(Default)]
+struct UsedIds {
+    ids: HashSet<ConstantIndex>,
+    // The assignment targets that have been encountered while walking the AST
+    assign_targets: HashSet<AstIndex>,
+}
+
+impl UsedIds {
+    fn find(ast: &Ast) -> Self {
+        let mut result = Self::default();
+        ast.visit(&mut result);
+        result
+    }
+
+    fn find_in(ast: &Ast, root: AstIndex) -> Self {
+        let mut result = Self::default();
+        ast.visit_from(root, &mut result);
+        result
+    }
+
+    fn contains(&self, id: ConstantIndex) -> bool {
+        self.ids.contains(&id)
+    }
+}
+
+impl AstVisitor for UsedIds {
+    fn visit(&mut self, ast: &Ast, index: AstIndex) -> bool {
+        // Assignments are visited before their targets, so the targets can be skipped
+        if self.assign_targets.contains(&index) {
+            return true;
+        }
+
+        match &ast.node(index).node {
+            Node::Id(id) | Node::NamedCall { id, .. } => {
+                self.ids.insert(*id);
+            }
+            Node::Assign { target, .. } => {
+                self.assign_targets.insert(target.target_index);
+            }
+            Node::MultiAssign { targets, .. } => {
+                self.assign_targets
+                    .extend(targets.iter().map(|target| target.target_index));
+            }
+            Node::Map(entries) => {
+                // Inline map entries without values use the value with the key's name
+                for (key, value) in entries.iter() {
+                    if let (MapKey::Id(id), None) = (key, value) {
+                        self.ids.insert(*id);
+                    }
+                }
+            }
+            Node::Import { items, from } => {
+                // The first item in an import path can refer to an existing value
+                let roots = if from.is_empty() {
+                    items.iter().filter_map(|item| item.first()).collect()
+                } else {
+                    vec![&from[0]]
+                };
+                for root in roots {
+                    if let ImportItemNode::Id(id) = root {
+                        self.ids.insert(*id);
+                    }
+                }
+            }
+            Node::Function(f) => {
+                self.ids.extend(f.accessed_non_locals.iter().copied());
+            }
+            _ => {}
+        }
+
+        true
+    }
+}
+
+fn constant_string(ast: &Ast, constant_index: ConstantIndex) -> String {
+    ast.constants().get_str(constant_index).to_string()
+}
@@ -0,0 +1,108 @@
+use {
+    koto_bytecode::{ChunkCache, Loader},
+    std::{
+        fs,
+        path::{Path, PathBuf},
+        rc::Rc,
+    },
+};
+
+// Creates an empty temporary directory for a test's modules
+fn make_module_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "koto_chunk_cache_{test_name}_{}",
+        std::process::id()
+    ));
+    if dir.exists() {
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_module(dir: &Path, name: &str, contents: &str) -> PathBuf {
+    let path = dir.join(format!("{name}.koto"));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn modules_are_shared_between_loaders() {
+    let dir = make_module_dir("shared");
+    let importer = write_module(&dir, "main", "import foo");
+    write_module(&dir, "foo", "export x = 42");
+
+    let cache = ChunkCache::default();
+
+    let mut loader_a = Loader::with_shared_cache(cache.clone());
+    let first = loader_a
+        .compile_module("foo", Some(importer.clone()))
+        .unwrap();
+    assert!(!first.loaded_from_cache);
+    assert_eq!(cache.len(), 1);
+
+    let mut loader_b = Loader::with_shared_cache(cache.clone());
+    let second = loader_b.compile_module("foo", Some(importer)).unwrap();
+    assert!(second.loaded_from_cache);
+    assert!(Rc::ptr_eq(&first.chunk, &second.chunk));
+    assert_eq!(cache.len(), 1);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn modules_are_recompiled_when_their_contents_change() {
+    let dir = make_module_dir("modified");
+    let importer = write_module(&dir, "main", "import foo");
+    write_module(&dir, "foo", "export x = 1");
+
+    let cache = ChunkCache::default();
+
+    let mut loader_a = Loader::with_shared_cache(cache.clone());
+    let first = loader_a
+        .compile_module("foo", Some(importer.clone()))
+        .unwrap();
+
+    write_module(&dir, "foo", "export x = 2");
+
+    let mut loader_b = Loader::with_shared_cache(cache.clone());
+    let second = loader_b
+        .compile_module("foo", Some(importer.clone()))
+        .unwrap();
+    assert!(!second.loaded_from_cache);
+    assert!(!Rc::ptr_eq(&first.chunk, &second.chunk));
+    // The recompiled chunk replaces the outdated chunk
+    assert_eq!(cache.len(), 1);
+
+    // The first loader sees the recompiled chunk
+    let third = loader_a.compile_module("foo", Some(importer)).unwrap();
+    assert!(third.loaded_from_cache);
+    assert!(Rc::ptr_eq(&second.chunk, &third.chunk));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn loaders_have_separate_caches_by_default() {
+    let dir = make_module_dir("separate");
+    let importer = write_module(&dir, "main", "import foo");
+    write_module(&dir, "foo", "export x = 42");
+
+    let mut loader_a = Loader::default();
+    let mut loader_b = Loader::default();
+
+    let first = loader_a
+        .compile_module("foo", Some(importer.clone()))
+        .unwrap();
+    let second = loader_b.compile_module("foo", Some(importer)).unwrap();
+    assert!(!first.loaded_from_cache);
+    assert!(!second.loaded_from_cache);
+    assert_eq!(loader_a.cache().len(), 1);
+    assert_eq!(loader_b.cache().len(), 1);
+
+    loader_a.clear_cache();
+    assert!(loader_a.cache().is_empty());
+    assert_eq!(loader_b.cache().len(), 1);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
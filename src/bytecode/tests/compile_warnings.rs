@@ -0,0 +1,231 @@
+mod compile_warnings {
+    use koto_bytecode::{CompileWarningKind, Loader};
+
+    // Checks the compiled script's warnings, each represented by its kind and its starting line
+    fn check_warnings(source: &str, expected: &[(CompileWarningKind, u32)]) {
+        let chunk = match Loader::default().compile_script(source, &None) {
+            Ok(chunk) => chunk,
+            Err(error) => panic!("Failure while compiling:\n{source}\n{error}"),
+        };
+
+        let warnings = chunk
+            .warnings
+            .iter()
+            .map(|warning| (warning.kind.clone(), warning.span.start.line))
+            .collect::<Vec<_>>();
+        assert_eq!(warnings, expected, "\n\nSource:\n{source}");
+    }
+
+    fn unused(name: &str) -> CompileWarningKind {
+        CompileWarningKind::UnusedBinding(name.into())
+    }
+
+    fn shadowed(name: &str) -> CompileWarningKind {
+        CompileWarningKind::ShadowedImport(name.into())
+    }
+
+    mod unused_bindings {
+        use super::*;
+
+        #[test]
+        fn unused_assignment() {
+            let source = "
+x = 1
+y = 2
+y
+";
+            check_warnings(source, &[(unused("x"), 2)]);
+        }
+
+        #[test]
+        fn unused_multi_assignment() {
+            let source = "
+a, b = 1, 2
+a
+";
+            check_warnings(source, &[(unused("b"), 2)]);
+        }
+
+        #[test]
+        fn unused_in_function() {
+            let source = "
+f = ||
+  x = 1
+  42
+f()
+";
+            check_warnings(source, &[(unused("x"), 3)]);
+        }
+
+        #[test]
+        fn underscore_prefixed_names_are_ignored() {
+            let source = "
+_x = 1
+_, _y = 2, 3
+null
+";
+            check_warnings(source, &[]);
+        }
+
+        #[test]
+        fn final_expression_is_ignored() {
+            let source = "
+f = ||
+  x = 42
+f()
+";
+            check_warnings(source, &[]);
+        }
+
+        #[test]
+        fn exported_values_are_ignored() {
+            let source = "
+export x = 1
+null
+";
+            check_warnings(source, &[]);
+        }
+
+        #[test]
+        fn captured_value() {
+            let source = "
+x = 1
+f = || x
+f()
+";
+            check_warnings(source, &[]);
+        }
+
+        #[test]
+        fn value_used_in_interpolated_string() {
+            let source = "
+x = 1
+'x: $x'
+";
+            check_warnings(source, &[]);
+        }
+
+        #[test]
+        fn value_used_in_compound_assignment() {
+            let source = "
+count = 0
+for _ in 0..10
+  count += 1
+null
+";
+            check_warnings(source, &[]);
+        }
+
+        #[test]
+        fn value_used_in_inline_map() {
+            let source = "
+x = 1
+{x}
+";
+            check_warnings(source, &[]);
+        }
+
+        #[test]
+        fn value_used_as_named_call() {
+            let source = "
+f = |n| n
+f 42
+";
+            check_warnings(source, &[]);
+        }
+    }
+
+    mod unreachable_code {
+        use super::*;
+
+        #[test]
+        fn after_return() {
+            let source = "
+f = ||
+  return 1
+  2
+f()
+";
+            check_warnings(source, &[(CompileWarningKind::UnreachableCode, 4)]);
+        }
+
+        #[test]
+        fn after_break() {
+            let source = "
+for x in 0..10
+  break
+  print x
+";
+            check_warnings(source, &[(CompileWarningKind::UnreachableCode, 4)]);
+        }
+
+        #[test]
+        fn after_throw() {
+            let source = "
+f = ||
+  throw 'oops'
+  print 'unreachable'
+  42
+f()
+";
+            check_warnings(source, &[(CompileWarningKind::UnreachableCode, 4)]);
+        }
+
+        #[test]
+        fn conditional_return() {
+            let source = "
+f = |x|
+  if x
+    return 1
+  2
+f true
+";
+            check_warnings(source, &[]);
+        }
+    }
+
+    mod shadowed_imports {
+        use super::*;
+
+        #[test]
+        fn shadowed_by_assignment() {
+            let source = "
+from test import assert
+assert = 42
+assert
+";
+            check_warnings(source, &[(shadowed("assert"), 2)]);
+        }
+
+        #[test]
+        fn shadowed_by_import() {
+            let source = "
+from number import abs
+from list import sort, abs
+abs
+";
+            check_warnings(source, &[(shadowed("abs"), 2)]);
+        }
+
+        #[test]
+        fn used_before_being_replaced() {
+            let source = "
+from test import assert
+assert true
+assert = 42
+assert
+";
+            check_warnings(source, &[]);
+        }
+
+        #[test]
+        fn used_in_the_replacing_assignment() {
+            let source = "
+import number.abs
+abs = |x| abs x
+abs
+";
+            check_warnings(source, &[]);
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! Layout calculations for the REPL's input line
+//!
+//! The prompt and input are treated as a single logical line of text which gets wrapped onto
+//! multiple rows when it's wider than the terminal. The functions here calculate where the text
+//! and cursor end up in the terminal, without needing access to an actual terminal.
+
+use unicode_width::UnicodeWidthChar;
+
+/// A position in the terminal, relative to the start of the prompt
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Position {
+    pub row: usize,
+    pub column: usize,
+}
+
+/// The layout of the prompt and input when rendered with a given terminal width
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InputLayout {
+    /// The position of the input's cursor
+    pub cursor: Position,
+    /// The position following the end of the input
+    pub end: Position,
+}
+
+impl InputLayout {
+    /// Calculates the layout of the prompt and input
+    ///
+    /// `cursor` is an index into the input, with `input.len()` placing the cursor after the input.
+    pub fn new(prompt: &str, input: &[char], cursor: usize, width: usize) -> Self {
+        let cursor = cursor.min(input.len());
+        let cursor_position =
+            position_after(prompt.chars().chain(input[..cursor].iter().copied()), width);
+        let end = position_after(prompt.chars().chain(input.iter().copied()), width);
+
+        Self {
+            cursor: cursor_position,
+            end,
+        }
+    }
+
+    /// True if the input ends exactly at the end of a row
+    ///
+    /// Terminals leave the cursor on the last column of the row when a row has been filled,
+    /// so the cursor needs to be explicitly moved to the start of the next row.
+    pub fn ends_at_row_boundary(&self) -> bool {
+        self.end.column == 0 && self.end.row > 0
+    }
+}
+
+/// Returns the position following the provided text when wrapped at the given width
+///
+/// Text that fills a row exactly results in a position at the start of the following row.
+/// Wide characters that don't fit at the end of a row are moved to the following row.
+pub fn position_after(text: impl IntoIterator<Item = char>, width: usize) -> Position {
+    let width = width.max(1);
+    let mut position = Position::default();
+
+    for c in text {
+        let char_width = c.width().unwrap_or(0);
+
+        if position.column + char_width > width {
+            position.row += 1;
+            position.column = 0;
+        }
+
+        position.column += char_width;
+
+        if position.column >= width {
+            position.row += 1;
+            position.column = 0;
+        }
+    }
+
+    position
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    fn pos(row: usize, column: usize) -> Position {
+        Position { row, column }
+    }
+
+    #[test]
+    fn position_after_short_text() {
+        assert_eq!(position_after("".chars(), 10), pos(0, 0));
+        assert_eq!(position_after("abc".chars(), 10), pos(0, 3));
+        assert_eq!(position_after("abcdefghi".chars(), 10), pos(0, 9));
+    }
+
+    #[test]
+    fn position_after_text_that_fills_a_row() {
+        assert_eq!(position_after("abcdefghij".chars(), 10), pos(1, 0));
+        assert_eq!(position_after("abcd".chars(), 2), pos(2, 0));
+    }
+
+    #[test]
+    fn position_after_wrapped_text() {
+        assert_eq!(position_after("abcdefghijk".chars(), 10), pos(1, 1));
+        assert_eq!(position_after("abcdefg".chars(), 3), pos(2, 1));
+        assert_eq!(position_after("a".repeat(95).chars(), 20), pos(4, 15));
+    }
+
+    #[test]
+    fn position_after_wide_chars() {
+        // Each of these characters occupies two columns
+        assert_eq!(position_after("日本".chars(), 10), pos(0, 4));
+        assert_eq!(position_after("日本語".chars(), 6), pos(1, 0));
+        // A wide char that doesn't fit at the end of a row is moved to the next row
+        assert_eq!(position_after("abc日".chars(), 4), pos(1, 2));
+    }
+
+    #[test]
+    fn position_after_with_a_tiny_width() {
+        assert_eq!(position_after("abc".chars(), 1), pos(3, 0));
+        assert_eq!(position_after("abc".chars(), 0), pos(3, 0));
+    }
+
+    #[test]
+    fn layout_with_cursor_at_end() {
+        let input = chars("foo");
+        let layout = InputLayout::new("» ", &input, input.len(), 80);
+        assert_eq!(layout.cursor, pos(0, 5));
+        assert_eq!(layout.end, pos(0, 5));
+        assert!(!layout.ends_at_row_boundary());
+    }
+
+    #[test]
+    fn layout_with_cursor_inside_wrapped_input() {
+        let input = chars("abcdefghijklmnopqrstuvwxyz");
+        // The prompt takes 2 columns, so at a width of 10 the rows are:
+        // "» abcdefgh", "ijklmnopqr", "stuvwxyz"
+        let layout = InputLayout::new("» ", &input, 0, 10);
+        assert_eq!(layout.cursor, pos(0, 2));
+        assert_eq!(layout.end, pos(2, 8));
+
+        let layout = InputLayout::new("» ", &input, 8, 10);
+        assert_eq!(layout.cursor, pos(1, 0));
+
+        let layout = InputLayout::new("» ", &input, 12, 10);
+        assert_eq!(layout.cursor, pos(1, 4));
+
+        let layout = InputLayout::new("» ", &input, input.len(), 10);
+        assert_eq!(layout.cursor, layout.end);
+    }
+
+    #[test]
+    fn layout_with_input_ending_at_row_boundary() {
+        let input = chars("abcdefgh");
+        let layout = InputLayout::new("» ", &input, input.len(), 10);
+        assert_eq!(layout.end, pos(1, 0));
+        assert!(layout.ends_at_row_boundary());
+
+        let layout = InputLayout::new("» ", &input, 3, 10);
+        assert_eq!(layout.cursor, pos(0, 5));
+    }
+
+    #[test]
+    fn layout_after_resizing() {
+        let input = chars("abcdefghijklmnopqrstuvwxyz");
+        let cursor = 20;
+
+        for (width, expected_cursor, expected_end) in [
+            (80, pos(0, 22), pos(0, 28)),
+            (20, pos(1, 2), pos(1, 8)),
+            (12, pos(1, 10), pos(2, 4)),
+            (5, pos(4, 2), pos(5, 3)),
+        ] {
+            let layout = InputLayout::new("» ", &input, cursor, width);
+            assert_eq!(layout.cursor, expected_cursor, "width: {width}");
+            assert_eq!(layout.end, expected_end, "width: {width}");
+        }
+    }
+
+    #[test]
+    fn layout_with_out_of_bounds_cursor() {
+        let input = chars("abc");
+        let layout = InputLayout::new("» ", &input, 99, 80);
+        assert_eq!(layout.cursor, layout.end);
+    }
+}
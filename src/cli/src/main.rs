@@ -1,17 +1,29 @@
 mod help;
+mod input_layout;
 mod repl;
+mod script_info;
 
 use {
     crossterm::tty::IsTty,
-    koto::{bytecode::Chunk, Koto, KotoSettings},
+    koto::{
+        bytecode::{Chunk, ChunkCache},
+        render_compile_error, render_compile_warning, ErrorRenderSettings, Koto, KotoError,
+        KotoSettings,
+    },
     repl::{Repl, ReplSettings},
-    std::{fs, io},
+    std::{env, fs, io},
 };
 
 #[cfg(all(jemalloc, not(target_env = "msvc")))]
 #[global_allocator]
 static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 
+// Scripts are run on a thread with a large stack, so that the CLI can support deeper nesting of
+// runtime calls than the runtime's default, which is sized for a 2MB stack.
+// See `VmSettings::max_nesting_depth` for details of how much stack each level of nesting needs.
+const STACK_SIZE: usize = 64 * 1024 * 1024;
+const MAX_NESTING_DEPTH: usize = 1000;
+
 fn version_string() -> String {
     format!("Koto {}", env!("CARGO_PKG_VERSION"))
 }
@@ -27,8 +39,14 @@ FLAGS:
     -e, --eval               Evaluate the script directly (rather than reading it from disk)
     -i, --show_instructions  Show compiled instructions annotated with source lines
     -b, --show_bytecode      Show the script's compiled bytecode
+    -I, --info               Print the script's top-level bindings and imports as JSON,
+                             without running the script
     -t, --tests              Run the script's tests before running the script
     -T, --import_tests       Run tests when importing modules
+    -q, --quiet              Don't print compilation warnings
+        --deny-warnings      Treat compilation warnings as errors
+        --no-color           Disable colors in error messages
+                             (colors are also disabled when NO_COLOR is set)
     -h, --help               Prints help information
     -v, --version            Prints version information
 
@@ -49,6 +67,10 @@ struct KotoArgs {
     run_import_tests: bool,
     show_bytecode: bool,
     show_instructions: bool,
+    show_info: bool,
+    quiet: bool,
+    deny_warnings: bool,
+    no_color: bool,
     script: Option<String>,
     script_args: Vec<String>,
 }
@@ -59,8 +81,12 @@ fn parse_arguments() -> Result<KotoArgs, String> {
     let eval_script = args.contains(["-e", "--eval"]);
     let show_instructions = args.contains(["-i", "--show_instructions"]);
     let show_bytecode = args.contains(["-b", "--show_bytecode"]);
+    let show_info = args.contains(["-I", "--info"]);
     let run_tests = args.contains(["-t", "--tests"]);
     let run_import_tests = args.contains(["-T", "--import_tests"]);
+    let quiet = args.contains(["-q", "--quiet"]);
+    let deny_warnings = args.contains("--deny-warnings");
+    let no_color = args.contains("--no-color");
     let help = args.contains(["-h", "--help"]);
     let version = args.contains(["-v", "--version"]);
 
@@ -88,15 +114,45 @@ fn parse_arguments() -> Result<KotoArgs, String> {
         run_import_tests,
         show_bytecode,
         show_instructions,
+        show_info,
+        quiet,
+        deny_warnings,
+        no_color,
         script,
         script_args,
     })
 }
 
+// Errors are rendered with colors when stderr is a terminal, unless disabled by the user
+//
+// See https://no-color.org
+fn error_render_settings(args: &KotoArgs) -> ErrorRenderSettings {
+    let no_color_env = matches!(env::var_os("NO_COLOR"), Some(value) if !value.is_empty());
+
+    ErrorRenderSettings {
+        use_color: !args.no_color && !no_color_env && io::stderr().is_tty(),
+    }
+}
+
+fn print_error(error: &KotoError, settings: ErrorRenderSettings) {
+    match error {
+        KotoError::ParseError(error) | KotoError::CompileError(error) => {
+            eprintln!("{}", render_compile_error(error, settings))
+        }
+        other => eprintln!("Error: {other}"),
+    }
+}
+
 fn main() {
-    std::process::exit(match run() {
-        Ok(_) => 0,
-        Err(_) => 1,
+    let result = std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(run)
+        .expect("Failed to spawn the main thread")
+        .join();
+
+    std::process::exit(match result {
+        Ok(Ok(_)) => 0,
+        _ => 1,
     })
 }
 
@@ -119,9 +175,13 @@ fn run() -> Result<(), ()> {
         return Ok(());
     }
 
+    let error_settings = error_render_settings(&args);
+
     let koto_settings = KotoSettings {
         run_tests: args.run_tests,
         run_import_tests: args.run_import_tests,
+        max_nesting_depth: MAX_NESTING_DEPTH,
+        chunk_cache: Some(ChunkCache::shared()),
         ..Default::default()
     };
 
@@ -156,15 +216,45 @@ fn run() -> Result<(), ()> {
             return Err(());
         }
 
+        if args.show_info {
+            return match koto.script_info(&script) {
+                Ok(info) => {
+                    println!("{}", script_info::script_info_to_json(&info));
+                    Ok(())
+                }
+                Err(error) => {
+                    print_error(&error, error_settings);
+                    Err(())
+                }
+            };
+        }
+
         let prelude = koto.prelude();
         prelude.add_map("json", koto_json::make_module());
         prelude.add_map("random", koto_random::make_module());
+        prelude.add_map("regex", koto_regex::make_module());
         prelude.add_map("tempfile", koto_tempfile::make_module());
         prelude.add_map("toml", koto_toml::make_module());
         prelude.add_map("yaml", koto_yaml::make_module());
 
         match koto.compile(&script) {
             Ok(chunk) => {
+                if !args.quiet || args.deny_warnings {
+                    for warning in chunk.warnings.iter() {
+                        eprintln!(
+                            "{}\n",
+                            render_compile_warning(warning, &chunk, error_settings)
+                        );
+                    }
+                }
+                if args.deny_warnings && !chunk.warnings.is_empty() {
+                    eprintln!(
+                        "Error: Compilation failed due to {} warning(s)",
+                        chunk.warnings.len()
+                    );
+                    return Err(());
+                }
+
                 if args.show_bytecode {
                     println!("{}\n", &Chunk::bytes_as_string(chunk.clone()));
                 }
@@ -180,13 +270,13 @@ fn run() -> Result<(), ()> {
                 match koto.run_with_args(&args.script_args) {
                     Ok(_) => {}
                     Err(error) => {
-                        eprintln!("Error: {error}");
+                        print_error(&error, error_settings);
                         return Err(());
                     }
                 }
             }
             Err(error) => {
-                eprintln!("Error: {error}");
+                print_error(&error, error_settings);
                 return Err(());
             }
         }
@@ -1,5 +1,5 @@
 use {
-    crate::help::Help,
+    crate::{help::Help, input_layout::InputLayout},
     crossterm::{
         cursor,
         event::{read, Event, KeyCode, KeyEvent, KeyModifiers},
@@ -14,7 +14,6 @@ use {
         fmt,
         io::{self, Stdout, Write},
     },
-    unicode_width::UnicodeWidthChar,
 };
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -55,6 +54,24 @@ pub struct Repl {
     input_history: Vec<String>,
     // The current index in the history
     history_position: Option<usize>,
+    // The prompt and input as they were last rendered, None when starting a new line
+    rendered_input: Option<RenderedInput>,
+}
+
+// A snapshot of the rendered prompt and input
+//
+// The rendered text is kept rather than the terminal position of the cursor, so that the cursor's
+// position can be recalculated when the terminal has been resized since the input was rendered.
+struct RenderedInput {
+    prompt: &'static str,
+    input: Vec<char>,
+    cursor: usize,
+}
+
+impl RenderedInput {
+    fn layout(&self, width: usize) -> InputLayout {
+        InputLayout::new(self.prompt, &self.input, self.cursor, width)
+    }
 }
 
 impl Repl {
@@ -66,6 +83,7 @@ impl Repl {
         let prelude = koto.prelude();
         prelude.add_map("json", koto_json::make_module());
         prelude.add_map("random", koto_random::make_module());
+        prelude.add_map("regex", koto_regex::make_module());
         prelude.add_map("tempfile", koto_tempfile::make_module());
         prelude.add_map("toml", koto_toml::make_module());
         prelude.add_map("yaml", koto_yaml::make_module());
@@ -88,48 +106,105 @@ impl Repl {
                 terminal::enable_raw_mode()?;
             }
 
-            if let Event::Key(key_event) = read()? {
-                // Handle the keypress
-                let should_exit = self.on_keypress(key_event, &mut stdout)?;
+            match read()? {
+                Event::Key(key_event) => {
+                    // Handle the keypress
+                    let should_exit = self.on_keypress(key_event, &mut stdout)?;
+
+                    if should_exit {
+                        return Ok(());
+                    }
 
-                if should_exit {
-                    return Ok(());
+                    // Show the prompt
+                    if stdout.is_tty() {
+                        self.render_input(&mut stdout)?;
+                    }
                 }
+                // The input is rendered again to match the terminal's new width
+                Event::Resize(_, _) if stdout.is_tty() => self.render_input(&mut stdout)?,
+                _ => {}
+            }
+        }
+    }
 
-                // Show the prompt
-                if stdout.is_tty() {
-                    let (_, cursor_y) = cursor::position()?;
+    fn prompt(&self) -> &'static str {
+        if self.continued_lines.is_empty() {
+            PROMPT
+        } else {
+            CONTINUED_PROMPT
+        }
+    }
 
-                    let prompt = if self.continued_lines.is_empty() {
-                        PROMPT
-                    } else {
-                        CONTINUED_PROMPT
-                    };
+    // Renders the prompt and input, replacing the previously rendered input
+    //
+    // The input is wrapped onto multiple rows if it's wider than the terminal,
+    // with the layout being recalculated from the terminal's current width on each render.
+    fn render_input(&mut self, stdout: &mut Stdout) -> Result<()> {
+        let width = terminal_width();
+
+        // Move back to the first row of the previously rendered input
+        if let Some(rendered) = &self.rendered_input {
+            let previous_layout = rendered.layout(width);
+            queue!(stdout, cursor::MoveUp(previous_layout.cursor.row as u16))?;
+        }
 
-                    queue!(
-                        stdout,
-                        cursor::MoveTo(0, cursor_y),
-                        terminal::Clear(ClearType::CurrentLine),
-                        style::Print(prompt),
-                        style::Print(&self.input.iter().collect::<String>()),
-                    )?;
-
-                    // Is the cursor inside the input?
-                    if self.cursor < self.input.len() {
-                        let cursor_x = prompt.len()
-                            + self.input[..self.cursor]
-                                .iter()
-                                .map(|c| c.width().unwrap_or(0))
-                                .sum::<usize>()
-                            - 1;
-
-                        queue!(stdout, cursor::MoveTo(cursor_x as u16, cursor_y))?;
-                    }
+        let rendered = RenderedInput {
+            prompt: self.prompt(),
+            input: self.input.clone(),
+            cursor: self.cursor,
+        };
+        let layout = rendered.layout(width);
 
-                    stdout.flush().unwrap();
-                }
-            }
+        queue!(
+            stdout,
+            style::Print('\r'),
+            terminal::Clear(ClearType::FromCursorDown),
+            style::Print(rendered.prompt),
+            style::Print(&self.input.iter().collect::<String>()),
+        )?;
+
+        if layout.ends_at_row_boundary() {
+            queue!(stdout, style::Print("\r\n"))?;
         }
+
+        // Move the cursor from the end of the input to the cursor position
+        queue!(
+            stdout,
+            cursor::MoveUp((layout.end.row - layout.cursor.row) as u16),
+            style::Print('\r'),
+            cursor::MoveRight(layout.cursor.column as u16),
+        )?;
+
+        stdout.flush()?;
+        self.rendered_input = Some(rendered);
+
+        Ok(())
+    }
+
+    // Moves the cursor to the last row of the rendered input,
+    // so that any following output doesn't overwrite wrapped input
+    fn move_cursor_to_end_of_input(&mut self, stdout: &mut Stdout) -> Result<()> {
+        if !stdout.is_tty() {
+            return Ok(());
+        }
+
+        if let Some(rendered) = self.rendered_input.take() {
+            let layout = rendered.layout(terminal_width());
+            let last_row = if layout.ends_at_row_boundary() {
+                layout.end.row - 1
+            } else {
+                layout.end.row
+            };
+            queue!(
+                stdout,
+                cursor::MoveDown((last_row.saturating_sub(layout.cursor.row)) as u16),
+                style::Print('\r'),
+                cursor::MoveRight(layout.end.column as u16),
+            )?;
+            stdout.flush()?;
+        }
+
+        Ok(())
     }
 
     // Handles a single input keypress
@@ -201,10 +276,14 @@ impl Repl {
                     }
                 }
             }
-            KeyCode::Enter => self.on_enter(stdout)?,
+            KeyCode::Enter => {
+                self.move_cursor_to_end_of_input(stdout)?;
+                self.on_enter(stdout)?
+            }
             KeyCode::Char(c) if event.modifiers.contains(KeyModifiers::CONTROL) => match c {
                 'c' => {
                     if self.input.is_empty() {
+                        self.move_cursor_to_end_of_input(stdout)?;
                         write!(stdout, "^C\r\n").unwrap();
                         stdout.flush().unwrap();
                         if stdout.is_tty() {
@@ -217,6 +296,7 @@ impl Repl {
                     }
                 }
                 'd' if self.input.is_empty() => {
+                    self.move_cursor_to_end_of_input(stdout)?;
                     write!(stdout, "^D\r\n").unwrap();
                     stdout.flush().unwrap();
                     if stdout.is_tty() {
@@ -271,6 +351,10 @@ impl Repl {
                         )?;
                     }
                     match self.koto.run() {
+                        // Strings are echoed as quoted literals so that they can be pasted back in
+                        Ok(result @ KotoValue::Str(_)) => {
+                            print_result(stdout, &KotoValue::Str(format!("{result:#}").into()))?;
+                        }
                         Ok(result) => match self.koto.value_to_string(result.clone()) {
                             Ok(result_string) => {
                                 print_result(stdout, &result_string)?;
@@ -0,0 +1,50 @@
+use {
+    koto::{
+        bytecode::{BindingInfo, ImportInfo, ScriptInfo},
+        parser::{Position, Span},
+    },
+    serde_json::{json, Value},
+};
+
+/// Converts the [ScriptInfo] produced by `koto --info` into pretty-printed JSON
+pub fn script_info_to_json(info: &ScriptInfo) -> String {
+    let result = json!({
+        "bindings": info.bindings.iter().map(binding_to_json).collect::<Vec<_>>(),
+        "imports": info.imports.iter().map(import_to_json).collect::<Vec<_>>(),
+    });
+
+    serde_json::to_string_pretty(&result).expect("Failed to serialize script info")
+}
+
+fn binding_to_json(binding: &BindingInfo) -> Value {
+    json!({
+        "name": binding.name,
+        "exported": binding.exported,
+        "span": span_to_json(&binding.span),
+        "function": binding.function.map(|f| json!({
+            "arg_count": f.arg_count,
+            "variadic": f.is_variadic,
+            "generator": f.is_generator,
+        })),
+    })
+}
+
+fn import_to_json(import: &ImportInfo) -> Value {
+    json!({
+        "from": (!import.from.is_empty()).then(|| import.from.join(".")),
+        "items": import.items.iter().map(|item| item.join(".")).collect::<Vec<_>>(),
+        "modules": import.modules(),
+        "span": span_to_json(&import.span),
+    })
+}
+
+fn span_to_json(span: &Span) -> Value {
+    json!({
+        "start": position_to_json(&span.start),
+        "end": position_to_json(&span.end),
+    })
+}
+
+fn position_to_json(position: &Position) -> Value {
+    json!({ "line": position.line, "column": position.column })
+}
@@ -59,4 +59,38 @@ xyz
 
         run_koto_eval_test(script, stdin, expected_output);
     }
+
+    #[test]
+    fn stdin_read_line_at_the_end_of_the_input() {
+        let script = "
+stdin = io.stdin()
+print stdin.read_line()
+print stdin.read_line()
+print stdin.read_line()
+";
+
+        run_koto_eval_test(script, "hello\n", "hello\nnull\nnull\n");
+    }
+
+    #[test]
+    fn stdin_lines() {
+        let script = "
+for line in io.stdin().lines()
+  print '<{}>', line
+print 'done'
+";
+
+        run_koto_eval_test(script, "one\ntwo\n", "<one>\n<two>\ndone\n");
+    }
+
+    #[test]
+    fn deep_recursion_through_core_library_callbacks() {
+        // Each call to `depth` re-enters the runtime via the iterator adaptor's callback
+        let script = "
+depth = |n| if n == 1 then 1 else 1 + [n - 1].each(|x| depth x).next()
+print depth 500
+";
+
+        run_koto_eval_test(script, "", "500\n");
+    }
 }
@@ -67,6 +67,27 @@ pub fn koto_benchmark(c: &mut Criterion) {
             runner.run();
         })
     });
+    c.bench_function("each_indexed", |b| {
+        let mut runner = BenchmarkRunner::setup("each_indexed.koto", &["100000".to_string()]);
+        b.iter(|| {
+            runner.run();
+        })
+    });
+    c.bench_function("enumerate_each", |b| {
+        let mut runner = BenchmarkRunner::setup(
+            "each_indexed.koto",
+            &["100000".to_string(), "enumerate".to_string()],
+        );
+        b.iter(|| {
+            runner.run();
+        })
+    });
+    c.bench_function("iterator_pipelines", |b| {
+        let mut runner = BenchmarkRunner::setup("iterator_pipelines.koto", &[]);
+        b.iter(|| {
+            runner.run();
+        })
+    });
     c.bench_function("string_formatting", |b| {
         let mut runner = BenchmarkRunner::setup(
             "string_formatting.koto",
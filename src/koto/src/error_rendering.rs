@@ -0,0 +1,230 @@
+use {
+    koto_bytecode::{Chunk, CompileWarning, LoaderError},
+    koto_parser::Span,
+    std::{fmt::Write, path::Path},
+};
+
+/// Settings that control how errors are rendered, see [render_error]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ErrorRenderSettings {
+    /// Whether or not ANSI color codes should be included in the rendered error
+    pub use_color: bool,
+}
+
+/// Renders an error message along with an annotated excerpt of the source that caused the error
+///
+/// The excerpt shows the source line that contains the error, with carets underlining the span
+/// of the error. Spans that cover multiple lines (e.g. an unterminated list) show the line where
+/// the span begins with a note, followed by the line where the error was encountered.
+pub fn render_error(
+    message: &str,
+    source: &str,
+    source_path: Option<&Path>,
+    span: Span,
+    settings: ErrorRenderSettings,
+) -> String {
+    render_annotated(
+        Severity::Error,
+        message,
+        source,
+        source_path,
+        span,
+        settings,
+    )
+}
+
+/// Renders a warning that was found while compiling a chunk, see [render_error]
+pub fn render_compile_warning(
+    warning: &CompileWarning,
+    chunk: &Chunk,
+    settings: ErrorRenderSettings,
+) -> String {
+    render_annotated(
+        Severity::Warning,
+        &warning.to_string(),
+        &chunk.debug_info.source,
+        chunk.source_path.as_deref(),
+        warning.span,
+        settings,
+    )
+}
+
+fn render_annotated(
+    severity: Severity,
+    message: &str,
+    source: &str,
+    source_path: Option<&Path>,
+    span: Span,
+    settings: ErrorRenderSettings,
+) -> String {
+    let style = Style::new(settings.use_color);
+    let highlight = |text: &str| style.highlight(severity, text);
+    let start = span.start;
+    // Spans that end at the start of a following line (e.g. an unexpected newline) are treated
+    // as single-line spans.
+    let end = if span.end.line > start.line && span.end.column <= 1 {
+        start
+    } else {
+        span.end
+    };
+
+    let number_width = end.line.to_string().len();
+    let padding = " ".repeat(number_width + 2);
+    let source_line = |line: u32| {
+        source
+            .lines()
+            .nth(line.saturating_sub(1) as usize)
+            .unwrap_or("")
+    };
+
+    let mut result = format!(
+        "{label}: {message}\n --- {location}\n{gutter}\n",
+        label = highlight(severity.label()),
+        message = style.bold(message),
+        location = location_string(source_path, span),
+        gutter = style.gutter(&format!("{padding}|")),
+    );
+
+    // Renders a source line, followed by an annotation line
+    let excerpt_line = |line: u32, annotation: String| {
+        format!(
+            "{} {}\n{}{}\n",
+            style.gutter(&format!(" {line:>number_width$} |")),
+            source_line(line),
+            style.gutter(&format!("{padding}|")),
+            annotation
+        )
+    };
+
+    if start.line == end.line {
+        let underline_width = end.column.saturating_sub(start.column).max(1) as usize;
+        result.push_str(&excerpt_line(
+            start.line,
+            format!(
+                "{}{}",
+                " ".repeat(start.column as usize),
+                highlight(&"^".repeat(underline_width))
+            ),
+        ));
+    } else {
+        result.push_str(&excerpt_line(
+            start.line,
+            format!(
+                "{}{} {}",
+                " ".repeat(start.column as usize),
+                highlight("^"),
+                style.note("note: started here")
+            ),
+        ));
+
+        if end.line - start.line > 1 {
+            writeln!(result, "{}", style.gutter(" ...")).ok();
+        }
+
+        let end_column = end.column.saturating_sub(1).max(1) as usize;
+        result.push_str(&excerpt_line(
+            end.line,
+            format!("{}{}", " ".repeat(end_column), highlight("^")),
+        ));
+    }
+
+    result.truncate(result.trim_end().len());
+    result
+}
+
+/// Renders an error produced while loading a script, see [render_error]
+///
+/// Errors that aren't associated with a source span (e.g. IO errors) are rendered without an
+/// excerpt.
+pub fn render_compile_error(error: &LoaderError, settings: ErrorRenderSettings) -> String {
+    let message = format!("{error:#}");
+
+    match error.span() {
+        Some(span) => render_error(
+            &message,
+            error.source_code(),
+            error.source_path(),
+            span,
+            settings,
+        ),
+        None => {
+            let style = Style::new(settings.use_color);
+            format!("{}: {}", style.error("Error"), style.bold(&message))
+        }
+    }
+}
+
+fn location_string(source_path: Option<&Path>, span: Span) -> String {
+    let position = format!("{}:{}", span.start.line, span.start.column);
+
+    match source_path {
+        Some(path) => {
+            let display_path = std::env::current_dir()
+                .ok()
+                .and_then(|current_dir| path.strip_prefix(current_dir).ok())
+                .unwrap_or(path);
+            format!("{} - {position}", display_path.display())
+        }
+        None => position,
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Error => "Error",
+            Self::Warning => "Warning",
+        }
+    }
+}
+
+struct Style {
+    use_color: bool,
+}
+
+impl Style {
+    fn new(use_color: bool) -> Self {
+        Self { use_color }
+    }
+
+    fn paint(&self, text: &str, code: &str) -> String {
+        if self.use_color {
+            format!("\x1b[{code}m{text}\x1b[0m")
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn bold(&self, text: &str) -> String {
+        self.paint(text, "1")
+    }
+
+    fn error(&self, text: &str) -> String {
+        self.paint(text, "1;31")
+    }
+
+    fn warning(&self, text: &str) -> String {
+        self.paint(text, "1;33")
+    }
+
+    fn highlight(&self, severity: Severity, text: &str) -> String {
+        match severity {
+            Severity::Error => self.error(text),
+            Severity::Warning => self.warning(text),
+        }
+    }
+
+    fn gutter(&self, text: &str) -> String {
+        self.paint(text, "1;34")
+    }
+
+    fn note(&self, text: &str) -> String {
+        self.paint(text, "1;36")
+    }
+}
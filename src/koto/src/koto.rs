@@ -1,7 +1,8 @@
 use {
     crate::prelude::*,
     dunce::canonicalize,
-    koto_runtime::ModuleImportedCallback,
+    koto_bytecode::CompilerSettings,
+    koto_runtime::{ErrorHook, ModuleImportedCallback},
     std::{error::Error, fmt, path::PathBuf, rc::Rc},
 };
 
@@ -9,6 +10,7 @@ use {
 #[allow(missing_docs)]
 #[derive(Debug)]
 pub enum KotoError {
+    ParseError(LoaderError),
     CompileError(LoaderError),
     RuntimeError(RuntimeError),
     NothingToRun,
@@ -16,19 +18,32 @@ pub enum KotoError {
     MissingKotoModuleInPrelude,
     InvalidTestsType(String),
     FunctionNotFound(String),
+    Interrupted,
 }
 
 impl KotoError {
-    /// Returns true if the error is a complier 'expected indentation' error
+    /// Returns true if the error is a parser 'expected indentation' error
     ///
     /// This is useful in the REPL, where an indentation error signals that the expression should be
     /// continued on an indented line.
     pub fn is_indentation_error(&self) -> bool {
         match &self {
-            Self::CompileError(e) => e.is_indentation_error(),
+            Self::ParseError(e) => e.is_indentation_error(),
             _ => false,
         }
     }
+
+    /// Returns the stack trace of a runtime error, starting with the frame where it was thrown
+    ///
+    /// An empty trace is returned for errors that weren't thrown at runtime.
+    ///
+    /// See [RuntimeError::stack_trace].
+    pub fn stack_trace(&self) -> Vec<StackFrame> {
+        match &self {
+            Self::RuntimeError(e) => e.stack_trace(),
+            _ => Vec::new(),
+        }
+    }
 }
 
 impl fmt::Display for KotoError {
@@ -36,7 +51,7 @@ impl fmt::Display for KotoError {
         use KotoError::*;
 
         match &self {
-            CompileError(e) => e.fmt(f),
+            ParseError(e) | CompileError(e) => e.fmt(f),
             RuntimeError(e) => e.fmt(f),
             NothingToRun => {
                 f.write_str("Missing compiled chunk, call compile() before calling run()")
@@ -53,6 +68,7 @@ impl fmt::Display for KotoError {
             FunctionNotFound(name) => {
                 write!(f, "Function '{name}' not found")
             }
+            Interrupted => f.write_str("Execution was interrupted"),
         }
     }
 }
@@ -61,7 +77,11 @@ impl Error for KotoError {}
 
 impl From<RuntimeError> for KotoError {
     fn from(error: RuntimeError) -> Self {
-        Self::RuntimeError(error)
+        if error.is_interrupted() {
+            Self::Interrupted
+        } else {
+            Self::RuntimeError(error)
+        }
     }
 }
 
@@ -92,6 +112,28 @@ pub struct KotoSettings {
     /// This allows you to track the runtime's dependencies, which might be useful if you want to
     /// reload the script when one of its dependencies has changed.
     pub module_imported_callback: Option<Box<dyn ModuleImportedCallback>>,
+    /// The maximum number of nested executions that are allowed in the runtime
+    ///
+    /// See [VmSettings::max_nesting_depth].
+    pub max_nesting_depth: usize,
+    /// Additional directories that are searched when importing modules
+    ///
+    /// Library paths are searched in order, after the importing module's directory and the root
+    /// script's directory. See [Loader::compile_module].
+    pub library_paths: Vec<PathBuf>,
+    /// An optional cache of compiled modules that's shared with other Koto instances
+    ///
+    /// If no cache is provided then the instance has its own cache.
+    /// See [Loader::with_shared_cache] and [ChunkCache::shared].
+    pub chunk_cache: Option<ChunkCache>,
+    /// An optional hook that's called whenever an error is raised while executing Koto code
+    ///
+    /// See [VmSettings::error_hook].
+    pub error_hook: Option<Box<dyn ErrorHook>>,
+    /// Whether or not the `os` core library module should be available to scripts
+    ///
+    /// See [VmSettings::enable_os_module].
+    pub enable_os_module: bool,
 }
 
 impl KotoSettings {
@@ -122,6 +164,22 @@ impl KotoSettings {
         }
     }
 
+    /// Helper for adding a library path, see [KotoSettings::library_paths]
+    #[must_use]
+    pub fn with_library_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.library_paths.push(path.into());
+        self
+    }
+
+    /// Helper for sharing a cache of compiled modules, see [KotoSettings::chunk_cache]
+    #[must_use]
+    pub fn with_chunk_cache(self, cache: ChunkCache) -> Self {
+        Self {
+            chunk_cache: Some(cache),
+            ..self
+        }
+    }
+
     /// Convenience function for declaring the 'module imported' callback
     #[must_use]
     pub fn with_module_imported_callback(
@@ -133,6 +191,15 @@ impl KotoSettings {
             ..self
         }
     }
+
+    /// Convenience function for declaring the error hook, see [KotoSettings::error_hook]
+    #[must_use]
+    pub fn with_error_hook(self, hook: impl ErrorHook + 'static) -> Self {
+        Self {
+            error_hook: Some(Box::new(hook)),
+            ..self
+        }
+    }
 }
 
 impl Default for KotoSettings {
@@ -146,6 +213,11 @@ impl Default for KotoSettings {
             stdout: default_vm_settings.stdout,
             stderr: default_vm_settings.stderr,
             module_imported_callback: None,
+            max_nesting_depth: default_vm_settings.max_nesting_depth,
+            library_paths: Vec::new(),
+            chunk_cache: None,
+            error_hook: None,
+            enable_os_module: default_vm_settings.enable_os_module,
         }
     }
 }
@@ -173,14 +245,27 @@ impl Koto {
 
     /// Initializes Koto with the provided settings
     pub fn with_settings(settings: KotoSettings) -> Self {
+        let runtime = Vm::with_settings(VmSettings {
+            stdin: settings.stdin,
+            stdout: settings.stdout,
+            stderr: settings.stderr,
+            run_import_tests: settings.run_import_tests,
+            module_imported_callback: settings.module_imported_callback,
+            max_nesting_depth: settings.max_nesting_depth,
+            error_hook: settings.error_hook,
+            enable_os_module: settings.enable_os_module,
+        });
+
+        if let Some(cache) = settings.chunk_cache {
+            *runtime.loader().borrow_mut() = Loader::with_shared_cache(cache);
+        }
+
+        for path in settings.library_paths {
+            runtime.loader().borrow_mut().add_library_path(path);
+        }
+
         Self {
-            runtime: Vm::with_settings(VmSettings {
-                stdin: settings.stdin,
-                stdout: settings.stdout,
-                stderr: settings.stderr,
-                run_import_tests: settings.run_import_tests,
-                module_imported_callback: settings.module_imported_callback,
-            }),
+            runtime,
             run_tests: settings.run_tests,
             repl_mode: settings.repl_mode,
             chunk: None,
@@ -190,27 +275,66 @@ impl Koto {
 
     /// Compiles a Koto script, returning the complied chunk if successful
     ///
+    /// This is a convenience for calling [Koto::parse] followed by [Koto::compile_ast].
+    ///
     /// On success, the chunk is cached as the current chunk for subsequent calls to [Koto::run].
     pub fn compile(&mut self, script: &str) -> Result<Rc<Chunk>, KotoError> {
-        let compile_result = if self.repl_mode {
-            self.runtime.loader().borrow_mut().compile_repl(script)
+        let parsed = self.parse(script)?;
+        let chunk = self.compile_ast(&parsed)?;
+        self.chunk = Some(chunk.clone());
+        Ok(chunk)
+    }
+
+    /// Parses a Koto script without compiling it
+    ///
+    /// The parsed script can be inspected (e.g. by a linter) and cached by the host,
+    /// and then compiled with [Koto::compile_ast] without the script needing to be parsed again.
+    pub fn parse(&self, script: &str) -> Result<ParsedScript, KotoError> {
+        // Scripts compiled in the REPL don't have a path
+        let script_path = if self.repl_mode {
+            None
         } else {
-            self.runtime
-                .loader()
-                .borrow_mut()
-                .compile_script(script, &self.script_path)
+            self.script_path.clone()
         };
 
-        match compile_result {
-            Ok(chunk) => {
-                self.chunk = Some(chunk.clone());
-                Ok(chunk)
-            }
-            Err(error) => Err(KotoError::CompileError(error)),
-        }
+        self.runtime
+            .loader()
+            .borrow()
+            .parse(script, &script_path)
+            .map_err(KotoError::ParseError)
+    }
+
+    /// Compiles a script that was parsed with [Koto::parse]
+    ///
+    /// Unlike [Koto::compile], the resulting chunk isn't cached for [Koto::run],
+    /// it should be run with [Koto::run_chunk].
+    pub fn compile_ast(&mut self, parsed: &ParsedScript) -> Result<Rc<Chunk>, KotoError> {
+        let settings = CompilerSettings {
+            repl_mode: self.repl_mode,
+        };
+
+        self.runtime
+            .loader()
+            .borrow_mut()
+            .compile_ast(parsed, settings)
+            .map_err(KotoError::CompileError)
+    }
+
+    /// Returns a summary of a script's top-level bindings and imports
+    ///
+    /// The script is parsed without being compiled or executed.
+    pub fn script_info(&self, script: &str) -> Result<ScriptInfo, KotoError> {
+        self.runtime
+            .loader()
+            .borrow()
+            .script_info(script, &self.script_path)
+            .map_err(KotoError::ParseError)
     }
 
     /// Clears the loader's cached modules
+    ///
+    /// If the cache is shared with other Koto instances (see [KotoSettings::chunk_cache]), then
+    /// the cache is also cleared for the other instances.
     pub fn clear_module_cache(&mut self) {
         self.runtime.loader().borrow_mut().clear_cache();
     }
@@ -238,7 +362,10 @@ impl Koto {
         self.run_tests = enabled;
     }
 
-    fn run_chunk(&mut self, chunk: Rc<Chunk>) -> KotoResult {
+    /// Runs a compiled chunk, e.g. one produced by [Koto::compile_ast]
+    ///
+    /// Tests and the `main` function are run as they are in [Koto::run].
+    pub fn run_chunk(&mut self, chunk: Rc<Chunk>) -> KotoResult {
         let result = self.runtime.run(chunk)?;
 
         if self.repl_mode {
@@ -292,6 +419,16 @@ impl Koto {
             .map_err(|e| e.into())
     }
 
+    /// Returns a handle that can be used to stop a running script from another thread
+    ///
+    /// After the handle has been stopped, the running script returns [KotoError::Interrupted].
+    /// The Koto instance can then continue to be used, with its exports left intact.
+    ///
+    /// See [InterruptHandle].
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.runtime.interrupt_handle()
+    }
+
     /// Returns a reference to the runtime's prelude
     pub fn prelude(&self) -> &ValueMap {
         self.runtime.prelude()
@@ -334,21 +471,22 @@ impl Koto {
                 let path = canonicalize(path)
                     .map_err(|_| KotoError::InvalidScriptPath(path.to_owned()))?;
 
-                let script_dir = path
-                    .parent()
-                    .map(|p| {
-                        let s = p.to_string_lossy();
-                        Str(s.into_owned().into())
-                    })
-                    .unwrap_or(Null);
+                let script_dir = path.parent().map(|p| p.to_path_buf());
                 let script_path = Str(path.display().to_string().into());
 
                 (script_dir, script_path)
             }
-            None => (Null, Null),
+            None => (None, Null),
         };
 
         self.script_path = path;
+        self.runtime
+            .loader()
+            .borrow_mut()
+            .set_script_dir(script_dir.clone());
+        let script_dir = script_dir
+            .map(|dir| Str(dir.to_string_lossy().into_owned().into()))
+            .unwrap_or(Null);
 
         match self
             .runtime
@@ -28,10 +28,18 @@
 
 #![warn(missing_docs)]
 
+mod error_rendering;
 mod koto;
 pub mod prelude;
+#[cfg(feature = "tooling")]
+pub mod tooling;
 
 pub use {
-    crate::koto::{Koto, KotoError, KotoSettings},
+    crate::{
+        error_rendering::{
+            render_compile_error, render_compile_warning, render_error, ErrorRenderSettings,
+        },
+        koto::{Koto, KotoError, KotoSettings},
+    },
     koto_bytecode as bytecode, koto_parser as parser, koto_runtime as runtime,
 };
@@ -2,6 +2,6 @@
 
 pub use {
     crate::{Koto, KotoError, KotoSettings},
-    koto_bytecode::{Chunk, Loader, LoaderError},
+    koto_bytecode::{Chunk, ChunkCache, Loader, LoaderError, ParsedScript, ScriptInfo},
     koto_runtime::prelude::*,
 };
@@ -0,0 +1,13 @@
+//! A read-only view of Koto's AST, for tools like linters and formatters
+//!
+//! Scripts are parsed with [Parser::parse] without being compiled, and the resulting [Ast] can
+//! then be walked with an [AstVisitor]. Every node has a corresponding [Span] in the source.
+//!
+//! Comments aren't included in the AST, they're available via [Parser::parse_comments].
+
+pub use koto_parser::{
+    AssignTarget, Ast, AstBinaryOp, AstFor, AstIf, AstIndex, AstNode, AstString, AstTry,
+    AstUnaryOp, AstVisitor, Comment, Constant, ConstantIndex, ConstantPool, Function,
+    ImportItemNode, LookupNode, MapKey, MatchArm, MetaKeyId, Node, Parser, ParserError, Position,
+    QuotationMark, Scope, Span, StringNode, SwitchArm,
+};
@@ -0,0 +1,132 @@
+//! Counts the allocations made while running scripts, to catch regressions in allocation churn
+//!
+//! Run with `--nocapture` to see the allocation counts.
+
+use {
+    koto::Koto,
+    std::{
+        alloc::{GlobalAlloc, Layout, System},
+        cell::Cell,
+    },
+};
+
+struct CountingAllocator;
+
+thread_local! {
+    // Allocations are counted per-thread given that tests run concurrently
+    static ALLOCATION_COUNT: Cell<usize> = Cell::new(0);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATION_COUNT.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATION_COUNT.try_with(|count| count.set(count.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocation_count() -> usize {
+    ALLOCATION_COUNT.with(|count| count.get())
+}
+
+// Compiles and runs the script, returning the number of allocations made while running
+fn count_allocations(script: &str) -> usize {
+    let mut koto = Koto::default();
+    if let Err(error) = koto.compile(script) {
+        panic!("{error}");
+    }
+
+    let before = allocation_count();
+    if let Err(error) = koto.run() {
+        panic!("{error}");
+    }
+    allocation_count() - before
+}
+
+fn check_allocations(name: &str, script: &str, max_allocations: usize) {
+    let allocations = count_allocations(script);
+    println!("{name}: {allocations} allocations");
+    assert!(
+        allocations <= max_allocations,
+        "{name}: expected at most {max_allocations} allocations, found {allocations}"
+    );
+}
+
+#[test]
+fn recursive_fib() {
+    let script = "
+fib = |n|
+  switch
+    n <= 0 then 0
+    n == 1 then 1
+    else (fib n - 1) + (fib n - 2)
+fib 20
+";
+    // The value and call stacks are reused between calls,
+    // so the number of allocations shouldn't depend on the number of calls.
+    check_allocations("recursive_fib", script, 20);
+}
+
+#[test]
+fn iterator_pipeline() {
+    let script = "
+total = 0
+for i in 0..1000
+  total += (i..i + 3)
+    .each |x| x * 2
+    .keep |x| x % 3 != 0
+    .intersperse || 1
+    .each |x| x + 1
+    .fold 0, |a, b| a + b
+total
+";
+    // One allocation per iterator in each pipeline, with the VMs spawned by the adaptors reusing
+    // spare stacks.
+    check_allocations("iterator_pipeline", script, 5 * 1000 + 100);
+}
+
+#[test]
+fn function_calls_with_tuples() {
+    let script = "
+f = |(a, b), c| a + b + c
+x = 0
+for i, n in (0..1000).enumerate()
+  x += f (i, n), i
+x
+";
+    // One allocation per iteration for the tuple's data, with the sequence builder's Vec reused
+    check_allocations("function_calls_with_tuples", script, 1000 + 100);
+}
+
+#[test]
+fn generator_collected_with_size_hint() {
+    let generator = "
+gen = ||
+  for i in 0..100000
+    yield i
+";
+    let without_hint = count_allocations(&format!("{generator}\ngen().to_list()"));
+    let with_hint = count_allocations(&format!(
+        "{generator}\ngen().with_size_hint(100000).to_list()"
+    ));
+    println!("generator without size hint: {without_hint} allocations");
+    println!("generator with size hint: {with_hint} allocations");
+
+    // Without a size hint the list's storage grows repeatedly while collecting the values,
+    // with the hint the storage is allocated up front.
+    assert!(
+        with_hint + 10 < without_hint,
+        "expected fewer allocations with a size hint ({with_hint} vs. {without_hint})"
+    );
+}
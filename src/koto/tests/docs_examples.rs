@@ -25,6 +25,7 @@ impl ExampleTestRunner {
                     output: output.clone(),
                 }),
                 stderr: Rc::new(OutputCapture { output }),
+                chunk_cache: Some(ChunkCache::shared()),
                 ..Default::default()
             }),
         }
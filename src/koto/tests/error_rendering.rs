@@ -0,0 +1,162 @@
+use {
+    koto::{bytecode::Loader, render_compile_error, render_compile_warning, ErrorRenderSettings},
+    std::path::PathBuf,
+};
+
+fn render(script: &str, script_path: Option<&str>, use_color: bool) -> String {
+    let script_path = script_path.map(PathBuf::from);
+    match Loader::default().compile_script(script, &script_path) {
+        Ok(_) => panic!("Expected a compilation error"),
+        Err(error) => render_compile_error(&error, ErrorRenderSettings { use_color }),
+    }
+}
+
+fn check_rendered_error(script: &str, script_path: Option<&str>, expected: &str) {
+    let rendered = render(script, script_path, false);
+    assert_eq!(rendered, expected, "\n\nRendered:\n{rendered}\n");
+}
+
+#[test]
+fn parser_error() {
+    check_rendered_error(
+        "x = (1 +",
+        None,
+        "\
+Error: Expected expression after binary operator
+ --- 1:8
+   |
+ 1 | x = (1 +
+   |        ^",
+    );
+}
+
+#[test]
+fn error_with_wide_span() {
+    check_rendered_error(
+        "\
+x = match 1
+  1 then 2
+    else 3
+",
+        None,
+        "\
+Error: Unexpected token
+ --- 3:5
+   |
+ 3 |     else 3
+   |     ^^^^",
+    );
+}
+
+#[test]
+fn error_with_script_path() {
+    check_rendered_error(
+        "\
+a = 1
+b = a +
+",
+        Some("scripts/foo.koto"),
+        "\
+Error: Expected expression after binary operator
+ --- scripts/foo.koto - 2:8
+   |
+ 2 | b = a +
+   |        ^",
+    );
+}
+
+#[test]
+fn unterminated_list_on_the_following_line() {
+    check_rendered_error(
+        "\
+f = ||
+  x = [1, 2
+y = 1
+",
+        None,
+        "\
+Error: Expected List end ']'
+ --- 2:7
+   |
+ 2 |   x = [1, 2
+   |       ^ note: started here
+ 3 | y = 1
+   | ^",
+    );
+}
+
+#[test]
+fn unterminated_map_over_several_lines() {
+    check_rendered_error(
+        "\
+x = {
+  foo: 1,
+  bar: 2,
+  baz: 3
+y = 1
+",
+        None,
+        "\
+Error: Expected '}' at end of map declaration
+ --- 1:5
+   |
+ 1 | x = {
+   |     ^ note: started here
+ ...
+ 5 | y = 1
+   | ^",
+    );
+}
+
+#[test]
+fn line_numbers_are_aligned() {
+    let script = format!("{}x = [1, 2\n\ny = 3\n", "\n".repeat(8));
+    check_rendered_error(
+        &script,
+        None,
+        "\
+Error: Expected List end ']'
+ --- 9:5
+    |
+  9 | x = [1, 2
+    |     ^ note: started here
+ ...
+ 11 | y = 3
+    | ^",
+    );
+}
+
+#[test]
+fn colored_error() {
+    let rendered = render("x = (1 +", None, true);
+    assert_eq!(
+        rendered,
+        "\
+\x1b[1;31mError\x1b[0m: \x1b[1mExpected expression after binary operator\x1b[0m
+ --- 1:8
+\x1b[1;34m   |\x1b[0m
+\x1b[1;34m 1 |\x1b[0m x = (1 +
+\x1b[1;34m   |\x1b[0m        \x1b[1;31m^\x1b[0m"
+    );
+}
+
+#[test]
+fn compile_warning() {
+    let chunk = Loader::default()
+        .compile_script("x = 1\ny = 2\ny\n", &None)
+        .unwrap();
+    let rendered = chunk
+        .warnings
+        .iter()
+        .map(|warning| render_compile_warning(warning, &chunk, ErrorRenderSettings::default()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rendered,
+        ["\
+Warning: 'x' is assigned but never used
+ --- 1:1
+   |
+ 1 | x = 1
+   | ^"]
+    );
+}
@@ -0,0 +1,108 @@
+use {
+    koto::prelude::*,
+    std::{thread, time::Duration},
+};
+
+// Runs the script, stopping it from another thread after a short delay
+fn run_and_interrupt(koto: &mut Koto, script: &str) -> Result<Value, KotoError> {
+    koto.compile(script).unwrap();
+
+    let handle = koto.interrupt_handle();
+    let interrupter = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        handle.stop();
+    });
+
+    let result = koto.run();
+    interrupter.join().unwrap();
+    result
+}
+
+fn check_script_is_interrupted(script: &str) {
+    let mut koto = Koto::default();
+    match run_and_interrupt(&mut koto, script) {
+        Err(KotoError::Interrupted) => {}
+        other => panic!("Expected an interruption, found: {other:?}"),
+    }
+}
+
+#[test]
+fn infinite_loop() {
+    check_script_is_interrupted(
+        "
+x = 0
+loop
+  x += 1
+",
+    );
+}
+
+#[test]
+fn interruptions_cant_be_caught() {
+    check_script_is_interrupted(
+        "
+loop
+  try
+    loop
+      x = 1
+  catch _
+    x = 2
+",
+    );
+}
+
+#[test]
+fn infinite_loop_in_function_called_by_iterator_adaptor() {
+    check_script_is_interrupted(
+        "
+f = |x|
+  loop
+    x += 1
+(1, 2, 3).each(f).to_list()
+",
+    );
+}
+
+#[test]
+fn consuming_an_infinite_iterator() {
+    check_script_is_interrupted("iterator.repeat(1).consume()");
+}
+
+#[test]
+fn counting_an_infinite_iterator() {
+    check_script_is_interrupted("iterator.repeat(1).count()");
+}
+
+#[test]
+fn runtime_is_reusable_after_interruption() {
+    let mut koto = Koto::default();
+
+    let script = "
+export x = 42
+loop
+  x += 1
+";
+    assert!(matches!(
+        run_and_interrupt(&mut koto, script),
+        Err(KotoError::Interrupted)
+    ));
+    assert!(koto.exports().data().get_with_string("x").is_some());
+
+    koto.compile("export y = x + 1").unwrap();
+    match koto.run() {
+        Ok(Value::Number(n)) => assert!(n > 42),
+        other => panic!("Unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn stopping_before_running_interrupts_the_next_run() {
+    let mut koto = Koto::default();
+    koto.compile("1 + 1").unwrap();
+
+    koto.interrupt_handle().stop();
+    assert!(matches!(koto.run(), Err(KotoError::Interrupted)));
+
+    // The stop request has been handled, so the script can now be run
+    assert!(matches!(koto.run(), Ok(Value::Number(n)) if n == 2));
+}
@@ -16,6 +16,7 @@ fn run_script(script: &str, script_path: Option<PathBuf>, expected_module_paths:
             run_tests: true,
             ..Default::default()
         }
+        .with_chunk_cache(ChunkCache::shared())
         .with_module_imported_callback({
             let loaded_module_paths = loaded_module_paths.clone();
             move |path: &Path| loaded_module_paths.borrow_mut().push(path.to_path_buf())
@@ -111,6 +112,7 @@ mod koto_tests {
     koto_test!(functions_in_lookups);
     koto_test!(io);
     koto_test!(iterators);
+    koto_test!(koto);
     koto_test!(line_breaks);
     koto_test!(list_ops);
     koto_test!(lists);
@@ -0,0 +1,223 @@
+use {
+    koto::prelude::*,
+    std::{
+        cell::RefCell,
+        fs::read_to_string,
+        path::{Path, PathBuf},
+        rc::Rc,
+    },
+};
+
+fn fixtures_dir() -> PathBuf {
+    let mut result = PathBuf::new();
+    result.push(env!("CARGO_MANIFEST_DIR"));
+    result.push("..");
+    result.push("..");
+    result.push("koto");
+    result.push("tests");
+    result.push("module_resolution");
+    result.canonicalize().unwrap()
+}
+
+// Makes a Koto instance with the fixture library path,
+// along with a list that records the paths of imported modules
+fn make_koto(
+    script_path: &Path,
+    chunk_cache: Option<ChunkCache>,
+) -> (Koto, Rc<RefCell<Vec<PathBuf>>>) {
+    let imported_paths = Rc::new(RefCell::new(vec![]));
+
+    let mut koto = Koto::with_settings(
+        KotoSettings {
+            chunk_cache,
+            ..Default::default()
+        }
+        .with_library_path(fixtures_dir().join("lib"))
+        .with_module_imported_callback({
+            let imported_paths = imported_paths.clone();
+            move |path: &Path| imported_paths.borrow_mut().push(path.to_path_buf())
+        }),
+    );
+    koto.set_script_path(Some(script_path.to_path_buf()))
+        .unwrap();
+
+    (koto, imported_paths)
+}
+
+fn run_entry_script(
+    entry_script: &str,
+    expected_imports: &[&str],
+    chunk_cache: Option<ChunkCache>,
+) {
+    let fixtures = fixtures_dir();
+    let script_path = fixtures.join(entry_script);
+    let script = read_to_string(&script_path).unwrap();
+
+    let (mut koto, imported_paths) = make_koto(&script_path, chunk_cache);
+    if let Err(error) = koto.compile(&script).and_then(|_| koto.run()) {
+        panic!("{error}");
+    }
+
+    let expected_imports = expected_imports
+        .iter()
+        .map(|path| fixtures.join(path))
+        .collect::<Vec<_>>();
+    assert_eq!(*imported_paths.borrow(), expected_imports);
+}
+
+#[test]
+fn entry_script_with_neighbouring_modules() {
+    run_entry_script(
+        "app_one/entry.koto",
+        &[
+            "lib/geometry/helpers.koto",
+            "lib/geometry/shapes.koto",
+            "app_one/units.koto",
+            "lib/geometry/main.koto",
+            "app_one/helpers.koto",
+        ],
+        None,
+    );
+}
+
+#[test]
+fn entry_script_in_a_nested_directory() {
+    run_entry_script(
+        "app_two/scripts/entry.koto",
+        &[
+            "lib/geometry/helpers.koto",
+            "lib/geometry/shapes.koto",
+            "app_two/scripts/units.koto",
+            "lib/geometry/main.koto",
+        ],
+        None,
+    );
+}
+
+#[test]
+fn compiled_modules_shared_between_instances() {
+    let cache = ChunkCache::default();
+
+    run_entry_script(
+        "app_one/entry.koto",
+        &[
+            "lib/geometry/helpers.koto",
+            "lib/geometry/shapes.koto",
+            "app_one/units.koto",
+            "lib/geometry/main.koto",
+            "app_one/helpers.koto",
+        ],
+        Some(cache.clone()),
+    );
+    assert_eq!(cache.len(), 5);
+
+    // The geometry modules are reused from the cache, but still get imported by the new instance
+    run_entry_script(
+        "app_two/scripts/entry.koto",
+        &[
+            "lib/geometry/helpers.koto",
+            "lib/geometry/shapes.koto",
+            "app_two/scripts/units.koto",
+            "lib/geometry/main.koto",
+        ],
+        Some(cache.clone()),
+    );
+    assert_eq!(cache.len(), 6);
+}
+
+#[test]
+fn compiled_modules_shared_between_instances_via_the_thread_cache() {
+    // The test is run on a new thread so that it starts with an empty shared cache
+    std::thread::spawn(|| {
+        // Instances only use the thread's cache when it's provided in the settings
+        run_entry_script(
+            "app_one/entry.koto",
+            &[
+                "lib/geometry/helpers.koto",
+                "lib/geometry/shapes.koto",
+                "app_one/units.koto",
+                "lib/geometry/main.koto",
+                "app_one/helpers.koto",
+            ],
+            KotoSettings::default().chunk_cache,
+        );
+        assert!(ChunkCache::shared().is_empty());
+
+        run_entry_script(
+            "app_one/entry.koto",
+            &[
+                "lib/geometry/helpers.koto",
+                "lib/geometry/shapes.koto",
+                "app_one/units.koto",
+                "lib/geometry/main.koto",
+                "app_one/helpers.koto",
+            ],
+            Some(ChunkCache::shared()),
+        );
+        assert_eq!(ChunkCache::shared().len(), 5);
+
+        run_entry_script(
+            "app_two/scripts/entry.koto",
+            &[
+                "lib/geometry/helpers.koto",
+                "lib/geometry/shapes.koto",
+                "app_two/scripts/units.koto",
+                "lib/geometry/main.koto",
+            ],
+            Some(ChunkCache::shared()),
+        );
+        assert_eq!(ChunkCache::shared().len(), 6);
+    })
+    .join()
+    .unwrap();
+}
+
+fn import_error(script_path: &str, script: &str) -> String {
+    let (mut koto, _) = make_koto(&fixtures_dir().join(script_path), None);
+
+    match koto.compile(script).and_then(|_| koto.run()) {
+        Ok(_) => panic!("Expected an import error"),
+        Err(error) => format!("{error:#}"),
+    }
+}
+
+#[test]
+fn module_not_found_error_lists_the_tried_paths() {
+    let fixtures = fixtures_dir();
+
+    assert_eq!(
+        import_error("app_two/scripts/entry.koto", "import missing"),
+        format!(
+            "\
+Failed to import 'missing': Unable to find module 'missing', the following paths were tried:
+  {scripts}/missing.koto
+  {scripts}/missing/main.koto
+  {lib}/missing.koto
+  {lib}/missing/main.koto",
+            scripts = fixtures.join("app_two/scripts").display(),
+            lib = fixtures.join("lib").display(),
+        )
+    );
+}
+
+#[test]
+fn module_not_found_in_a_nested_module() {
+    let fixtures = fixtures_dir();
+
+    assert_eq!(
+        import_error("app_two/scripts/entry.koto", "import 'geometry/broken'"),
+        format!(
+            "\
+Failed to import 'missing': Unable to find module 'missing', the following paths were tried:
+  {geometry}/missing.koto
+  {geometry}/missing/main.koto
+  {scripts}/missing.koto
+  {scripts}/missing/main.koto
+  {lib}/missing.koto
+  {lib}/missing/main.koto",
+            geometry = fixtures.join("lib/geometry").display(),
+            scripts = fixtures.join("app_two/scripts").display(),
+            lib = fixtures.join("lib").display(),
+        )
+    );
+}
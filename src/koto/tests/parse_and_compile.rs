@@ -0,0 +1,105 @@
+use koto::{parser::Span, prelude::*};
+
+// Runs the script via Koto::compile and Koto::run
+fn run_combined(script: &str) -> Result<Value, KotoError> {
+    let mut koto = Koto::default();
+    koto.compile(script)?;
+    koto.run()
+}
+
+// Runs the script with separate parsing, compilation, and running steps
+fn run_split(script: &str) -> Result<Value, KotoError> {
+    let mut koto = Koto::default();
+    let parsed = koto.parse(script)?;
+    let chunk = koto.compile_ast(&parsed)?;
+    koto.run_chunk(chunk)
+}
+
+fn check_identical_results(script: &str) {
+    match (run_combined(script), run_split(script)) {
+        (Ok(combined), Ok(split)) => assert_eq!(format!("{combined:#}"), format!("{split:#}")),
+        (combined, split) => {
+            panic!("Unexpected results - combined: {combined:?}, split: {split:?}")
+        }
+    }
+}
+
+fn error_span(error: &LoaderError) -> Span {
+    error.span().expect("Missing span in error")
+}
+
+#[test]
+fn scripts_produce_identical_results() {
+    check_identical_results("1 + 2");
+    check_identical_results(
+        "
+f = |x| x * 2
+(1..=5).each(f).to_tuple()
+",
+    );
+    check_identical_results(
+        "
+x = {foo: 'hello', bar: [1, 2.5, null]}
+x.baz = (true,)
+x
+",
+    );
+}
+
+#[test]
+fn parse_errors_are_reported_from_the_parse_phase() {
+    let script = "
+x = 1
+y = (x +
+";
+
+    let combined = run_combined(script).unwrap_err();
+    let split = Koto::default().parse(script).unwrap_err();
+
+    match (&combined, &split) {
+        (KotoError::ParseError(combined), KotoError::ParseError(split)) => {
+            assert_eq!(error_span(combined), error_span(split));
+        }
+        _ => panic!("Expected parse errors, found '{combined:?}' and '{split:?}'"),
+    }
+}
+
+#[test]
+fn compile_errors_are_reported_from_the_compile_phase() {
+    let script = "
+x = 1
+x = 1 + _
+";
+
+    let combined = run_combined(script).unwrap_err();
+
+    let mut koto = Koto::default();
+    let parsed = koto.parse(script).expect("Parsing should succeed");
+    let split = koto.compile_ast(&parsed).unwrap_err();
+
+    match (&combined, &split) {
+        (KotoError::CompileError(combined), KotoError::CompileError(split)) => {
+            let span = error_span(combined);
+            assert_eq!(span, error_span(split));
+            assert_eq!(span.start.line, 3);
+        }
+        _ => panic!("Expected compile errors, found '{combined:?}' and '{split:?}'"),
+    }
+}
+
+#[test]
+fn parsed_scripts_can_be_compiled_more_than_once() {
+    let mut koto = Koto::default();
+    let parsed = koto.parse("x = 40\nx + 2").unwrap();
+
+    // The AST can be inspected before compilation
+    assert!(parsed.ast().entry_point().is_some());
+
+    for _ in 0..2 {
+        let chunk = koto.compile_ast(&parsed).unwrap();
+        match koto.run_chunk(chunk).unwrap() {
+            Value::Number(n) => assert_eq!(n, 42),
+            unexpected => panic!("Expected a Number, found '{unexpected}'"),
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use koto::{
+    bytecode::{FunctionInfo, ScriptInfo},
+    parser::Position,
+    Koto,
+};
+
+const SCRIPT: &str = "
+from number import pi
+import io, string.to_lowercase
+
+x = 42
+export square = |n| n * n
+sum_all = |first, rest...| first
+gen = ||
+  yield 1
+a, b = 1, 2
+x.foo = 99
+
+@main = ||
+  import os
+  print square x
+";
+
+fn script_info(script: &str) -> ScriptInfo {
+    match Koto::default().script_info(script) {
+        Ok(info) => info,
+        Err(error) => panic!("{error}"),
+    }
+}
+
+fn function(arg_count: usize, is_variadic: bool, is_generator: bool) -> Option<FunctionInfo> {
+    Some(FunctionInfo {
+        arg_count,
+        is_variadic,
+        is_generator,
+    })
+}
+
+#[test]
+fn bindings() {
+    let info = script_info(SCRIPT);
+
+    let bindings = info
+        .bindings
+        .iter()
+        .map(|binding| (binding.name.as_str(), binding.function))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        bindings,
+        [
+            ("x", None),
+            ("square", function(1, false, false)),
+            ("sum_all", function(2, true, false)),
+            ("gen", function(0, false, true)),
+            ("a", None),
+            ("b", None),
+        ]
+    );
+
+    let square = &info.bindings[1];
+    assert_eq!(square.span.start, Position { line: 6, column: 8 });
+    assert_eq!(
+        square.span.end,
+        Position {
+            line: 6,
+            column: 14
+        }
+    );
+
+    let exports = info
+        .exports()
+        .map(|binding| binding.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(exports, ["square"]);
+}
+
+#[test]
+fn imports() {
+    let info = script_info(SCRIPT);
+
+    let imports = info
+        .imports
+        .iter()
+        .map(|import| (import.from.clone(), import.items.clone(), import.span.start))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        imports,
+        [
+            (
+                vec!["number".to_string()],
+                vec![vec!["pi".to_string()]],
+                Position { line: 2, column: 1 }
+            ),
+            (
+                vec![],
+                vec![
+                    vec!["io".to_string()],
+                    vec!["string".to_string(), "to_lowercase".to_string()]
+                ],
+                Position { line: 3, column: 1 }
+            ),
+            (
+                vec![],
+                vec![vec!["os".to_string()]],
+                Position {
+                    line: 14,
+                    column: 3
+                }
+            ),
+        ]
+    );
+
+    let modules = info
+        .imports
+        .iter()
+        .flat_map(|import| import.modules())
+        .collect::<Vec<_>>();
+    assert_eq!(modules, ["number", "io", "string", "os"]);
+}
+
+#[test]
+fn script_isnt_executed() {
+    // The script would throw an error if it was run
+    let info = script_info(
+        "
+export f = |x| x
+throw 'error'
+",
+    );
+    assert_eq!(info.bindings.len(), 1);
+}
+
+#[test]
+fn parser_error() {
+    assert!(Koto::default().script_info("x = = 1").is_err());
+}
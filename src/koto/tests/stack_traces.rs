@@ -0,0 +1,35 @@
+use koto::prelude::*;
+
+#[test]
+fn runtime_errors_provide_a_stack_trace() {
+    let script = "
+g = |x| throw 'error: {}'.format x
+f = |x| g x
+f 42
+";
+    let mut koto = Koto::default();
+    koto.compile(script).unwrap();
+
+    let error = koto.run().unwrap_err();
+    let frames = error
+        .stack_trace()
+        .into_iter()
+        .map(|frame| (frame.function.to_string(), frame.span.unwrap().start.line))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        frames,
+        [
+            ("g".to_string(), 2),
+            ("f".to_string(), 3),
+            ("<module>".to_string(), 4)
+        ]
+    );
+}
+
+#[test]
+fn compilation_errors_have_an_empty_stack_trace() {
+    let mut koto = Koto::default();
+    let error = koto.compile("x = [").unwrap_err();
+    assert!(error.stack_trace().is_empty());
+}
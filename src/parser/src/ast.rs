@@ -99,4 +99,34 @@ impl Ast {
     pub fn nodes(&self) -> &[AstNode] {
         &self.nodes
     }
+
+    /// Walks through the tree with the given visitor, starting at the entry point
+    ///
+    /// Nodes are visited depth-first, in the order that they appear in the source.
+    pub fn visit(&self, visitor: &mut impl AstVisitor) {
+        if self.entry_point().is_some() {
+            self.visit_from(self.entry_point, visitor);
+        }
+    }
+
+    /// Walks through the tree with the given visitor, starting at the given node
+    pub fn visit_from(&self, index: AstIndex, visitor: &mut impl AstVisitor) {
+        if visitor.visit(self, index) {
+            for child in self.node(index).node.children() {
+                self.visit_from(child, visitor);
+            }
+        }
+        visitor.leave(self, index);
+    }
+}
+
+/// A visitor that's used to walk through the nodes of an [Ast], see [Ast::visit]
+pub trait AstVisitor {
+    /// Called when a node is visited
+    ///
+    /// The node's children will be visited if `true` is returned.
+    fn visit(&mut self, ast: &Ast, index: AstIndex) -> bool;
+
+    /// Called after the node and any of its children have been visited
+    fn leave(&mut self, _ast: &Ast, _index: AstIndex) {}
 }
@@ -0,0 +1,12 @@
+use koto_lexer::Span;
+
+/// A comment found in a script's source, see [Parser::parse_comments](crate::Parser::parse_comments)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Comment<'source> {
+    /// The comment's text, including the `#` or `#-`/`-#` delimiters
+    pub text: &'source str,
+    /// The comment's span in the source
+    pub span: Span,
+    /// True if the comment is a multi-line comment, e.g. `#- ... -#`
+    pub is_multiline: bool,
+}
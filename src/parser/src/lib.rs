@@ -3,6 +3,7 @@
 #![warn(missing_docs)]
 
 mod ast;
+mod comment;
 mod constant_index;
 mod constant_pool;
 mod error;
@@ -11,6 +12,7 @@ mod parser;
 
 pub use {
     ast::*,
+    comment::Comment,
     constant_index::{ConstantIndex, ConstantIndexTryFromOutOfRange},
     constant_pool::{Constant, ConstantPool},
     error::{format_error_with_excerpt, ParserError},
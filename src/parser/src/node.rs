@@ -265,6 +265,129 @@ impl Default for Node {
     }
 }
 
+impl Node {
+    /// Returns the indices of the node's child nodes, in the order they appear in the source
+    pub fn children(&self) -> Vec<AstIndex> {
+        use Node::*;
+
+        let mut result = Vec::new();
+
+        match self {
+            Null
+            | Id(_)
+            | Meta(_, _)
+            | BoolTrue
+            | BoolFalse
+            | SmallInt(_)
+            | Int(_)
+            | Float(_)
+            | RangeFull
+            | Wildcard(_)
+            | Ellipsis(_)
+            | Continue => {}
+            Nested(node) | Throw(node) | Yield(node) => result.push(*node),
+            Lookup((lookup_node, next)) => {
+                match lookup_node {
+                    LookupNode::Root(node) | LookupNode::Index(node) => result.push(*node),
+                    LookupNode::Id(_) => {}
+                    LookupNode::Str(s) => s.push_children(&mut result),
+                    LookupNode::Call { args, .. } => result.extend_from_slice(args),
+                }
+                result.extend(next);
+            }
+            NamedCall { args, .. } => result.extend_from_slice(args),
+            Str(s) => s.push_children(&mut result),
+            List(elements) | Tuple(elements) | TempTuple(elements) | Block(elements) => {
+                result.extend_from_slice(elements)
+            }
+            Range { start, end, .. } => result.extend([*start, *end]),
+            RangeFrom { start } => result.push(*start),
+            RangeTo { end, .. } => result.push(*end),
+            Map(entries) => {
+                for (key, value) in entries.iter() {
+                    if let MapKey::Str(s) = key {
+                        s.push_children(&mut result);
+                    }
+                    result.extend(value);
+                }
+            }
+            MainBlock { body, .. } => result.extend_from_slice(body),
+            Function(f) => {
+                result.extend_from_slice(&f.args);
+                result.push(f.body);
+            }
+            Import { items, from } => {
+                for item in from.iter().chain(items.iter().flatten()) {
+                    if let ImportItemNode::Str(s) = item {
+                        s.push_children(&mut result);
+                    }
+                }
+            }
+            Assign { target, expression } => result.extend([target.target_index, *expression]),
+            MultiAssign {
+                targets,
+                expression,
+            } => {
+                result.extend(targets.iter().map(|target| target.target_index));
+                result.push(*expression);
+            }
+            UnaryOp { value, .. } => result.push(*value),
+            BinaryOp { lhs, rhs, .. } => result.extend([*lhs, *rhs]),
+            If(AstIf {
+                condition,
+                then_node,
+                else_if_blocks,
+                else_node,
+            }) => {
+                result.extend([*condition, *then_node]);
+                for (else_if_condition, else_if_node) in else_if_blocks.iter() {
+                    result.extend([*else_if_condition, *else_if_node]);
+                }
+                result.extend(else_node);
+            }
+            Match { expression, arms } => {
+                result.push(*expression);
+                for arm in arms.iter() {
+                    result.extend_from_slice(&arm.patterns);
+                    result.extend(arm.condition);
+                    result.push(arm.expression);
+                }
+            }
+            Switch(arms) => {
+                for arm in arms.iter() {
+                    result.extend(arm.condition);
+                    result.push(arm.expression);
+                }
+            }
+            For(AstFor {
+                args,
+                iterable,
+                body,
+            }) => {
+                result.extend_from_slice(args);
+                result.extend([*iterable, *body]);
+            }
+            Loop { body } => result.push(*body),
+            While { condition, body } | Until { condition, body } => {
+                result.extend([*condition, *body])
+            }
+            Break(value) | Return(value) => result.extend(value),
+            Try(AstTry {
+                try_block,
+                catch_arg,
+                catch_block,
+                finally_block,
+            }) => {
+                result.extend([*try_block, *catch_arg, *catch_block]);
+                result.extend(finally_block);
+            }
+            Debug { expression, .. } => result.push(*expression),
+        }
+
+        result
+    }
+}
+
 impl fmt::Display for Node {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use Node::*;
@@ -356,6 +479,15 @@ pub struct AstString {
     pub nodes: Vec<StringNode>,
 }
 
+impl AstString {
+    fn push_children(&self, children: &mut Vec<AstIndex>) {
+        children.extend(self.nodes.iter().filter_map(|node| match node {
+            StringNode::Literal(_) => None,
+            StringNode::Expr(expression) => Some(*expression),
+        }));
+    }
+}
+
 /// A node in a string definition
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum StringNode {
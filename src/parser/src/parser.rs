@@ -255,6 +255,27 @@ impl<'source> Parser<'source> {
         Ok(parser.ast)
     }
 
+    /// Takes in a source script, and produces the list of comments that it contains
+    ///
+    /// Comments aren't included in the [Ast], so this can be used alongside [Parser::parse] when
+    /// the comments are needed, e.g. by tooling.
+    pub fn parse_comments(source: &'source str) -> Vec<Comment<'source>> {
+        let mut lexer = Lexer::new(source);
+        let mut result = Vec::new();
+
+        while let Some(token) = lexer.next() {
+            if matches!(token, Token::CommentSingle | Token::CommentMulti) {
+                result.push(Comment {
+                    text: lexer.slice(),
+                    span: lexer.span(),
+                    is_multiline: token == Token::CommentMulti,
+                });
+            }
+        }
+
+        result
+    }
+
     // Parses the main 'top-level' block
     fn parse_main_block(&mut self) -> Result<AstIndex, ParserError> {
         self.frame_stack.push(Frame::default());
@@ -1738,7 +1759,7 @@ impl<'source> Parser<'source> {
                 &context.with_expected_indentation(Indentation::GreaterThan(start_indent)),
             )
         } else {
-            self.error(SyntaxError::ExpectedCloseParen)
+            self.error_with_start_span(SyntaxError::ExpectedCloseParen, start_span)
         }
     }
 
@@ -1759,7 +1780,7 @@ impl<'source> Parser<'source> {
                 &context.with_expected_indentation(Indentation::GreaterThan(start_indent)),
             )
         } else {
-            self.error(SyntaxError::ExpectedListEnd)
+            self.error_with_start_span(SyntaxError::ExpectedListEnd, start_span)
         }
     }
 
@@ -1896,7 +1917,7 @@ impl<'source> Parser<'source> {
             self.consume_token_with_context(&map_end_context),
             Some((Token::CurlyClose, _))
         ) {
-            return self.error(SyntaxError::ExpectedMapEnd);
+            return self.error_with_start_span(SyntaxError::ExpectedMapEnd, start_span);
         }
 
         let map_node = self.push_node_with_start_span(Node::Map(entries), start_span)?;
@@ -2929,6 +2950,23 @@ impl<'source> Parser<'source> {
         error
     }
 
+    // Produces an error with a span that begins at the provided start span
+    //
+    // This is used for unterminated containers, so that the error refers back to where the
+    // container began.
+    fn error_with_start_span<E, T>(
+        &mut self,
+        error_type: E,
+        start_span: Span,
+    ) -> Result<T, ParserError>
+    where
+        E: Into<ParserErrorType>,
+    {
+        let mut error = self.make_error(error_type);
+        error.span.start = start_span.start;
+        Err(error)
+    }
+
     fn consume_token_on_same_line_and_error<E, T>(
         &mut self,
         error_type: E,
@@ -0,0 +1,138 @@
+use koto_parser::{Ast, AstIndex, AstVisitor, Node, Parser, Position, Span};
+
+const SCRIPT: &str = "\
+# Returns a greeting
+greet = |name| 'Hello, ${name}!'
+
+#- Functions can be nested,
+   and can appear in interpolated strings -#
+make_counter = ||
+  count = {n: 0}
+  increment = ||
+    count.n += 1
+  '${(|| increment())()}'
+
+export main = ||
+  for x in [1, 2, 3]
+    print (greet x) # Call the greet function
+";
+
+fn span(start: (u32, u32), end: (u32, u32)) -> Span {
+    Span {
+        start: Position {
+            line: start.0,
+            column: start.1,
+        },
+        end: Position {
+            line: end.0,
+            column: end.1,
+        },
+    }
+}
+
+#[derive(Default)]
+struct FunctionCollector {
+    // The (arg count, depth, span) of each function definition
+    functions: Vec<(usize, usize, Span)>,
+    depth: usize,
+}
+
+impl AstVisitor for FunctionCollector {
+    fn visit(&mut self, ast: &Ast, index: AstIndex) -> bool {
+        let node = ast.node(index);
+        if let Node::Function(f) = &node.node {
+            self.functions
+                .push((f.args.len(), self.depth, *ast.span(node.span)));
+            self.depth += 1;
+        }
+        true
+    }
+
+    fn leave(&mut self, ast: &Ast, index: AstIndex) {
+        if matches!(ast.node(index).node, Node::Function(_)) {
+            self.depth -= 1;
+        }
+    }
+}
+
+#[test]
+fn collect_function_definitions() {
+    let ast = Parser::parse(SCRIPT).unwrap();
+
+    let mut collector = FunctionCollector::default();
+    ast.visit(&mut collector);
+
+    assert_eq!(
+        collector.functions,
+        vec![
+            (1, 0, span((2, 9), (2, 33))),
+            (0, 0, span((6, 16), (10, 26))),
+            (0, 1, span((8, 15), (9, 17))),
+            (0, 1, span((10, 7), (10, 21))),
+            (0, 0, span((12, 15), (14, 20))),
+        ]
+    );
+}
+
+#[test]
+fn every_node_is_visited_once() {
+    struct Counter(Vec<usize>);
+
+    impl AstVisitor for Counter {
+        fn visit(&mut self, _ast: &Ast, index: AstIndex) -> bool {
+            self.0[index as usize] += 1;
+            true
+        }
+    }
+
+    let ast = Parser::parse(SCRIPT).unwrap();
+    let mut counter = Counter(vec![0; ast.nodes().len()]);
+    ast.visit(&mut counter);
+
+    assert!(counter.0.iter().all(|count| *count == 1));
+}
+
+#[test]
+fn skipping_children() {
+    struct TopLevelFunctions(usize);
+
+    impl AstVisitor for TopLevelFunctions {
+        fn visit(&mut self, ast: &Ast, index: AstIndex) -> bool {
+            if matches!(ast.node(index).node, Node::Function(_)) {
+                self.0 += 1;
+                false
+            } else {
+                true
+            }
+        }
+    }
+
+    let ast = Parser::parse(SCRIPT).unwrap();
+    let mut visitor = TopLevelFunctions(0);
+    ast.visit(&mut visitor);
+
+    assert_eq!(visitor.0, 3);
+}
+
+#[test]
+fn comments() {
+    let comments = Parser::parse_comments(SCRIPT);
+
+    let comments: Vec<_> = comments
+        .iter()
+        .map(|comment| (comment.text, comment.is_multiline, comment.span))
+        .collect();
+
+    assert_eq!(
+        comments,
+        vec![
+            ("# Returns a greeting", false, span((1, 1), (1, 21))),
+            (
+                "#- Functions can be nested,\n   and can appear in interpolated strings -#",
+                true,
+                span((4, 1), (5, 45))
+            ),
+            ("# Call the greet function", false, span((14, 21), (14, 46))),
+        ]
+    );
+}
@@ -0,0 +1,14 @@
+//! Helpers for matching the arguments passed to external functions
+
+use crate::Value;
+
+/// Checks that all of the arguments are iterable
+///
+/// If a non-iterable argument is found then its index is returned as an error,
+/// which can be passed to [type_error_with_arg](crate::type_error_with_arg).
+pub fn all_iterable(args: &[Value]) -> Result<(), usize> {
+    match args.iter().position(|arg| !arg.is_iterable()) {
+        Some(index) => Err(index),
+        None => Ok(()),
+    }
+}
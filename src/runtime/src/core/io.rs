@@ -29,7 +29,10 @@ pub fn make_module() -> ValueMap {
                 let path = Path::new(path.as_str()).to_path_buf();
                 match fs::File::create(&path) {
                     Ok(file) => Ok(File::system_file(file, path)),
-                    Err(error) => runtime_error!("io.create: Error while creating file: {error}"),
+                    Err(error) => runtime_error!(
+                        "io.create: Error while creating '{}': {error}",
+                        path.to_string_lossy()
+                    ),
                 }
             }
             unexpected => type_error_with_slice("a path String as argument", unexpected),
@@ -66,16 +69,35 @@ pub fn make_module() -> ValueMap {
         ),
     });
 
-    result.add_fn("open", {
-        |vm, args| match vm.get_args(args) {
-            [Str(path)] => match fs::canonicalize(path.as_str()) {
-                Ok(path) => match fs::File::open(&path) {
-                    Ok(file) => Ok(File::system_file(file, path)),
-                    Err(error) => runtime_error!("io.open: Error while opening path: {error}"),
-                },
-                Err(_) => runtime_error!("io.open: Failed to canonicalize path"),
-            },
-            unexpected => type_error_with_slice("a path String as argument", unexpected),
+    result.add_fn("open", |vm, args| {
+        let (path, mode) = match vm.get_args(args) {
+            [Str(path)] => (path, "r"),
+            [Str(path), Str(mode)] => (path, mode.as_str()),
+            unexpected => {
+                return type_error_with_slice(
+                    "a path String as argument, with an optional mode String",
+                    unexpected,
+                )
+            }
+        };
+
+        let mut options = fs::OpenOptions::new();
+        match mode {
+            "r" => options.read(true),
+            "w" => options.read(true).write(true).create(true).truncate(true),
+            "a" => options.read(true).append(true).create(true),
+            other => {
+                return runtime_error!("io.open: Invalid mode '{other}', expected 'r', 'w', or 'a'")
+            }
+        };
+
+        match options.open(path.as_str()) {
+            Ok(file) => {
+                let path = fs::canonicalize(path.as_str())
+                    .unwrap_or_else(|_| Path::new(path.as_str()).to_path_buf());
+                Ok(File::system_file(file, path))
+            }
+            Err(error) => runtime_error!("io.open: Error while opening '{path}': {error}"),
         }
     });
 
@@ -84,7 +106,7 @@ pub fn make_module() -> ValueMap {
             [Str(s)] => vm.stdout().write_line(s.as_str()),
             [Str(format), format_args @ ..] => {
                 let format = format.clone();
-                let format_args = format_args.to_vec();
+                let format_args = format_args.iter().cloned().collect::<ValueVec>();
                 match format::format_string(vm, &format, &format_args) {
                     Ok(result) => vm.stdout().write_line(&result),
                     Err(error) => Err(error),
@@ -162,19 +184,16 @@ fn make_file_meta_map() -> Rc<RefCell<MetaMap>> {
 
     MetaMapBuilder::<File>::new("File")
         .data_fn_mut("flush", |file| file.flush().map(|_| Null))
+        .data_fn("lines", |file| {
+            Ok(Value::Iterator(ValueIterator::new(Lines(file.0.clone()))))
+        })
         .data_fn_mut("path", |file| file.path().map(Value::from))
         .data_fn_mut("read_line", |file| {
-            file.read_line().map(|result| match result {
-                Some(result) => {
-                    if !result.is_empty() {
-                        let newline_bytes = if result.ends_with("\r\n") { 2 } else { 1 };
-                        result[..result.len() - newline_bytes].into()
-                    } else {
-                        Null
-                    }
-                }
-                None => Null,
-            })
+            file.read_line()
+                .map(|result| match result.as_deref().map(trim_newline) {
+                    Some(line) => line.into(),
+                    None => Null,
+                })
         })
         .data_fn_mut("read_to_string", |file: &mut File| {
             file.read_to_string().map(Value::from)
@@ -256,6 +275,47 @@ impl File {
 
 impl ExternalData for File {}
 
+// Removes a trailing newline from a line returned by KotoRead::read_line
+fn trim_newline(line: &str) -> &str {
+    line.strip_suffix('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .unwrap_or(line)
+}
+
+/// An iterator that outputs the lines of a file, without newline characters
+///
+/// The lines are read via [KotoRead::read_line], so any file type that supports reading lines
+/// can be iterated over. Copies of the iterator share the underlying file.
+#[derive(Clone)]
+struct Lines(Rc<dyn KotoFile>);
+
+impl KotoIterator for Lines {
+    fn make_copy(&self) -> ValueIterator {
+        ValueIterator::new(self.clone())
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        // Reading from the file advances its position
+        true
+    }
+
+    fn name(&self) -> String {
+        "Lines <- File".into()
+    }
+}
+
+impl Iterator for Lines {
+    type Item = ValueIteratorOutput;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.0.read_line() {
+            Ok(Some(line)) => Some(ValueIteratorOutput::Value(trim_newline(&line).into())),
+            Ok(None) => None,
+            Err(error) => Some(ValueIteratorOutput::Error(error)),
+        }
+    }
+}
+
 struct BufferedSystemFile<T>
 where
     T: Write,
@@ -276,6 +336,20 @@ where
     }
 }
 
+impl<T> BufferedSystemFile<T>
+where
+    T: Write,
+{
+    // Converts an io::Error into a RuntimeError that includes the file's path
+    fn map_io_err(&self, e: io::Error) -> RuntimeError {
+        format!(
+            "Error while accessing '{}': {e}",
+            self.path.to_string_lossy()
+        )
+        .into()
+    }
+}
+
 impl<T> KotoFile for BufferedSystemFile<T>
 where
     T: Read + Write + Seek,
@@ -292,7 +366,7 @@ where
         self.file
             .borrow_mut()
             .seek(SeekFrom::Start(position))
-            .map_err(map_io_err)?;
+            .map_err(|e| self.map_io_err(e))?;
         Ok(())
     }
 }
@@ -307,7 +381,7 @@ where
             .file
             .borrow_mut()
             .read_line(&mut buffer)
-            .map_err(map_io_err)?
+            .map_err(|e| self.map_io_err(e))?
         {
             0 => Ok(None),
             _ => Ok(Some(buffer)),
@@ -319,7 +393,7 @@ where
         self.file
             .borrow_mut()
             .read_to_string(&mut buffer)
-            .map_err(map_io_err)?;
+            .map_err(|e| self.map_io_err(e))?;
         Ok(buffer)
     }
 }
@@ -329,19 +403,29 @@ where
     T: Read + Write,
 {
     fn write(&self, bytes: &[u8]) -> Result<(), RuntimeError> {
-        self.file.borrow_mut().write(bytes).map_err(map_io_err)?;
+        self.file
+            .borrow_mut()
+            .write(bytes)
+            .map_err(|e| self.map_io_err(e))?;
         Ok(())
     }
 
     fn write_line(&self, text: &str) -> Result<(), RuntimeError> {
         let mut borrowed = self.file.borrow_mut();
-        borrowed.write(text.as_bytes()).map_err(map_io_err)?;
-        borrowed.write("\n".as_bytes()).map_err(map_io_err)?;
+        borrowed
+            .write(text.as_bytes())
+            .map_err(|e| self.map_io_err(e))?;
+        borrowed
+            .write("\n".as_bytes())
+            .map_err(|e| self.map_io_err(e))?;
         Ok(())
     }
 
     fn flush(&self) -> Result<(), RuntimeError> {
-        self.file.borrow_mut().flush().map_err(map_io_err)
+        self.file
+            .borrow_mut()
+            .flush()
+            .map_err(|e| self.map_io_err(e))
     }
 }
 
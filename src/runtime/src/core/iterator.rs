@@ -4,8 +4,16 @@ pub mod adaptors;
 pub mod generators;
 
 use {
-    super::{num2::num2_from_iterator, num4::num4_from_iterator},
-    crate::{prelude::*, ValueIteratorOutput as Output},
+    super::{
+        num2::num2_from_iterator, num4::num4_from_iterator, string::iterators as string_iterators,
+    },
+    crate::{
+        prelude::*,
+        value_sort::{sort_values, sort_values_by_keys},
+        ValueIteratorOutput as Output,
+    },
+    std::{cell::RefCell, collections::VecDeque, rc::Rc},
+    unicode_segmentation::UnicodeSegmentation,
 };
 
 /// Initializes the `iterator` core library module
@@ -19,7 +27,7 @@ pub fn make_module() -> ValueMap {
             let iterable = iterable.clone();
             let predicate = predicate.clone();
 
-            for output in vm.make_iterator(iterable)? {
+            for output in vm.make_interruptible_iterator(iterable)? {
                 let predicate_result = match output {
                     Output::Value(value) => {
                         vm.run_function(predicate.clone(), CallArgs::Single(value))
@@ -56,7 +64,7 @@ pub fn make_module() -> ValueMap {
             let iterable = iterable.clone();
             let predicate = predicate.clone();
 
-            for output in vm.make_iterator(iterable)? {
+            for output in vm.make_interruptible_iterator(iterable)? {
                 let predicate_result = match output {
                     Output::Value(value) => {
                         vm.run_function(predicate.clone(), CallArgs::Single(value))
@@ -89,20 +97,30 @@ pub fn make_module() -> ValueMap {
     });
 
     result.add_fn("chain", |vm, args| match vm.get_args(args) {
-        [iterable_a, iterable_b] if iterable_a.is_iterable() && iterable_b.is_iterable() => {
-            let iterable_a = iterable_a.clone();
-            let iterable_b = iterable_b.clone();
-            let result = ValueIterator::new(adaptors::Chain::new(
-                vm.make_iterator(iterable_a)?,
-                vm.make_iterator(iterable_b)?,
-            ));
-
-            Ok(Iterator(result))
-        }
-        unexpected => type_error_with_slice("two iterable values as arguments", unexpected),
+        iterables @ [_, _, ..] => match all_iterable(iterables) {
+            Ok(()) => {
+                let iterables = iterables.iter().cloned().collect::<ValueVec>();
+                let mut result = vm.make_iterator(iterables[0].clone())?;
+                for iterable in iterables[1..].iter().cloned() {
+                    result = ValueIterator::new(adaptors::Chain::new(
+                        result,
+                        vm.make_iterator(iterable)?,
+                    ));
+                }
+                Ok(Iterator(result))
+            }
+            Err(index) => {
+                type_error_with_arg("two or more iterable values as arguments", iterables, index)
+            }
+        },
+        unexpected => type_error_with_slice("two or more iterable values as arguments", unexpected),
     });
 
     result.add_fn("chunks", |vm, args| match vm.get_args(args) {
+        [Str(s), Number(n)] if *n >= 1.0 => {
+            let result = string_iterators::Chunks::new(s.clone(), n.into());
+            Ok(ValueIterator::new(result).into())
+        }
         [iterable, Number(n)] if iterable.is_iterable() => {
             let iterable = iterable.clone();
             let n = *n;
@@ -112,8 +130,7 @@ pub fn make_module() -> ValueMap {
             }
         }
         unexpected => type_error_with_slice(
-            "a value with a range (like a List or String), \
-             and a chunk size greater than zero as arguments",
+            "an iterable value and a chunk size greater than zero as arguments",
             unexpected,
         ),
     });
@@ -121,7 +138,7 @@ pub fn make_module() -> ValueMap {
     result.add_fn("consume", |vm, args| match vm.get_args(args) {
         [iterable] if iterable.is_iterable() => {
             let iterable = iterable.clone();
-            for output in vm.make_iterator(iterable)? {
+            for output in vm.make_interruptible_iterator(iterable)? {
                 if let Output::Error(error) = output {
                     return Err(error);
                 }
@@ -131,7 +148,7 @@ pub fn make_module() -> ValueMap {
         [iterable, f] if iterable.is_iterable() && f.is_callable() => {
             let iterable = iterable.clone();
             let f = f.clone();
-            for output in vm.make_iterator(iterable)? {
+            for output in vm.make_interruptible_iterator(iterable)? {
                 match output {
                     Output::Value(value) => {
                         vm.run_function(f.clone(), CallArgs::Single(value))?;
@@ -145,21 +162,23 @@ pub fn make_module() -> ValueMap {
             Ok(Null)
         }
         unexpected => type_error_with_slice(
-            "an Iterable Value (and optional Function) as arguments",
+            "an iterable value and an optional Function as arguments",
             unexpected,
         ),
     });
 
     result.add_fn("copy", |vm, args| match vm.get_args(args) {
         [Iterator(iter)] => Ok(Iterator(iter.make_copy())),
-        unexpected => type_error_with_slice("an Iterator as argument", unexpected),
+        // Iterables that aren't iterators don't have an iteration position to copy
+        [iterable] if iterable.is_iterable() => Ok(iterable.clone()),
+        unexpected => type_error_with_slice("an iterable value as argument", unexpected),
     });
 
     result.add_fn("count", |vm, args| match vm.get_args(args) {
         [iterable] if iterable.is_iterable() => {
             let iterable = iterable.clone();
             let mut result = 0;
-            for output in vm.make_iterator(iterable)? {
+            for output in vm.make_interruptible_iterator(iterable)? {
                 if let Output::Error(error) = output {
                     return Err(error);
                 }
@@ -167,9 +186,75 @@ pub fn make_module() -> ValueMap {
             }
             Ok(Number(result.into()))
         }
+        [iterable, predicate] if iterable.is_iterable() && predicate.is_callable() => {
+            let iterable = iterable.clone();
+            let predicate = predicate.clone();
+            let mut result = 0;
+
+            for output in vm.make_interruptible_iterator(iterable)? {
+                let predicate_result = match output {
+                    Output::Value(value) => {
+                        vm.run_function(predicate.clone(), CallArgs::Single(value))
+                    }
+                    Output::ValuePair(a, b) => {
+                        vm.run_function(predicate.clone(), CallArgs::AsTuple(&[a, b]))
+                    }
+                    Output::Error(error) => return Err(error),
+                };
+
+                match predicate_result {
+                    Ok(Bool(true)) => result += 1,
+                    Ok(Bool(false)) => {}
+                    Ok(unexpected) => {
+                        return type_error("a Bool to be returned from the predicate", &unexpected)
+                    }
+                    Err(error) => return Err(error),
+                }
+            }
+
+            Ok(Number(result.into()))
+        }
+        unexpected => type_error_with_slice(
+            "an iterable value and optional predicate Function as arguments",
+            unexpected,
+        ),
+    });
+
+    result.add_fn("cycle", |vm, args| match vm.get_args(args) {
+        [iterable] if iterable.is_iterable() => {
+            let iterable = iterable.clone();
+            let result = adaptors::Cycle::new(vm.make_iterator(iterable)?);
+
+            Ok(ValueIterator::new(result).into())
+        }
         unexpected => type_error_with_slice("an iterable value as argument", unexpected),
     });
 
+    result.add_fn("dedup", |vm, args| match vm.get_args(args) {
+        [iterable] if iterable.is_iterable() => {
+            let iterable = iterable.clone();
+            let result =
+                adaptors::Dedup::new(vm.make_iterator(iterable)?, None, vm.spawn_shared_vm());
+
+            Ok(ValueIterator::new(result).into())
+        }
+        [iterable, compare] if iterable.is_iterable() && compare.is_callable() => {
+            let iterable = iterable.clone();
+            let compare = compare.clone();
+            let result = adaptors::Dedup::new(
+                vm.make_iterator(iterable)?,
+                Some(compare),
+                vm.spawn_shared_vm(),
+            );
+
+            Ok(ValueIterator::new(result).into())
+        }
+        unexpected => type_error_with_slice(
+            "an iterable value and an optional comparison Function as arguments",
+            unexpected,
+        ),
+    });
+
     result.add_fn("each", |vm, args| match vm.get_args(args) {
         [iterable, f] if iterable.is_iterable() && f.is_callable() => {
             let iterable = iterable.clone();
@@ -183,14 +268,18 @@ pub fn make_module() -> ValueMap {
         }
     });
 
-    result.add_fn("cycle", |vm, args| match vm.get_args(args) {
-        [iterable] if iterable.is_iterable() => {
+    result.add_fn("each_indexed", |vm, args| match vm.get_args(args) {
+        [iterable, f] if iterable.is_iterable() && f.is_callable() => {
             let iterable = iterable.clone();
-            let result = adaptors::Cycle::new(vm.make_iterator(iterable)?);
+            let f = f.clone();
+            let result =
+                adaptors::EachIndexed::new(vm.make_iterator(iterable)?, f, vm.spawn_shared_vm());
 
             Ok(ValueIterator::new(result).into())
         }
-        unexpected => type_error_with_slice("an iterable value as argument", unexpected),
+        unexpected => {
+            type_error_with_slice("an iterable value and a Function as arguments", unexpected)
+        }
     });
 
     result.add_fn("enumerate", |vm, args| match vm.get_args(args) {
@@ -207,7 +296,7 @@ pub fn make_module() -> ValueMap {
             let iterable = iterable.clone();
             let predicate = predicate.clone();
 
-            for output in vm.make_iterator(iterable)?.map(collect_pair) {
+            for output in vm.make_interruptible_iterator(iterable)?.map(collect_pair) {
                 match output {
                     Output::Value(value) => {
                         match vm.run_function(predicate.clone(), CallArgs::Single(value.clone())) {
@@ -266,7 +355,10 @@ pub fn make_module() -> ValueMap {
                                         f.clone(),
                                         CallArgs::Separate(&[fold_result, value]),
                                     ) {
-                                        Ok(result) => fold_result = result,
+                                        Ok(result) => match stopped_value(&result) {
+                                            Some(stopped) => return Some(Output::Value(stopped)),
+                                            None => fold_result = result,
+                                        },
                                         Err(error) => return Some(Output::Error(error)),
                                     }
                                 }
@@ -304,6 +396,72 @@ pub fn make_module() -> ValueMap {
         unexpected => type_error_with_slice("(Function), or (Number, Function)", unexpected),
     });
 
+    result.add_fn("group", |vm, args| {
+        let (iterable, key_fn, value_fn) = match vm.get_args(args) {
+            [iterable, key_fn] if iterable.is_iterable() && key_fn.is_callable() => {
+                (iterable.clone(), key_fn.clone(), None)
+            }
+            [iterable, key_fn, value_fn]
+                if iterable.is_iterable() && key_fn.is_callable() && value_fn.is_callable() =>
+            {
+                (iterable.clone(), key_fn.clone(), Some(value_fn.clone()))
+            }
+            unexpected => {
+                return type_error_with_slice(
+                    "an iterable value, a key Function, \
+                     and an optional value Function as arguments",
+                    unexpected,
+                )
+            }
+        };
+
+        let mut groups = DataMap::default();
+
+        for output in vm.make_interruptible_iterator(iterable)? {
+            let value = match output {
+                Output::Value(value) => value,
+                Output::ValuePair(a, b) => Tuple(vec![a, b].into()),
+                Output::Error(error) => return Err(error),
+            };
+
+            let key = vm.run_function(key_fn.clone(), CallArgs::Single(value.clone()))?;
+            if !key.is_immutable() {
+                return runtime_error!(
+                    "iterator.group: Only immutable Values can be used as keys (found '{}')",
+                    key.type_as_string()
+                );
+            }
+
+            let value = match &value_fn {
+                Some(value_fn) => vm.run_function(value_fn.clone(), CallArgs::Single(value))?,
+                None => value,
+            };
+
+            let key = ValueKey::from(key);
+            match groups.get(&key) {
+                Some(List(group)) => group.data_mut().push(value),
+                _ => {
+                    groups.insert(key, List(ValueList::from_slice(&[value])));
+                }
+            }
+        }
+
+        Ok(Map(ValueMap::with_data(groups)))
+    });
+
+    result.add_fn("inspect", |vm, args| match vm.get_args(args) {
+        [iterable, f] if iterable.is_iterable() && f.is_callable() => {
+            let iterable = iterable.clone();
+            let f = f.clone();
+            let result =
+                adaptors::Inspect::new(vm.make_iterator(iterable)?, f, vm.spawn_shared_vm());
+            Ok(ValueIterator::new(result).into())
+        }
+        unexpected => {
+            type_error_with_slice("an iterable value and a Function as arguments", unexpected)
+        }
+    });
+
     result.add_fn("intersperse", |vm, args| match vm.get_args(args) {
         [iterable, separator_fn] if iterable.is_iterable() && separator_fn.is_callable() => {
             let iterable = iterable.clone();
@@ -355,7 +513,7 @@ pub fn make_module() -> ValueMap {
             let iterable = iterable.clone();
             let mut result = Null;
 
-            let mut iter = vm.make_iterator(iterable)?.map(collect_pair);
+            let mut iter = vm.make_interruptible_iterator(iterable)?.map(collect_pair);
             for output in &mut iter {
                 match output {
                     Output::Value(value) => result = value,
@@ -385,6 +543,22 @@ pub fn make_module() -> ValueMap {
         ),
     });
 
+    result.add_fn("max_index", |vm, args| match vm.get_args(args) {
+        [iterable] if iterable.is_iterable() => {
+            let iterable = iterable.clone();
+            run_iterator_comparison_with_index(vm, iterable, None, InvertResult::Yes)
+        }
+        [iterable, key_fn] if iterable.is_iterable() && key_fn.is_callable() => {
+            let iterable = iterable.clone();
+            let key_fn = key_fn.clone();
+            run_iterator_comparison_with_index(vm, iterable, Some(key_fn), InvertResult::Yes)
+        }
+        unexpected => type_error_with_slice(
+            "an iterable value and an optional key function as arguments",
+            unexpected,
+        ),
+    });
+
     result.add_fn("min", |vm, args| match vm.get_args(args) {
         [iterable] if iterable.is_iterable() => {
             let iterable = iterable.clone();
@@ -401,12 +575,28 @@ pub fn make_module() -> ValueMap {
         ),
     });
 
+    result.add_fn("min_index", |vm, args| match vm.get_args(args) {
+        [iterable] if iterable.is_iterable() => {
+            let iterable = iterable.clone();
+            run_iterator_comparison_with_index(vm, iterable, None, InvertResult::No)
+        }
+        [iterable, key_fn] if iterable.is_iterable() && key_fn.is_callable() => {
+            let iterable = iterable.clone();
+            let key_fn = key_fn.clone();
+            run_iterator_comparison_with_index(vm, iterable, Some(key_fn), InvertResult::No)
+        }
+        unexpected => type_error_with_slice(
+            "an iterable value and an optional key function as arguments",
+            unexpected,
+        ),
+    });
+
     result.add_fn("min_max", |vm, args| match vm.get_args(args) {
         [iterable] if iterable.is_iterable() => {
             let iterable = iterable.clone();
             let mut result = None;
 
-            for iter_output in vm.make_iterator(iterable)?.map(collect_pair) {
+            for iter_output in vm.make_interruptible_iterator(iterable)?.map(collect_pair) {
                 match iter_output {
                     Output::Value(value) => {
                         result = Some(match result {
@@ -422,14 +612,14 @@ pub fn make_module() -> ValueMap {
                 }
             }
 
-            Ok(result.map_or(Null, |(min, max)| Tuple(vec![min, max].into())))
+            Ok(result.map_or(Null, |(min, max)| Tuple([min, max].into_iter().collect())))
         }
         [iterable, key_fn] if iterable.is_iterable() && key_fn.is_callable() => {
             let iterable = iterable.clone();
             let key_fn = key_fn.clone();
             let mut result = None;
 
-            for iter_output in vm.make_iterator(iterable)?.map(collect_pair) {
+            for iter_output in vm.make_interruptible_iterator(iterable)?.map(collect_pair) {
                 match iter_output {
                     Output::Value(value) => {
                         let key =
@@ -459,7 +649,9 @@ pub fn make_module() -> ValueMap {
                 }
             }
 
-            Ok(result.map_or(Null, |((min, _), (max, _))| Tuple(vec![min, max].into())))
+            Ok(result.map_or(Null, |((min, _), (max, _))| {
+                Tuple([min, max].into_iter().collect())
+            }))
         }
         unexpected => type_error_with_slice(
             "an iterable value and an optional key function as arguments",
@@ -468,21 +660,93 @@ pub fn make_module() -> ValueMap {
     });
 
     result.add_fn("next", |vm, args| match vm.get_args(args) {
-        [Iterator(i)] => match i.clone().next().map(collect_pair) {
-            Some(Output::Value(value)) => Ok(value),
-            Some(Output::Error(error)) => Err(error),
-            None => Ok(Value::Null),
-            _ => unreachable!(),
-        },
+        [iterable] if iterable.is_iterable() => {
+            // Iterators are advanced in place, other iterables produce their first value
+            let iterable = iterable.clone();
+            match vm.make_iterator(iterable)?.next().map(collect_pair) {
+                Some(Output::Value(value)) => Ok(value),
+                Some(Output::Error(error)) => Err(error),
+                None => Ok(Value::Null),
+                _ => unreachable!(),
+            }
+        }
+        unexpected => type_error_with_slice("an iterable value as argument", unexpected),
+    });
+
+    result.add_fn("partition", |vm, args| match vm.get_args(args) {
+        [iterable, predicate] if iterable.is_iterable() && predicate.is_callable() => {
+            let iterable = iterable.clone();
+            let predicate = predicate.clone();
+            let iterator = vm.make_interruptible_iterator(iterable)?;
+
+            let size_hint = iterator.capacity_hint();
+            let mut matching = ValueVec::with_capacity(size_hint);
+            let mut rest = ValueVec::with_capacity(size_hint);
+
+            for output in iterator.map(collect_pair) {
+                let value = match output {
+                    Output::Value(value) => value,
+                    Output::Error(error) => return Err(error),
+                    _ => unreachable!(),
+                };
+
+                match vm.run_function(predicate.clone(), CallArgs::Single(value.clone())) {
+                    Ok(Bool(true)) => matching.push(value),
+                    Ok(Bool(false)) => rest.push(value),
+                    Ok(unexpected) => {
+                        return type_error("a Bool to be returned from the predicate", &unexpected)
+                    }
+                    Err(error) => return Err(error),
+                }
+            }
+
+            Ok(Tuple(
+                vec![
+                    List(ValueList::with_data(matching)),
+                    List(ValueList::with_data(rest)),
+                ]
+                .into(),
+            ))
+        }
+        unexpected => type_error_with_slice(
+            "an iterable value and a predicate Function as arguments",
+            unexpected,
+        ),
+    });
+
+    result.add_fn("peek", |vm, args| match vm.get_args(args) {
+        [Iterator(iter)] => {
+            let mut iter = iter.clone();
+            if !iter.is_peekable() {
+                return runtime_error!(
+                    "iterator.peek: the iterator isn't peekable, see iterator.peekable"
+                );
+            }
+            match iter.peek().map(collect_pair) {
+                Some(Output::Value(value)) => Ok(value),
+                Some(Output::Error(error)) => Err(error),
+                None => Ok(Null),
+                _ => unreachable!(),
+            }
+        }
         unexpected => type_error_with_slice("an Iterator as argument", unexpected),
     });
 
+    result.add_fn("peekable", |vm, args| match vm.get_args(args) {
+        [iterable] if iterable.is_iterable() => {
+            let iterable = iterable.clone();
+            let result = adaptors::Peekable::new(vm.make_iterator(iterable)?);
+            Ok(ValueIterator::new(result).into())
+        }
+        unexpected => type_error_with_slice("an iterable value as argument", unexpected),
+    });
+
     result.add_fn("position", |vm, args| match vm.get_args(args) {
         [iterable, predicate] if iterable.is_iterable() && predicate.is_callable() => {
             let iterable = iterable.clone();
             let predicate = predicate.clone();
 
-            for (i, output) in vm.make_iterator(iterable)?.enumerate() {
+            for (i, output) in vm.make_interruptible_iterator(iterable)?.enumerate() {
                 let predicate_result = match output {
                     Output::Value(value) => {
                         vm.run_function(predicate.clone(), CallArgs::Single(value))
@@ -554,8 +818,52 @@ pub fn make_module() -> ValueMap {
                 Err(e) => runtime_error!("iterator.reversed: {}", e),
             }
         }
+        unexpected => type_error_with_slice("an iterable value as argument", unexpected),
+    });
+
+    result.add_fn("round_robin", |vm, args| match vm.get_args(args) {
+        [iterables] if iterables.is_iterable() => {
+            let iterables = iterables.clone();
+            let mut iterators = Vec::new();
+
+            for output in vm.make_iterator(iterables)?.map(collect_pair) {
+                match output {
+                    Output::Value(iterable) if iterable.is_iterable() => {
+                        iterators.push(vm.make_iterator(iterable)?);
+                    }
+                    Output::Value(unexpected) => {
+                        return type_error("an iterable value in round_robin's input", &unexpected)
+                    }
+                    Output::Error(error) => return Err(error),
+                    _ => unreachable!(),
+                }
+            }
+
+            Ok(Iterator(ValueIterator::new(adaptors::RoundRobin::new(
+                iterators,
+            ))))
+        }
+        unexpected => {
+            type_error_with_slice("an iterable of iterable values as argument", unexpected)
+        }
+    });
+
+    result.add_fn("scan", |vm, args| match vm.get_args(args) {
+        [iterable, initial_state, f] if iterable.is_iterable() && f.is_callable() => {
+            let iterable = iterable.clone();
+            let initial_state = initial_state.clone();
+            let f = f.clone();
+            let result = adaptors::Scan::new(
+                vm.make_iterator(iterable)?,
+                initial_state,
+                f,
+                vm.spawn_shared_vm(),
+            );
+            Ok(ValueIterator::new(result).into())
+        }
         unexpected => type_error_with_slice(
-            "an iterable value and non-negative number as arguments",
+            "an iterable value, an initial state, and a Function that takes the state and a \
+             value and returns the next state as arguments",
             unexpected,
         ),
     });
@@ -580,6 +888,159 @@ pub fn make_module() -> ValueMap {
         ),
     });
 
+    result.add_fn("skip_last", |vm, args| match vm.get_args(args) {
+        [iterable, Number(n)] if iterable.is_iterable() && *n >= 0.0 => {
+            let iterable = iterable.clone();
+            let n = *n;
+            let result = adaptors::SkipLast::new(vm.make_iterator(iterable)?, n.into());
+            Ok(ValueIterator::new(result).into())
+        }
+        unexpected => type_error_with_slice(
+            "an iterable value and non-negative number as arguments",
+            unexpected,
+        ),
+    });
+
+    result.add_fn("skip_while", |vm, args| match vm.get_args(args) {
+        [iterable, predicate] if iterable.is_iterable() && predicate.is_callable() => {
+            let iterable = iterable.clone();
+            let predicate = predicate.clone();
+            let result = adaptors::SkipWhile::new(
+                vm.make_iterator(iterable)?,
+                predicate,
+                vm.spawn_shared_vm(),
+            );
+            Ok(ValueIterator::new(result).into())
+        }
+        unexpected => type_error_with_slice(
+            "an iterable value and a predicate Function as arguments",
+            unexpected,
+        ),
+    });
+
+    result.add_fn("slice", |vm, args| match vm.get_args(args) {
+        [iterable, range @ (Range(_) | IndexRange(_))] if iterable.is_iterable() => {
+            let (start, end) = match range {
+                Range(r) if r.start < 0 || r.end < 0 => {
+                    return runtime_error!(
+                        "iterator.slice: Negative indices aren't allowed (found '{range}')"
+                    )
+                }
+                Range(r) if !r.is_ascending() => {
+                    return runtime_error!(
+                        "iterator.slice: Expected an ascending range (found '{range}')"
+                    )
+                }
+                IndexRange(r) if r.start < 0 || matches!(r.end, Some(end) if end < 0) => {
+                    return runtime_error!(
+                        "iterator.slice: Negative indices aren't allowed (found '{range}')"
+                    )
+                }
+                Range(r) => (r.start as usize, Some(r.exclusive_end() as usize)),
+                IndexRange(r) => (r.start as usize, r.end.map(|end| end.max(r.start) as usize)),
+                _ => unreachable!(), // Ranges were matched above
+            };
+
+            // Clamps the slice's bounds to the provided size
+            let clamp = |size: usize| {
+                let end = end.map_or(size, |end| end.min(size));
+                (start.min(end), end)
+            };
+
+            // Lists, Tuples, and Strings produce iterators over sub-views of their data, while
+            // other iterables skip values lazily
+            let result = match iterable {
+                List(l) => {
+                    let (start, end) = clamp(l.len());
+                    ValueIterator::with_list_range(l.clone(), start..end)
+                }
+                Tuple(t) => {
+                    let (start, end) = clamp(t.len());
+                    // Safety: the bounds have been clamped to the tuple's size
+                    ValueIterator::with_tuple(t.make_sub_tuple(start..end).unwrap())
+                }
+                Str(s) => {
+                    let byte_offset = |index: usize| {
+                        s.grapheme_indices(true)
+                            .nth(index)
+                            .map_or(s.len(), |(offset, _)| offset)
+                    };
+                    let start_offset = byte_offset(start);
+                    let end_offset = end.map_or(s.len(), byte_offset).max(start_offset);
+                    // Safety: the offsets are at grapheme boundaries within the string
+                    ValueIterator::with_string(s.with_bounds(start_offset..end_offset).unwrap())
+                }
+                _ => {
+                    let iterable = iterable.clone();
+                    ValueIterator::new(adaptors::Slice::new(
+                        vm.make_iterator(iterable)?,
+                        start,
+                        end,
+                    ))
+                }
+            };
+
+            Ok(Iterator(result))
+        }
+        unexpected => {
+            type_error_with_slice("an iterable value and a Range as arguments", unexpected)
+        }
+    });
+
+    result.add_fn("sorted", |vm, args| {
+        let (iterable, key_fn) = match vm.get_args(args) {
+            [iterable] if iterable.is_iterable() => (iterable.clone(), None),
+            [iterable, key_fn] if iterable.is_iterable() && key_fn.is_callable() => {
+                (iterable.clone(), Some(key_fn.clone()))
+            }
+            unexpected => {
+                return type_error_with_slice(
+                    "an iterable value and an optional key Function as arguments",
+                    unexpected,
+                )
+            }
+        };
+
+        let mut values = iterable_to_vec(vm, iterable)?;
+
+        match key_fn {
+            Some(key_fn) => {
+                // The key function is called once for each value before sorting
+                let keys = values
+                    .iter()
+                    .map(|value| vm.run_function(key_fn.clone(), CallArgs::Single(value.clone())))
+                    .collect::<Result<Vec<_>, _>>()?;
+                sort_values_by_keys(vm, &mut values, &keys)?;
+            }
+            None => sort_values(vm, &mut values)?,
+        }
+
+        Ok(Iterator(
+            vm.make_iterator(List(ValueList::with_data(values)))?,
+        ))
+    });
+
+    result.add_fn("step_by", |vm, args| match vm.get_args(args) {
+        [iterable, Number(ValueNumber::I64(step))] if iterable.is_iterable() => {
+            if *step < 1 {
+                return runtime_error!("iterator.step_by: Expected a positive step (found {step})");
+            }
+
+            let step = *step as usize;
+            let iterable = iterable.clone();
+            let result = adaptors::StepBy::new(vm.make_iterator(iterable)?, step);
+            Ok(ValueIterator::new(result).into())
+        }
+        unexpected => {
+            type_error_with_slice("an iterable value and an Integer as arguments", unexpected)
+        }
+    });
+
+    result.add_fn("stop", |vm, args| match vm.get_args(args) {
+        [value] => Ok(Stop::make_value(value.clone())),
+        unexpected => type_error_with_slice("a single argument", unexpected),
+    });
+
     result.add_fn("sum", |vm, args| {
         let (iterable, initial_value) = match vm.get_args(args) {
             [iterable] if iterable.is_iterable() => (iterable.clone(), Value::Number(0.into())),
@@ -604,28 +1065,59 @@ pub fn make_module() -> ValueMap {
             let result = adaptors::Take::new(vm.make_iterator(iterable)?, n.into());
             Ok(ValueIterator::new(result).into())
         }
+        [iterable, predicate] if iterable.is_iterable() && predicate.is_callable() => {
+            let iterable = iterable.clone();
+            let predicate = predicate.clone();
+            let result = adaptors::TakeWhile::new(
+                vm.make_iterator(iterable)?,
+                predicate,
+                vm.spawn_shared_vm(),
+            );
+            Ok(ValueIterator::new(result).into())
+        }
         unexpected => type_error_with_slice(
-            "an iterable value and non-negative number as arguments",
+            "an iterable value and either a non-negative number or a predicate Function \
+             as arguments",
             unexpected,
         ),
     });
 
-    result.add_fn("to_list", |vm, args| match vm.get_args(args) {
-        [iterable] if iterable.is_iterable() => {
+    result.add_fn("take_last", |vm, args| match vm.get_args(args) {
+        [iterable, Number(n)] if iterable.is_iterable() && *n >= 0.0 => {
             let iterable = iterable.clone();
-            let iterator = vm.make_iterator(iterable)?;
-            let (size_hint, _) = iterator.size_hint();
-            let mut result = ValueVec::with_capacity(size_hint);
+            let n: usize = n.into();
+            let iterator = vm.make_interruptible_iterator(iterable)?;
 
+            // The iterator is consumed, with the last n values kept in a ring buffer
+            let mut buffer = VecDeque::with_capacity(capped_capacity(n, &iterator));
             for output in iterator.map(collect_pair) {
                 match output {
-                    Output::Value(value) => result.push(value),
+                    Output::Value(value) => {
+                        if buffer.len() == n {
+                            buffer.pop_front();
+                        }
+                        if n > 0 {
+                            buffer.push_back(value);
+                        }
+                    }
                     Output::Error(error) => return Err(error),
                     _ => unreachable!(),
                 }
             }
 
-            Ok(List(ValueList::with_data(result)))
+            let result = ValueList::with_data(buffer.into_iter().collect());
+            Ok(ValueIterator::with_list(result).into())
+        }
+        unexpected => type_error_with_slice(
+            "an iterable value and non-negative number as arguments",
+            unexpected,
+        ),
+    });
+
+    result.add_fn("to_list", |vm, args| match vm.get_args(args) {
+        [iterable] if iterable.is_iterable() => {
+            let iterable = iterable.clone();
+            iterable_to_list(vm, iterable)
         }
         unexpected => type_error_with_slice("an iterable value as argument", unexpected),
     });
@@ -633,32 +1125,7 @@ pub fn make_module() -> ValueMap {
     result.add_fn("to_map", |vm, args| match vm.get_args(args) {
         [iterable] if iterable.is_iterable() => {
             let iterable = iterable.clone();
-            let iterator = vm.make_iterator(iterable)?;
-            let (size_hint, _) = iterator.size_hint();
-            let mut result = DataMap::with_capacity(size_hint);
-
-            for output in iterator {
-                let (key, value) = match output {
-                    Output::ValuePair(key, value) => (key, value),
-                    Output::Value(Tuple(t)) if t.len() == 2 => {
-                        let key = t[0].clone();
-                        let value = t[1].clone();
-                        (key, value)
-                    }
-                    Output::Value(value) => (value, Null),
-                    Output::Error(error) => return Err(error),
-                };
-
-                if !key.is_immutable() {
-                    return runtime_error!(
-                        "Only immutable Values can be used as keys (found '{}')",
-                        key.type_as_string()
-                    );
-                }
-                result.insert(key.into(), value);
-            }
-
-            Ok(Map(ValueMap::with_data(result)))
+            iterable_to_map(vm, iterable)
         }
         unexpected => type_error_with_slice("an iterable value as argument", unexpected),
     });
@@ -684,8 +1151,8 @@ pub fn make_module() -> ValueMap {
     result.add_fn("to_string", |vm, args| match vm.get_args(args) {
         [iterable] if iterable.is_iterable() => {
             let iterable = iterable.clone();
-            let iterator = vm.make_iterator(iterable)?;
-            let (size_hint, _) = iterator.size_hint();
+            let iterator = vm.make_interruptible_iterator(iterable)?;
+            let size_hint = iterator.capacity_hint();
             let mut result = String::with_capacity(size_hint);
 
             for output in iterator.map(collect_pair) {
@@ -705,8 +1172,8 @@ pub fn make_module() -> ValueMap {
     result.add_fn("to_tuple", |vm, args| match vm.get_args(args) {
         [iterable] if iterable.is_iterable() => {
             let iterable = iterable.clone();
-            let iterator = vm.make_iterator(iterable)?;
-            let (size_hint, _) = iterator.size_hint();
+            let iterator = vm.make_interruptible_iterator(iterable)?;
+            let size_hint = iterator.capacity_hint();
             let mut result = Vec::with_capacity(size_hint);
 
             for output in iterator.map(collect_pair) {
@@ -722,7 +1189,21 @@ pub fn make_module() -> ValueMap {
         unexpected => type_error_with_slice("an iterable value as argument", unexpected),
     });
 
+    result.add_fn("unique", |vm, args| match vm.get_args(args) {
+        [iterable] if iterable.is_iterable() => {
+            let iterable = iterable.clone();
+            let result = adaptors::Unique::new(vm.make_iterator(iterable)?, vm.spawn_shared_vm());
+
+            Ok(ValueIterator::new(result).into())
+        }
+        unexpected => type_error_with_slice("an iterable value as argument", unexpected),
+    });
+
     result.add_fn("windows", |vm, args| match vm.get_args(args) {
+        [Str(s), Number(n)] if *n >= 1.0 => {
+            let result = string_iterators::Windows::new(s.clone(), n.into());
+            Ok(ValueIterator::new(result).into())
+        }
         [iterable, Number(n)] if iterable.is_iterable() => {
             let iterable = iterable.clone();
             let n = *n;
@@ -732,33 +1213,189 @@ pub fn make_module() -> ValueMap {
             }
         }
         unexpected => type_error_with_slice(
-            "a value with a range (like a List or String), \
-             and a chunk size greater than zero as arguments",
+            "an iterable value and a window size greater than zero as arguments",
             unexpected,
         ),
     });
 
-    result.add_fn("zip", |vm, args| match vm.get_args(args) {
-        [iterable_a, iterable_b] if iterable_a.is_iterable() && iterable_b.is_iterable() => {
-            let iterable_a = iterable_a.clone();
-            let iterable_b = iterable_b.clone();
-            let result =
-                adaptors::Zip::new(vm.make_iterator(iterable_a)?, vm.make_iterator(iterable_b)?);
+    result.add_fn("with_size_hint", |vm, args| match vm.get_args(args) {
+        [iterable, Number(n)] if iterable.is_iterable() && *n >= 0.0 => {
+            let iterable = iterable.clone();
+            let n = *n;
+            let result = adaptors::WithSizeHint::new(vm.make_iterator(iterable)?, n.into());
+            Ok(ValueIterator::new(result).into())
+        }
+        unexpected => type_error_with_slice(
+            "an iterable value and non-negative number as arguments",
+            unexpected,
+        ),
+    });
+
+    result.add_fn("with_state", |vm, args| match vm.get_args(args) {
+        [iterable, state, f] if iterable.is_iterable() && f.is_callable() => {
+            let iterable = iterable.clone();
+            let state = state.clone();
+            let f = f.clone();
+            let result = adaptors::WithState::new(
+                vm.make_iterator(iterable)?,
+                state,
+                f,
+                vm.spawn_shared_vm(),
+            );
             Ok(ValueIterator::new(result).into())
         }
+        unexpected => type_error_with_slice(
+            "an iterable value, an initial state, and a Function as arguments",
+            unexpected,
+        ),
+    });
+
+    result.add_fn("zip", |vm, args| match vm.get_args(args) {
+        iterables @ [iterable_a, iterable_b] => match all_iterable(iterables) {
+            Ok(()) => {
+                let iterable_a = iterable_a.clone();
+                let iterable_b = iterable_b.clone();
+                let result = adaptors::Zip::new(
+                    vm.make_iterator(iterable_a)?,
+                    vm.make_iterator(iterable_b)?,
+                );
+                Ok(ValueIterator::new(result).into())
+            }
+            Err(index) => type_error_with_arg("two iterable values as arguments", iterables, index),
+        },
         unexpected => type_error_with_slice("two iterable values as arguments", unexpected),
     });
 
+    result.add_fn("zip_longest", |vm, args| {
+        let expected_error = "two iterable values and an optional fill value as arguments";
+
+        let (iterable_a, iterable_b, fill) = match vm.get_args(args) {
+            iterables @ [_, _] | iterables @ [_, _, _] => {
+                if let Err(index) = all_iterable(&iterables[..2]) {
+                    return type_error_with_arg(expected_error, iterables, index);
+                }
+                let fill = iterables.get(2).cloned().unwrap_or(Null);
+                (iterables[0].clone(), iterables[1].clone(), fill)
+            }
+            unexpected => return type_error_with_slice(expected_error, unexpected),
+        };
+
+        let result = adaptors::ZipLongest::new(
+            vm.make_iterator(iterable_a)?,
+            vm.make_iterator(iterable_b)?,
+            fill,
+        );
+        Ok(ValueIterator::new(result).into())
+    });
+
     result
 }
 
+// Collects the output of an iterable value into a List
+//
+// Pairs of values produced by the iterator are collected as Tuples.
+pub(crate) fn iterable_to_list(vm: &mut Vm, iterable: Value) -> RuntimeResult {
+    let result = iterable_to_vec(vm, iterable)?;
+    Ok(Value::List(ValueList::with_data(result)))
+}
+
+// Collects the output of an iterable value into a Map
+//
+// Pairs of values (or 2-Tuples) are inserted as key/value entries,
+// with other values being inserted as keys with Null values.
+pub(crate) fn iterable_to_map(vm: &mut Vm, iterable: Value) -> RuntimeResult {
+    let iterator = vm.make_interruptible_iterator(iterable)?;
+    let size_hint = iterator.capacity_hint();
+    let mut result = DataMap::with_capacity(size_hint);
+
+    for output in iterator {
+        let (key, value) = match output {
+            Output::ValuePair(key, value) => (key, value),
+            Output::Value(Value::Tuple(t)) if t.len() == 2 => {
+                let key = t[0].clone();
+                let value = t[1].clone();
+                (key, value)
+            }
+            Output::Value(value) => (value, Value::Null),
+            Output::Error(error) => return Err(error),
+        };
+
+        if !key.is_immutable() {
+            return runtime_error!(
+                "Only immutable Values can be used as keys (found '{}')",
+                key.type_as_string()
+            );
+        }
+        result.insert(key.into(), value);
+    }
+
+    Ok(Value::Map(ValueMap::with_data(result)))
+}
+
+fn iterable_to_vec(vm: &mut Vm, iterable: Value) -> Result<ValueVec, RuntimeError> {
+    let iterator = vm.make_interruptible_iterator(iterable)?;
+    let size_hint = iterator.capacity_hint();
+    let mut result = ValueVec::with_capacity(size_hint);
+
+    for output in iterator.map(collect_pair) {
+        match output {
+            Output::Value(value) => result.push(value),
+            Output::Error(error) => return Err(error),
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(result)
+}
+
+// Returns the capacity to use for a buffer of up to `requested` values taken from the iterator
+//
+// The requested size is often provided by a script, so it's capped by the iterator's size hint to
+// avoid making huge allocations up front.
+pub(crate) fn capped_capacity(requested: usize, iterator: &impl Iterator) -> usize {
+    let (lower, upper) = iterator.size_hint();
+    requested.min(upper.unwrap_or(lower))
+}
+
 pub(crate) fn collect_pair(iterator_output: Output) -> Output {
     match iterator_output {
-        Output::ValuePair(first, second) => Output::Value(Value::Tuple(vec![first, second].into())),
+        Output::ValuePair(first, second) => {
+            Output::Value(Value::Tuple([first, second].into_iter().collect()))
+        }
         _ => iterator_output,
     }
 }
 
+// A value produced by `iterator.stop`, used to end a fold early
+#[derive(Debug)]
+struct Stop(Value);
+
+impl Stop {
+    fn make_value(value: Value) -> Value {
+        ExternalValue::with_shared_meta_map(Self(value), Self::meta()).into()
+    }
+
+    fn meta() -> Rc<RefCell<MetaMap>> {
+        STOP_META.with(|meta| meta.clone())
+    }
+}
+
+impl ExternalData for Stop {}
+
+thread_local! {
+    static STOP_META: Rc<RefCell<MetaMap>> = MetaMapBuilder::<Stop>::new("Stop")
+        .data_fn("value", |data| Ok(data.0.clone()))
+        .build();
+}
+
+// Returns the wrapped value if the value was produced by `iterator.stop`
+fn stopped_value(value: &Value) -> Option<Value> {
+    match value {
+        Value::ExternalValue(external) => external.data::<Stop>().map(|stop| stop.0.clone()),
+        _ => None,
+    }
+}
+
 fn fold_with_operator(
     vm: &mut Vm,
     iterable: Value,
@@ -767,7 +1404,7 @@ fn fold_with_operator(
 ) -> RuntimeResult {
     let mut result = initial_value;
 
-    for output in vm.make_iterator(iterable)?.map(collect_pair) {
+    for output in vm.make_interruptible_iterator(iterable)?.map(collect_pair) {
         match output {
             Output::Value(rhs_value) => {
                 result = vm.run_binary_op(operator, result, rhs_value)?;
@@ -787,7 +1424,7 @@ fn run_iterator_comparison(
 ) -> RuntimeResult {
     let mut result: Option<Value> = None;
 
-    for iter_output in vm.make_iterator(iterable)?.map(collect_pair) {
+    for iter_output in vm.make_interruptible_iterator(iterable)?.map(collect_pair) {
         match iter_output {
             Output::Value(value) => {
                 result = Some(match result {
@@ -813,7 +1450,7 @@ fn run_iterator_comparison_by_key(
 ) -> RuntimeResult {
     let mut result_and_key: Option<(Value, Value)> = None;
 
-    for iter_output in vm.make_iterator(iterable)?.map(collect_pair) {
+    for iter_output in vm.make_interruptible_iterator(iterable)?.map(collect_pair) {
         match iter_output {
             Output::Value(value) => {
                 let key = vm.run_function(key_fn.clone(), CallArgs::Single(value.clone()))?;
@@ -834,6 +1471,70 @@ fn run_iterator_comparison_by_key(
     Ok(result_and_key.map_or(Value::Null, |(value, _)| value))
 }
 
+// Finds the index of the minimum (or maximum if `invert_result` is Yes) value in the iterable
+//
+// If a key function is provided then it's called once per value, with the keys being compared.
+//
+// Returns a tuple containing the index and value, with ties resolved in favour of the first
+// occurrence. Null is returned if the iterable is empty.
+fn run_iterator_comparison_with_index(
+    vm: &mut Vm,
+    iterable: Value,
+    key_fn: Option<Value>,
+    invert_result: InvertResult,
+) -> RuntimeResult {
+    use {InvertResult::*, Value::Bool};
+
+    // (index, value, key)
+    let mut result: Option<(usize, Value, Value)> = None;
+
+    for (index, iter_output) in vm
+        .make_interruptible_iterator(iterable)?
+        .map(collect_pair)
+        .enumerate()
+    {
+        match iter_output {
+            Output::Value(value) => {
+                let key = match &key_fn {
+                    Some(f) => vm.run_function(f.clone(), CallArgs::Single(value.clone()))?,
+                    None => value.clone(),
+                };
+
+                let replace_result = match &result {
+                    Some((_, _, result_key)) => {
+                        // Only replace the current result if the new key is strictly lesser
+                        // (or greater), so that the first occurrence wins in the case of a tie.
+                        let (a, b) = match invert_result {
+                            No => (key.clone(), result_key.clone()),
+                            Yes => (result_key.clone(), key.clone()),
+                        };
+                        match vm.run_binary_op(BinaryOp::Less, a, b)? {
+                            Bool(less) => less,
+                            other => {
+                                return runtime_error!(
+                                    "Expected Bool from '<' comparison, found '{}'",
+                                    other.type_as_string()
+                                )
+                            }
+                        }
+                    }
+                    None => true,
+                };
+
+                if replace_result {
+                    result = Some((index, value, key));
+                }
+            }
+            Output::Error(error) => return Err(error),
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(result.map_or(Value::Null, |(index, value, _)| {
+        Value::Tuple([index.into(), value].into_iter().collect())
+    }))
+}
+
 // Compares two values using BinaryOp::Less
 //
 // Returns the lesser of the two values, unless `invert_result` is set to Yes
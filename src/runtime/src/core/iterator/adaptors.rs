@@ -1,9 +1,14 @@
 //! Adapators used by the `iterator` core library module
 
 use {
-    super::collect_pair,
+    super::{capped_capacity, collect_pair},
     crate::{prelude::*, ValueIteratorOutput as Output},
-    std::{error, fmt},
+    rustc_hash::FxHasher,
+    std::{
+        collections::{HashSet, VecDeque},
+        error, fmt,
+        hash::BuildHasherDefault,
+    },
 };
 
 /// An iterator that links the output of two iterators together in a chained sequence
@@ -39,6 +44,13 @@ impl KotoIterator for Chain {
         }
         self.iter_b.might_have_side_effects()
     }
+
+    fn name(&self) -> String {
+        match &self.iter_a {
+            Some(iter_a) => format!("Chain({}, {})", iter_a.name(), self.iter_b.name()),
+            None => format!("Chain({})", self.iter_b.name()),
+        }
+    }
 }
 
 impl Iterator for Chain {
@@ -76,7 +88,9 @@ impl Iterator for Chain {
     }
 }
 
-/// An iterator that splits the incoming iterator into iterators of size N
+/// An iterator that splits the incoming iterator into tuples of size N
+///
+/// The final tuple may have fewer than N values.
 pub struct Chunks {
     iter: ValueIterator,
     chunk_size: usize,
@@ -87,8 +101,6 @@ impl Chunks {
     pub fn new(iter: ValueIterator, chunk_size: usize) -> Result<Self, ChunksError> {
         if chunk_size < 1 {
             Err(ChunksError::ChunkSizeMustBeAtLeastOne)
-        } else if iter.might_have_side_effects() {
-            Err(ChunksError::IteratorMightHaveSideEffects)
         } else {
             Ok(Self { iter, chunk_size })
         }
@@ -107,27 +119,30 @@ impl KotoIterator for Chunks {
     fn might_have_side_effects(&self) -> bool {
         self.iter.might_have_side_effects()
     }
+
+    fn name(&self) -> String {
+        format!("Chunks({}) <- {}", self.chunk_size, self.iter.name())
+    }
 }
 
 impl Iterator for Chunks {
     type Item = Output;
 
     fn next(&mut self) -> Option<Self::Item> {
-        // Make a copy of the iterator, positioned at the start of the next chunk
-        let result_iter = self.iter.make_copy();
-        // Is there at least one element in the chunk?
-        if self.iter.next().is_some() {
-            // Skip the input iterator to the end of the chunk
-            // (one element from the chunk has already been consumed).
-            if self.chunk_size > 1 {
-                self.iter.nth(self.chunk_size - 2);
+        let mut chunk = ValueVec::with_capacity(capped_capacity(self.chunk_size, &self.iter));
+
+        for output in (&mut self.iter).take(self.chunk_size).map(collect_pair) {
+            match output {
+                Output::Value(value) => chunk.push(value),
+                error @ Output::Error(_) => return Some(error),
+                _ => unreachable!(),
             }
+        }
 
-            // Make the chunk iterator by using a Take adaptor.
-            let chunk_iter = Take::new(result_iter, self.chunk_size);
-            Some(Output::Value(ValueIterator::new(chunk_iter).into()))
-        } else {
+        if chunk.is_empty() {
             None
+        } else {
+            Some(Output::Value(Value::Tuple(chunk.into_iter().collect())))
         }
     }
 
@@ -157,16 +172,12 @@ impl Iterator for Chunks {
 /// An error that can be returned by [Chunks::new]
 #[allow(missing_docs)]
 pub enum ChunksError {
-    IteratorMightHaveSideEffects,
     ChunkSizeMustBeAtLeastOne,
 }
 
 impl fmt::Display for ChunksError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ChunksError::IteratorMightHaveSideEffects => {
-                write!(f, "the iterator to be chunked should not run any functions")
-            }
             ChunksError::ChunkSizeMustBeAtLeastOne => {
                 write!(f, "the chunk size must be at least 1")
             }
@@ -210,6 +221,10 @@ impl KotoIterator for Cycle {
     fn might_have_side_effects(&self) -> bool {
         self.iter.might_have_side_effects()
     }
+
+    fn name(&self) -> String {
+        format!("Cycle <- {}", self.iter.name())
+    }
 }
 
 impl Iterator for Cycle {
@@ -240,6 +255,110 @@ impl Iterator for Cycle {
     }
 }
 
+/// An iterator that skips values that are duplicates of the previously output value
+///
+/// Values are compared with the `==` operator, or with a comparison function that returns true
+/// when the values should be treated as duplicates. Value pairs are collected into tuples before
+/// being compared.
+pub struct Dedup {
+    iter: ValueIterator,
+    compare: Option<Value>,
+    previous: Option<Value>,
+    vm: Vm,
+}
+
+impl Dedup {
+    /// Creates a new [Dedup] adaptor
+    pub fn new(iter: ValueIterator, compare: Option<Value>, vm: Vm) -> Self {
+        Self {
+            iter,
+            compare,
+            previous: None,
+            vm,
+        }
+    }
+
+    fn is_duplicate(&mut self, previous: Value, value: Value) -> Result<bool, RuntimeError> {
+        let result = match &self.compare {
+            Some(compare) => self
+                .vm
+                .run_function(compare.clone(), CallArgs::Separate(&[previous, value]))?,
+            None => self.vm.run_binary_op(BinaryOp::Equal, previous, value)?,
+        };
+
+        match result {
+            Value::Bool(result) => Ok(result),
+            unexpected => Err(make_runtime_error!(format!(
+                "iterator.dedup: Expected a Bool to be returned from the {}, found '{}'",
+                if self.compare.is_some() {
+                    "comparison function"
+                } else {
+                    "equality comparison"
+                },
+                unexpected.type_as_string()
+            ))),
+        }
+    }
+}
+
+impl KotoIterator for Dedup {
+    fn make_copy(&self) -> ValueIterator {
+        let result = Self {
+            iter: self.iter.make_copy(),
+            compare: self.compare.clone(),
+            previous: self.previous.clone(),
+            vm: self.vm.spawn_shared_vm(),
+        };
+        ValueIterator::new(result)
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> String {
+        format!("Dedup <- {}", self.iter.name())
+    }
+}
+
+impl Iterator for Dedup {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let output = self.iter.next()?;
+            let value = match collect_pair(output.clone()) {
+                Output::Value(value) => value,
+                error => return Some(error),
+            };
+
+            // The first value is always output, with later values being compared against the
+            // previously output value.
+            if let Some(previous) = &self.previous {
+                match self.is_duplicate(previous.clone(), value.clone()) {
+                    Ok(true) => continue,
+                    Ok(false) => {}
+                    Err(error) => return Some(Output::Error(error)),
+                }
+            }
+
+            self.previous = Some(value);
+            return Some(output);
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        // At least one value will be output if a value hasn't yet been output
+        let lower = if self.previous.is_none() {
+            lower.min(1)
+        } else {
+            0
+        };
+        (lower, upper)
+    }
+}
+
 /// An iterator that runs a function on each output value from the adapted iterator
 pub struct Each {
     iter: ValueIterator,
@@ -267,6 +386,10 @@ impl KotoIterator for Each {
     fn might_have_side_effects(&self) -> bool {
         true
     }
+
+    fn name(&self) -> String {
+        format!("Each <- {}", self.iter.name())
+    }
 }
 
 impl Iterator for Each {
@@ -294,6 +417,77 @@ impl Iterator for Each {
     }
 }
 
+/// An iterator that runs a function on each output value from the adapted iterator,
+/// with the value's iteration position passed as the first argument
+///
+/// Value pairs are collected into tuples before being passed to the function.
+pub struct EachIndexed {
+    iter: ValueIterator,
+    function: Value,
+    index: usize,
+    vm: Vm,
+}
+
+impl EachIndexed {
+    /// Creates a new [EachIndexed] adaptor
+    pub fn new(iter: ValueIterator, function: Value, vm: Vm) -> Self {
+        Self {
+            iter,
+            function,
+            index: 0,
+            vm,
+        }
+    }
+}
+
+impl KotoIterator for EachIndexed {
+    fn make_copy(&self) -> ValueIterator {
+        let result = Self {
+            iter: self.iter.make_copy(),
+            function: self.function.clone(),
+            index: self.index,
+            vm: self.vm.spawn_shared_vm(),
+        };
+        ValueIterator::new(result)
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> String {
+        format!("EachIndexed <- {}", self.iter.name())
+    }
+}
+
+impl Iterator for EachIndexed {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.iter.next().map(collect_pair).map(|output| {
+            let function = self.function.clone();
+            let functor_result = match output {
+                // The index and value are passed as separate arguments,
+                // avoiding the need to allocate a tuple for each value.
+                Output::Value(value) => self
+                    .vm
+                    .run_function(function, CallArgs::Separate(&[self.index.into(), value])),
+                other => return other,
+            };
+            match functor_result {
+                Ok(result) => Output::Value(result),
+                Err(error) => Output::Error(error),
+            }
+        });
+        self.index += 1;
+        result
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
 /// An iterator that attaches an enumerated iteration position to each value
 pub struct Enumerate {
     iter: ValueIterator,
@@ -319,6 +513,10 @@ impl KotoIterator for Enumerate {
     fn might_have_side_effects(&self) -> bool {
         self.iter.might_have_side_effects()
     }
+
+    fn name(&self) -> String {
+        format!("Enumerate <- {}", self.iter.name())
+    }
 }
 
 impl Iterator for Enumerate {
@@ -374,6 +572,10 @@ impl KotoIterator for Flatten {
     fn might_have_side_effects(&self) -> bool {
         true
     }
+
+    fn name(&self) -> String {
+        format!("Flatten <- {}", self.iter.name())
+    }
 }
 
 impl Iterator for Flatten {
@@ -403,6 +605,73 @@ impl Iterator for Flatten {
     }
 }
 
+/// An iterator that passes each output from the adapted iterator to a function before yielding it
+///
+/// The function is called for side effects (e.g. logging), with its result being ignored.
+/// Errors are passed to the function as a tuple of `'error'` and the error's value,
+/// and are then yielded unchanged.
+pub struct Inspect {
+    iter: ValueIterator,
+    function: Value,
+    vm: Vm,
+}
+
+impl Inspect {
+    /// Creates a new [Inspect] adaptor
+    pub fn new(iter: ValueIterator, function: Value, vm: Vm) -> Self {
+        Self { iter, function, vm }
+    }
+}
+
+impl KotoIterator for Inspect {
+    fn make_copy(&self) -> ValueIterator {
+        let result = Self {
+            iter: self.iter.make_copy(),
+            function: self.function.clone(),
+            vm: self.vm.spawn_shared_vm(),
+        };
+        ValueIterator::new(result)
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> String {
+        format!("Inspect <- {}", self.iter.name())
+    }
+}
+
+impl Iterator for Inspect {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|output| {
+            let function = self.function.clone();
+            let inspect_result = match &output {
+                Output::Value(value) => self
+                    .vm
+                    .run_function(function, CallArgs::Single(value.clone())),
+                Output::ValuePair(a, b) => self
+                    .vm
+                    .run_function(function, CallArgs::AsTuple(&[a.clone(), b.clone()])),
+                Output::Error(error) => self.vm.run_function(
+                    function,
+                    CallArgs::AsTuple(&["error".into(), error.to_catch_value()]),
+                ),
+            };
+            match inspect_result {
+                Ok(_) => output,
+                Err(error) => Output::Error(error),
+            }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
 /// An iterator that inserts a separator value between each output value from the adapted iterator
 pub struct Intersperse {
     iter: ValueIterator,
@@ -437,6 +706,10 @@ impl KotoIterator for Intersperse {
     fn might_have_side_effects(&self) -> bool {
         self.iter.might_have_side_effects()
     }
+
+    fn name(&self) -> String {
+        format!("Intersperse <- {}", self.iter.name())
+    }
 }
 
 impl Iterator for Intersperse {
@@ -504,6 +777,10 @@ impl KotoIterator for IntersperseWith {
     fn might_have_side_effects(&self) -> bool {
         true
     }
+
+    fn name(&self) -> String {
+        format!("IntersperseWith <- {}", self.iter.name())
+    }
 }
 
 impl Iterator for IntersperseWith {
@@ -581,6 +858,10 @@ impl KotoIterator for Keep {
     fn might_have_side_effects(&self) -> bool {
         true
     }
+
+    fn name(&self) -> String {
+        format!("Keep <- {}", self.iter.name())
+    }
 }
 
 impl Iterator for Keep {
@@ -644,6 +925,10 @@ impl KotoIterator for PairFirst {
     fn might_have_side_effects(&self) -> bool {
         self.iter.might_have_side_effects()
     }
+
+    fn name(&self) -> String {
+        format!("PairFirst <- {}", self.iter.name())
+    }
 }
 
 impl Iterator for PairFirst {
@@ -684,6 +969,10 @@ impl KotoIterator for PairSecond {
     fn might_have_side_effects(&self) -> bool {
         self.iter.might_have_side_effects()
     }
+
+    fn name(&self) -> String {
+        format!("PairSecond <- {}", self.iter.name())
+    }
 }
 
 impl Iterator for PairSecond {
@@ -701,6 +990,75 @@ impl Iterator for PairSecond {
     }
 }
 
+/// An iterator adaptor that allows the next output of the input iterator to be inspected
+/// without advancing the iterator
+pub struct Peekable {
+    iter: ValueIterator,
+    // Some(None) when the input iterator has been found to be exhausted
+    peeked: Option<Option<Output>>,
+}
+
+impl Peekable {
+    /// Creates a new [Peekable] adaptor
+    pub fn new(iter: ValueIterator) -> Self {
+        Self { iter, peeked: None }
+    }
+}
+
+impl KotoIterator for Peekable {
+    fn make_copy(&self) -> ValueIterator {
+        let result = Self {
+            iter: self.iter.make_copy(),
+            peeked: self.peeked.clone(),
+        };
+        ValueIterator::new(result)
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        self.iter.might_have_side_effects()
+    }
+
+    fn name(&self) -> String {
+        format!("Peekable <- {}", self.iter.name())
+    }
+
+    fn is_peekable(&self) -> bool {
+        true
+    }
+
+    fn peek(&mut self) -> Option<Output> {
+        // Errors are cached along with values, so that they're returned from the following call
+        // to next()
+        let iter = &mut self.iter;
+        self.peeked.get_or_insert_with(|| iter.next()).clone()
+    }
+}
+
+impl Iterator for Peekable {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.peeked.take() {
+            Some(peeked) => peeked,
+            None => self.iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.peeked {
+            Some(None) => (0, Some(0)),
+            Some(Some(_)) => {
+                let (lower, upper) = self.iter.size_hint();
+                (
+                    lower.saturating_add(1),
+                    upper.and_then(|upper| upper.checked_add(1)),
+                )
+            }
+            None => self.iter.size_hint(),
+        }
+    }
+}
+
 /// An iterator adaptor that reverses the output of the input iterator
 pub struct Reversed {
     iter: ValueIterator,
@@ -730,6 +1088,10 @@ impl KotoIterator for Reversed {
     fn might_have_side_effects(&self) -> bool {
         self.iter.might_have_side_effects()
     }
+
+    fn name(&self) -> String {
+        format!("Reversed <- {}", self.iter.name())
+    }
 }
 
 impl Iterator for Reversed {
@@ -768,61 +1130,745 @@ impl fmt::Debug for ReversedError {
 
 impl error::Error for ReversedError {}
 
-/// An iterator that takes up to N values from the adapted iterator, and then stops
-pub struct Take {
-    iter: ValueIterator,
-    remaining: usize,
+/// An iterator that takes values from a series of iterators in rotation
+///
+/// When an iterator is exhausted it's removed from the rotation, and iteration continues with the
+/// remaining iterators until they're all exhausted.
+pub struct RoundRobin {
+    iterators: Vec<ValueIterator>,
+    index: usize,
 }
 
-impl Take {
-    /// Creates a new [Take] adaptor
-    pub fn new(iter: ValueIterator, count: usize) -> Self {
+impl RoundRobin {
+    /// Creates a new [RoundRobin] adaptor
+    pub fn new(iterators: Vec<ValueIterator>) -> Self {
         Self {
-            iter,
-            remaining: count,
+            iterators,
+            index: 0,
         }
     }
 }
 
-impl KotoIterator for Take {
+impl KotoIterator for RoundRobin {
     fn make_copy(&self) -> ValueIterator {
         let result = Self {
-            iter: self.iter.make_copy(),
-            remaining: self.remaining,
+            iterators: self.iterators.iter().map(|iter| iter.make_copy()).collect(),
+            index: self.index,
         };
         ValueIterator::new(result)
     }
 
     fn might_have_side_effects(&self) -> bool {
-        self.iter.might_have_side_effects()
+        self.iterators
+            .iter()
+            .any(|iter| iter.might_have_side_effects())
+    }
+
+    fn name(&self) -> String {
+        let names: Vec<String> = self.iterators.iter().map(|iter| iter.name()).collect();
+        format!("RoundRobin({})", names.join(", "))
     }
 }
 
-impl Iterator for Take {
+impl Iterator for RoundRobin {
     type Item = Output;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.remaining > 0 {
-            self.remaining -= 1;
-            self.iter.next()
-        } else {
-            None
-        }
-    }
+        while !self.iterators.is_empty() {
+            if self.index >= self.iterators.len() {
+                self.index = 0;
+            }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let (lower, upper) = self.iter.size_hint();
-        (
+            match self.iterators[self.index].next() {
+                Some(output) => {
+                    self.index += 1;
+                    return Some(output);
+                }
+                None => {
+                    // The iterator is exhausted, so remove it from the rotation,
+                    // the next iterator will then be found at the same index.
+                    self.iterators.remove(self.index);
+                }
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iterators
+            .iter()
+            .fold((0, Some(0)), |(lower, upper), iter| {
+                let (iter_lower, iter_upper) = iter.size_hint();
+                let upper = match (upper, iter_upper) {
+                    (Some(a), Some(b)) => a.checked_add(b),
+                    _ => None,
+                };
+                (lower.saturating_add(iter_lower), upper)
+            })
+    }
+}
+
+/// An iterator that outputs the running results of passing a state value along with each output
+/// value from the adapted iterator into a function
+///
+/// The function's result is output, and then becomes the state that's passed to the function
+/// along with the next value. Value pairs are collected into tuples before being passed to the
+/// function.
+pub struct Scan {
+    iter: ValueIterator,
+    state: Value,
+    function: Value,
+    vm: Vm,
+}
+
+impl Scan {
+    /// Creates a new [Scan] adaptor
+    pub fn new(iter: ValueIterator, initial_state: Value, function: Value, vm: Vm) -> Self {
+        Self {
+            iter,
+            state: initial_state,
+            function,
+            vm,
+        }
+    }
+}
+
+impl KotoIterator for Scan {
+    fn make_copy(&self) -> ValueIterator {
+        let result = Self {
+            iter: self.iter.make_copy(),
+            state: self.state.clone(),
+            function: self.function.clone(),
+            vm: self.vm.spawn_shared_vm(),
+        };
+        ValueIterator::new(result)
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> String {
+        format!("Scan <- {}", self.iter.name())
+    }
+}
+
+impl Iterator for Scan {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(collect_pair)
+            .map(|output| match output {
+                Output::Value(value) => {
+                    let state = self.state.clone();
+                    match self
+                        .vm
+                        .run_function(self.function.clone(), CallArgs::Separate(&[state, value]))
+                    {
+                        Ok(result) => {
+                            self.state = result.clone();
+                            Output::Value(result)
+                        }
+                        Err(error) => Output::Error(error),
+                    }
+                }
+                other => other,
+            })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// An iterator that outputs all but the last N values from the adapted iterator
+///
+/// The adapted iterator's output is buffered in a window of N + 1 values,
+/// with the oldest value being output each time the window is full.
+pub struct SkipLast {
+    iter: ValueIterator,
+    buffer: VecDeque<Output>,
+    count: usize,
+}
+
+impl SkipLast {
+    /// Creates a new [SkipLast] adaptor
+    pub fn new(iter: ValueIterator, count: usize) -> Self {
+        // The buffer holds up to count + 1 values
+        let capacity = capped_capacity(count.saturating_add(1), &iter);
+        Self {
+            iter,
+            buffer: VecDeque::with_capacity(capacity),
+            count,
+        }
+    }
+}
+
+impl KotoIterator for SkipLast {
+    fn make_copy(&self) -> ValueIterator {
+        let result = Self {
+            iter: self.iter.make_copy(),
+            buffer: self.buffer.clone(),
+            count: self.count,
+        };
+        ValueIterator::new(result)
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        self.iter.might_have_side_effects()
+    }
+
+    fn name(&self) -> String {
+        format!("SkipLast({}) <- {}", self.count, self.iter.name())
+    }
+}
+
+impl Iterator for SkipLast {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.buffer.len() <= self.count {
+            match self.iter.next() {
+                Some(error @ Output::Error(_)) => return Some(error),
+                Some(output) => self.buffer.push_back(output),
+                None => return None,
+            }
+        }
+
+        self.buffer.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        let buffered = self.buffer.len();
+        (
+            (lower + buffered).saturating_sub(self.count),
+            upper.map(|upper| (upper + buffered).saturating_sub(self.count)),
+        )
+    }
+}
+
+/// An iterator that skips values while they pass a predicate, and then outputs all other values
+///
+/// The values are skipped lazily when the first value is requested, and the first value that
+/// fails the predicate is included in the output.
+pub struct SkipWhile {
+    iter: ValueIterator,
+    predicate: Value,
+    vm: Vm,
+    skipping: bool,
+}
+
+impl SkipWhile {
+    /// Creates a new [SkipWhile] adaptor
+    pub fn new(iter: ValueIterator, predicate: Value, vm: Vm) -> Self {
+        Self {
+            iter,
+            predicate,
+            vm,
+            skipping: true,
+        }
+    }
+}
+
+impl KotoIterator for SkipWhile {
+    fn make_copy(&self) -> ValueIterator {
+        let result = Self {
+            iter: self.iter.make_copy(),
+            predicate: self.predicate.clone(),
+            vm: self.vm.spawn_shared_vm(),
+            skipping: self.skipping,
+        };
+        ValueIterator::new(result)
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> String {
+        format!("SkipWhile <- {}", self.iter.name())
+    }
+}
+
+impl Iterator for SkipWhile {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.skipping {
+            return self.iter.next();
+        }
+
+        for output in &mut self.iter {
+            let predicate = self.predicate.clone();
+            let predicate_result = match &output {
+                Output::Value(value) => self
+                    .vm
+                    .run_function(predicate, CallArgs::Single(value.clone())),
+                Output::ValuePair(a, b) => self
+                    .vm
+                    .run_function(predicate, CallArgs::AsTuple(&[a.clone(), b.clone()])),
+                error @ Output::Error(_) => return Some(error.clone()),
+            };
+
+            let result = match predicate_result {
+                Ok(Value::Bool(true)) => continue,
+                Ok(Value::Bool(false)) => {
+                    self.skipping = false;
+                    output
+                }
+                Ok(unexpected) => Output::Error(make_runtime_error!(format!(
+                    "iterator.skip_while: Expected a Bool to be returned from the predicate, \
+                     found '{}'",
+                    unexpected.type_as_string()
+                ))),
+                Err(error) => Output::Error(error),
+            };
+
+            return Some(result);
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        if self.skipping {
+            (0, upper)
+        } else {
+            (lower, upper)
+        }
+    }
+}
+
+/// An iterator that skips values up to a start index, and then yields values up to an end index
+///
+/// The values are skipped lazily when the first value is requested.
+pub struct Slice {
+    iter: ValueIterator,
+    start: usize,
+    end: Option<usize>,
+    // The number of values that still need to be skipped
+    to_skip: usize,
+    // The number of values remaining to be yielded after skipping, None for open-ended slices
+    remaining: Option<usize>,
+}
+
+impl Slice {
+    /// Creates a new [Slice] adaptor
+    ///
+    /// `end` must be greater than or equal to `start`.
+    pub fn new(iter: ValueIterator, start: usize, end: Option<usize>) -> Self {
+        debug_assert!(end.map_or(true, |end| end >= start));
+
+        Self {
+            iter,
+            start,
+            end,
+            to_skip: start,
+            remaining: end.map(|end| end - start),
+        }
+    }
+}
+
+impl KotoIterator for Slice {
+    fn make_copy(&self) -> ValueIterator {
+        let result = Self {
+            iter: self.iter.make_copy(),
+            start: self.start,
+            end: self.end,
+            to_skip: self.to_skip,
+            remaining: self.remaining,
+        };
+        ValueIterator::new(result)
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        self.iter.might_have_side_effects()
+    }
+
+    fn name(&self) -> String {
+        match self.end {
+            Some(end) => format!("Slice({}..{end}) <- {}", self.start, self.iter.name()),
+            None => format!("Slice({}..) <- {}", self.start, self.iter.name()),
+        }
+    }
+}
+
+impl Iterator for Slice {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.to_skip > 0 {
+            self.to_skip -= 1;
+            match self.iter.next() {
+                Some(error @ Output::Error(_)) => return Some(error),
+                Some(_) => {}
+                None => {
+                    self.to_skip = 0;
+                    self.remaining = Some(0);
+                    return None;
+                }
+            }
+        }
+
+        match &mut self.remaining {
+            Some(0) => None,
+            Some(remaining) => {
+                *remaining -= 1;
+                self.iter.next()
+            }
+            None => self.iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        let lower = lower.saturating_sub(self.to_skip);
+        let upper = upper.map(|upper| upper.saturating_sub(self.to_skip));
+
+        match self.remaining {
+            Some(remaining) => (
+                lower.min(remaining),
+                Some(upper.map_or(remaining, |upper| upper.min(remaining))),
+            ),
+            None => (lower, upper),
+        }
+    }
+}
+
+/// An iterator that outputs the first value from an iterator, and then every step-th value
+pub struct StepBy {
+    iter: ValueIterator,
+    step: usize,
+    first_taken: bool,
+}
+
+impl StepBy {
+    /// Creates a new [StepBy] adaptor
+    ///
+    /// The step is expected to be greater than zero.
+    pub fn new(iter: ValueIterator, step: usize) -> Self {
+        Self {
+            iter,
+            step,
+            first_taken: false,
+        }
+    }
+}
+
+impl KotoIterator for StepBy {
+    fn make_copy(&self) -> ValueIterator {
+        let result = Self {
+            iter: self.iter.make_copy(),
+            step: self.step,
+            first_taken: self.first_taken,
+        };
+        ValueIterator::new(result)
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        self.iter.might_have_side_effects()
+    }
+
+    fn name(&self) -> String {
+        format!("StepBy({}) <- {}", self.step, self.iter.name())
+    }
+}
+
+impl Iterator for StepBy {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.first_taken {
+            for _ in 1..self.step {
+                match self.iter.next() {
+                    Some(Output::Error(error)) => return Some(Output::Error(error)),
+                    Some(_) => {}
+                    None => return None,
+                }
+            }
+        } else {
+            self.first_taken = true;
+        }
+
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let steps = |remaining: usize| {
+            if self.first_taken {
+                remaining / self.step
+            } else if remaining > 0 {
+                1 + (remaining - 1) / self.step
+            } else {
+                0
+            }
+        };
+
+        let (lower, upper) = self.iter.size_hint();
+        (steps(lower), upper.map(steps))
+    }
+}
+
+/// An iterator that takes up to N values from the adapted iterator, and then stops
+pub struct Take {
+    iter: ValueIterator,
+    remaining: usize,
+}
+
+impl Take {
+    /// Creates a new [Take] adaptor
+    pub fn new(iter: ValueIterator, count: usize) -> Self {
+        Self {
+            iter,
+            remaining: count,
+        }
+    }
+}
+
+impl KotoIterator for Take {
+    fn make_copy(&self) -> ValueIterator {
+        let result = Self {
+            iter: self.iter.make_copy(),
+            remaining: self.remaining,
+        };
+        ValueIterator::new(result)
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        self.iter.might_have_side_effects()
+    }
+
+    fn name(&self) -> String {
+        format!("Take({}) <- {}", self.remaining, self.iter.name())
+    }
+}
+
+impl Iterator for Take {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            self.iter.next()
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        (
             lower.min(self.remaining),
             upper.map(|upper| upper.min(self.remaining)),
         )
     }
 }
 
-/// An iterator that splits the incoming iterator into overlapping iterators of size N
+/// An iterator that takes values from the adapted iterator while they pass a predicate
+///
+/// Iteration stops at the first value that fails the predicate.
+pub struct TakeWhile {
+    iter: ValueIterator,
+    predicate: Value,
+    vm: Vm,
+    finished: bool,
+}
+
+impl TakeWhile {
+    /// Creates a new [TakeWhile] adaptor
+    pub fn new(iter: ValueIterator, predicate: Value, vm: Vm) -> Self {
+        Self {
+            iter,
+            predicate,
+            vm,
+            finished: false,
+        }
+    }
+}
+
+impl KotoIterator for TakeWhile {
+    fn make_copy(&self) -> ValueIterator {
+        let result = Self {
+            iter: self.iter.make_copy(),
+            predicate: self.predicate.clone(),
+            vm: self.vm.spawn_shared_vm(),
+            finished: self.finished,
+        };
+        ValueIterator::new(result)
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> String {
+        format!("TakeWhile <- {}", self.iter.name())
+    }
+}
+
+impl Iterator for TakeWhile {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let output = self.iter.next()?;
+        let predicate = self.predicate.clone();
+        let predicate_result = match &output {
+            Output::Value(value) => self
+                .vm
+                .run_function(predicate, CallArgs::Single(value.clone())),
+            Output::ValuePair(a, b) => self
+                .vm
+                .run_function(predicate, CallArgs::AsTuple(&[a.clone(), b.clone()])),
+            error @ Output::Error(_) => return Some(error.clone()),
+        };
+
+        match predicate_result {
+            Ok(Value::Bool(true)) => Some(output),
+            Ok(Value::Bool(false)) => {
+                self.finished = true;
+                None
+            }
+            Ok(unexpected) => {
+                self.finished = true;
+                Some(Output::Error(make_runtime_error!(format!(
+                    "iterator.take: Expected a Bool to be returned from the predicate, found '{}'",
+                    unexpected.type_as_string()
+                ))))
+            }
+            Err(error) => {
+                self.finished = true;
+                Some(Output::Error(error))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.finished {
+            (0, Some(0))
+        } else {
+            let (_lower, upper) = self.iter.size_hint();
+            (0, upper)
+        }
+    }
+}
+
+/// An iterator that outputs values from the adapted iterator that haven't been seen before
+///
+/// Immutable values (i.e. values that can be used as map keys) are tracked in a hash set,
+/// while other values are compared with previously seen values using the `==` operator.
+/// Value pairs are collected into tuples before being compared.
+pub struct Unique {
+    iter: ValueIterator,
+    seen_keys: HashSet<ValueKey, BuildHasherDefault<FxHasher>>,
+    seen_values: Vec<Value>,
+    vm: Vm,
+}
+
+impl Unique {
+    /// Creates a new [Unique] adaptor
+    pub fn new(iter: ValueIterator, vm: Vm) -> Self {
+        Self {
+            iter,
+            seen_keys: HashSet::default(),
+            seen_values: Vec::new(),
+            vm,
+        }
+    }
+
+    // Returns true if the value hasn't been seen before, and then marks the value as seen
+    fn is_unique(&mut self, value: &Value) -> Result<bool, RuntimeError> {
+        if value.is_immutable() {
+            return Ok(self.seen_keys.insert(ValueKey::from(value.clone())));
+        }
+
+        for seen in self.seen_values.iter() {
+            match self
+                .vm
+                .run_binary_op(BinaryOp::Equal, seen.clone(), value.clone())?
+            {
+                Value::Bool(true) => return Ok(false),
+                Value::Bool(false) => {}
+                unexpected => {
+                    return Err(make_runtime_error!(format!(
+                        "iterator.unique: Expected a Bool to be returned from the equality \
+                         comparison, found '{}'",
+                        unexpected.type_as_string()
+                    )))
+                }
+            }
+        }
+
+        self.seen_values.push(value.clone());
+        Ok(true)
+    }
+}
+
+impl KotoIterator for Unique {
+    fn make_copy(&self) -> ValueIterator {
+        let result = Self {
+            iter: self.iter.make_copy(),
+            seen_keys: self.seen_keys.clone(),
+            seen_values: self.seen_values.clone(),
+            vm: self.vm.spawn_shared_vm(),
+        };
+        ValueIterator::new(result)
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> String {
+        format!("Unique <- {}", self.iter.name())
+    }
+}
+
+impl Iterator for Unique {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let output = self.iter.next()?;
+            let value = match collect_pair(output.clone()) {
+                Output::Value(value) => value,
+                error => return Some(error),
+            };
+
+            match self.is_unique(&value) {
+                Ok(true) => return Some(output),
+                Ok(false) => continue,
+                Err(error) => return Some(Output::Error(error)),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        // The next value is unique if no values have been seen yet
+        let lower = if self.seen_keys.is_empty() && self.seen_values.is_empty() {
+            lower.min(1)
+        } else {
+            0
+        };
+        (lower, upper)
+    }
+}
+
+/// An iterator that splits the incoming iterator into overlapping tuples of size N
+///
+/// The most recent N values from the adapted iterator are kept in a rolling buffer.
 pub struct Windows {
     iter: ValueIterator,
-    end_iter: ValueIterator,
+    buffer: VecDeque<Value>,
     window_size: usize,
 }
 
@@ -831,18 +1877,11 @@ impl Windows {
     pub fn new(iter: ValueIterator, window_size: usize) -> Result<Self, WindowsError> {
         if window_size < 1 {
             Err(WindowsError::WindowSizeMustBeAtLeastOne)
-        } else if iter.might_have_side_effects() {
-            Err(WindowsError::IteratorMightHaveSideEffects)
         } else {
-            let mut end_iter = iter.make_copy();
-            // Skip the end iterator to 'one before the last' of the first window
-            if window_size > 1 {
-                end_iter.nth(window_size - 2);
-            }
-
+            let capacity = capped_capacity(window_size, &iter);
             Ok(Self {
                 iter,
-                end_iter,
+                buffer: VecDeque::with_capacity(capacity),
                 window_size,
             })
         }
@@ -853,7 +1892,7 @@ impl KotoIterator for Windows {
     fn make_copy(&self) -> ValueIterator {
         let result = Self {
             iter: self.iter.make_copy(),
-            end_iter: self.end_iter.make_copy(),
+            buffer: self.buffer.clone(),
             window_size: self.window_size,
         };
         ValueIterator::new(result)
@@ -862,48 +1901,61 @@ impl KotoIterator for Windows {
     fn might_have_side_effects(&self) -> bool {
         self.iter.might_have_side_effects()
     }
+
+    fn name(&self) -> String {
+        format!("Windows({}) <- {}", self.window_size, self.iter.name())
+    }
 }
 
 impl Iterator for Windows {
     type Item = Output;
 
     fn next(&mut self) -> Option<Self::Item> {
-        // The end iterator is positioned just before the end of the window,
-        // if next() outputs a value then there's at least one more window.
-        if self.end_iter.next().is_some() {
-            // Make the next window by using a Take adaptor.
-            let window_iter = Take::new(self.iter.make_copy(), self.window_size);
-
-            // Move the input iterator to the start of the next window
-            self.iter.next();
+        // A full buffer contains the previous window, so its oldest value is dropped
+        if self.buffer.len() == self.window_size {
+            self.buffer.pop_front();
+        }
 
-            Some(Output::Value(ValueIterator::new(window_iter).into()))
-        } else {
-            None
+        while self.buffer.len() < self.window_size {
+            match self.iter.next().map(collect_pair) {
+                Some(Output::Value(value)) => self.buffer.push_back(value),
+                Some(error @ Output::Error(_)) => return Some(error),
+                _ => return None,
+            }
         }
+
+        Some(Output::Value(Value::Tuple(
+            self.buffer.iter().cloned().collect(),
+        )))
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
         let (lower, upper) = self.iter.size_hint();
-        let lower = lower.saturating_sub(self.window_size) + 1;
-        let upper = upper.map(|upper| upper.saturating_sub(self.window_size) + 1);
-        (lower, upper)
+        // The values in a full buffer have already been output in the previous window,
+        // apart from the newest value which will be part of the next window.
+        let buffered = if self.buffer.len() == self.window_size {
+            self.window_size - 1
+        } else {
+            self.buffer.len()
+        };
+        let windows = |input: usize| {
+            input
+                .saturating_add(buffered + 1)
+                .saturating_sub(self.window_size)
+        };
+        (windows(lower), upper.map(windows))
     }
 }
 
 /// An error that can be returned by [Windows::new]
 #[allow(missing_docs)]
 pub enum WindowsError {
-    IteratorMightHaveSideEffects,
     WindowSizeMustBeAtLeastOne,
 }
 
 impl fmt::Display for WindowsError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            WindowsError::IteratorMightHaveSideEffects => {
-                write!(f, "the iterator to be chunked should not run any functions")
-            }
             WindowsError::WindowSizeMustBeAtLeastOne => {
                 write!(f, "the window size must be at least 1")
             }
@@ -919,6 +1971,163 @@ impl fmt::Debug for WindowsError {
 
 impl error::Error for WindowsError {}
 
+/// An iterator that overrides the capacity hint of the adapted iterator
+///
+/// The hint is reduced as values are consumed so that it reflects the number of remaining values.
+///
+/// The hint can't be verified, so it's only used as a capacity hint (see
+/// [KotoIterator::capacity_hint]), with the adapted iterator's size hint being left unchanged.
+pub struct WithSizeHint {
+    iter: ValueIterator,
+    remaining: usize,
+}
+
+impl WithSizeHint {
+    /// Creates a new [WithSizeHint] adaptor
+    pub fn new(iter: ValueIterator, size_hint: usize) -> Self {
+        Self {
+            iter,
+            remaining: size_hint,
+        }
+    }
+}
+
+impl KotoIterator for WithSizeHint {
+    fn make_copy(&self) -> ValueIterator {
+        let result = Self {
+            iter: self.iter.make_copy(),
+            remaining: self.remaining,
+        };
+        ValueIterator::new(result)
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        self.iter.might_have_side_effects()
+    }
+
+    fn name(&self) -> String {
+        format!("WithSizeHint({}) <- {}", self.remaining, self.iter.name())
+    }
+
+    fn capacity_hint(&self) -> usize {
+        // The adapted iterator's upper bound is respected if available, and the hint is capped
+        // to avoid making huge allocations for incorrect hints.
+        let (_lower, upper) = self.iter.size_hint();
+        let hint = match upper {
+            Some(upper) => self.remaining.min(upper),
+            None => self.remaining,
+        };
+        hint.min(MAX_CAPACITY_HINT)
+    }
+}
+
+// The maximum capacity hint that will be reported by WithSizeHint
+const MAX_CAPACITY_HINT: usize = 1 << 16;
+
+impl Iterator for WithSizeHint {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.iter.next();
+        if result.is_some() {
+            self.remaining = self.remaining.saturating_sub(1);
+        }
+        result
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// An iterator that threads a state value through calls to a function
+///
+/// The function is called with the current state and the next value from the adapted iterator,
+/// and returns a tuple containing the new state and the value to yield.
+/// If the yielded value is Null then the output is skipped.
+pub struct WithState {
+    iter: ValueIterator,
+    state: Value,
+    function: Value,
+    vm: Vm,
+}
+
+impl WithState {
+    /// Creates a new [WithState] adaptor
+    pub fn new(iter: ValueIterator, state: Value, function: Value, vm: Vm) -> Self {
+        Self {
+            iter,
+            state,
+            function,
+            vm,
+        }
+    }
+}
+
+impl KotoIterator for WithState {
+    fn make_copy(&self) -> ValueIterator {
+        let result = Self {
+            iter: self.iter.make_copy(),
+            state: self.state.clone(),
+            function: self.function.clone(),
+            vm: self.vm.spawn_shared_vm(),
+        };
+        ValueIterator::new(result)
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> String {
+        format!("WithState <- {}", self.iter.name())
+    }
+}
+
+impl Iterator for WithState {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for output in (&mut self.iter).map(collect_pair) {
+            let value = match output {
+                Output::Value(value) => value,
+                error @ Output::Error(_) => return Some(error),
+                _ => unreachable!(),
+            };
+
+            let function_result = self.vm.run_function(
+                self.function.clone(),
+                CallArgs::Separate(&[self.state.clone(), value]),
+            );
+
+            let result = match function_result {
+                Ok(Value::Tuple(t)) if t.len() == 2 => {
+                    self.state = t[0].clone();
+                    match &t[1] {
+                        Value::Null => continue,
+                        yielded => Output::Value(yielded.clone()),
+                    }
+                }
+                Ok(unexpected) => Output::Error(make_runtime_error!(format!(
+                    "iterator.with_state: Expected a Tuple containing the new state and \
+                     the value to yield, found '{}'",
+                    unexpected.type_as_string()
+                ))),
+                Err(error) => Output::Error(error),
+            };
+
+            return Some(result);
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_lower, upper) = self.iter.size_hint();
+        (0, upper)
+    }
+}
+
 /// An iterator that combines the output of two iterators, 'zipping' output pairs together
 pub struct Zip {
     iter_a: ValueIterator,
@@ -944,6 +2153,10 @@ impl KotoIterator for Zip {
     fn might_have_side_effects(&self) -> bool {
         self.iter_a.might_have_side_effects() || self.iter_b.might_have_side_effects()
     }
+
+    fn name(&self) -> String {
+        format!("Zip({}, {})", self.iter_a.name(), self.iter_b.name())
+    }
 }
 
 impl Iterator for Zip {
@@ -975,4 +2188,111 @@ impl Iterator for Zip {
     }
 }
 
+/// An iterator that zips the output of two iterators together until both are exhausted
+///
+/// Once one of the iterators is exhausted, its side of each output pair is filled with a provided
+/// fill value.
+pub struct ZipLongest {
+    iter_a: Option<ValueIterator>,
+    iter_b: Option<ValueIterator>,
+    fill: Value,
+}
+
+impl ZipLongest {
+    /// Creates a new [ZipLongest] adaptor
+    pub fn new(iter_a: ValueIterator, iter_b: ValueIterator, fill: Value) -> Self {
+        Self {
+            iter_a: Some(iter_a),
+            iter_b: Some(iter_b),
+            fill,
+        }
+    }
+}
+
+impl KotoIterator for ZipLongest {
+    fn make_copy(&self) -> ValueIterator {
+        let result = Self {
+            iter_a: self.iter_a.as_ref().map(|iter| iter.make_copy()),
+            iter_b: self.iter_b.as_ref().map(|iter| iter.make_copy()),
+            fill: self.fill.clone(),
+        };
+        ValueIterator::new(result)
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        let side_effects = |iter: &Option<ValueIterator>| match iter {
+            Some(iter) => iter.might_have_side_effects(),
+            None => false,
+        };
+        side_effects(&self.iter_a) || side_effects(&self.iter_b)
+    }
+
+    fn name(&self) -> String {
+        let name = |iter: &Option<ValueIterator>| match iter {
+            Some(iter) => iter.name(),
+            None => "Exhausted".to_string(),
+        };
+        format!("ZipLongest({}, {})", name(&self.iter_a), name(&self.iter_b))
+    }
+}
+
+// Takes the next value from one side of a ZipLongest
+//
+// Ok(None) is returned when the iterator is exhausted, after which it's discarded.
+fn next_or_exhausted(iter: &mut Option<ValueIterator>) -> Result<Option<Value>, Output> {
+    let output = match iter {
+        Some(iter) => iter.next().map(collect_pair),
+        None => return Ok(None),
+    };
+
+    match output {
+        Some(Output::Value(value)) => Ok(Some(value)),
+        Some(error @ Output::Error(_)) => Err(error),
+        _ => {
+            *iter = None;
+            Ok(None)
+        }
+    }
+}
+
+impl Iterator for ZipLongest {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value_a = match next_or_exhausted(&mut self.iter_a) {
+            Ok(value) => value,
+            Err(error) => return Some(error),
+        };
+        let value_b = match next_or_exhausted(&mut self.iter_b) {
+            Ok(value) => value,
+            Err(error) => return Some(error),
+        };
+
+        match (value_a, value_b) {
+            (None, None) => None,
+            (value_a, value_b) => Some(Output::ValuePair(
+                value_a.unwrap_or_else(|| self.fill.clone()),
+                value_b.unwrap_or_else(|| self.fill.clone()),
+            )),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let size_hint = |iter: &Option<ValueIterator>| match iter {
+            Some(iter) => iter.size_hint(),
+            None => (0, Some(0)),
+        };
+        let (lower_a, upper_a) = size_hint(&self.iter_a);
+        let (lower_b, upper_b) = size_hint(&self.iter_b);
+
+        let lower = lower_a.max(lower_b);
+        let upper = match (upper_a, upper_b) {
+            (Some(upper_a), Some(upper_b)) => Some(upper_a.max(upper_b)),
+            _ => None,
+        };
+
+        (lower, upper)
+    }
+}
+
 // See runtime/tests/iterator_adaptor_tests.rs for tests
@@ -28,6 +28,10 @@ impl KotoIterator for Repeat {
     fn might_have_side_effects(&self) -> bool {
         false
     }
+
+    fn name(&self) -> String {
+        "Repeat".into()
+    }
 }
 
 impl Iterator for Repeat {
@@ -66,6 +70,10 @@ impl KotoIterator for RepeatN {
     fn might_have_side_effects(&self) -> bool {
         false
     }
+
+    fn name(&self) -> String {
+        format!("Repeat({})", self.remaining)
+    }
 }
 
 impl Iterator for RepeatN {
@@ -110,6 +118,10 @@ impl KotoIterator for Generate {
     fn might_have_side_effects(&self) -> bool {
         true
     }
+
+    fn name(&self) -> String {
+        "Generate".into()
+    }
 }
 
 impl Iterator for Generate {
@@ -156,6 +168,10 @@ impl KotoIterator for GenerateN {
     fn might_have_side_effects(&self) -> bool {
         true
     }
+
+    fn name(&self) -> String {
+        format!("Generate({})", self.remaining)
+    }
 }
 
 impl Iterator for GenerateN {
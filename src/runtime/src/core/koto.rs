@@ -1,6 +1,13 @@
 //! The `koto` core library module
 
-use crate::prelude::*;
+mod memoize;
+
+use {
+    crate::prelude::*,
+    memoize::MemoizedFunction,
+    rustc_hash::FxHasher,
+    std::hash::{Hash, Hasher},
+};
 
 /// Initializes the `koto` core library module
 pub fn make_module() -> ValueMap {
@@ -8,17 +15,528 @@ pub fn make_module() -> ValueMap {
 
     let result = ValueMap::new();
 
+    result.add_fn("and", |vm, args| match vm.get_args(args) {
+        [f, g] if f.is_callable() && g.is_callable() => {
+            let f = f.clone();
+            let g = g.clone();
+            Ok(ExternalFunction(crate::ExternalFunction::new(
+                move |vm, args| {
+                    let args = vm.get_args(args).iter().cloned().collect::<ValueVec>();
+                    if run_predicate(vm, f.clone(), &args, "and")? {
+                        Ok(run_predicate(vm, g.clone(), &args, "and")?.into())
+                    } else {
+                        Ok(false.into())
+                    }
+                },
+                false,
+            )))
+        }
+        unexpected => type_error_with_slice("two Functions as arguments", unexpected),
+    });
+
+    result.add_fn("apply_op", |vm, args| match vm.get_args(args) {
+        [Str(op), a, b] => match binary_op_from_name(op) {
+            Some(op) => {
+                let a = a.clone();
+                let b = b.clone();
+                vm.run_binary_op(op, a, b)
+            }
+            None => {
+                let valid_ops = APPLY_OPS
+                    .iter()
+                    .map(|(name, _)| format!("'{name}'"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                runtime_error!(
+                    "koto.apply_op: Unknown operator '{op}', expected one of {valid_ops}"
+                )
+            }
+        },
+        unexpected => {
+            type_error_with_slice("an operator String and two Values as arguments", unexpected)
+        }
+    });
+
     result.add_value("args", Tuple(ValueTuple::default()));
 
+    result.add_fn("deep_equals", |vm, args| match vm.get_args(args) {
+        [a, b] => {
+            let a = a.clone();
+            let b = b.clone();
+            vm.values_equal(&a, &b).map(Bool)
+        }
+        unexpected => type_error_with_slice("two Values as arguments", unexpected),
+    });
+
+    result.add_fn("describe_iterator", |vm, args| match vm.get_args(args) {
+        [iterable] if iterable.is_iterable() => {
+            let iterable = iterable.clone();
+            Ok(vm.make_iterator(iterable)?.name().into())
+        }
+        unexpected => type_error_with_slice("an iterable value as argument", unexpected),
+    });
+
     result.add_fn("exports", |vm, _| Ok(Map(vm.exports().clone())));
 
+    result.add_fn("from_data", |vm, args| match vm.get_args(args) {
+        [value] => from_data(value).map_err(|e| e.with_prefix("koto.from_data")),
+        unexpected => type_error_with_slice("a single argument", unexpected),
+    });
+
+    result.add_fn("hash", |vm, args| match vm.get_args(args) {
+        [value] if value.is_immutable() => {
+            let mut hasher = FxHasher::default();
+            ValueKey::from(value.clone()).hash(&mut hasher);
+            Ok((hasher.finish() as i64).into())
+        }
+        [value] => runtime_error!(
+            "koto.hash: Only immutable Values can be hashed (found '{}')",
+            value.type_as_string()
+        ),
+        unexpected => type_error_with_slice("a single argument", unexpected),
+    });
+
+    add_type_check(&result, "is_bool", |value| matches!(value, Bool(_)));
+    add_type_check(&result, "is_iterator", |value| matches!(value, Iterator(_)));
+    add_type_check(&result, "is_list", |value| matches!(value, List(_)));
+    add_type_check(&result, "is_map", |value| matches!(value, Map(_)));
+    add_type_check(&result, "is_null", |value| matches!(value, Null));
+    add_type_check(&result, "is_number", |value| matches!(value, Number(_)));
+    add_type_check(&result, "is_string", |value| matches!(value, Str(_)));
+    add_type_check(&result, "is_tuple", |value| matches!(value, Tuple(_)));
+
+    result.add_fn("memoize_limited", |vm, args| match vm.get_args(args) {
+        [f, Number(n)] if f.is_callable() && *n >= 1.0 => {
+            let memoized = MemoizedFunction::new(f.clone(), n.into());
+            Ok(ExternalFunction(crate::ExternalFunction::with_callable(
+                memoized, false,
+            )))
+        }
+        unexpected => {
+            type_error_with_slice("a Function and a positive Number as arguments", unexpected)
+        }
+    });
+
+    result.add_fn("memoize_stats", |vm, args| match vm.get_args(args) {
+        [ExternalFunction(f)] => match f.function.downcast_ref::<MemoizedFunction>() {
+            Some(memoized) => Ok(Map(memoized.stats())),
+            None => runtime_error!(
+                "koto.memoize_stats: Expected a function produced by koto.memoize_limited"
+            ),
+        },
+        unexpected => type_error_with_slice(
+            "a function produced by koto.memoize_limited as argument",
+            unexpected,
+        ),
+    });
+
+    result.add_fn("modules", |vm, _| {
+        let result = ValueMap::new();
+        for (key, value) in vm.prelude().data().iter() {
+            if let Map(module) = value {
+                result.insert(key.clone(), Map(module.clone()));
+            }
+        }
+        result.data_mut().sort_keys();
+        Ok(Map(result))
+    });
+
+    result.add_fn("not", |vm, args| match vm.get_args(args) {
+        [f] if f.is_callable() => {
+            let f = f.clone();
+            Ok(ExternalFunction(crate::ExternalFunction::new(
+                move |vm, args| {
+                    let args = vm.get_args(args).iter().cloned().collect::<ValueVec>();
+                    Ok((!run_predicate(vm, f.clone(), &args, "not")?).into())
+                },
+                false,
+            )))
+        }
+        unexpected => type_error_with_slice("a Function as argument", unexpected),
+    });
+
+    result.add_fn("or", |vm, args| match vm.get_args(args) {
+        [f, g] if f.is_callable() && g.is_callable() => {
+            let f = f.clone();
+            let g = g.clone();
+            Ok(ExternalFunction(crate::ExternalFunction::new(
+                move |vm, args| {
+                    let args = vm.get_args(args).iter().cloned().collect::<ValueVec>();
+                    if run_predicate(vm, f.clone(), &args, "or")? {
+                        Ok(true.into())
+                    } else {
+                        Ok(run_predicate(vm, g.clone(), &args, "or")?.into())
+                    }
+                },
+                false,
+            )))
+        }
+        unexpected => type_error_with_slice("two Functions as arguments", unexpected),
+    });
+
+    result.add_fn("pipe_value", |vm, args| match vm.get_args(args) {
+        [value, functions @ ..] => {
+            if let Some(index) = functions.iter().position(|f| !f.is_callable()) {
+                return type_error_with_arg(
+                    "a Value followed by callable values as arguments",
+                    vm.get_args(args),
+                    index + 1,
+                );
+            }
+
+            let value = value.clone();
+            let functions = functions.iter().cloned().collect::<ValueVec>();
+            pipe_value(vm, value, &functions)
+        }
+        unexpected => type_error_with_slice(
+            "a Value followed by callable values as arguments",
+            unexpected,
+        ),
+    });
+
+    result.add_fn("pipe_value_with", |vm, args| match vm.get_args(args) {
+        [value, List(functions)] => {
+            let value = value.clone();
+            let functions = functions.data().iter().cloned().collect::<ValueVec>();
+            if let Some((index, f)) = functions.iter().enumerate().find(|(_, f)| !f.is_callable()) {
+                return runtime_error!(
+                    "koto.pipe_value_with: Expected a callable value at position {index} \
+                     in the List, found '{}'",
+                    f.type_as_string()
+                );
+            }
+
+            pipe_value(vm, value, &functions)
+        }
+        unexpected => type_error_with_slice(
+            "a Value and a List of callable values as arguments",
+            unexpected,
+        ),
+    });
+
+    result.add_fn("repeat_until", |vm, args| match vm.get_args(args) {
+        [body, condition] if body.is_callable() && condition.is_callable() => {
+            let body = body.clone();
+            let condition = condition.clone();
+            loop {
+                let last_result = vm.run_function(body.clone(), CallArgs::None)?;
+                if run_predicate(vm, condition.clone(), &[], "repeat_until")? {
+                    return Ok(last_result);
+                }
+            }
+        }
+        unexpected => type_error_with_slice("two Functions as arguments", unexpected),
+    });
+
+    result.add_fn("repeat_while", |vm, args| match vm.get_args(args) {
+        [condition, body] if condition.is_callable() && body.is_callable() => {
+            let condition = condition.clone();
+            let body = body.clone();
+            let mut last_result = Null;
+            while run_predicate(vm, condition.clone(), &[], "repeat_while")? {
+                last_result = vm.run_function(body.clone(), CallArgs::None)?;
+            }
+            Ok(last_result)
+        }
+        unexpected => type_error_with_slice("two Functions as arguments", unexpected),
+    });
+
     result.add_value("script_dir", Null);
     result.add_value("script_path", Null);
 
+    result.add_fn("times", |vm, args| match vm.get_args(args) {
+        [Number(n), f] if *n >= 0.0 && f.is_callable() => {
+            let n: usize = n.into();
+            let f = f.clone();
+            // The count is provided by the script, so the preallocation is capped to avoid
+            // making a huge allocation up front.
+            let mut result = ValueVec::with_capacity(n.min(MAX_TIMES_PREALLOCATION));
+            for i in 0..n {
+                result.push(vm.run_function(f.clone(), CallArgs::Single(i.into()))?);
+            }
+            Ok(List(ValueList::with_data(result)))
+        }
+        unexpected => type_error_with_slice(
+            "a non-negative Number and a Function as arguments",
+            unexpected,
+        ),
+    });
+
+    result.add_fn("to_data", |vm, args| match vm.get_args(args) {
+        [value] => to_data(value).map_err(|e| e.with_prefix("koto.to_data")),
+        unexpected => type_error_with_slice("a single argument", unexpected),
+    });
+
     result.add_fn("type", |vm, args| match vm.get_args(args) {
         [value] => Ok(value.type_as_string().into()),
         unexpected => type_error_with_slice("a single argument", unexpected),
     });
 
+    result.add_fn("type_name", |vm, args| match vm.get_args(args) {
+        [value] => Ok(value.type_as_string().into()),
+        unexpected => type_error_with_slice("a single argument", unexpected),
+    });
+
+    result.add_fn("unique_id", |vm, args| match vm.get_args(args) {
+        [] => Ok(vm.next_unique_id().into()),
+        unexpected => type_error_with_slice("no arguments", unexpected),
+    });
+
+    #[cfg(feature = "uuid")]
+    result.add_fn("uuid", |vm, args| match vm.get_args(args) {
+        [] => {
+            let mut bytes = [0; 16];
+            if let Err(error) = getrandom::getrandom(&mut bytes) {
+                return runtime_error!("koto.uuid: Failed to generate random data ({error})");
+            }
+            Ok(format_uuid_v4(bytes).into())
+        }
+        unexpected => type_error_with_slice("no arguments", unexpected),
+    });
+
+    result
+}
+
+// The operators that are supported by koto.apply_op
+// The maximum number of results that are preallocated by koto.times
+const MAX_TIMES_PREALLOCATION: usize = 1024;
+
+const APPLY_OPS: &[(&str, BinaryOp)] = &[
+    ("+", BinaryOp::Add),
+    ("-", BinaryOp::Subtract),
+    ("*", BinaryOp::Multiply),
+    ("/", BinaryOp::Divide),
+    ("%", BinaryOp::Remainder),
+    ("<", BinaryOp::Less),
+    ("<=", BinaryOp::LessOrEqual),
+    (">", BinaryOp::Greater),
+    (">=", BinaryOp::GreaterOrEqual),
+    ("==", BinaryOp::Equal),
+    ("!=", BinaryOp::NotEqual),
+];
+
+fn binary_op_from_name(name: &str) -> Option<BinaryOp> {
+    APPLY_OPS
+        .iter()
+        .find(|(op_name, _)| *op_name == name)
+        .map(|(_, op)| *op)
+}
+
+// Formats random bytes as a version 4 UUID, e.g. `f81d4fae-7dec-41d0-a765-00a0c91e6bf6`
+#[cfg(feature = "uuid")]
+fn format_uuid_v4(mut bytes: [u8; 16]) -> String {
+    // Set the version (4) and variant (RFC 4122) bits
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let mut result = String::with_capacity(36);
+    for (i, byte) in bytes.iter().enumerate() {
+        if matches!(i, 4 | 6 | 8 | 10) {
+            result.push('-');
+        }
+        result.push_str(&format!("{byte:02x}"));
+    }
     result
 }
+
+// Adds a function that checks the type of its argument
+//
+// The check is made against the value's core type, so a map that overrides `@type` is still
+// considered to be a Map.
+fn add_type_check(module: &ValueMap, name: &str, check: fn(&Value) -> bool) {
+    module.add_fn(name, move |vm, args| match vm.get_args(args) {
+        [value] => Ok(check(value).into()),
+        unexpected => type_error_with_slice("a single argument", unexpected),
+    });
+}
+
+// Passes the value through each of the functions in turn, returning the final result
+fn pipe_value(vm: &mut Vm, value: Value, functions: &[Value]) -> RuntimeResult {
+    let mut result = value;
+    for f in functions {
+        result = vm.run_function(f.clone(), CallArgs::Single(result))?;
+    }
+    Ok(result)
+}
+
+// Calls a predicate with the given args, returning an error if the result isn't a Bool
+fn run_predicate(vm: &mut Vm, f: Value, args: &[Value], name: &str) -> Result<bool, RuntimeError> {
+    match vm.run_function(f, CallArgs::Separate(args))? {
+        Value::Bool(result) => Ok(result),
+        unexpected => runtime_error!(
+            "koto.{name}: Expected a Bool to be returned from the predicate, found '{}'",
+            unexpected.type_as_string()
+        ),
+    }
+}
+
+/// The maximum depth of nested containers that will be converted by [to_data] and [from_data]
+///
+/// Containers can contain themselves, so the depth limit guards against infinite recursion.
+pub const MAX_DATA_DEPTH: usize = 128;
+
+/// The maximum number of values that will be taken from iterators by [to_data]
+///
+/// Iterators can be infinite, so the limit guards against [to_data] never returning.
+/// The limit applies to the total number of values taken from all of the iterators in the input.
+pub const MAX_DATA_ITERATOR_VALUES: usize = 1 << 20;
+
+/// Deeply converts a value into 'plain data'
+///
+/// The result only contains Null, Bool, Number, String, List, and Map values.
+///
+/// - Tuples are converted into Lists.
+/// - Maps are copied without their meta maps, and with their keys converted into Strings.
+/// - Num2 and Num4 values are converted into Lists of Numbers.
+/// - Ranges are converted into Maps with `start` and `end` entries,
+///   with the end of the range being exclusive.
+/// - Iterators are copied and then expanded into Lists,
+///   with output pairs being converted into Lists of two values.
+///
+/// An error is returned if any other type of value is encountered (e.g. functions),
+/// if the maximum nesting depth is exceeded, or if iterators produce more than
+/// [MAX_DATA_ITERATOR_VALUES] values.
+pub fn to_data(value: &Value) -> RuntimeResult {
+    let mut iterator_values = 0;
+    to_data_with_depth(value, 0, &mut iterator_values)
+}
+
+fn to_data_with_depth(value: &Value, depth: usize, iterator_values: &mut usize) -> RuntimeResult {
+    use Value::*;
+
+    if depth > MAX_DATA_DEPTH {
+        return data_depth_error();
+    }
+
+    let result = match value {
+        Null | Bool(_) | Number(_) | Str(_) => value.clone(),
+        Num2(n) => List(ValueList::from_slice(&[n.0.into(), n.1.into()])),
+        Num4(n) => List(ValueList::from_slice(&[
+            n.0.into(),
+            n.1.into(),
+            n.2.into(),
+            n.3.into(),
+        ])),
+        Range(r) => {
+            let result = ValueMap::with_capacity(2);
+            result.add_value("start", r.start.into());
+            result.add_value("end", r.exclusive_end().into());
+            Map(result)
+        }
+        List(l) => values_to_data(&l.data(), depth, iterator_values)?,
+        Tuple(t) => values_to_data(t, depth, iterator_values)?,
+        Map(m) => {
+            let result = ValueMap::with_capacity(m.len());
+            for (key, value) in m.data().iter() {
+                let key = match key.value() {
+                    Str(s) => s.clone(),
+                    other => other.to_string().into(),
+                };
+                result.data_mut().insert(
+                    key.into(),
+                    to_data_with_depth(value, depth + 1, iterator_values)?,
+                );
+            }
+            Map(result)
+        }
+        Iterator(i) => {
+            let mut data = ValueVec::new();
+            for output in i.make_copy() {
+                *iterator_values += 1;
+                if *iterator_values > MAX_DATA_ITERATOR_VALUES {
+                    return runtime_error!(
+                        "The maximum number of iterator values ({MAX_DATA_ITERATOR_VALUES}) \
+                         was exceeded, the data might contain an infinite iterator"
+                    );
+                }
+
+                let value = match output {
+                    ValueIteratorOutput::Value(value) => {
+                        to_data_with_depth(&value, depth + 1, iterator_values)?
+                    }
+                    ValueIteratorOutput::ValuePair(first, second) => {
+                        List(ValueList::from_slice(&[
+                            to_data_with_depth(&first, depth + 2, iterator_values)?,
+                            to_data_with_depth(&second, depth + 2, iterator_values)?,
+                        ]))
+                    }
+                    ValueIteratorOutput::Error(error) => return Err(error),
+                };
+                data.push(value);
+            }
+            List(ValueList::with_data(data))
+        }
+        unexpected => return not_data_error(unexpected),
+    };
+
+    Ok(result)
+}
+
+/// Checks that a value only contains 'plain data', and returns a deep copy of the value
+///
+/// This is the inverse of [to_data], accepting the values that [to_data] produces, i.e. Null,
+/// Bool, Number, String, and List and Map containers without meta maps.
+///
+/// An error is returned if any other type of value is encountered,
+/// or if the maximum nesting depth is exceeded.
+pub fn from_data(value: &Value) -> RuntimeResult {
+    from_data_with_depth(value, 0)
+}
+
+fn from_data_with_depth(value: &Value, depth: usize) -> RuntimeResult {
+    use Value::*;
+
+    if depth > MAX_DATA_DEPTH {
+        return data_depth_error();
+    }
+
+    let result = match value {
+        Null | Bool(_) | Number(_) | Str(_) => value.clone(),
+        List(l) => {
+            let l = l.data();
+            let mut data = ValueVec::with_capacity(l.len());
+            for value in l.iter() {
+                data.push(from_data_with_depth(value, depth + 1)?);
+            }
+            List(ValueList::with_data(data))
+        }
+        Map(m) if m.meta_map().is_none() => {
+            let result = ValueMap::with_capacity(m.len());
+            for (key, value) in m.data().iter() {
+                if !matches!(key.value(), Str(_)) {
+                    return runtime_error!(
+                        "Expected String keys in data Maps, found '{}'",
+                        key.value().type_as_string()
+                    );
+                }
+                result
+                    .data_mut()
+                    .insert(key.clone(), from_data_with_depth(value, depth + 1)?);
+            }
+            Map(result)
+        }
+        Map(_) => return runtime_error!("Maps with meta maps aren't supported as data"),
+        unexpected => return not_data_error(unexpected),
+    };
+
+    Ok(result)
+}
+
+fn values_to_data(values: &[Value], depth: usize, iterator_values: &mut usize) -> RuntimeResult {
+    let mut data = ValueVec::with_capacity(values.len());
+    for value in values.iter() {
+        data.push(to_data_with_depth(value, depth + 1, iterator_values)?);
+    }
+    Ok(Value::List(ValueList::with_data(data)))
+}
+
+fn not_data_error(value: &Value) -> RuntimeResult {
+    runtime_error!("'{}' can't be converted into data", value.type_as_string())
+}
+
+fn data_depth_error() -> RuntimeResult {
+    runtime_error!(
+        "The maximum data depth of {MAX_DATA_DEPTH} was exceeded, \
+         the data might contain a reference to itself"
+    )
+}
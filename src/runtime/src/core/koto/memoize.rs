@@ -0,0 +1,192 @@
+//! Memoized functions produced by `koto.memoize_limited`
+
+use {
+    crate::{
+        external::{ArgRegisters, ExternalCallable},
+        prelude::*,
+    },
+    rustc_hash::FxHashMap,
+    std::cell::RefCell,
+};
+
+/// A function that caches its results, produced by `koto.memoize_limited`
+///
+/// The function's cache is owned by the function, and is dropped along with it.
+/// Cache statistics are available via [MemoizedFunction::stats].
+pub struct MemoizedFunction {
+    function: Value,
+    cache: RefCell<LruCache>,
+}
+
+impl MemoizedFunction {
+    /// Makes a memoized function that caches up to `capacity` results
+    pub fn new(function: Value, capacity: usize) -> Self {
+        Self {
+            function,
+            cache: RefCell::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Returns a map containing the cache's hit and miss counts, along with its size and capacity
+    pub fn stats(&self) -> ValueMap {
+        let cache = self.cache.borrow();
+        let result = ValueMap::with_capacity(4);
+        result.add_value("hits", cache.hits.into());
+        result.add_value("misses", cache.misses.into());
+        result.add_value("entries", cache.index.len().into());
+        result.add_value("capacity", cache.capacity.into());
+        result
+    }
+}
+
+impl ExternalCallable for MemoizedFunction {
+    fn call(&self, vm: &mut Vm, args: &ArgRegisters) -> RuntimeResult {
+        let args = vm.get_args(args).iter().cloned().collect::<ValueVec>();
+        let key = memoize_key(&args)?;
+
+        if let Some(cached) = self.cache.borrow_mut().get(&key) {
+            return Ok(cached);
+        }
+
+        // The cache isn't borrowed while the function is running,
+        // allowing recursive functions to make use of the cache.
+        let result = vm.run_function(self.function.clone(), CallArgs::Separate(&args))?;
+        self.cache.borrow_mut().insert(key, result.clone());
+        Ok(result)
+    }
+}
+
+// Makes a cache key from the args passed to a memoized function
+fn memoize_key(args: &[Value]) -> Result<Vec<ValueKey>, RuntimeError> {
+    args.iter()
+        .map(|arg| {
+            if arg.is_immutable() {
+                Ok(ValueKey::from(arg.clone()))
+            } else {
+                runtime_error!(
+                    "koto.memoize_limited: Only immutable values can be used as arguments \
+                     for memoized functions, found '{}'",
+                    arg.type_as_string()
+                )
+            }
+        })
+        .collect()
+}
+
+// A least-recently-used cache of function results
+//
+// The entries form a doubly linked list in order of use, with links stored as indices into the
+// entries vec, so that marking an entry as used and evicting the least recently used entry are
+// both O(1). Evicted entries have their slots reused, so the vec never grows beyond the capacity.
+struct LruCache {
+    index: FxHashMap<Vec<ValueKey>, usize>,
+    entries: Vec<LruEntry>,
+    // The least recently used entry
+    oldest: Option<usize>,
+    // The most recently used entry
+    newest: Option<usize>,
+    capacity: usize,
+    hits: usize,
+    misses: usize,
+}
+
+struct LruEntry {
+    key: Vec<ValueKey>,
+    value: Value,
+    // The entry that was used before this one
+    older: Option<usize>,
+    // The entry that was used after this one
+    newer: Option<usize>,
+}
+
+impl LruCache {
+    fn new(capacity: usize) -> Self {
+        debug_assert!(capacity > 0);
+        Self {
+            index: FxHashMap::default(),
+            entries: Vec::new(),
+            oldest: None,
+            newest: None,
+            capacity,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    // Returns the cached result for the given args, marking the entry as the most recently used
+    fn get(&mut self, key: &[ValueKey]) -> Option<Value> {
+        match self.index.get(key).copied() {
+            Some(entry) => {
+                self.hits += 1;
+                self.unlink(entry);
+                self.push_newest(entry);
+                Some(self.entries[entry].value.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    // Caches a result, evicting the least recently used entry if the cache is full
+    fn insert(&mut self, key: Vec<ValueKey>, value: Value) {
+        // A recursive call may have already cached a result for the same args
+        if let Some(entry) = self.index.get(&key).copied() {
+            self.entries[entry].value = value;
+            self.unlink(entry);
+            self.push_newest(entry);
+            return;
+        }
+
+        let entry = if self.entries.len() < self.capacity {
+            self.entries.push(LruEntry {
+                key: key.clone(),
+                value,
+                older: None,
+                newer: None,
+            });
+            self.entries.len() - 1
+        } else {
+            // The cache is full, so the oldest entry's slot is reused
+            let entry = self.oldest.expect("Missing oldest entry in full cache");
+            self.unlink(entry);
+            let evicted = &mut self.entries[entry];
+            self.index.remove(&evicted.key);
+            evicted.key = key.clone();
+            evicted.value = value;
+            entry
+        };
+
+        self.index.insert(key, entry);
+        self.push_newest(entry);
+    }
+
+    // Removes the entry from the list, joining its neighbours together
+    fn unlink(&mut self, entry: usize) {
+        let LruEntry { older, newer, .. } = self.entries[entry];
+
+        match older {
+            Some(older) => self.entries[older].newer = newer,
+            None => self.oldest = newer,
+        }
+        match newer {
+            Some(newer) => self.entries[newer].older = older,
+            None => self.newest = older,
+        }
+
+        let entry = &mut self.entries[entry];
+        entry.older = None;
+        entry.newer = None;
+    }
+
+    // Adds an unlinked entry to the list as the most recently used entry
+    fn push_newest(&mut self, entry: usize) {
+        match self.newest {
+            Some(newest) => self.entries[newest].newer = Some(entry),
+            None => self.oldest = Some(entry),
+        }
+        self.entries[entry].older = self.newest;
+        self.newest = Some(entry);
+    }
+}
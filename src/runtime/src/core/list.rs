@@ -4,7 +4,8 @@ use {
     super::iterator::collect_pair,
     crate::{
         prelude::*,
-        value_sort::{compare_values, sort_values},
+        value::resolve_index,
+        value_sort::{is_less, sort_values, sort_values_by_keys},
     },
     std::{cmp::Ordering, ops::DerefMut},
 };
@@ -15,6 +16,23 @@ pub fn make_module() -> ValueMap {
 
     let result = ValueMap::new();
 
+    result.add_fn("binary_search", |vm, args| match vm.get_args(args) {
+        [List(l), value] => {
+            let l = l.clone();
+            let value = value.clone();
+
+            let index = partition_point(vm, "binary_search", &l, |vm, x| is_less(vm, x, &value))?;
+            let probe = l.data().get(index).cloned();
+            let found = match probe {
+                Some(probe) => !is_less(vm, &value, &probe)?,
+                None => false,
+            };
+
+            Ok(Tuple(vec![found.into(), index.into()].into()))
+        }
+        unexpected => type_error_with_slice("a List and a Value as arguments", unexpected),
+    });
+
     result.add_fn("clear", |vm, args| match vm.get_args(args) {
         [List(l)] => {
             l.data_mut().clear();
@@ -50,6 +68,58 @@ pub fn make_module() -> ValueMap {
         unexpected => type_error_with_slice("a List as argument", unexpected),
     });
 
+    result.add_fn("count", |vm, args| match vm.get_args(args) {
+        [List(l), predicate] if predicate.is_callable() => {
+            let l = l.clone();
+            let predicate = predicate.clone();
+            let mut result = 0;
+
+            // The list is indexed rather than iterated so that its data isn't borrowed while the
+            // predicate is being called.
+            let mut i = 0;
+            while let Some(value) = l.data().get(i).cloned() {
+                match vm.run_function(predicate.clone(), CallArgs::Single(value)) {
+                    Ok(Bool(true)) => result += 1,
+                    Ok(Bool(false)) => {}
+                    Ok(unexpected) => {
+                        return type_error("a Bool to be returned from the predicate", &unexpected)
+                    }
+                    Err(error) => return Err(error),
+                }
+                i += 1;
+            }
+
+            Ok(Number(result.into()))
+        }
+        [List(l), value] => {
+            let l = l.clone();
+            let value = value.clone();
+            let mut result = 0;
+
+            let mut i = 0;
+            while let Some(candidate) = l.data().get(i).cloned() {
+                match vm.run_binary_op(BinaryOp::Equal, value.clone(), candidate) {
+                    Ok(Bool(true)) => result += 1,
+                    Ok(Bool(false)) => {}
+                    Ok(unexpected) => {
+                        return runtime_error!(
+                            "list.count: Expected Bool from comparison, found '{}'",
+                            unexpected.type_as_string()
+                        )
+                    }
+                    Err(error) => return Err(error),
+                }
+                i += 1;
+            }
+
+            Ok(Number(result.into()))
+        }
+        unexpected => type_error_with_slice(
+            "a List and a Value or predicate Function as arguments",
+            unexpected,
+        ),
+    });
+
     result.add_fn("deep_copy", |vm, args| match vm.get_args(args) {
         [value @ List(_)] => Ok(value.deep_copy()),
         unexpected => type_error_with_slice("a List as argument", unexpected),
@@ -57,7 +127,9 @@ pub fn make_module() -> ValueMap {
 
     result.add_fn("extend", |vm, args| match vm.get_args(args) {
         [List(l), List(other)] => {
-            l.data_mut().extend(other.data().iter().cloned());
+            // The other list's data is copied before extending, given that it might be the same list
+            let new_values = other.data().clone();
+            l.data_mut().extend(new_values);
             Ok(List(l.clone()))
         }
         [List(l), Tuple(other)] => {
@@ -67,21 +139,8 @@ pub fn make_module() -> ValueMap {
         [List(l), iterable] if iterable.is_iterable() => {
             let l = l.clone();
             let iterable = iterable.clone();
-            let iterator = vm.make_iterator(iterable)?;
-
-            {
-                let mut list_data = l.data_mut();
-                let (size_hint, _) = iterator.size_hint();
-                list_data.reserve(size_hint);
-
-                for value in iterator.map(collect_pair) {
-                    match value {
-                        ValueIteratorOutput::Value(value) => list_data.push(value.clone()),
-                        ValueIteratorOutput::Error(error) => return Err(error),
-                        _ => unreachable!(),
-                    }
-                }
-            }
+            let new_values = collect_values(vm, iterable)?;
+            l.data_mut().extend(new_values);
 
             Ok(List(l))
         }
@@ -125,21 +184,98 @@ pub fn make_module() -> ValueMap {
     });
 
     result.add_fn("insert", |vm, args| match vm.get_args(args) {
-        [List(l), Number(n), value] if *n >= 0.0 => {
-            let index: usize = n.into();
-            if index > l.data().len() {
-                return runtime_error!("list.insert: Index out of bounds");
-            }
-
+        [List(l), Number(n), value] => {
+            let index = insert_position("insert", l, n)?;
             l.data_mut().insert(index, value.clone());
             Ok(List(l.clone()))
         }
+        unexpected => type_error_with_slice("a List, a Number, and Value as arguments", unexpected),
+    });
+
+    result.add_fn("insert_all", |vm, args| match vm.get_args(args) {
+        [List(l), Number(n), iterable] if iterable.is_iterable() => {
+            let l = l.clone();
+            let index = insert_position("insert_all", &l, n)?;
+            let iterable = iterable.clone();
+            let new_values = collect_values(vm, iterable)?;
+            l.data_mut().insert_many(index, new_values);
+            Ok(List(l))
+        }
         unexpected => type_error_with_slice(
-            "a List, a non-negative Number, and Value as arguments",
+            "a List, a Number, and an iterable value as arguments",
             unexpected,
         ),
     });
 
+    result.add_fn("insert_sorted", |vm, args| match vm.get_args(args) {
+        [List(l), value] => {
+            let l = l.clone();
+            let value = value.clone();
+
+            // The value is inserted after any equal values
+            let index = partition_point(vm, "insert_sorted", &l, |vm, x| {
+                Ok(!is_less(vm, &value, x)?)
+            })?;
+            l.data_mut().insert(index, value);
+
+            Ok(List(l))
+        }
+        unexpected => type_error_with_slice("a List and a Value as arguments", unexpected),
+    });
+
+    result.add_fn("interleave", |vm, args| match vm.get_args(args) {
+        [List(a), List(b)] => {
+            let a = a.data();
+            let b = b.data();
+            let result = a
+                .iter()
+                .zip(b.iter())
+                .flat_map(|(x, y)| [x.clone(), y.clone()])
+                .collect::<ValueVec>();
+            Ok(List(ValueList::with_data(result)))
+        }
+        unexpected => type_error_with_slice("two Lists as arguments", unexpected),
+    });
+
+    result.add_fn("interleave_longest", |vm, args| {
+        let (a, b, fill) = match vm.get_args(args) {
+            [List(a), List(b)] => (a, b, &Null),
+            [List(a), List(b), fill] => (a, b, fill),
+            unexpected => {
+                return type_error_with_slice(
+                    "two Lists and an optional fill Value as arguments",
+                    unexpected,
+                )
+            }
+        };
+
+        let a = a.data();
+        let b = b.data();
+        let len = a.len().max(b.len());
+        let mut result = ValueVec::with_capacity(len * 2);
+        for i in 0..len {
+            result.push(a.get(i).unwrap_or(fill).clone());
+            result.push(b.get(i).unwrap_or(fill).clone());
+        }
+        Ok(List(ValueList::with_data(result)))
+    });
+
+    result.add_fn("interleave_with", |vm, args| match vm.get_args(args) {
+        [List(a), List(b), f] if f.is_callable() => {
+            // The lists are cloned so that the function is free to modify them
+            let a = a.data().clone();
+            let b = b.data().clone();
+            let f = f.clone();
+
+            let mut result = ValueVec::with_capacity(a.len().min(b.len()));
+            for (x, y) in a.into_iter().zip(b) {
+                result.push(vm.run_function(f.clone(), CallArgs::Separate(&[x, y]))?);
+            }
+            Ok(List(ValueList::with_data(result)))
+        }
+        unexpected => type_error_with_slice("two Lists and a Function as arguments", unexpected),
+    });
+
     result.add_fn("is_empty", |vm, args| match vm.get_args(args) {
         [List(l)] => Ok(l.data().is_empty().into()),
         unexpected => type_error_with_slice("a List as argument", unexpected),
@@ -153,6 +289,44 @@ pub fn make_module() -> ValueMap {
         unexpected => type_error_with_slice("a List as argument", unexpected),
     });
 
+    result.add_fn("max_n", |vm, args| match vm.get_args(args) {
+        [List(l), Number(n)] if *n >= 0.0 => {
+            let l = l.clone();
+            let n = n.into();
+            select_n(vm, &l, n, None, SelectOrder::Largest)
+        }
+        unexpected => expected_list_and_count_error(unexpected),
+    });
+
+    result.add_fn("max_n_by", |vm, args| match vm.get_args(args) {
+        [List(l), Number(n), key_fn] if *n >= 0.0 && key_fn.is_callable() => {
+            let l = l.clone();
+            let n = n.into();
+            let key_fn = key_fn.clone();
+            select_n(vm, &l, n, Some(key_fn), SelectOrder::Largest)
+        }
+        unexpected => expected_list_count_and_key_fn_error(unexpected),
+    });
+
+    result.add_fn("min_n", |vm, args| match vm.get_args(args) {
+        [List(l), Number(n)] if *n >= 0.0 => {
+            let l = l.clone();
+            let n = n.into();
+            select_n(vm, &l, n, None, SelectOrder::Smallest)
+        }
+        unexpected => expected_list_and_count_error(unexpected),
+    });
+
+    result.add_fn("min_n_by", |vm, args| match vm.get_args(args) {
+        [List(l), Number(n), key_fn] if *n >= 0.0 && key_fn.is_callable() => {
+            let l = l.clone();
+            let n = n.into();
+            let key_fn = key_fn.clone();
+            select_n(vm, &l, n, Some(key_fn), SelectOrder::Smallest)
+        }
+        unexpected => expected_list_count_and_key_fn_error(unexpected),
+    });
+
     result.add_fn("pop", |vm, args| match vm.get_args(args) {
         [List(l)] => match l.data_mut().pop() {
             Some(value) => Ok(value),
@@ -170,21 +344,20 @@ pub fn make_module() -> ValueMap {
     });
 
     result.add_fn("remove", |vm, args| match vm.get_args(args) {
-        [List(l), Number(n)] if *n >= 0.0 => {
-            let index: usize = n.into();
-            if index >= l.data().len() {
+        [List(l), Number(n)] => {
+            let size = l.len();
+            let index = resolve_index(n.into(), size);
+            if index < 0 || index as usize >= size {
                 return runtime_error!(
                     "list.remove: Index out of bounds - \
-                     the index is {index} but the List only has {} elements",
-                    l.data().len(),
+                     the index is {} but the List only has {size} elements",
+                    describe_index(n, index),
                 );
             }
 
-            Ok(l.data_mut().remove(index))
-        }
-        unexpected => {
-            type_error_with_slice("a List and non-negative Number as arguments", unexpected)
+            Ok(l.data_mut().remove(index as usize))
         }
+        unexpected => type_error_with_slice("a List and Number as arguments", unexpected),
     });
 
     result.add_fn("resize", |vm, args| match vm.get_args(args) {
@@ -235,14 +408,15 @@ pub fn make_module() -> ValueMap {
                 let l = l.clone();
                 let f = f.clone();
 
-                let mut write_index = 0;
-                for read_index in 0..l.len() {
-                    let value = l.data()[read_index].clone();
+                // The predicate is called with the list's borrow released,
+                // so a copy of the list's data is filtered and then written back to the list.
+                let data = l.data().clone();
+                let mut retained = ValueVec::with_capacity(data.len());
+                for value in data {
                     match vm.run_function(f.clone(), CallArgs::Single(value.clone())) {
                         Ok(Bool(result)) => {
                             if result {
-                                l.data_mut()[write_index] = value;
-                                write_index += 1;
+                                retained.push(value);
                             }
                         }
                         Ok(unexpected) => {
@@ -254,7 +428,7 @@ pub fn make_module() -> ValueMap {
                         Err(error) => return Err(error),
                     }
                 }
-                l.data_mut().resize(write_index, Null);
+                *l.data_mut() = retained;
                 l
             }
             [List(l), value] => {
@@ -262,7 +436,10 @@ pub fn make_module() -> ValueMap {
                 let value = value.clone();
 
                 let mut error = None;
-                l.data_mut().retain(|x| {
+                // Overloaded equality operators might access the list,
+                // so the list's data is copied rather than being borrowed during the comparisons.
+                let mut data = l.data().clone();
+                data.retain(|x| {
                     if error.is_some() {
                         return true;
                     }
@@ -285,6 +462,7 @@ pub fn make_module() -> ValueMap {
                 if let Some(error) = error {
                     return error;
                 }
+                *l.data_mut() = data;
                 l
             }
             unexpected => {
@@ -314,52 +492,26 @@ pub fn make_module() -> ValueMap {
     result.add_fn("sort", |vm, args| match vm.get_args(args) {
         [List(l)] => {
             let l = l.clone();
-            let mut data = l.data_mut();
+            // Values are sorted outside of the list's borrow,
+            // which allows overloaded comparison operators to access the list.
+            let mut data = l.data().clone();
             sort_values(vm, &mut data)?;
-            Ok(List(l.clone()))
+            *l.data_mut() = data;
+            Ok(List(l))
         }
         [List(l), f] if f.is_callable() => {
             let l = l.clone();
             let f = f.clone();
 
-            // apply function and construct a vec of (key, value)
-            let mut pairs = l
-                .data()
+            // The sort keys are produced before sorting, with the list's borrow released
+            let mut values = l.data().clone();
+            let keys = values
                 .iter()
-                .map(
-                    |value| match vm.run_function(f.clone(), CallArgs::Single(value.clone())) {
-                        Ok(key) => Ok((key, value.clone())),
-                        Err(e) => Err(e),
-                    },
-                )
+                .map(|value| vm.run_function(f.clone(), CallArgs::Single(value.clone())))
                 .collect::<Result<Vec<_>, _>>()?;
 
-            let mut error = None;
-
-            // sort array by key (i.e. from [key, value])
-            pairs.sort_by(|a, b| {
-                if error.is_some() {
-                    return Ordering::Equal;
-                }
-
-                match compare_values(vm, &a.0, &b.0) {
-                    Ok(ordering) => ordering,
-                    Err(e) => {
-                        error.get_or_insert(e);
-                        Ordering::Equal
-                    }
-                }
-            });
-
-            if let Some(error) = error {
-                return Err(error);
-            }
-
-            // collect values
-            *l.data_mut() = pairs
-                .iter()
-                .map(|(_key, value)| value.clone())
-                .collect::<_>();
+            sort_values_by_keys(vm, &mut values, &keys)?;
+            *l.data_mut() = values;
 
             Ok(List(l))
         }
@@ -384,12 +536,13 @@ pub fn make_module() -> ValueMap {
             let l = l.clone();
             let f = f.clone();
 
-            for value in l.data_mut().iter_mut() {
-                *value = match vm.run_function(f.clone(), CallArgs::Single(value.clone())) {
-                    Ok(result) => result,
-                    Err(error) => return Err(error),
-                }
+            // The function is called for each value with the list's borrow released,
+            // and then the transformed values replace the list's contents.
+            let mut data = l.data().clone();
+            for value in data.iter_mut() {
+                *value = vm.run_function(f.clone(), CallArgs::Single(value.clone()))?;
             }
+            *l.data_mut() = data;
 
             Ok(List(l))
         }
@@ -408,3 +561,242 @@ pub fn make_module() -> ValueMap {
 
     result
 }
+
+// Collects the output of an iterable value
+//
+// The values are collected before being added to a list, given that the iterator might call
+// functions that access the list.
+fn collect_values(vm: &mut Vm, iterable: Value) -> Result<Vec<Value>, RuntimeError> {
+    let iterator = vm.make_interruptible_iterator(iterable)?;
+    let size_hint = iterator.capacity_hint();
+    let mut result = Vec::with_capacity(size_hint);
+    for value in iterator.map(collect_pair) {
+        match value {
+            ValueIteratorOutput::Value(value) => result.push(value),
+            ValueIteratorOutput::Error(error) => return Err(error),
+            _ => unreachable!(),
+        }
+    }
+    Ok(result)
+}
+
+// Resolves the position at which values should be inserted into the list
+//
+// Negative indices count back from the end of the list, and the list's size is a valid position.
+fn insert_position(fn_name: &str, l: &ValueList, n: &ValueNumber) -> Result<usize, RuntimeError> {
+    let size = l.len();
+    let index = resolve_index(n.into(), size);
+    if index < 0 || index as usize > size {
+        runtime_error!(
+            "list.{fn_name}: Index out of bounds - \
+             the index is {} but the List only has {size} elements",
+            describe_index(n, index),
+        )
+    } else {
+        Ok(index as usize)
+    }
+}
+
+// Describes an index for error messages, including the resolved index for negative indices
+fn describe_index(n: &ValueNumber, resolved: isize) -> String {
+    if *n < 0.0 {
+        format!("{n} (resolved to {resolved})")
+    } else {
+        n.to_string()
+    }
+}
+
+fn expected_list_and_count_error(unexpected: &[Value]) -> RuntimeResult {
+    type_error_with_slice("a List and a non-negative Number as arguments", unexpected)
+}
+
+fn expected_list_count_and_key_fn_error(unexpected: &[Value]) -> RuntimeResult {
+    type_error_with_slice(
+        "a List, a non-negative Number, and a key Function as arguments",
+        unexpected,
+    )
+}
+
+// Returns the index of the first value in a sorted list for which the predicate returns false
+//
+// The list's borrow is released while calling the predicate,
+// which allows overloaded comparison operators to access the list.
+fn partition_point(
+    vm: &mut Vm,
+    function_name: &str,
+    list: &ValueList,
+    mut predicate: impl FnMut(&mut Vm, &Value) -> Result<bool, RuntimeError>,
+) -> Result<usize, RuntimeError> {
+    let mut start = 0;
+    let mut end = list.len();
+
+    while start < end {
+        let middle = start + (end - start) / 2;
+        let probe = match list.data().get(middle).cloned() {
+            Some(probe) => probe,
+            None => return runtime_error!("list.{function_name}: The list was modified"),
+        };
+
+        if predicate(vm, &probe)? {
+            start = middle + 1;
+        } else {
+            end = middle;
+        }
+    }
+
+    Ok(start)
+}
+
+// Which end of the sorted order should be selected in select_n
+#[derive(Clone, Copy)]
+enum SelectOrder {
+    Smallest,
+    Largest,
+}
+
+// A candidate value for selection, with its sort key and its index in the input list
+struct SelectEntry {
+    key: Value,
+    index: usize,
+}
+
+// Selects the n smallest or largest values from a list, returning them in sorted order
+//
+// The values (or the results of calling the key function with the values) are compared using
+// Koto operators, which can fail or be overloaded, so a bounded heap is maintained manually
+// rather than with std's BinaryHeap which would require an infallible `Ord`.
+//
+// The heap holds the n best candidates found so far with the 'worst' candidate at the top,
+// so each of the k input values only needs O(log n) comparisons.
+//
+// Equal values are returned in the order that they appear in the input.
+fn select_n(
+    vm: &mut Vm,
+    list: &ValueList,
+    n: usize,
+    key_fn: Option<Value>,
+    order: SelectOrder,
+) -> RuntimeResult {
+    let values = list.data().clone();
+    // If all of the values are being selected then the heap isn't needed,
+    // and the entries can be sorted directly.
+    let use_heap = n < values.len();
+
+    let mut entries = Vec::with_capacity(n.min(values.len()));
+    for (index, value) in values.iter().enumerate() {
+        let key = match &key_fn {
+            Some(f) => vm.run_function(f.clone(), CallArgs::Single(value.clone()))?,
+            None => value.clone(),
+        };
+        let entry = SelectEntry { key, index };
+
+        if !use_heap {
+            entries.push(entry);
+        } else if entries.len() < n {
+            entries.push(entry);
+            let last = entries.len() - 1;
+            sift_up(vm, &mut entries, last, order)?;
+        } else if n > 0 && select_cmp(vm, &entry, &entries[0], order)? == Ordering::Less {
+            entries[0] = entry;
+            sift_down(vm, &mut entries, 0, order)?;
+        }
+    }
+
+    // Sort the selected entries, starting from input order so that the stable sort keeps equal
+    // values in the order that they appear in the input.
+    entries.sort_by_key(|entry| entry.index);
+    if matches!(order, SelectOrder::Largest) {
+        // Sorting the reversed entries and then reversing the result produces a descending
+        // order, with equal values remaining in input order.
+        entries.reverse();
+    }
+    let keys = entries
+        .iter()
+        .map(|entry| entry.key.clone())
+        .collect::<Vec<_>>();
+    let mut indices = entries.iter().map(|entry| entry.index).collect::<Vec<_>>();
+    sort_values_by_keys(vm, &mut indices, &keys)?;
+    if matches!(order, SelectOrder::Largest) {
+        indices.reverse();
+    }
+
+    let result = indices
+        .iter()
+        .map(|index| values[*index].clone())
+        .collect::<ValueVec>();
+    Ok(Value::List(ValueList::with_data(result)))
+}
+
+// Compares two entries, with 'better' candidates for selection ordered first
+fn select_cmp(
+    vm: &mut Vm,
+    a: &SelectEntry,
+    b: &SelectEntry,
+    order: SelectOrder,
+) -> Result<Ordering, RuntimeError> {
+    let (first, second) = match order {
+        SelectOrder::Smallest => (&a.key, &b.key),
+        SelectOrder::Largest => (&b.key, &a.key),
+    };
+    // Only `<` is used for comparisons, matching the behaviour of the sort functions
+    let ordering = if is_less(vm, first, second)? {
+        Ordering::Less
+    } else if is_less(vm, second, first)? {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    };
+    // Equal keys are ordered by their position in the input
+    Ok(ordering.then(a.index.cmp(&b.index)))
+}
+
+// Moves an entry up the heap until its parent is ordered after it
+fn sift_up(
+    vm: &mut Vm,
+    heap: &mut [SelectEntry],
+    mut index: usize,
+    order: SelectOrder,
+) -> Result<(), RuntimeError> {
+    while index > 0 {
+        let parent = (index - 1) / 2;
+        if select_cmp(vm, &heap[index], &heap[parent], order)? == Ordering::Greater {
+            heap.swap(index, parent);
+            index = parent;
+        } else {
+            break;
+        }
+    }
+    Ok(())
+}
+
+// Moves an entry down the heap until its children are ordered before it
+fn sift_down(
+    vm: &mut Vm,
+    heap: &mut [SelectEntry],
+    mut index: usize,
+    order: SelectOrder,
+) -> Result<(), RuntimeError> {
+    loop {
+        let left = index * 2 + 1;
+        let right = left + 1;
+        let mut largest = index;
+
+        if left < heap.len()
+            && select_cmp(vm, &heap[left], &heap[largest], order)? == Ordering::Greater
+        {
+            largest = left;
+        }
+        if right < heap.len()
+            && select_cmp(vm, &heap[right], &heap[largest], order)? == Ordering::Greater
+        {
+            largest = right;
+        }
+
+        if largest == index {
+            return Ok(());
+        }
+
+        heap.swap(index, largest);
+        index = largest;
+    }
+}
@@ -1,9 +1,9 @@
 //! The `map` core library module
 
 use {
-    super::iterator::adaptors,
-    crate::{prelude::*, value_sort::compare_values},
-    std::{cmp::Ordering, ops::Deref},
+    super::iterator::{adaptors, iterable_to_list, iterable_to_map},
+    crate::{prelude::*, value_sort::sort_values_by_keys},
+    std::ops::Deref,
 };
 
 /// Initializes the `map` core library module
@@ -51,42 +51,49 @@ pub fn make_module() -> ValueMap {
         [Map(m), iterable] if iterable.is_iterable() => {
             let m = m.clone();
             let iterable = iterable.clone();
-            let iterator = vm.make_iterator(iterable)?;
-
-            {
-                let mut map_data = m.data_mut();
-                let (size_hint, _) = iterator.size_hint();
-                map_data.reserve(size_hint);
-
-                for output in iterator {
-                    use ValueIteratorOutput as Output;
-                    let (key, value) = match output {
-                        Output::ValuePair(key, value) => (key, value),
-                        Output::Value(Tuple(t)) if t.len() == 2 => {
-                            let key = t[0].clone();
-                            let value = t[1].clone();
-                            (key, value)
-                        }
-                        Output::Value(value) => (value, Null),
-                        Output::Error(error) => return Err(error),
-                    };
-
-                    if !key.is_immutable() {
-                        return runtime_error!(
-                            "map.extend: Only immutable Values can be used as keys (found '{}')",
-                            key.type_as_string()
-                        );
+            let iterator = vm.make_interruptible_iterator(iterable)?;
+
+            // The new entries are collected before being added to the map, given that the
+            // iterator might call functions that access the map.
+            let size_hint = iterator.capacity_hint();
+            let mut new_entries = Vec::with_capacity(size_hint);
+            for output in iterator {
+                use ValueIteratorOutput as Output;
+                let (key, value) = match output {
+                    Output::ValuePair(key, value) => (key, value),
+                    Output::Value(Tuple(t)) if t.len() == 2 => {
+                        let key = t[0].clone();
+                        let value = t[1].clone();
+                        (key, value)
                     }
+                    Output::Value(value) => (value, Null),
+                    Output::Error(error) => return Err(error),
+                };
 
-                    map_data.insert(key.into(), value);
+                if !key.is_immutable() {
+                    return runtime_error!(
+                        "map.extend: Only immutable Values can be used as keys (found '{}')",
+                        key.type_as_string()
+                    );
                 }
+
+                new_entries.push((ValueKey::from(key), value));
             }
+            m.data_mut().extend(new_entries);
 
             Ok(Map(m))
         }
         unexpected => type_error_with_slice("a Map and iterable value as arguments", unexpected),
     });
 
+    result.add_fn("from_pairs", |vm, args| match vm.get_args(args) {
+        [iterable] if iterable.is_iterable() => {
+            let iterable = iterable.clone();
+            iterable_to_map(vm, iterable)
+        }
+        unexpected => type_error_with_slice("an iterable value as argument", unexpected),
+    });
+
     result.add_fn("get", |vm, args| {
         let (map, key, default) = match vm.get_args(args) {
             [Map(map), key] if key.is_immutable() => (map, key, &Null),
@@ -110,7 +117,9 @@ pub fn make_module() -> ValueMap {
         };
 
         match map.data().get_index(index.into()) {
-            Some((key, value)) => Ok(Tuple(vec![key.deref().clone(), value.clone()].into())),
+            Some((key, value)) => Ok(Tuple(
+                [key.deref().clone(), value.clone()].into_iter().collect(),
+            )),
             None => Ok(default.clone()),
         }
     });
@@ -129,6 +138,33 @@ pub fn make_module() -> ValueMap {
         unexpected => type_error_with_slice("a Map", unexpected),
     });
 
+    result.add_fn("get_or_insert", |vm, args| match vm.get_args(args) {
+        [Map(m), key, default] if key.is_immutable() => {
+            let m = m.clone();
+            let key = ValueKey::from(key.clone());
+
+            if default.is_callable() {
+                let default = default.clone();
+                if let Some(value) = m.data().get(&key) {
+                    return Ok(value.clone());
+                }
+                // The map isn't borrowed while the default is being produced,
+                // so an entry for the key could have been inserted by the function.
+                let value = vm.run_function(default, CallArgs::None)?;
+                Ok(m.data_mut().entry(key).or_insert(value).clone())
+            } else {
+                Ok(m.data_mut()
+                    .entry(key)
+                    .or_insert_with(|| default.clone())
+                    .clone())
+            }
+        }
+        unexpected => type_error_with_slice(
+            "a Map, key, and default Value (or Function) as arguments",
+            unexpected,
+        ),
+    });
+
     result.add_fn("insert", |vm, args| match vm.get_args(args) {
         [Map(m), key] if key.is_immutable() => {
             match m.data_mut().insert(key.clone().into(), Null) {
@@ -148,6 +184,54 @@ pub fn make_module() -> ValueMap {
         ),
     });
 
+    result.add_fn("insert_at", |vm, args| match vm.get_args(args) {
+        [Map(m), Number(n), key, value] if *n >= 0.0 && key.is_immutable() => {
+            let index: usize = n.into();
+            let key = ValueKey::from(key.clone());
+            let mut data = m.data_mut();
+
+            if index > data.len() {
+                return runtime_error!("map.insert_at: Index out of bounds");
+            }
+            if data.contains_key(&key) {
+                return runtime_error!("map.insert_at: The key '{}' already exists", key.value());
+            }
+
+            // The entry is inserted at the end of the map,
+            // and then moved into position by shifting the later entries along.
+            data.insert(key, value.clone());
+            for i in (index..data.len() - 1).rev() {
+                data.swap_indices(i, i + 1);
+            }
+
+            Ok(Map(m.clone()))
+        }
+        unexpected => type_error_with_slice(
+            "a Map, a non-negative Number, a key, and a Value as arguments",
+            unexpected,
+        ),
+    });
+
+    result.add_fn("invert", |vm, args| match vm.get_args(args) {
+        [Map(m)] => {
+            let data = m.data();
+            let mut result = DataMap::with_capacity(data.len());
+
+            for (key, value) in data.iter() {
+                if !value.is_immutable() {
+                    return runtime_error!(
+                        "map.invert: Only immutable Values can be used as keys (found '{}')",
+                        value.type_as_string()
+                    );
+                }
+                result.insert(value.clone().into(), key.value().clone());
+            }
+
+            Ok(Map(ValueMap::with_data(result)))
+        }
+        unexpected => type_error_with_slice("a Map as argument", unexpected),
+    });
+
     result.add_fn("is_empty", |vm, args| match vm.get_args(args) {
         [Map(m)] => Ok(m.is_empty().into()),
         unexpected => type_error_with_slice("a Map as argument", unexpected),
@@ -171,6 +255,44 @@ pub fn make_module() -> ValueMap {
         unexpected => type_error_with_slice("a Map and key as arguments", unexpected),
     });
 
+    result.add_fn("rename_key", |vm, args| match vm.get_args(args) {
+        [Map(m), old_key, new_key] if old_key.is_immutable() && new_key.is_immutable() => {
+            let old_key = ValueKey::from(old_key.clone());
+            let new_key = ValueKey::from(new_key.clone());
+            let mut data = m.data_mut();
+
+            let index = match data.get_index_of(&old_key) {
+                Some(index) => index,
+                None => {
+                    return runtime_error!(
+                        "map.rename_key: The key '{}' wasn't found",
+                        old_key.value()
+                    )
+                }
+            };
+            if old_key == new_key {
+                return Ok(Map(m.clone()));
+            }
+            if data.contains_key(&new_key) {
+                return runtime_error!(
+                    "map.rename_key: The key '{}' already exists",
+                    new_key.value()
+                );
+            }
+
+            // The value is inserted at the end of the map with the new key,
+            // and then swapped into the old entry's position before the old entry is removed.
+            let value = data[index].clone();
+            data.insert(new_key, value);
+            let last = data.len() - 1;
+            data.swap_indices(index, last);
+            data.pop();
+
+            Ok(Map(m.clone()))
+        }
+        unexpected => type_error_with_slice("a Map and two keys as arguments", unexpected),
+    });
+
     result.add_fn("size", |vm, args| match vm.get_args(args) {
         [Map(m)] => Ok(Number(m.len().into())),
         unexpected => type_error_with_slice("a Map as argument", unexpected),
@@ -178,65 +300,39 @@ pub fn make_module() -> ValueMap {
 
     result.add_fn("sort", |vm, args| match vm.get_args(args) {
         [Map(m)] => {
-            m.data_mut().sort_keys();
-            Ok(Map(m.clone()))
+            let m = m.clone();
+            let mut entries = map_entries(&m);
+            let keys = entries
+                .iter()
+                .map(|(key, _)| key.value().clone())
+                .collect::<Vec<_>>();
+
+            sort_values_by_keys(vm, &mut entries, &keys)?;
+            *m.data_mut() = entries.into_iter().collect();
+
+            Ok(Map(m))
         }
         [Map(m), f] if f.is_callable() => {
             let m = m.clone();
             let f = f.clone();
-            let mut error = None;
 
-            let get_sort_key =
-                |vm: &mut Vm, cache: &mut DataMap, key: &Value, value: &Value| -> RuntimeResult {
-                    let value = vm.run_function(
+            // The sort key function is called with the map's borrow released,
+            // so a copy of the map's data is sorted and then written back to the map.
+            let mut entries = map_entries(&m);
+            let keys = entries
+                .iter()
+                .map(|(key, value)| {
+                    vm.run_function(
                         f.clone(),
-                        CallArgs::Separate(&[key.clone(), value.clone()]),
-                    )?;
-                    cache.insert(key.clone().into(), value.clone());
-                    Ok(value)
-                };
-
-            let mut cache = DataMap::with_capacity(m.len());
-            m.data_mut().sort_by(|key_a, value_a, key_b, value_b| {
-                if error.is_some() {
-                    return Ordering::Equal;
-                }
-
-                let value_a = match cache.get(key_a) {
-                    Some(value) => value.clone(),
-                    None => match get_sort_key(vm, &mut cache, key_a, value_a) {
-                        Ok(val) => val,
-                        Err(e) => {
-                            error.get_or_insert(Err(e));
-                            Null
-                        }
-                    },
-                };
-                let value_b = match cache.get(key_b) {
-                    Some(value) => value.clone(),
-                    None => match get_sort_key(vm, &mut cache, key_b, value_b) {
-                        Ok(val) => val,
-                        Err(e) => {
-                            error.get_or_insert(Err(e));
-                            Null
-                        }
-                    },
-                };
+                        CallArgs::Separate(&[key.value().clone(), value.clone()]),
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()?;
 
-                match compare_values(vm, &value_a, &value_b) {
-                    Ok(ordering) => ordering,
-                    Err(e) => {
-                        error.get_or_insert(Err(e));
-                        Ordering::Equal
-                    }
-                }
-            });
+            sort_values_by_keys(vm, &mut entries, &keys)?;
+            *m.data_mut() = entries.into_iter().collect();
 
-            if let Some(error) = error {
-                error
-            } else {
-                Ok(Map(m))
-            }
+            Ok(Map(m))
         }
         unexpected => type_error_with_slice(
             "a Map and optional sort key Function as arguments",
@@ -244,6 +340,35 @@ pub fn make_module() -> ValueMap {
         ),
     });
 
+    result.add_fn("to_flat_list", |vm, args| match vm.get_args(args) {
+        [Map(m)] => {
+            let result = m
+                .data()
+                .iter()
+                .flat_map(|(key, value)| [key.value().clone(), value.clone()])
+                .collect::<ValueVec>();
+            Ok(List(ValueList::with_data(result)))
+        }
+        unexpected => type_error_with_slice("a Map as argument", unexpected),
+    });
+
+    result.add_fn("to_list", |vm, args| match vm.get_args(args) {
+        // Maps that implement @iterator are collected using their iterator, matching the
+        // behaviour of iterator.to_list, with other maps producing a list of their entries.
+        [Map(m)] => iterable_to_list(vm, Map(m.clone())),
+        unexpected => type_error_with_slice("a Map as argument", unexpected),
+    });
+
+    result.add_fn("to_sorted_list", |vm, args| match vm.get_args(args) {
+        [Map(m)] => {
+            // The map's data is copied so that the input map's order is left unchanged
+            let mut data = m.data().clone();
+            data.sort_keys();
+            Ok(entries_to_list(&data))
+        }
+        unexpected => type_error_with_slice("a Map as argument", unexpected),
+    });
+
     result.add_fn("update", |vm, args| match vm.get_args(args) {
         [Map(m), key, f] if key.is_immutable() && f.is_callable() => {
             do_map_update(m.clone(), key.clone().into(), Null, f.clone(), vm)
@@ -277,6 +402,22 @@ pub fn make_module() -> ValueMap {
     result
 }
 
+// Returns a List containing the map's entries as `(key, value)` Tuples
+fn entries_to_list(data: &DataMap) -> Value {
+    let result = data
+        .iter()
+        .map(|(key, value)| Value::Tuple(vec![key.value().clone(), value.clone()].into()))
+        .collect::<ValueVec>();
+    Value::List(ValueList::with_data(result))
+}
+
+fn map_entries(map: &ValueMap) -> Vec<(ValueKey, Value)> {
+    map.data()
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
 fn do_map_update(
     map: ValueMap,
     key: ValueKey,
@@ -284,15 +425,10 @@ fn do_map_update(
     f: Value,
     vm: &mut Vm,
 ) -> RuntimeResult {
-    if !map.data().contains_key(&key) {
-        map.data_mut().insert(key.clone(), default);
-    }
-    let value = map.data().get(&key).cloned().unwrap();
-    match vm.run_function(f, CallArgs::Single(value)) {
-        Ok(new_value) => {
-            map.data_mut().insert(key, new_value.clone());
-            Ok(new_value)
-        }
-        Err(error) => Err(error),
-    }
+    // The map's borrow needs to be released before calling the function,
+    // so that the function is free to access the map.
+    let value = map.data_mut().entry(key.clone()).or_insert(default).clone();
+    let new_value = vm.run_function(f, CallArgs::Single(value))?;
+    map.data_mut().insert(key, new_value.clone());
+    Ok(new_value)
 }
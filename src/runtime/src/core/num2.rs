@@ -13,7 +13,18 @@ pub fn make_module() -> ValueMap {
 
     result.add_fn("angle", |vm, args| match vm.get_args(args) {
         [Num2(n)] => Ok(Number(n[1].atan2(n[0]).into())),
-        unexpected => num2_error(unexpected),
+        [Num2(a), Num2(b)] => Ok(Number(a.angle_between(b).into())),
+        unexpected => type_error_with_slice("a Num2, or two Num2s as arguments", unexpected),
+    });
+
+    result.add_fn("distance", |vm, args| match vm.get_args(args) {
+        [Num2(a), Num2(b)] => Ok(Number((b - a).length().into())),
+        unexpected => num2_pair_error(unexpected),
+    });
+
+    result.add_fn("dot", |vm, args| match vm.get_args(args) {
+        [Num2(a), Num2(b)] => Ok(Number(a.dot(b).into())),
+        unexpected => num2_pair_error(unexpected),
     });
 
     result.add_fn("length", |vm, args| match vm.get_args(args) {
@@ -73,14 +84,16 @@ pub fn make_module() -> ValueMap {
     result.add_fn("with", |vm, args| match vm.get_args(args) {
         [Num2(n), Number(i), Number(value)] => {
             let mut result = *n;
-            match usize::from(i) {
+            match i64::from(i) {
                 0 => result.0 = value.into(),
                 1 => result.1 = value.into(),
-                other => return runtime_error!("Invalid index '{other}'"),
+                other => return runtime_error!("num2.with: invalid index '{other}'"),
             }
             Ok(Num2(result))
         }
-        unexpected => num2_error(unexpected),
+        unexpected => {
+            type_error_with_slice("a Num2, an index, and a Number as arguments", unexpected)
+        }
     });
 
     result.add_fn("x", |vm, args| match vm.get_args(args) {
@@ -100,6 +113,10 @@ fn num2_error(unexpected: &[Value]) -> RuntimeResult {
     type_error_with_slice("a Num2 as argument", unexpected)
 }
 
+fn num2_pair_error(unexpected: &[Value]) -> RuntimeResult {
+    type_error_with_slice("two Num2s as arguments", unexpected)
+}
+
 pub(crate) fn num2_from_iterator(iterator: ValueIterator) -> Result<num2::Num2, RuntimeError> {
     let mut result = num2::Num2::default();
     for (i, value) in iterator.take(2).map(collect_pair).enumerate() {
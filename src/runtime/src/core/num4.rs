@@ -11,6 +11,26 @@ pub fn make_module() -> ValueMap {
 
     let result = ValueMap::new();
 
+    result.add_fn("angle", |vm, args| match vm.get_args(args) {
+        [Num4(a), Num4(b)] => Ok(Number(a.angle_between(b).into())),
+        unexpected => num4_pair_error(unexpected),
+    });
+
+    result.add_fn("cross", |vm, args| match vm.get_args(args) {
+        [Num4(a), Num4(b)] => Ok(Num4(a.cross(b))),
+        unexpected => num4_pair_error(unexpected),
+    });
+
+    result.add_fn("distance", |vm, args| match vm.get_args(args) {
+        [Num4(a), Num4(b)] => Ok(Number((b - a).length().into())),
+        unexpected => num4_pair_error(unexpected),
+    });
+
+    result.add_fn("dot", |vm, args| match vm.get_args(args) {
+        [Num4(a), Num4(b)] => Ok(Number(a.dot(b).into())),
+        unexpected => num4_pair_error(unexpected),
+    });
+
     result.add_fn("length", |vm, args| match vm.get_args(args) {
         [Num4(n)] => Ok(Number(n.length().into())),
         unexpected => num4_error(unexpected),
@@ -80,7 +100,7 @@ pub fn make_module() -> ValueMap {
     result.add_fn("with", |vm, args| match vm.get_args(args) {
         [Num4(n), Number(i), Number(value)] => {
             let mut result = *n;
-            match usize::from(i) {
+            match i64::from(i) {
                 0 => result.0 = value.into(),
                 1 => result.1 = value.into(),
                 2 => result.2 = value.into(),
@@ -89,7 +109,9 @@ pub fn make_module() -> ValueMap {
             }
             Ok(Num4(result))
         }
-        unexpected => num4_error(unexpected),
+        unexpected => {
+            type_error_with_slice("a Num4, an index, and a Number as arguments", unexpected)
+        }
     });
 
     result.add_fn("r", |vm, args| match vm.get_args(args) {
@@ -139,6 +161,10 @@ fn num4_error(unexpected: &[Value]) -> RuntimeResult {
     type_error_with_slice("a Num4 as argument", unexpected)
 }
 
+fn num4_pair_error(unexpected: &[Value]) -> RuntimeResult {
+    type_error_with_slice("two Num4s as arguments", unexpected)
+}
+
 pub(crate) fn num4_from_iterator(iterator: ValueIterator) -> Result<num4::Num4, RuntimeError> {
     let mut result = num4::Num4::default();
     for (i, value) in iterator.take(4).map(collect_pair).enumerate() {
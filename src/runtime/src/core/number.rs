@@ -1,5 +1,7 @@
 //! The `number` core library module
 
+pub mod format;
+
 use crate::prelude::*;
 
 /// Initializes the `number` core library module
@@ -81,10 +83,24 @@ pub fn make_module() -> ValueMap {
 
     number_fn!(ceil);
 
-    result.add_fn("clamp", |vm, args| match vm.get_args(args) {
-        [Number(x), Number(a), Number(b)] => Ok(Number(*a.max(b.min(x)))),
-        unexpected => type_error_with_slice("three Numbers as arguments", unexpected),
-    });
+    result.add_typed_fn(
+        "clamp",
+        |x: ValueNumber, min: ValueNumber, max: ValueNumber| {
+            if min > max {
+                return runtime_error!(
+                    "number.clamp: Expected a min that's less than or equal to max \
+                 (found min: {min}, max: {max})"
+                );
+            }
+
+            let result = min.max(max.min(x));
+            if x.is_f64() || min.is_f64() || max.is_f64() {
+                Ok(Number(f64::from(result).into()))
+            } else {
+                Ok(Number(result))
+            }
+        },
+    );
 
     number_f64_fn!(cos);
     number_f64_fn!(cosh);
@@ -102,8 +118,50 @@ pub fn make_module() -> ValueMap {
 
     number_fn!(floor);
 
+    result.add_fn("format", |vm, args| match vm.get_args(args) {
+        [Number(n)] => Ok(format::group_digits(&n.to_string(), ",").into()),
+        [Number(n), Map(options)] => {
+            let options = options.data();
+
+            let separator = match options.get_with_string("separator") {
+                Some(Str(separator)) => separator.to_string(),
+                None | Some(Null) => ",".to_string(),
+                Some(unexpected) => {
+                    return runtime_error!(
+                        "number.format: Expected a String for 'separator', found '{}'",
+                        unexpected.type_as_string()
+                    )
+                }
+            };
+
+            let formatted = match options.get_with_string("decimals") {
+                Some(Number(ValueNumber::I64(decimals))) => {
+                    format::to_fixed(n, decimal_count("number.format", *decimals)?)
+                }
+                None | Some(Null) => n.to_string(),
+                Some(unexpected) => {
+                    return runtime_error!(
+                        "number.format: Expected an Int for 'decimals', found '{}'",
+                        unexpected.type_as_string()
+                    )
+                }
+            };
+
+            Ok(format::group_digits(&formatted, &separator).into())
+        }
+        unexpected => {
+            type_error_with_slice("a Number and optional options Map as arguments", unexpected)
+        }
+    });
+
     result.add_value("infinity", Number(std::f64::INFINITY.into()));
 
+    result.add_fn("is_finite", |vm, args| match vm.get_args(args) {
+        [Number(ValueNumber::F64(n))] => Ok(n.is_finite().into()),
+        [Number(ValueNumber::I64(_))] => Ok(true.into()),
+        unexpected => type_error_with_slice("a Number as argument", unexpected),
+    });
+
     result.add_fn("is_nan", |vm, args| match vm.get_args(args) {
         [Number(n)] => Ok(n.is_nan().into()),
         unexpected => type_error_with_slice("a Number as argument", unexpected),
@@ -149,9 +207,24 @@ pub fn make_module() -> ValueMap {
     number_f64_fn!(recip);
     number_fn!(round);
 
+    result.add_fn("round_to", |vm, args| match vm.get_args(args) {
+        [Number(n), Number(ValueNumber::I64(places))] => Ok(Number(round_to(*n, *places))),
+        unexpected => type_error_with_slice("a Number and an Int as arguments", unexpected),
+    });
+
     bitwise_fn_positive_arg!(shift_left, <<);
     bitwise_fn_positive_arg!(shift_right, >>);
 
+    result.add_fn("sign", |vm, args| match vm.get_args(args) {
+        [Number(ValueNumber::I64(n))] => Ok(Number(n.signum().into())),
+        [Number(ValueNumber::F64(n))] => {
+            // f64::signum returns 1.0 for zero, which is returned as zero here to match Ints
+            let result = if *n == 0.0 { 0.0 } else { n.signum() };
+            Ok(Number(result.into()))
+        }
+        unexpected => type_error_with_slice("a Number as argument", unexpected),
+    });
+
     number_f64_fn!(sin);
     number_f64_fn!(sinh);
     number_f64_fn!(sqrt);
@@ -160,6 +233,14 @@ pub fn make_module() -> ValueMap {
 
     result.add_value("tau", Number(std::f64::consts::TAU.into()));
 
+    result.add_fn("to_fixed", |vm, args| match vm.get_args(args) {
+        [Number(n), Number(ValueNumber::I64(decimals))] => {
+            let decimals = decimal_count("number.to_fixed", *decimals)?;
+            Ok(format::to_fixed(n, decimals).into())
+        }
+        unexpected => type_error_with_slice("a Number and an Int as arguments", unexpected),
+    });
+
     result.add_fn("to_float", |vm, args| match vm.get_args(args) {
         [Number(n)] => Ok(Number(f64::from(n).into())),
         unexpected => type_error_with_slice("a Number as argument", unexpected),
@@ -170,7 +251,78 @@ pub fn make_module() -> ValueMap {
         unexpected => type_error_with_slice("a Number as argument", unexpected),
     });
 
+    result.add_fn("to_precision", |vm, args| match vm.get_args(args) {
+        [Number(n), Number(ValueNumber::I64(digits))] => {
+            if *digits < 1 {
+                return runtime_error!(
+                    "number.to_precision: Expected a positive number of significant digits \
+                     (found {digits})"
+                );
+            }
+            Ok(format::to_precision(n, *digits as usize).into())
+        }
+        unexpected => type_error_with_slice("a Number and an Int as arguments", unexpected),
+    });
+
     bitwise_fn!(xor, ^);
 
     result
 }
+
+// Rounds the number to the given number of decimal places, rounding half-way values away from 0
+//
+// Negative numbers of places round to the left of the decimal point, e.g. -2 rounds to the
+// nearest hundred. Ints stay as Ints, and Floats stay as Floats.
+fn round_to(n: ValueNumber, places: i64) -> ValueNumber {
+    match n {
+        ValueNumber::I64(n) => {
+            if places >= 0 {
+                return n.into();
+            }
+
+            let factor = match u32::try_from(-places)
+                .ok()
+                .and_then(|p| 10i64.checked_pow(p))
+            {
+                Some(factor) => factor,
+                // The factor is larger than any Int, so the result rounds to zero
+                None => return 0.into(),
+            };
+
+            let mut quotient = n / factor;
+            if (n % factor).abs() >= factor - factor / 2 {
+                quotient += n.signum();
+            }
+            quotient.saturating_mul(factor).into()
+        }
+        ValueNumber::F64(n) => {
+            let factor = 10f64.powi(places.unsigned_abs().min(i32::MAX as u64) as i32);
+
+            let result = if places >= 0 {
+                let scaled = n * factor;
+                if scaled.is_finite() {
+                    scaled.round() / factor
+                } else {
+                    // The number already has fewer decimal places than the requested precision
+                    n
+                }
+            } else if factor.is_finite() {
+                (n / factor).round() * factor
+            } else {
+                0.0
+            };
+
+            result.into()
+        }
+    }
+}
+
+fn decimal_count(fn_name: &str, decimals: i64) -> Result<usize, RuntimeError> {
+    if decimals < 0 {
+        runtime_error!(
+            "{fn_name}: Expected a non-negative number of decimal places (found {decimals})"
+        )
+    } else {
+        Ok(decimals as usize)
+    }
+}
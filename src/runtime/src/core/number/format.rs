@@ -0,0 +1,142 @@
+//! Number formatting support for the `number` module and `string.format`
+//!
+//! Values are rounded to the nearest representable output, with ties being rounded to the nearest
+//! even digit (e.g. `2.5` with no decimal places is formatted as `2`).
+
+use crate::ValueNumber;
+
+/// Formats the number with a fixed number of decimal places
+pub fn to_fixed(n: &ValueNumber, decimals: usize) -> String {
+    match n {
+        ValueNumber::F64(n) if n.is_finite() => format!("{n:.decimals$}"),
+        // Non-finite values are formatted without decimal places, e.g. `inf`
+        ValueNumber::F64(_) => n.to_string(),
+        // Integers are formatted directly to avoid losing precision for large values
+        ValueNumber::I64(n) if decimals > 0 => format!("{n}.{}", "0".repeat(decimals)),
+        ValueNumber::I64(n) => n.to_string(),
+    }
+}
+
+/// Formats the number with the given number of significant digits
+///
+/// Exponential notation (e.g. `1.5e6`) is used when the number's exponent is less than -6, or
+/// when it's greater than or equal to the number of significant digits.
+///
+/// `significant_digits` is expected to be greater than zero.
+pub fn to_precision(n: &ValueNumber, significant_digits: usize) -> String {
+    let n = f64::from(n);
+    if !n.is_finite() {
+        return ValueNumber::from(n).to_string();
+    }
+
+    let precision = significant_digits.max(1) - 1;
+    let exponential = format!("{n:.precision$e}");
+    // The exponent is taken from the rounded output, so that e.g. 9.99 with 2 significant digits
+    // is treated as 10
+    let exponent = exponential
+        .rsplit('e')
+        .next()
+        .and_then(|exponent| exponent.parse::<i64>().ok())
+        .unwrap_or(0);
+
+    if exponent < -6 || exponent >= significant_digits as i64 {
+        exponential
+    } else {
+        let decimals = (precision as i64 - exponent) as usize;
+        format!("{n:.decimals$}")
+    }
+}
+
+/// Inserts a separator between each group of three digits in a formatted number's integer part
+///
+/// e.g. `-1234567.89` with `,` as the separator becomes `-1,234,567.89`
+pub fn group_digits(formatted: &str, separator: &str) -> String {
+    let digits_start = formatted
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or(formatted.len());
+    let digits_end = formatted[digits_start..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(formatted.len(), |end| digits_start + end);
+    let (prefix, rest) = formatted.split_at(digits_start);
+    let (digits, suffix) = rest.split_at(digits_end - digits_start);
+
+    let mut result = String::with_capacity(formatted.len() + digits.len() / 3 * separator.len());
+    result.push_str(prefix);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            result.push_str(separator);
+        }
+        result.push(digit);
+    }
+    result.push_str(suffix);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed() {
+        for (n, decimals, expected) in [
+            (ValueNumber::from(1.0), 2, "1.00"),
+            (1.23456.into(), 2, "1.23"),
+            (0.5.into(), 0, "0"),
+            (1.5.into(), 0, "2"),
+            (2.5.into(), 0, "2"),
+            ((-2.5).into(), 0, "-2"),
+            (0.125.into(), 2, "0.12"),
+            (0.375.into(), 2, "0.38"),
+            (42.into(), 0, "42"),
+            (42.into(), 3, "42.000"),
+            (i64::MAX.into(), 1, "9223372036854775807.0"),
+            (f64::INFINITY.into(), 2, "inf"),
+            (f64::NAN.into(), 2, "nan"),
+        ] {
+            assert_eq!(
+                to_fixed(&n, decimals),
+                expected,
+                "to_fixed({n}, {decimals})"
+            );
+        }
+    }
+
+    #[test]
+    fn precision() {
+        for (n, digits, expected) in [
+            (ValueNumber::from(1.23456), 3, "1.23"),
+            (1500000.into(), 2, "1.5e6"),
+            (123.456.into(), 4, "123.5"),
+            (123.456.into(), 3, "123"),
+            (123.456.into(), 2, "1.2e2"),
+            (9.99.into(), 2, "10"),
+            (2.5.into(), 1, "2"),
+            ((-2.5).into(), 1, "-2"),
+            (0.00012345.into(), 2, "0.00012"),
+            (0.00000012345.into(), 2, "1.2e-7"),
+            (0.into(), 3, "0.00"),
+            (f64::NEG_INFINITY.into(), 3, "-inf"),
+        ] {
+            assert_eq!(
+                to_precision(&n, digits),
+                expected,
+                "to_precision({n}, {digits})"
+            );
+        }
+    }
+
+    #[test]
+    fn grouped_digits() {
+        for (formatted, separator, expected) in [
+            ("1", ",", "1"),
+            ("123", ",", "123"),
+            ("1234", ",", "1,234"),
+            ("-1234567.89", ",", "-1,234,567.89"),
+            ("123456.7891", "_", "123_456.7891"),
+            ("1000000", " ", "1 000 000"),
+            ("inf", ",", "inf"),
+        ] {
+            assert_eq!(group_digits(formatted, separator), expected);
+        }
+    }
+}
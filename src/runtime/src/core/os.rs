@@ -2,7 +2,10 @@
 
 use {
     crate::prelude::*,
-    chrono::prelude::*,
+    chrono::{
+        format::{Item, StrftimeItems},
+        prelude::*,
+    },
     instant::Instant,
     std::{cell::RefCell, rc::Rc},
 };
@@ -29,6 +32,20 @@ pub fn make_module() -> ValueMap {
         ),
     });
 
+    result.add_fn("time_string", |vm, args| match vm.get_args(args) {
+        [Value::Str(format)] => {
+            let items = StrftimeItems::new(format).collect::<Vec<_>>();
+            if items.iter().any(|item| matches!(item, Item::Error)) {
+                return runtime_error!("os.time_string: Invalid format string '{format}'");
+            }
+            Ok(Local::now()
+                .format_with_items(items.into_iter())
+                .to_string()
+                .into())
+        }
+        unexpected => type_error_with_slice("a format String as argument", unexpected),
+    });
+
     result
 }
 
@@ -10,6 +10,7 @@ pub fn make_module() -> ValueMap {
 
     result.add_fn("contains", |vm, args| match vm.get_args(args) {
         [Range(r), Number(n)] => {
+            let r = r.to_exclusive();
             let result = if r.is_ascending() {
                 n.floor() >= r.start && n.ceil() < r.end
             } else {
@@ -32,19 +33,29 @@ pub fn make_module() -> ValueMap {
                 Ok(Range(IntRange {
                     start: r.start - n,
                     end: r.end + n,
+                    ..*r
                 }))
             } else {
                 Ok(Range(IntRange {
                     start: r.start + n,
                     end: r.end - n,
+                    ..*r
                 }))
             }
         }
         unexpected => type_error_with_slice("a Range and Number as arguments", unexpected),
     });
 
+    result.add_fn("intersection", |vm, args| match vm.get_args(args) {
+        [Range(a), Range(b)] => match a.intersection(b) {
+            Some(result) => Ok(Range(result)),
+            None => Ok(Null),
+        },
+        unexpected => type_error_with_slice("two Ranges as arguments", unexpected),
+    });
+
     result.add_fn("size", |vm, args| match vm.get_args(args) {
-        [Range(r)] => Ok(Number((r.end - r.start).into())),
+        [Range(r)] => Ok(Number((r.exclusive_end() - r.start).into())),
         unexpected => type_error_with_slice("a Range as argument", unexpected),
     });
 
@@ -56,39 +67,51 @@ pub fn make_module() -> ValueMap {
     result.add_fn("union", |vm, args| match vm.get_args(args) {
         [Range(r), Number(n)] => {
             let n = isize::from(n);
-            if r.is_ascending() {
-                Ok(Range(IntRange {
+            let inclusive = r.inclusive;
+            let r = r.to_exclusive();
+            let result = if r.is_ascending() {
+                IntRange {
                     start: r.start.min(n),
                     end: r.end.max(n + 1),
-                }))
+                    inclusive: false,
+                }
             } else {
-                Ok(Range(IntRange {
+                IntRange {
                     start: r.start.max(n),
                     end: r.end.min(n - 1),
-                }))
-            }
+                    inclusive: false,
+                }
+            };
+
+            Ok(Range(result.with_inclusivity(inclusive)))
         }
         [Range(a), Range(b)] => {
+            let inclusive = a.inclusive;
+            let (a, b) = (a.to_exclusive(), b.to_exclusive());
             let result = match (a.is_ascending(), b.is_ascending()) {
-                (true, true) => Range(IntRange {
+                (true, true) => IntRange {
                     start: a.start.min(b.start),
                     end: a.end.max(b.end),
-                }),
-                (true, false) => Range(IntRange {
+                    inclusive: false,
+                },
+                (true, false) => IntRange {
                     start: a.start.min(b.end + 1),
                     end: a.end.max(b.start + 1),
-                }),
-                (false, true) => Range(IntRange {
+                    inclusive: false,
+                },
+                (false, true) => IntRange {
                     start: a.start.max(b.end - 1),
                     end: a.end.min(b.start),
-                }),
-                (false, false) => Range(IntRange {
+                    inclusive: false,
+                },
+                (false, false) => IntRange {
                     start: a.start.max(b.start),
                     end: a.end.min(b.end),
-                }),
+                    inclusive: false,
+                },
             };
 
-            Ok(result)
+            Ok(Range(result.with_inclusivity(inclusive)))
         }
         unexpected => type_error_with_slice(
             "a Range and another Range or a Number as arguments",
@@ -1,5 +1,6 @@
 //! The `string` core library module
 
+mod encoding;
 pub mod format;
 pub mod iterators;
 
@@ -8,6 +9,12 @@ use {
     unicode_segmentation::UnicodeSegmentation,
 };
 
+/// The maximum size in bytes of strings produced by `string.repeat` and `string.pad_*`
+///
+/// The size of the result is checked before it's produced, so that a large count or width results
+/// in an error rather than the allocation failing.
+pub const MAX_GENERATED_STRING_SIZE: usize = 1 << 30;
+
 /// Initializes the `string` core library module
 pub fn make_module() -> ValueMap {
     use Value::*;
@@ -22,6 +29,14 @@ pub fn make_module() -> ValueMap {
         unexpected => expected_string_error(unexpected),
     });
 
+    result.add_fn("char_code", |vm, args| match vm.get_args(args) {
+        [Str(s)] => match s.chars().next() {
+            Some(c) => Ok((c as u32).into()),
+            None => runtime_error!("string.char_code: Expected a non-empty String"),
+        },
+        unexpected => expected_string_error(unexpected),
+    });
+
     result.add_fn("chars", |vm, args| match vm.get_args(args) {
         [Str(s)] => Ok(Iterator(ValueIterator::with_string(s.clone()))),
         unexpected => expected_string_error(unexpected),
@@ -37,32 +52,58 @@ pub fn make_module() -> ValueMap {
         unexpected => expected_two_strings_error(unexpected),
     });
 
+    result.add_fn("equals_ignore_case", |vm, args| match vm.get_args(args) {
+        [Str(a), Str(b)] => Ok((case_fold(a) == case_fold(b)).into()),
+        unexpected => expected_two_strings_error(unexpected),
+    });
+
     result.add_fn("escape", |vm, args| match vm.get_args(args) {
         [Str(s)] => Ok(s.escape_default().to_string().into()),
         unexpected => expected_string_error(unexpected),
     });
 
     result.add_fn("format", |vm, args| match vm.get_args(args) {
-        [result @ Str(_)] => Ok(result.clone()),
         [Str(format), format_args @ ..] => {
             let format = format.clone();
-            let format_args = format_args.to_vec();
+            let format_args = format_args.iter().cloned().collect::<ValueVec>();
             match format::format_string(vm, &format, &format_args) {
                 Ok(result) => Ok(result.into()),
                 Err(error) => Err(error),
             }
         }
-        unexpected => type_error_with_slice(
+        unexpected => type_error_with_arg(
+            "a String as argument, followed by optional additional Values",
+            unexpected,
+            0,
+        ),
+    });
+
+    result.add_fn("format_strict", |vm, args| match vm.get_args(args) {
+        [Str(format), format_args @ ..] => {
+            let format = format.clone();
+            let format_args = format_args.iter().cloned().collect::<ValueVec>();
+            match format::format_string_strict(vm, &format, &format_args) {
+                Ok(result) => Ok(result.into()),
+                Err(error) => Err(error),
+            }
+        }
+        unexpected => type_error_with_arg(
             "a String as argument, followed by optional additional Values",
             unexpected,
+            0,
         ),
     });
 
+    result.add_fn("from_base64", |vm, args| match vm.get_args(args) {
+        [Str(s)] => decoded_bytes_to_string("from_base64", encoding::from_base64(s)),
+        unexpected => expected_string_error(unexpected),
+    });
+
     result.add_fn("from_bytes", |vm, args| match vm.get_args(args) {
         [iterable] if iterable.is_iterable() => {
             let iterable = iterable.clone();
-            let iterator = vm.make_iterator(iterable)?;
-            let (size_hint, _) = iterator.size_hint();
+            let iterator = vm.make_interruptible_iterator(iterable)?;
+            let size_hint = iterator.capacity_hint();
             let mut bytes = Vec::<u8>::with_capacity(size_hint);
 
             for output in iterator.map(collect_pair) {
@@ -86,11 +127,44 @@ pub fn make_module() -> ValueMap {
         unexpected => type_error_with_slice("an iterable value as argument", unexpected),
     });
 
+    result.add_fn("from_char_code", |vm, args| match vm.get_args(args) {
+        [Number(n)] => match u32::try_from(n.as_i64()).ok().and_then(char::from_u32) {
+            Some(c) => Ok(c.to_string().into()),
+            None => runtime_error!("string.from_char_code: '{n}' isn't a valid Unicode code point"),
+        },
+        unexpected => type_error_with_slice("a Number as argument", unexpected),
+    });
+
+    result.add_fn("from_hex", |vm, args| match vm.get_args(args) {
+        [Str(s)] => decoded_bytes_to_string("from_hex", encoding::from_hex(s)),
+        unexpected => expected_string_error(unexpected),
+    });
+
+    result.add_fn("index_of", |vm, args| {
+        let (s, pattern, start) = match vm.get_args(args) {
+            [Str(s), Str(pattern)] => (s, pattern, 0),
+            [Str(s), Str(pattern), Number(start)] if *start >= 0.0 => (s, pattern, start.into()),
+            unexpected => {
+                return type_error_with_slice(
+                    "two Strings and an optional non-negative start index as arguments",
+                    unexpected,
+                )
+            }
+        };
+
+        Ok(index_of(s, pattern, start).map_or(Null, Value::from))
+    });
+
     result.add_fn("is_empty", |vm, args| match vm.get_args(args) {
         [Str(s)] => Ok(s.is_empty().into()),
         unexpected => expected_string_error(unexpected),
     });
 
+    result.add_fn("last_index_of", |vm, args| match vm.get_args(args) {
+        [Str(s), Str(pattern)] => Ok(last_index_of(s, pattern).map_or(Null, Value::from)),
+        unexpected => expected_two_strings_error(unexpected),
+    });
+
     result.add_fn("lines", |vm, args| match vm.get_args(args) {
         [Str(s)] => {
             let result = iterators::Lines::new(s.clone());
@@ -99,11 +173,117 @@ pub fn make_module() -> ValueMap {
         unexpected => expected_string_error(unexpected),
     });
 
-    result.add_fn("replace", |vm, args| match vm.get_args(args) {
-        [Str(input), Str(pattern), Str(replace)] => {
-            Ok(input.replace(pattern.as_str(), replace).into())
+    result.add_fn("matches", |vm, args| {
+        let iterator = match vm.get_args(args) {
+            [Str(_), Str(pattern)] if pattern.is_empty() => {
+                return runtime_error!("string.matches: Empty patterns aren't allowed")
+            }
+            [Str(input), Str(pattern)] => {
+                let result = iterators::Matches::new(input.clone(), pattern.clone());
+                ValueIterator::new(result)
+            }
+            [Str(input), predicate] if predicate.is_callable() => {
+                let result = iterators::MatchesWith::new(
+                    input.clone(),
+                    predicate.clone(),
+                    vm.spawn_shared_vm(),
+                );
+                ValueIterator::new(result)
+            }
+            unexpected => {
+                return type_error_with_slice(
+                    "a String and either a String or predicate Function as arguments",
+                    unexpected,
+                )
+            }
+        };
+
+        Ok(Iterator(iterator))
+    });
+
+    result.add_fn("pad_left", |vm, args| {
+        pad("pad_left", vm.get_args(args), true)
+    });
+
+    result.add_fn("pad_right", |vm, args| {
+        pad("pad_right", vm.get_args(args), false)
+    });
+
+    result.add_fn("parse_number", |vm, args| match vm.get_args(args) {
+        [Str(s)] => match parse_number(s) {
+            Some(n) => Ok(Number(n)),
+            None => Ok(Null),
+        },
+        unexpected => expected_string_error(unexpected),
+    });
+
+    result.add_typed_fn(
+        "replace",
+        |input: ValueString, pattern: ValueString, replace: ValueString, count: Option<usize>| {
+            let result = match count {
+                Some(count) => input.replacen(pattern.as_str(), &replace, count),
+                None => input.replace(pattern.as_str(), &replace),
+            };
+            Ok(result)
+        },
+    );
+
+    result.add_fn("repeat", |vm, args| match vm.get_args(args) {
+        [Str(s), Number(n)] if *n >= 0.0 => {
+            let n: usize = n.into();
+            match s.len().checked_mul(n) {
+                Some(size) if size <= MAX_GENERATED_STRING_SIZE => Ok(s.repeat(n).into()),
+                _ => generated_string_size_error("repeat"),
+            }
+        }
+        unexpected => type_error_with_slice(
+            "a String and a non-negative Number as arguments",
+            unexpected,
+        ),
+    });
+
+    result.add_fn("replace_all", |vm, args| match vm.get_args(args) {
+        [Str(input), Map(replacements)] => {
+            let mut patterns = Vec::with_capacity(replacements.len());
+            for (pattern, replacement) in replacements.data().iter() {
+                patterns.push(replacement_pattern(pattern, replacement)?);
+            }
+            Ok(replace_all(input, &patterns, ReplaceAllMatch::Longest).into())
+        }
+        [Str(input), List(replacements)] => {
+            let mut patterns = Vec::with_capacity(replacements.len());
+            for entry in replacements.data().iter() {
+                match entry {
+                    Tuple(t) if t.len() == 2 => patterns.push(replacement_pattern(&t[0], &t[1])?),
+                    unexpected => {
+                        return type_error(
+                            "a (pattern, replacement) Tuple in the replacements List",
+                            unexpected,
+                        )
+                    }
+                }
+            }
+            Ok(replace_all(input, &patterns, ReplaceAllMatch::First).into())
         }
-        unexpected => type_error_with_slice("three Strings as arguments", unexpected),
+        unexpected => type_error_with_slice(
+            "a String and a Map or List of replacements as arguments",
+            unexpected,
+        ),
+    });
+
+    result.add_typed_fn(
+        "replace_first",
+        |input: ValueString, pattern: ValueString, replace: ValueString| {
+            Ok(input.replacen(pattern.as_str(), &replace, 1))
+        },
+    );
+
+    result.add_fn("reversed", |vm, args| match vm.get_args(args) {
+        [Str(s)] => {
+            let result = iterators::ReversedGraphemes::new(s.clone());
+            Ok(ValueIterator::new(result).into())
+        }
+        unexpected => expected_string_error(unexpected),
     });
 
     result.add_fn("size", |vm, args| match vm.get_args(args) {
@@ -141,53 +321,481 @@ pub fn make_module() -> ValueMap {
         unexpected => expected_two_strings_error(unexpected),
     });
 
+    result.add_fn("to_base64", |vm, args| match vm.get_args(args) {
+        [Str(s)] => Ok(encoding::to_base64(s.as_bytes()).into()),
+        unexpected => expected_string_error(unexpected),
+    });
+
+    result.add_fn("to_casefold", |vm, args| match vm.get_args(args) {
+        [Str(s)] => Ok(case_fold(s).into()),
+        unexpected => expected_string_error(unexpected),
+    });
+
+    result.add_fn("to_hex", |vm, args| match vm.get_args(args) {
+        [Str(s)] => Ok(encoding::to_hex(s.as_bytes()).into()),
+        unexpected => expected_string_error(unexpected),
+    });
+
     result.add_fn("to_lowercase", |vm, args| match vm.get_args(args) {
-        [Str(s)] => {
-            let result = s.chars().flat_map(|c| c.to_lowercase()).collect::<String>();
-            Ok(result.into())
-        }
+        [Str(s)] => Ok(s.to_lowercase().into()),
         unexpected => expected_string_error(unexpected),
     });
 
     result.add_fn("to_number", |vm, args| match vm.get_args(args) {
-        [Str(s)] => match s.parse::<i64>() {
-            Ok(n) => Ok(Number(n.into())),
-            Err(_) => match s.parse::<f64>() {
-                Ok(n) => Ok(Number(n.into())),
-                Err(_) => {
-                    runtime_error!("string.to_number: Failed to convert '{s}'")
-                }
-            },
+        [Str(s)] => match parse_number(s) {
+            Some(n) => Ok(Number(n)),
+            None => runtime_error!("string.to_number: Failed to convert '{s}'"),
         },
         unexpected => expected_string_error(unexpected),
     });
 
     result.add_fn("to_uppercase", |vm, args| match vm.get_args(args) {
-        [Str(s)] => {
-            let result = s.chars().flat_map(|c| c.to_uppercase()).collect::<String>();
-            Ok(result.into())
-        }
+        [Str(s)] => Ok(s.to_uppercase().into()),
         unexpected => expected_string_error(unexpected),
     });
 
-    result.add_fn("trim", |vm, args| match vm.get_args(args) {
-        [Str(s)] => {
-            let result = match s.find(|c: char| !c.is_whitespace()) {
-                Some(start) => {
-                    let end = s.rfind(|c: char| !c.is_whitespace()).unwrap();
-                    s.with_bounds(start..(end + 1)).unwrap()
+    result.add_fn("trim", |vm, args| trim(vm.get_args(args), true, true));
+
+    result.add_fn("trim_end", |vm, args| trim(vm.get_args(args), false, true));
+
+    result.add_fn("trim_start", |vm, args| {
+        trim(vm.get_args(args), true, false)
+    });
+
+    result.add_fn("truncate", |vm, args| {
+        let (input, width, ellipsis) =
+            match vm.get_args(args) {
+                [Str(input), Number(width)] if *width >= 0.0 => (input, width, None),
+                [Str(input), Number(width), Str(ellipsis)] if *width >= 0.0 => {
+                    (input, width, Some(ellipsis.as_str()))
                 }
-                None => s.with_bounds(0..0).unwrap(),
+                unexpected => return type_error_with_slice(
+                    "a String, a non-negative width, and an optional ellipsis String as arguments",
+                    unexpected,
+                ),
             };
 
-            Ok(result.into())
+        Ok(truncate(input, width.into(), ellipsis.unwrap_or("…")).into())
+    });
+
+    result.add_fn("wrap", |vm, args| match vm.get_args(args) {
+        [Str(input), Number(width)] if *width >= 1.0 => {
+            let result = wrap(input, width.into())
+                .into_iter()
+                .map(Str)
+                .collect::<ValueVec>();
+            Ok(List(ValueList::with_data(result)))
         }
-        unexpected => expected_string_error(unexpected),
+        unexpected => type_error_with_slice(
+            "a String and a width greater than zero as arguments",
+            unexpected,
+        ),
     });
 
     result
 }
 
+// Truncates a string so that it's no longer than `width` graphemes, see `string.truncate`
+//
+// If the string needs to be truncated then the ellipsis is included in the result's width.
+// If the ellipsis is wider than `width` then the ellipsis is itself truncated.
+fn truncate(input: &ValueString, width: usize, ellipsis: &str) -> ValueString {
+    let end_of_grapheme = |s: &str, count: usize| match s.grapheme_indices(true).nth(count) {
+        Some((index, _)) => index,
+        None => s.len(),
+    };
+
+    if input.graphemes(true).nth(width).is_none() {
+        return input.clone();
+    }
+
+    let ellipsis_width = ellipsis.graphemes(true).count();
+    if ellipsis_width >= width {
+        return ellipsis[..end_of_grapheme(ellipsis, width)].into();
+    }
+
+    let end = end_of_grapheme(input, width - ellipsis_width);
+    format!("{}{ellipsis}", &input[..end]).into()
+}
+
+// Wraps a string into lines that are no longer than `width` graphemes, see `string.wrap`
+//
+// Lines are broken at whitespace, and words that are longer than `width` are broken into
+// `width`-sized pieces. Newlines in the input are kept as line breaks.
+// The wrapped lines share the input string's data.
+fn wrap(input: &ValueString, width: usize) -> Vec<ValueString> {
+    let mut result = Vec::new();
+    let mut line_offset = 0;
+
+    for line in input.split('\n') {
+        let next_line_offset = line_offset + line.len() + 1;
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        // The byte ranges of the line's graphemes, along with whether or not they're whitespace
+        let graphemes = line
+            .grapheme_indices(true)
+            .map(|(start, g)| {
+                let start = line_offset + start;
+                (start..start + g.len(), g.chars().all(char::is_whitespace))
+            })
+            .collect::<Vec<_>>();
+
+        // Makes a substring from a range of grapheme indices
+        let substring = |start: usize, end: usize| {
+            input
+                .with_bounds(graphemes[start].0.start..graphemes[end - 1].0.end)
+                .unwrap()
+        };
+
+        let mut words = Vec::new();
+        let mut word_start = None;
+        for (i, (_, is_whitespace)) in graphemes.iter().enumerate() {
+            match (word_start, is_whitespace) {
+                (None, false) => word_start = Some(i),
+                (Some(start), true) => {
+                    words.push((start, i));
+                    word_start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(start) = word_start {
+            words.push((start, graphemes.len()));
+        }
+
+        // The range of grapheme indices in the line that's currently being wrapped
+        let mut current: Option<(usize, usize)> = None;
+        let mut line_count = 0;
+        for (mut word_start, word_end) in words {
+            if let Some((current_start, current_end)) = current {
+                if word_end - current_start <= width {
+                    current = Some((current_start, word_end));
+                    continue;
+                }
+                result.push(substring(current_start, current_end));
+                line_count += 1;
+            }
+
+            while word_end - word_start > width {
+                result.push(substring(word_start, word_start + width));
+                line_count += 1;
+                word_start += width;
+            }
+
+            current = Some((word_start, word_end));
+        }
+
+        match current {
+            Some((start, end)) => result.push(substring(start, end)),
+            None if line_count == 0 => result.push(input.with_bounds(0..0).unwrap()),
+            None => {}
+        }
+
+        line_offset = next_line_offset;
+    }
+
+    result
+}
+
+// Pads a string with a fill grapheme so that it's at least `width` graphemes long,
+// see `string.pad_left` and `string.pad_right`
+fn pad(name: &str, args: &[Value], pad_start: bool) -> RuntimeResult {
+    use Value::*;
+
+    let (input, width, fill) = match args {
+        [Str(input), Number(width)] if *width >= 0.0 => (input, width, " "),
+        [Str(input), Number(width), Str(fill)] if *width >= 0.0 => (input, width, fill.as_str()),
+        unexpected => {
+            return type_error_with_slice(
+                "a String, a non-negative width, and an optional fill String as arguments",
+                unexpected,
+            )
+        }
+    };
+
+    if fill.graphemes(true).count() != 1 {
+        return runtime_error!(
+            "string.{name}: Expected a fill String containing a single grapheme, found '{fill}'"
+        );
+    }
+
+    let width: usize = width.into();
+    let size = input.graphemes(true).count();
+    if size >= width {
+        return Ok(Str(input.clone()));
+    }
+
+    let padding_count = width - size;
+    match fill
+        .len()
+        .checked_mul(padding_count)
+        .and_then(|padding_size| padding_size.checked_add(input.len()))
+    {
+        Some(result_size) if result_size <= MAX_GENERATED_STRING_SIZE => {}
+        _ => return generated_string_size_error(name),
+    }
+
+    let padding = fill.repeat(padding_count);
+    let result = if pad_start {
+        padding + input
+    } else {
+        format!("{input}{padding}")
+    };
+    Ok(result.into())
+}
+
+fn generated_string_size_error(name: &str) -> RuntimeResult {
+    runtime_error!(
+        "string.{name}: The result would exceed the maximum string size \
+         of {MAX_GENERATED_STRING_SIZE} bytes"
+    )
+}
+
+// Trims the start and/or end of a string, see `string.trim`
+//
+// Whitespace is trimmed by default, otherwise any graphemes that match graphemes in the provided
+// trim set are trimmed. The result shares the input string's data.
+fn trim(args: &[Value], trim_start: bool, trim_end: bool) -> RuntimeResult {
+    use Value::*;
+
+    let (s, trim_set) = match args {
+        [Str(s)] => (s, None),
+        [Str(s), Str(trim_set)] => (s, Some(trim_set)),
+        unexpected => {
+            return type_error_with_slice(
+                "a String, and an optional String containing the characters to trim",
+                unexpected,
+            )
+        }
+    };
+
+    let (start, end) = match trim_set {
+        None => {
+            let start = if trim_start {
+                s.len() - s.trim_start().len()
+            } else {
+                0
+            };
+            let end = if trim_end {
+                s.trim_end().len()
+            } else {
+                s.len()
+            };
+            (start, end)
+        }
+        Some(trim_set) => {
+            let trim_set = trim_set.graphemes(true).collect::<Vec<_>>();
+            let start = if trim_start {
+                s.grapheme_indices(true)
+                    .find(|(_, grapheme)| !trim_set.contains(grapheme))
+                    .map_or(s.len(), |(offset, _)| offset)
+            } else {
+                0
+            };
+            let end = if trim_end {
+                s.grapheme_indices(true)
+                    .rev()
+                    .find(|(_, grapheme)| !trim_set.contains(grapheme))
+                    .map_or(0, |(offset, grapheme)| offset + grapheme.len())
+            } else {
+                s.len()
+            };
+            (start, end)
+        }
+    };
+
+    // When the whole string is trimmed, the end will be before the start
+    Ok(s.with_bounds(start..end.max(start)).unwrap().into())
+}
+
+// Returns the byte offsets of the string's grapheme boundaries, including the end of the string
+fn grapheme_boundaries(s: &str) -> Vec<usize> {
+    s.grapheme_indices(true)
+        .map(|(offset, _)| offset)
+        .chain(std::iter::once(s.len()))
+        .collect()
+}
+
+// Finds the first occurrence of the pattern in the string at or after the `start` grapheme index,
+// see `string.index_of`
+//
+// Matches that don't begin and end on grapheme boundaries are skipped, so that the returned index
+// is consistent with `string.size` and slicing.
+fn index_of(s: &str, pattern: &str, start: usize) -> Option<usize> {
+    let boundaries = grapheme_boundaries(s);
+    let mut search_start = *boundaries.get(start)?;
+
+    while let Some(offset) = s[search_start..].find(pattern) {
+        let match_start = search_start + offset;
+        if let Ok(index) = boundaries.binary_search(&match_start) {
+            if boundaries
+                .binary_search(&(match_start + pattern.len()))
+                .is_ok()
+            {
+                return Some(index);
+            }
+        }
+        // Continue searching from the char following the start of the rejected match
+        search_start = match_start + s[match_start..].chars().next()?.len_utf8();
+    }
+
+    None
+}
+
+// Finds the last occurrence of the pattern in the string, see `string.last_index_of`
+//
+// As with `index_of`, matches that don't begin and end on grapheme boundaries are skipped.
+fn last_index_of(s: &str, pattern: &str) -> Option<usize> {
+    let boundaries = grapheme_boundaries(s);
+    let mut search_end = s.len();
+
+    while let Some(match_start) = s[..search_end].rfind(pattern) {
+        let match_end = match_start + pattern.len();
+        if let Ok(index) = boundaries.binary_search(&match_start) {
+            if boundaries.binary_search(&match_end).is_ok() {
+                return Some(index);
+            }
+        }
+        // Continue searching for matches that end before the end of the rejected match
+        search_end = match_end - s[..match_end].chars().next_back()?.len_utf8();
+    }
+
+    None
+}
+
+// Folds the string's case so that it can be used in caseless comparisons
+//
+// Each char is mapped to lowercase, then uppercase, and then back to lowercase, without the
+// context-sensitive mappings that are used by str::to_lowercase (e.g. a final 'Σ' folds to 'σ'
+// rather than 'ς'). This matches Unicode's full case folding for the vast majority of chars,
+// including those that fold to multiple chars (e.g. 'ß' folds to 'ss').
+fn case_fold(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        // The dotless 'ı' is only folded to 'i' by the Turkic mappings, which aren't applied here
+        if c == 'ı' {
+            result.push(c);
+        } else {
+            result.extend(
+                c.to_lowercase()
+                    .flat_map(char::to_uppercase)
+                    .flat_map(char::to_lowercase),
+            );
+        }
+    }
+    result
+}
+
+// Parses a string as a number, used by `string.to_number` and `string.parse_number`
+//
+// - Leading and trailing whitespace is ignored.
+// - A single leading `+` or `-` sign is allowed.
+// - Integers are returned as `Int`s, falling back to `Float`s for larger values.
+// - Decimal points and exponents (e.g. `1.5e3`) produce `Float`s.
+// - `inf`, `-inf`, and `nan` are accepted, matching the way that the values are displayed.
+//   Other spellings (e.g. `infinity` or `NaN`) aren't accepted, and neither are underscores.
+fn parse_number(s: &str) -> Option<ValueNumber> {
+    let s = s.trim();
+
+    match s {
+        "inf" | "+inf" => return Some(f64::INFINITY.into()),
+        "-inf" => return Some(f64::NEG_INFINITY.into()),
+        "nan" => return Some(f64::NAN.into()),
+        _ => {}
+    }
+
+    if !s
+        .chars()
+        .all(|c| matches!(c, '0'..='9' | '.' | '+' | '-' | 'e' | 'E'))
+    {
+        return None;
+    }
+
+    match s.parse::<i64>() {
+        Ok(n) => Some(n.into()),
+        Err(_) => s.parse::<f64>().ok().map(|n| n.into()),
+    }
+}
+
+// Validates a pattern and replacement pair for string.replace_all
+fn replacement_pattern(
+    pattern: &Value,
+    replacement: &Value,
+) -> Result<(ValueString, ValueString), RuntimeError> {
+    match (pattern, replacement) {
+        (Value::Str(pattern), _) if pattern.is_empty() => {
+            runtime_error!("string.replace_all: Empty patterns aren't allowed")
+        }
+        (Value::Str(pattern), Value::Str(replacement)) => {
+            Ok((pattern.clone(), replacement.clone()))
+        }
+        (Value::Str(_), unexpected) => type_error("a String as replacement", unexpected),
+        (unexpected, _) => type_error("a String as pattern", unexpected),
+    }
+}
+
+// Determines which pattern is used by replace_all when more than one pattern matches
+enum ReplaceAllMatch {
+    // The longest matching pattern is used
+    Longest,
+    // The first matching pattern is used
+    First,
+}
+
+// Replaces all occurrences of the patterns in a single pass over the input
+//
+// At each position in the input the patterns are checked for a match, and if a match is found
+// then the replacement is appended to the output and scanning continues after the match.
+// Replacements are never themselves checked for matches.
+fn replace_all(
+    input: &str,
+    patterns: &[(ValueString, ValueString)],
+    match_mode: ReplaceAllMatch,
+) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut position = 0;
+
+    while position < input.len() {
+        let remaining = &input[position..];
+        let mut matches = patterns
+            .iter()
+            .filter(|(pattern, _)| remaining.starts_with(pattern.as_str()));
+
+        let found = match match_mode {
+            ReplaceAllMatch::Longest => matches.max_by_key(|(pattern, _)| pattern.len()),
+            ReplaceAllMatch::First => matches.next(),
+        };
+
+        match found {
+            Some((pattern, replacement)) => {
+                result.push_str(replacement);
+                position += pattern.len();
+            }
+            None => {
+                let c = remaining.chars().next().unwrap();
+                result.push(c);
+                position += c.len_utf8();
+            }
+        }
+    }
+
+    result
+}
+
+// Makes a String from decoded bytes, see `string.from_base64` and `string.from_hex`
+fn decoded_bytes_to_string(name: &str, decoded: Result<Vec<u8>, String>) -> RuntimeResult {
+    match decoded {
+        Ok(bytes) => match String::from_utf8(bytes) {
+            Ok(result) => Ok(result.into()),
+            Err(_) => runtime_error!(
+                "string.{name}: The decoded bytes aren't valid UTF-8, \
+                 only encoded strings can be decoded"
+            ),
+        },
+        Err(error) => runtime_error!("string.{name}: {error}"),
+    }
+}
+
 fn expected_string_error(unexpected: &[Value]) -> RuntimeResult {
     type_error_with_slice("a String as argument", unexpected)
 }
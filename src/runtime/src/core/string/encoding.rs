@@ -0,0 +1,184 @@
+//! Hex and base64 encoding support for the `string` module
+
+use std::fmt::Write;
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE64_PADDING: u8 = b'=';
+
+/// Encodes the bytes as a string of lowercase hex digits, with two digits per byte
+pub fn to_hex(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        // Safety: writing to a String can't fail
+        write!(result, "{byte:02x}").unwrap();
+    }
+    result
+}
+
+/// Decodes a string of hex digits, with either upper or lower case digits being accepted
+pub fn from_hex(input: &str) -> Result<Vec<u8>, String> {
+    if input.len() % 2 != 0 {
+        return Err(format!(
+            "Expected an even number of hex digits, found {}",
+            input.len()
+        ));
+    }
+
+    input
+        .as_bytes()
+        .chunks(2)
+        .enumerate()
+        .map(|(i, pair)| match (hex_digit(pair[0]), hex_digit(pair[1])) {
+            (Some(high), Some(low)) => Ok(high << 4 | low),
+            _ => Err(format!(
+                "Invalid hex digits '{}' at position {}",
+                String::from_utf8_lossy(pair),
+                i * 2
+            )),
+        })
+        .collect()
+}
+
+fn hex_digit(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Encodes the bytes as standard base64 (RFC 4648), with padding
+pub fn to_base64(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity((bytes.len() + 2) / 3 * 4);
+
+    for chunk in bytes.chunks(3) {
+        let b0 = chunk[0] as u32;
+        let b1 = chunk.get(1).copied().unwrap_or(0) as u32;
+        let b2 = chunk.get(2).copied().unwrap_or(0) as u32;
+        let group = b0 << 16 | b1 << 8 | b2;
+
+        // Each chunk of 1, 2, or 3 bytes is encoded as 2, 3, or 4 chars, followed by padding
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (group >> (18 - i * 6)) & 0x3f;
+                result.push(BASE64_CHARS[index as usize] as char);
+            } else {
+                result.push(BASE64_PADDING as char);
+            }
+        }
+    }
+
+    result
+}
+
+/// Decodes a string of standard base64 (RFC 4648), with padding
+pub fn from_base64(input: &str) -> Result<Vec<u8>, String> {
+    let input = input.as_bytes();
+
+    if input.len() % 4 != 0 {
+        return Err(format!(
+            "Expected the input's length to be a multiple of 4, found {}",
+            input.len()
+        ));
+    }
+
+    let mut result = Vec::with_capacity(input.len() / 4 * 3);
+
+    for (chunk_index, chunk) in input.chunks(4).enumerate() {
+        let is_last_chunk = (chunk_index + 1) * 4 == input.len();
+        let padding = chunk
+            .iter()
+            .rev()
+            .take_while(|c| **c == BASE64_PADDING)
+            .count();
+
+        if padding > 2 || (padding > 0 && !is_last_chunk) {
+            return Err("Invalid padding".into());
+        }
+
+        let mut group = 0;
+        for (i, c) in chunk[..4 - padding].iter().enumerate() {
+            match base64_value(*c) {
+                Some(value) => group |= value << (18 - i * 6),
+                None => {
+                    return Err(format!(
+                        "Invalid character '{}' at position {}",
+                        char::from(*c),
+                        chunk_index * 4 + i
+                    ))
+                }
+            }
+        }
+
+        let bytes = [(group >> 16) as u8, (group >> 8) as u8, group as u8];
+        result.extend_from_slice(&bytes[..3 - padding]);
+    }
+
+    Ok(result)
+}
+
+fn base64_value(c: u8) -> Option<u32> {
+    let value = match c {
+        b'A'..=b'Z' => c - b'A',
+        b'a'..=b'z' => c - b'a' + 26,
+        b'0'..=b'9' => c - b'0' + 52,
+        b'+' => 62,
+        b'/' => 63,
+        _ => return None,
+    };
+    Some(value as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The test vectors from RFC 4648
+    const BASE64_VECTORS: &[(&str, &str)] = &[
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("fooba", "Zm9vYmE="),
+        ("foobar", "Zm9vYmFy"),
+    ];
+
+    #[test]
+    fn base64_round_trip() {
+        for (input, encoded) in BASE64_VECTORS {
+            assert_eq!(to_base64(input.as_bytes()), *encoded);
+            assert_eq!(from_base64(encoded).unwrap(), input.as_bytes());
+        }
+    }
+
+    #[test]
+    fn base64_all_bytes() {
+        let bytes = (0..=255).collect::<Vec<u8>>();
+        assert_eq!(from_base64(&to_base64(&bytes)).unwrap(), bytes);
+    }
+
+    #[test]
+    fn base64_invalid_input() {
+        assert!(from_base64("Zg=").is_err());
+        assert!(from_base64("Z===").is_err());
+        assert!(from_base64("Zg==Zg==").is_err());
+        assert!(from_base64("Zm9*").is_err());
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        let bytes = (0..=255).collect::<Vec<u8>>();
+        let encoded = to_hex(&bytes);
+        assert_eq!(&encoded[..8], "00010203");
+        assert_eq!(from_hex(&encoded).unwrap(), bytes);
+        assert_eq!(from_hex("FF0a").unwrap(), &[255, 10]);
+    }
+
+    #[test]
+    fn hex_invalid_input() {
+        assert!(from_hex("abc").is_err());
+        assert!(from_hex("zz").is_err());
+    }
+}
@@ -3,7 +3,7 @@
 use unicode_segmentation::UnicodeSegmentation;
 
 use {
-    crate::{runtime_error, RuntimeError, UnaryOp, Value, Vm},
+    crate::{core::number::format::to_fixed, runtime_error, RuntimeError, UnaryOp, Value, Vm},
     koto_lexer::{is_id_continue, is_id_start},
     std::{iter::Peekable, str::Chars},
 };
@@ -23,6 +23,8 @@ struct FormatSpec {
     alignment: Option<FormatAlign>,
     min_width: Option<u32>,
     precision: Option<u32>,
+    // Numbers are padded with zeros after the sign, e.g. '{:08.3}'
+    zero_pad: bool,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -46,6 +48,20 @@ impl<'a> FormatLexer<'a> {
     }
 
     fn consume_format_spec(&mut self, chars: &mut Peekable<Chars>) -> Result<FormatSpec, String> {
+        let start = self.position;
+
+        self.consume_format_spec_contents(chars).map_err(|error| {
+            // Include the spec in the error, up to the end of the placeholder
+            let spec = self.format_string.get(start..).unwrap_or_default();
+            let spec = spec.find('}').map_or(spec, |end| &spec[..end]);
+            format!("{error} in format spec ':{spec}'")
+        })
+    }
+
+    fn consume_format_spec_contents(
+        &mut self,
+        chars: &mut Peekable<Chars>,
+    ) -> Result<FormatSpec, String> {
         let mut result = FormatSpec::default();
 
         if let Some(maybe_fill) = chars.peek().cloned() {
@@ -77,6 +93,17 @@ impl<'a> FormatLexer<'a> {
             _ => {}
         }
 
+        // A '0' followed by the width enables zero padding
+        if matches!(chars.peek(), Some('0')) {
+            let mut lookahead = chars.clone();
+            lookahead.next();
+            if matches!(lookahead.next(), Some('0'..='9')) {
+                chars.next();
+                self.position += 1;
+                result.zero_pad = true;
+            }
+        }
+
         if matches!(chars.peek(), Some('0'..='9')) {
             result.min_width = Some(self.consume_u32(chars)?);
         }
@@ -200,6 +227,7 @@ impl<'a> Iterator for FormatLexer<'a> {
 
                                 let start = self.position;
                                 let mut end = start + 1;
+                                let mut closed = false;
                                 chars.next();
                                 self.position += 1;
 
@@ -212,11 +240,13 @@ impl<'a> Iterator for FormatLexer<'a> {
                                         ':' => match self.consume_format_spec(&mut chars) {
                                             Ok(spec) => {
                                                 format_spec = spec;
+                                                closed = true;
                                                 break;
                                             }
                                             Err(error) => return Some(Error(error)),
                                         },
                                         '}' => {
+                                            closed = true;
                                             break;
                                         }
                                         other => {
@@ -227,6 +257,12 @@ impl<'a> Iterator for FormatLexer<'a> {
                                     }
                                 }
 
+                                if !closed {
+                                    return Some(Error(
+                                        "Unexpected end of format argument".to_string(),
+                                    ));
+                                }
+
                                 Some(Identifier(&self.format_string[start..end], format_spec))
                             }
                             // The start of a formatting specifier, e.g. "{:.2}"
@@ -297,45 +333,125 @@ impl<'a> Iterator for FormatLexer<'a> {
 }
 
 /// Formats a string, used by `string.format` and `io.print`
+///
+/// An error is returned if a placeholder refers to an argument that wasn't provided.
 pub fn format_string(
     vm: &mut Vm,
     format_string: &str,
     format_args: &[Value],
 ) -> Result<String, RuntimeError> {
-    let mut arg_iter = format_args.iter();
+    format_string_with_options(vm, format_string, format_args, false)
+}
+
+/// Formats a string, used by `string.format_strict`
+///
+/// In addition to the errors produced by [format_string], an error is returned if any of the
+/// arguments aren't referenced by a placeholder.
+pub fn format_string_strict(
+    vm: &mut Vm,
+    format_string: &str,
+    format_args: &[Value],
+) -> Result<String, RuntimeError> {
+    format_string_with_options(vm, format_string, format_args, true)
+}
+
+fn format_string_with_options(
+    vm: &mut Vm,
+    format_string: &str,
+    format_args: &[Value],
+    strict: bool,
+) -> Result<String, RuntimeError> {
+    let mut next_arg = 0;
+    let mut used_args = vec![false; format_args.len()];
     let mut result = String::with_capacity(format_string.len());
 
     for token in FormatLexer::new(format_string) {
         match token {
             FormatToken::String(s) => result.push_str(s),
-            FormatToken::Placeholder(format_spec) => match arg_iter.next() {
-                Some(arg) => result.push_str(&value_to_string(vm, arg, format_spec)?),
-                None => return runtime_error!("Not enough arguments for format string"),
-            },
+            FormatToken::Placeholder(format_spec) => {
+                match format_args.get(next_arg) {
+                    Some(arg) => result.push_str(&value_to_string(vm, arg, format_spec)?),
+                    None => {
+                        return runtime_error!(
+                            "Missing argument for placeholder '{{}}' at index {next_arg}, {}",
+                            provided_args(format_args)
+                        )
+                    }
+                }
+                used_args[next_arg] = true;
+                next_arg += 1;
+            }
             FormatToken::Positional(n, format_spec) => match format_args.get(n as usize) {
-                Some(arg) => result.push_str(&value_to_string(vm, arg, format_spec)?),
-                None => return runtime_error!("Missing argument for index {n}"),
+                Some(arg) => {
+                    result.push_str(&value_to_string(vm, arg, format_spec)?);
+                    used_args[n as usize] = true;
+                }
+                None => {
+                    return runtime_error!(
+                        "Missing argument for placeholder '{{{n}}}', {}",
+                        provided_args(format_args)
+                    )
+                }
             },
             FormatToken::Identifier(id, format_spec) => match format_args.first() {
                 Some(Value::Map(map)) => match map.data().get_with_string(id) {
-                    Some(value) => result.push_str(&value_to_string(vm, value, format_spec)?),
-                    None => return runtime_error!("Key '{id}' not found in map"),
+                    Some(value) => {
+                        result.push_str(&value_to_string(vm, value, format_spec)?);
+                        used_args[0] = true;
+                    }
+                    None => {
+                        return runtime_error!(
+                            "Key '{id}' for placeholder '{{{id}}}' not found in map"
+                        )
+                    }
                 },
                 Some(other) => {
                     return runtime_error!(
-                        "Expected map as first argument, found '{}'",
+                        "Expected map as first argument for placeholder '{{{id}}}', found '{}'",
                         other.type_as_string()
                     )
                 }
-                None => return runtime_error!("Expected map as first argument"),
+                None => {
+                    return runtime_error!(
+                        "Expected map as first argument for placeholder '{{{id}}}'"
+                    )
+                }
             },
             FormatToken::Error(error) => return runtime_error!("Invalid format string: {error}"),
         }
     }
 
+    if strict {
+        let unused = used_args
+            .iter()
+            .enumerate()
+            .filter(|(_, used)| !**used)
+            .map(|(index, _)| index.to_string())
+            .collect::<Vec<_>>();
+
+        if !unused.is_empty() {
+            return runtime_error!(
+                "Unused arguments for format string at {} {}",
+                if unused.len() == 1 {
+                    "index"
+                } else {
+                    "indices"
+                },
+                unused.join(", ")
+            );
+        }
+    }
+
     Ok(result)
 }
 
+fn provided_args(format_args: &[Value]) -> String {
+    match format_args.len() {
+        1 => "1 argument was provided".to_string(),
+        n => format!("{n} arguments were provided"),
+    }
+}
+
 fn value_to_string(
     vm: &mut Vm,
     value: &Value,
@@ -345,13 +461,32 @@ fn value_to_string(
         Value::Number(n) => match format_spec.precision {
             Some(precision) => {
                 if n.is_f64() || n.is_i64_in_f64_range() {
-                    format!("{:.*}", precision as usize, f64::from(n))
+                    to_fixed(&f64::from(n).into(), precision as usize)
                 } else {
                     n.to_string()
                 }
             }
             None => n.to_string(),
         },
+        // Precision is applied to each of the components of Num2s and Num4s
+        Value::Num2(n) if format_spec.precision.is_some() => {
+            let precision = format_spec.precision.unwrap() as usize;
+            format!(
+                "num2({}, {})",
+                to_fixed(&n[0].into(), precision),
+                to_fixed(&n[1].into(), precision)
+            )
+        }
+        Value::Num4(n) if format_spec.precision.is_some() => {
+            let precision = format_spec.precision.unwrap() as usize;
+            format!(
+                "num4({}, {}, {}, {})",
+                to_fixed(&(n[0] as f64).into(), precision),
+                to_fixed(&(n[1] as f64).into(), precision),
+                to_fixed(&(n[2] as f64).into(), precision),
+                to_fixed(&(n[3] as f64).into(), precision)
+            )
+        }
         _ => match vm.run_unary_op(UnaryOp::Display, value.clone())? {
             Value::Str(result) => {
                 match format_spec.precision {
@@ -381,10 +516,27 @@ fn value_to_string(
             let min_width = min_width as usize;
             let len = result.graphemes(true).count();
             if len < min_width {
-                let fill = format_spec.fill.unwrap_or(' ').to_string();
                 let fill_chars = min_width - len;
+                let fill = match format_spec.fill {
+                    Some(fill) => fill,
+                    None if format_spec.zero_pad => '0',
+                    None => ' ',
+                }
+                .to_string();
+
+                // Numbers are zero padded after their sign, ignoring the fill and alignment
+                let zero_pad_number = format_spec.zero_pad
+                    && matches!(value, Value::Number(_))
+                    && result
+                        .trim_start_matches('-')
+                        .starts_with(|c: char| c.is_ascii_digit());
 
                 match format_spec.alignment {
+                    _ if zero_pad_number => {
+                        let digits = result.trim_start_matches('-');
+                        let sign = &result[..result.len() - digits.len()];
+                        format!("{sign}{}{digits}", "0".repeat(fill_chars))
+                    }
                     Some(FormatAlign::Left) => result + &fill.repeat(fill_chars),
                     Some(FormatAlign::Center) => {
                         let half_fill_chars = fill_chars as f32 / 2.0;
@@ -418,7 +570,7 @@ fn value_to_string(
 mod tests {
     use {
         super::*,
-        crate::{DataMap, ValueMap},
+        crate::{DataMap, Num2, Num4, ValueMap},
     };
 
     fn spec_with_precision(precision: u32) -> FormatSpec {
@@ -522,6 +674,7 @@ mod tests {
                             alignment: Some(FormatAlign::Right),
                             min_width: Some(5),
                             precision: Some(3),
+                            zero_pad: false,
                         },
                     ),
                     FormatToken::String(" bar"),
@@ -529,6 +682,12 @@ mod tests {
             )
         }
 
+        #[test]
+        fn unterminated_identifier_placeholder() {
+            let mut lexer = FormatLexer::new("{foo");
+            assert!(matches!(lexer.next(), Some(FormatToken::Error(_))));
+        }
+
         #[test]
         fn identifier_placeholders() {
             let input = "x = {foo}, y = {bar:.2}";
@@ -588,6 +747,79 @@ mod tests {
             check_format_output("{x:.2} - {y:.1}", &[map], "42.00 - -1.0");
         }
 
+        fn check_format_error(format: &str, args: &[Value], strict: bool, expected: &str) {
+            let mut vm = Vm::default();
+            match format_string_with_options(&mut vm, format, args, strict) {
+                Ok(result) => panic!("Expected an error, found '{result}'"),
+                Err(error) => assert_eq!(format!("{error:#}"), expected),
+            }
+        }
+
+        #[test]
+        fn missing_arguments() {
+            let one = Value::Number(1.into());
+            check_format_error(
+                "{} {}",
+                &[one.clone()],
+                false,
+                "Missing argument for placeholder '{}' at index 1, 1 argument was provided",
+            );
+            check_format_error(
+                "{0} {3}",
+                &[one.clone(), one.clone(), one],
+                false,
+                "Missing argument for placeholder '{3}', 3 arguments were provided",
+            );
+        }
+
+        #[test]
+        fn missing_map_keys() {
+            let mut map_data = DataMap::default();
+            map_data.insert("name".into(), Value::Number(42.into()));
+            let map = Value::Map(ValueMap::with_data(map_data));
+
+            check_format_error(
+                "{nmae}",
+                &[map],
+                false,
+                "Key 'nmae' for placeholder '{nmae}' not found in map",
+            );
+            check_format_error(
+                "{name}",
+                &[Value::Null],
+                false,
+                "Expected map as first argument for placeholder '{name}', found 'Null'",
+            );
+        }
+
+        #[test]
+        fn unused_arguments() {
+            let one = Value::Number(1.into());
+            let args = &[one.clone(), one.clone(), one];
+
+            check_format_output("{} {{}}", args, "1 {}");
+            check_format_error(
+                "{} {{}}",
+                args,
+                true,
+                "Unused arguments for format string at indices 1, 2",
+            );
+            check_format_error(
+                "{2} {}",
+                args,
+                true,
+                "Unused arguments for format string at index 1",
+            );
+        }
+
+        #[test]
+        fn strict_with_all_arguments_used() {
+            let mut vm = Vm::default();
+            let args = &[Value::Number(1.into()), Value::Str("x".into())];
+            let result = format_string_strict(&mut vm, "{1}{0} {}", args).unwrap();
+            assert_eq!(result, "x1 1");
+        }
+
         #[test]
         fn fill_and_align_string() {
             let s = &[Value::Str("abcd".into())];
@@ -622,5 +854,54 @@ mod tests {
             check_format_output("{:-<8.2}", n_negative, "-0.33---");
             check_format_output("{:8.3}", n_negative, "  -0.333");
         }
+
+        #[test]
+        fn zero_padded_number() {
+            let n = &[Value::Number((1.0 / 3.0).into())];
+            let n_negative = &[Value::Number((-1.0 / 3.0).into())];
+            let n_int = &[Value::Number(42.into())];
+            check_format_output("{:08.3}", n, "0000.333");
+            check_format_output("{:08.3}", n_negative, "-000.333");
+            check_format_output("{:05}", n_int, "00042");
+            check_format_output("{:02}", n_int, "42");
+            // The fill and alignment are ignored when zero padding numbers
+            check_format_output("{:_<05}", n_int, "00042");
+            // Width-only specs that start with 0 aren't zero padded
+            check_format_output("{:0}", n_int, "42");
+            // Zero padding other values uses 0 as the fill character
+            check_format_output("{:05}", &[Value::Str("ab".into())], "ab000");
+        }
+
+        #[test]
+        fn num2_and_num4_precision() {
+            let n2 = &[Value::Num2(Num2(1.0 / 3.0, 2.0))];
+            let n4 = &[Value::Num4(Num4(1.0, -0.25, 0.5, 10.0))];
+            check_format_output("{:.2}", n2, "num2(0.33, 2.00)");
+            check_format_output("{:.1}", n4, "num4(1.0, -0.2, 0.5, 10.0)");
+            check_format_output("{:>20.1}", n2, "      num2(0.3, 2.0)");
+        }
+
+        #[test]
+        fn invalid_format_specs() {
+            let one = &[Value::Number(1.into())];
+            check_format_error(
+                "{:x}",
+                one,
+                false,
+                "Invalid format string: Expected '}', found 'x' in format spec ':x'",
+            );
+            check_format_error(
+                "{0:.} foo",
+                one,
+                false,
+                "Invalid format string: Expected digit, found '}' in format spec ':.'",
+            );
+            check_format_error(
+                "{x:>8.2q}",
+                one,
+                false,
+                "Invalid format string: Expected '}', found 'q' in format spec ':>8.2q'",
+            );
+        }
     }
 }
@@ -6,7 +6,7 @@ use {
         value_iterator::{KotoIterator, ValueIterator, ValueIteratorOutput as Output},
         CallArgs, Value, ValueString, Vm,
     },
-    unicode_segmentation::UnicodeSegmentation,
+    unicode_segmentation::{GraphemeCursor, UnicodeSegmentation},
 };
 
 /// An iterator that outputs the individual bytes contained in a string
@@ -31,6 +31,10 @@ impl KotoIterator for Bytes {
     fn might_have_side_effects(&self) -> bool {
         false
     }
+
+    fn name(&self) -> String {
+        "Bytes <- String".into()
+    }
 }
 
 impl Iterator for Bytes {
@@ -52,6 +56,67 @@ impl Iterator for Bytes {
     }
 }
 
+/// An iterator that splits up a string into substrings containing N graphemes
+///
+/// The substrings share the input string's data.
+/// The final substring may contain fewer than N graphemes.
+#[derive(Clone)]
+pub struct Chunks {
+    input: ValueString,
+    chunk_size: usize,
+    start: usize,
+}
+
+impl Chunks {
+    /// Creates a new [Chunks] iterator
+    pub fn new(input: ValueString, chunk_size: usize) -> Self {
+        debug_assert!(chunk_size > 0);
+        Self {
+            input,
+            chunk_size,
+            start: 0,
+        }
+    }
+}
+
+impl KotoIterator for Chunks {
+    fn make_copy(&self) -> ValueIterator {
+        ValueIterator::new(self.clone())
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        false
+    }
+
+    fn name(&self) -> String {
+        format!("Chunks({}) <- String", self.chunk_size)
+    }
+}
+
+impl Iterator for Chunks {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.start;
+        let (last_index, last_grapheme) = self.input[start..]
+            .grapheme_indices(true)
+            .take(self.chunk_size)
+            .last()?;
+        let end = start + last_index + last_grapheme.len();
+        self.start = end;
+
+        // Safety: start and end are both on grapheme boundaries
+        let chunk = self.input.with_bounds(start..end).unwrap();
+        Some(Output::Value(Value::Str(chunk)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining_bytes = self.input.len() - self.start;
+        let upper = (remaining_bytes + self.chunk_size - 1) / self.chunk_size;
+        (1.min(remaining_bytes), Some(upper))
+    }
+}
+
 /// An iterator that yields the lines contained in a string
 ///
 /// - Lines end with either `\r\n` or `\n`.
@@ -78,6 +143,10 @@ impl KotoIterator for Lines {
     fn might_have_side_effects(&self) -> bool {
         false
     }
+
+    fn name(&self) -> String {
+        "Lines <- String".into()
+    }
 }
 
 impl Iterator for Lines {
@@ -115,6 +184,211 @@ impl Iterator for Lines {
     }
 }
 
+/// An iterator that yields the non-overlapping matches of a pattern in a string
+///
+/// The matches share the input string's data.
+#[derive(Clone)]
+pub struct Matches {
+    input: ValueString,
+    pattern: ValueString,
+    start: usize,
+}
+
+impl Matches {
+    /// Creates a new [Matches] iterator
+    pub fn new(input: ValueString, pattern: ValueString) -> Self {
+        debug_assert!(!pattern.is_empty());
+        Self {
+            input,
+            pattern,
+            start: 0,
+        }
+    }
+}
+
+impl KotoIterator for Matches {
+    fn make_copy(&self) -> ValueIterator {
+        ValueIterator::new(self.clone())
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        false
+    }
+
+    fn name(&self) -> String {
+        "Matches <- String".into()
+    }
+}
+
+impl Iterator for Matches {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.start + self.input[self.start..].find(self.pattern.as_str())?;
+        let end = start + self.pattern.len();
+        self.start = end;
+
+        let output = Value::Str(self.input.with_bounds(start..end).unwrap());
+        Some(Output::Value(output))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining_bytes = self.input.len() - self.start;
+        (0, Some(remaining_bytes / self.pattern.len()))
+    }
+}
+
+/// An iterator that yields runs of graphemes in a string that pass a predicate
+///
+/// The matches share the input string's data.
+pub struct MatchesWith {
+    input: ValueString,
+    predicate: Value,
+    vm: Vm,
+    start: usize,
+}
+
+impl MatchesWith {
+    /// Creates a new [MatchesWith] iterator
+    pub fn new(input: ValueString, predicate: Value, vm: Vm) -> Self {
+        Self {
+            input,
+            predicate,
+            vm,
+            start: 0,
+        }
+    }
+}
+
+impl KotoIterator for MatchesWith {
+    fn make_copy(&self) -> ValueIterator {
+        let result = Self {
+            input: self.input.clone(),
+            predicate: self.predicate.clone(),
+            vm: self.vm.spawn_shared_vm(),
+            start: self.start,
+        };
+        ValueIterator::new(result)
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> String {
+        "Matches <- String".into()
+    }
+}
+
+impl Iterator for MatchesWith {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        use Value::{Bool, Str};
+
+        let start = self.start;
+        let mut match_start = None;
+
+        for (grapheme_index, grapheme) in self.input[start..].grapheme_indices(true) {
+            let grapheme_start = start + grapheme_index;
+            let grapheme_end = grapheme_start + grapheme.len();
+            let x = self
+                .input
+                .with_bounds(grapheme_start..grapheme_end)
+                .unwrap();
+            match self
+                .vm
+                .run_function(self.predicate.clone(), CallArgs::Single(Str(x)))
+            {
+                Ok(Bool(true)) => {
+                    if match_start.is_none() {
+                        match_start = Some(grapheme_start);
+                    }
+                }
+                Ok(Bool(false)) => {
+                    if let Some(match_start) = match_start {
+                        self.start = grapheme_end;
+                        let output =
+                            Str(self.input.with_bounds(match_start..grapheme_start).unwrap());
+                        return Some(Output::Value(output));
+                    }
+                }
+                Ok(unexpected) => {
+                    let error = make_runtime_error!(format!(
+                        "string.matches: Expected a Bool from the match function, found '{}'",
+                        unexpected.type_as_string()
+                    ));
+                    return Some(Output::Error(error));
+                }
+                Err(error) => return Some(Output::Error(error.with_prefix("string.matches"))),
+            }
+        }
+
+        self.start = self.input.len();
+        match_start.map(|match_start| {
+            let output = Str(self.input.with_bounds(match_start..self.start).unwrap());
+            Output::Value(output)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining_bytes = self.input.len() - self.start;
+        (0, Some((remaining_bytes + 1) / 2))
+    }
+}
+
+/// An iterator that yields the graphemes contained in a string in reverse order
+///
+/// The graphemes share the input string's data.
+#[derive(Clone)]
+pub struct ReversedGraphemes {
+    input: ValueString,
+    end: usize,
+}
+
+impl ReversedGraphemes {
+    /// Creates a new [ReversedGraphemes] iterator
+    pub fn new(input: ValueString) -> Self {
+        let end = input.len();
+        Self { input, end }
+    }
+}
+
+impl KotoIterator for ReversedGraphemes {
+    fn make_copy(&self) -> ValueIterator {
+        ValueIterator::new(self.clone())
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        false
+    }
+
+    fn name(&self) -> String {
+        "Reversed <- String".into()
+    }
+}
+
+impl Iterator for ReversedGraphemes {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let end = self.end;
+        let remaining = &self.input[..end];
+        let start = GraphemeCursor::new(end, end, true)
+            .prev_boundary(remaining, 0)
+            .unwrap()?; // Safety: the cursor is given the complete remaining string
+
+        // Safety: start and end are both on grapheme boundaries
+        let result = Value::Str(self.input.with_bounds(start..end).unwrap());
+        self.end = start;
+        Some(Output::Value(result))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (1.min(self.end), Some(self.end))
+    }
+}
+
 /// An iterator that splits up a string into parts, separated by a provided pattern
 #[derive(Clone)]
 pub struct Split {
@@ -142,6 +416,10 @@ impl KotoIterator for Split {
     fn might_have_side_effects(&self) -> bool {
         false
     }
+
+    fn name(&self) -> String {
+        "Split <- String".into()
+    }
 }
 
 impl Iterator for Split {
@@ -203,6 +481,10 @@ impl KotoIterator for SplitWith {
     fn might_have_side_effects(&self) -> bool {
         true
     }
+
+    fn name(&self) -> String {
+        "Split <- String".into()
+    }
 }
 
 impl Iterator for SplitWith {
@@ -260,3 +542,81 @@ impl Iterator for SplitWith {
         (1.min(remaining_bytes), Some(remaining_bytes))
     }
 }
+
+/// An iterator that yields overlapping substrings containing N graphemes
+///
+/// The substrings share the input string's data.
+/// If the input contains fewer than N graphemes then no substrings are produced.
+#[derive(Clone)]
+pub struct Windows {
+    input: ValueString,
+    window_size: usize,
+    start: usize,
+}
+
+impl Windows {
+    /// Creates a new [Windows] iterator
+    pub fn new(input: ValueString, window_size: usize) -> Self {
+        debug_assert!(window_size > 0);
+        Self {
+            input,
+            window_size,
+            start: 0,
+        }
+    }
+}
+
+impl KotoIterator for Windows {
+    fn make_copy(&self) -> ValueIterator {
+        ValueIterator::new(self.clone())
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        false
+    }
+
+    fn name(&self) -> String {
+        format!("Windows({}) <- String", self.window_size)
+    }
+}
+
+impl Iterator for Windows {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.start;
+        let mut grapheme_ends = self.input[start..]
+            .grapheme_indices(true)
+            .map(|(index, grapheme)| start + index + grapheme.len());
+
+        // The next window starts after the first grapheme of this window
+        let next_start = grapheme_ends.next()?;
+        let end = if self.window_size == 1 {
+            Some(next_start)
+        } else {
+            grapheme_ends.nth(self.window_size - 2)
+        };
+
+        match end {
+            Some(end) => {
+                self.start = next_start;
+                // Safety: start and end are both on grapheme boundaries
+                let window = self.input.with_bounds(start..end).unwrap();
+                Some(Output::Value(Value::Str(window)))
+            }
+            None => {
+                // There aren't enough graphemes remaining for another window
+                self.start = self.input.len();
+                None
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining_bytes = self.input.len() - self.start;
+        (
+            0,
+            Some((remaining_bytes + 1).saturating_sub(self.window_size)),
+        )
+    }
+}
@@ -1,6 +1,6 @@
 //! The `test` core library module
 
-use crate::{num2, num4, prelude::*};
+use crate::prelude::*;
 
 /// Initializes the `test` core library module
 pub fn make_module() -> ValueMap {
@@ -29,9 +29,12 @@ pub fn make_module() -> ValueMap {
             let result = vm.run_binary_op(BinaryOp::Equal, a.clone(), b.clone());
             match result {
                 Ok(Bool(true)) => Ok(Null),
-                Ok(Bool(false)) => {
-                    runtime_error!("Assertion failed, '{a}' is not equal to '{b}'")
-                }
+                Ok(Bool(false)) => match first_mismatched_component(&a, &b) {
+                    Some(index) => runtime_error!(
+                        "Assertion failed, '{a}' is not equal to '{b}' (mismatch at component {index})"
+                    ),
+                    None => runtime_error!("Assertion failed, '{a}' is not equal to '{b}'"),
+                },
                 Ok(unexpected) => type_error("Bool from equality comparison", &unexpected),
                 Err(e) => Err(e),
             }
@@ -57,17 +60,9 @@ pub fn make_module() -> ValueMap {
     });
 
     result.add_fn("assert_near", |vm, args| match vm.get_args(args) {
-        [Number(a), Number(b)] => number_near(*a, *b, 1.0e-12),
-        [Number(a), Number(b), Number(allowed_diff)] => number_near(*a, *b, allowed_diff.into()),
-        [Num2(a), Num2(b)] => num2_near(*a, *b, 1.0e-12),
-        [Num2(a), Num2(b), Number(allowed_diff)] => num2_near(*a, *b, allowed_diff.into()),
-        [Num4(a), Num4(b)] => num4_near(*a, *b, 1.0e-6),
-        [Num4(a), Num4(b), Number(allowed_diff)] => num4_near(*a, *b, allowed_diff.into()),
-        unexpected => type_error_with_slice(
-            "two Numbers (or Num2s or Num4s) as arguments, \
-             followed by an optional Number that specifies the allowed difference",
-            unexpected,
-        ),
+        [a, b] => values_near(a, b, None),
+        [a, b, Number(allowed_diff)] => values_near(a, b, Some(allowed_diff.into())),
+        unexpected => assert_near_error(unexpected),
     });
 
     result.add_fn("run_tests", |vm, args| match vm.get_args(args) {
@@ -81,40 +76,106 @@ pub fn make_module() -> ValueMap {
     result
 }
 
-fn f32_near(a: f32, b: f32, allowed_diff: f32) -> bool {
-    (a - b).abs() <= allowed_diff
+fn assert_near_error(unexpected: &[Value]) -> RuntimeResult {
+    type_error_with_slice(
+        "two Numbers, Num2s, Num4s, or same-length Tuples or Lists of Numbers as arguments, \
+         followed by an optional Number that specifies the allowed difference",
+        unexpected,
+    )
 }
 
-fn f64_near(a: f64, b: f64, allowed_diff: f64) -> bool {
-    (a - b).abs() <= allowed_diff
+// Checks that two values are equal within an allowed difference, see `test.assert_near`
+//
+// Num2s, Num4s, Tuples, and Lists are compared component-wise.
+fn values_near(a: &Value, b: &Value, allowed_diff: Option<f64>) -> RuntimeResult {
+    use Value::*;
+
+    let default_allowed_diff = match (a, b) {
+        (Number(a), Number(b)) => {
+            return number_near(*a, *b, allowed_diff.unwrap_or(1.0e-12));
+        }
+        (Num4(_), Num4(_)) => 1.0e-6,
+        _ => 1.0e-12,
+    };
+
+    let (a_components, b_components) = match (numeric_components(a, b), numeric_components(b, a)) {
+        (Some(a_components), Some(b_components)) => (a_components, b_components),
+        _ => return assert_near_error(&[a.clone(), b.clone()]),
+    };
+
+    if a_components.len() != b_components.len() {
+        return runtime_error!(
+            "assert_near: Expected values with matching sizes, \
+             found '{a}' (size {}) and '{b}' (size {})",
+            a_components.len(),
+            b_components.len()
+        );
+    }
+
+    let allowed_diff = allowed_diff.unwrap_or(default_allowed_diff);
+    for (index, (x, y)) in a_components.iter().zip(b_components.iter()).enumerate() {
+        if !f64_near(*x, *y, allowed_diff) {
+            return runtime_error!(
+                "Assertion failed, '{a}' and '{b}' are not within {allowed_diff} of each other \
+                 (component {index} differs by {})",
+                (x - y).abs()
+            );
+        }
+    }
+
+    Ok(Null)
 }
 
-fn number_near(a: ValueNumber, b: ValueNumber, allowed_diff: f64) -> RuntimeResult {
-    if f64_near(a.into(), b.into(), allowed_diff) {
-        Ok(Value::Null)
-    } else {
-        runtime_error!(
-            "Assertion failed, '{a}' and '{b}' are not within {allowed_diff} of each other"
-        )
+// Returns the numeric components of a value that's being compared with another value of the
+// same type
+//
+// None is returned if the values have different types, or if a Tuple or List contains
+// non-Number values.
+fn numeric_components(value: &Value, other: &Value) -> Option<Vec<f64>> {
+    use Value::*;
+
+    let numbers = |values: &[Value]| {
+        values
+            .iter()
+            .map(|value| match value {
+                Number(n) => Some(f64::from(n)),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+    };
+
+    match (value, other) {
+        (Number(n), Number(_)) => Some(vec![n.into()]),
+        (Num2(n), Num2(_)) => Some(vec![n.0, n.1]),
+        (Num4(n), Num4(_)) => Some(vec![n.0 as f64, n.1 as f64, n.2 as f64, n.3 as f64]),
+        (Tuple(t), Tuple(_)) => numbers(t),
+        (List(l), List(_)) => numbers(&l.data()),
+        _ => None,
     }
 }
 
-fn num2_near(a: num2::Num2, b: num2::Num2, allowed_diff: f64) -> RuntimeResult {
-    if f64_near(a.0, b.0, allowed_diff) && f64_near(a.1, b.1, allowed_diff) {
-        Ok(Value::Null)
-    } else {
-        runtime_error!(
-            "Assertion failed, '{a}' and '{b}' are not within {allowed_diff} of each other"
-        )
+// Returns the index of the first differing component of two non-scalar numeric values
+//
+// Used to provide extra detail in `assert_eq` failures.
+fn first_mismatched_component(a: &Value, b: &Value) -> Option<usize> {
+    if matches!(a, Value::Number(_)) {
+        return None;
+    }
+
+    match (numeric_components(a, b), numeric_components(b, a)) {
+        (Some(a), Some(b)) if a.len() == b.len() => {
+            a.iter().zip(b.iter()).position(|(x, y)| x != y)
+        }
+        _ => None,
     }
 }
 
-fn num4_near(a: num4::Num4, b: num4::Num4, allowed_diff: f32) -> RuntimeResult {
-    if f32_near(a.0, b.0, allowed_diff)
-        && f32_near(a.1, b.1, allowed_diff)
-        && f32_near(a.2, b.2, allowed_diff)
-        && f32_near(a.3, b.3, allowed_diff)
-    {
+fn f64_near(a: f64, b: f64, allowed_diff: f64) -> bool {
+    (a - b).abs() <= allowed_diff
+}
+
+fn number_near(a: ValueNumber, b: ValueNumber, allowed_diff: f64) -> RuntimeResult {
+    if f64_near(a.into(), b.into(), allowed_diff) {
         Ok(Value::Null)
     } else {
         runtime_error!(
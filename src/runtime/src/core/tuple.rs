@@ -1,6 +1,9 @@
 //! The `tuple` core library module
 
-use crate::{prelude::*, value_sort::sort_values};
+use crate::{
+    prelude::*,
+    value_sort::{sort_values, sort_values_by_keys},
+};
 
 /// Initializes the `tuple` core library module
 pub fn make_module() -> ValueMap {
@@ -85,6 +88,32 @@ pub fn make_module() -> ValueMap {
         unexpected => expected_tuple_error(unexpected),
     });
 
+    result.add_fn("sorted", |vm, args| match vm.get_args(args) {
+        [Tuple(t)] => {
+            let mut result = t.to_vec();
+            sort_values(vm, &mut result)?;
+            Ok(Tuple(result.into()))
+        }
+        [Tuple(t), f] if f.is_callable() => {
+            let t = t.clone();
+            let f = f.clone();
+            let mut result = t.to_vec();
+
+            // The key function is called once for each value before sorting
+            let keys = t
+                .iter()
+                .map(|value| vm.run_function(f.clone(), CallArgs::Single(value.clone())))
+                .collect::<Result<Vec<_>, _>>()?;
+            sort_values_by_keys(vm, &mut result, &keys)?;
+
+            Ok(Tuple(result.into()))
+        }
+        unexpected => type_error_with_slice(
+            "a Tuple and optional sort key Function as arguments",
+            unexpected,
+        ),
+    });
+
     result.add_fn("to_list", |vm, args| match vm.get_args(args) {
         [Tuple(t)] => Ok(List(ValueList::from_slice(t))),
         unexpected => expected_tuple_error(unexpected),
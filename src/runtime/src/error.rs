@@ -1,8 +1,10 @@
 use {
-    crate::{UnaryOp, Value, Vm},
+    crate::{
+        ExternalFunction, MetaKey, MetaMap, UnaryOp, Value, ValueList, ValueMap, ValueVec, Vm,
+    },
     koto_bytecode::Chunk,
-    koto_parser::format_error_with_excerpt,
-    std::{cell::RefCell, error, fmt, rc::Rc},
+    koto_parser::{format_error_with_excerpt, Span},
+    std::{cell::RefCell, error, fmt, path::PathBuf, rc::Rc},
 };
 
 /// A chunk and ip in a call stack where an error was thrown
@@ -10,6 +12,71 @@ use {
 pub struct ErrorFrame {
     chunk: Rc<Chunk>,
     instruction: usize,
+    function: FrameFunction,
+}
+
+impl ErrorFrame {
+    fn span(&self) -> Option<Span> {
+        self.chunk.debug_info.get_source_span(self.instruction)
+    }
+}
+
+/// The function that was being executed in a [StackFrame]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FrameFunction {
+    /// The top level of a script or module
+    Module,
+    /// A function that was called from a script
+    ///
+    /// The name is taken from the source of the call's callee expression, e.g. `foo` or `x.foo`.
+    Named(String),
+    /// A function without a known name, e.g. a function that was called by an iterator adaptor
+    Anonymous,
+}
+
+impl fmt::Display for FrameFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Module => f.write_str("<module>"),
+            Self::Named(name) => f.write_str(name),
+            Self::Anonymous => f.write_str("<anonymous>"),
+        }
+    }
+}
+
+/// A frame in a [RuntimeError]'s stack trace
+///
+/// See [RuntimeError::stack_trace].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StackFrame {
+    /// The function that was being executed in the frame
+    pub function: FrameFunction,
+    /// The path of the script that contains the frame's function, if available
+    pub source_path: Option<PathBuf>,
+    /// The source span of the expression that was being executed in the frame, if available
+    ///
+    /// For frames other than the innermost frame, this is the span of the call that was being
+    /// made when the error was thrown.
+    pub span: Option<Span>,
+}
+
+impl fmt::Display for StackFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.function)?;
+
+        match (&self.source_path, self.span) {
+            (Some(path), Some(span)) => write!(
+                f,
+                " at {} - {}:{}",
+                path.display(),
+                span.start.line,
+                span.start.column
+            ),
+            (Some(path), None) => write!(f, " at {}", path.display()),
+            (None, Some(span)) => write!(f, " at {}:{}", span.start.line, span.start.column),
+            (None, None) => Ok(()),
+        }
+    }
 }
 
 /// The different error types that can be thrown by the Koto runtime
@@ -28,6 +95,8 @@ pub(crate) enum RuntimeErrorType {
         /// A VM that should be used to format the thrown value
         vm: Option<Rc<RefCell<Vm>>>,
     },
+    /// Execution was stopped via an [InterruptHandle](crate::InterruptHandle)
+    Interrupted,
 }
 
 /// An error thrown by the Koto runtime
@@ -35,6 +104,19 @@ pub(crate) enum RuntimeErrorType {
 pub struct RuntimeError {
     pub(crate) error: RuntimeErrorType,
     pub(crate) trace: Vec<ErrorFrame>,
+    /// The index of the external function argument that caused the error
+    ///
+    /// The index is resolved into `arg_span` by the VM when the external function returns.
+    pub(crate) arg_index: Option<usize>,
+    /// The source span of the argument that caused the error
+    ///
+    /// If available, the span is used in place of the first frame's span when displaying the error.
+    pub(crate) arg_span: Option<Span>,
+    /// True if the error has been passed to the VM's error hook
+    ///
+    /// Errors can pass through several nested executions while unwinding,
+    /// so this ensures that the hook is only called once per error.
+    pub(crate) hook_called: bool,
 }
 
 impl RuntimeError {
@@ -43,6 +125,9 @@ impl RuntimeError {
         Self {
             error,
             trace: Vec::new(),
+            arg_index: None,
+            arg_span: None,
+            hook_called: false,
         }
     }
 
@@ -54,9 +139,114 @@ impl RuntimeError {
         })
     }
 
-    /// Extends the error stack with the given [Chunk] and ip
-    pub(crate) fn extend_trace(&mut self, chunk: Rc<Chunk>, instruction: usize) {
-        self.trace.push(ErrorFrame { chunk, instruction });
+    /// Initializes an error that signals that execution has been interrupted
+    pub(crate) fn interrupted() -> Self {
+        Self::new(RuntimeErrorType::Interrupted)
+    }
+
+    /// Returns true if the error was caused by execution being interrupted
+    ///
+    /// See [InterruptHandle](crate::InterruptHandle).
+    pub fn is_interrupted(&self) -> bool {
+        matches!(self.error, RuntimeErrorType::Interrupted)
+    }
+
+    /// Returns the value that's provided to a `catch` block when the error is caught
+    ///
+    /// Thrown values are returned as they are, while other errors are converted into `Error` maps,
+    /// see [RuntimeError::to_error_map].
+    pub(crate) fn to_catch_value(&self) -> Value {
+        match &self.error {
+            RuntimeErrorType::KotoError { thrown_value, .. } => thrown_value.clone(),
+            _ => Value::Map(self.to_error_map()),
+        }
+    }
+
+    /// Returns a map that describes the error, allowing it to be inspected by scripts
+    ///
+    /// The map has the type `Error`, and contains the error's `message`,
+    /// along with its `trace`, which is a list of maps containing the `function`, `path`, `line`,
+    /// and `column` of each frame in the error's stack trace.
+    ///
+    /// The map's `@display` function returns the error's message.
+    pub(crate) fn to_error_map(&self) -> ValueMap {
+        let trace = self
+            .stack_trace()
+            .iter()
+            .map(|frame| {
+                let result = ValueMap::default();
+                result.add_value("function", frame.function.to_string().into());
+                result.add_value(
+                    "path",
+                    match &frame.source_path {
+                        Some(path) => path.to_string_lossy().as_ref().into(),
+                        None => Value::Null,
+                    },
+                );
+                let (line, column) = match frame.span {
+                    Some(span) => (span.start.line.into(), span.start.column.into()),
+                    None => (Value::Null, Value::Null),
+                };
+                result.add_value("line", line);
+                result.add_value("column", column);
+                Value::Map(result)
+            })
+            .collect::<ValueVec>();
+
+        let mut meta = MetaMap::default();
+        meta.insert(MetaKey::Type, "Error".into());
+        meta.insert(
+            MetaKey::UnaryOp(UnaryOp::Display),
+            Value::ExternalFunction(ExternalFunction::new(
+                |vm, args| match vm.get_args(args) {
+                    [Value::Map(error)] => match error.data().get_with_string("message") {
+                        Some(message @ Value::Str(_)) => Ok(message.clone()),
+                        _ => Ok("Error".into()),
+                    },
+                    unexpected => type_error_with_slice("an Error", unexpected),
+                },
+                true,
+            )),
+        );
+
+        let result = ValueMap::with_contents(Default::default(), Some(meta));
+        result.add_value("message", format!("{self:#}").into());
+        result.add_value("trace", Value::List(ValueList::with_data(trace)));
+        result
+    }
+
+    /// Extends the error stack with the given [Chunk] and ip, and the frame's function
+    pub(crate) fn extend_trace(
+        &mut self,
+        chunk: Rc<Chunk>,
+        instruction: usize,
+        function: FrameFunction,
+    ) {
+        self.trace.push(ErrorFrame {
+            chunk,
+            instruction,
+            function,
+        });
+    }
+
+    /// Returns the error's stack trace, starting with the frame where the error was thrown
+    ///
+    /// Frames from functions that were run by spawned VMs (e.g. functions called by iterator
+    /// adaptors) are included in the trace, followed by the frames of the VM that spawned them.
+    pub fn stack_trace(&self) -> Vec<StackFrame> {
+        self.trace
+            .iter()
+            .enumerate()
+            .map(|(i, frame)| StackFrame {
+                function: frame.function.clone(),
+                source_path: frame.chunk.source_path.clone(),
+                span: if i == 0 {
+                    self.arg_span.or_else(|| frame.span())
+                } else {
+                    frame.span()
+                },
+            })
+            .collect()
     }
 
     /// Modifies string errors to include the given prefix
@@ -71,6 +261,17 @@ impl RuntimeError {
 
         self
     }
+
+    /// Associates the error with one of the arguments passed to an external function
+    ///
+    /// The index refers to the argument's position in the slice returned by [Vm::get_args].
+    /// When the function has been called from a script, the argument will be highlighted
+    /// when the error is displayed.
+    #[must_use]
+    pub fn with_arg_index(mut self, index: usize) -> Self {
+        self.arg_index = Some(index);
+        self
+    }
 }
 
 impl From<String> for RuntimeError {
@@ -106,36 +307,39 @@ impl fmt::Display for RuntimeError {
                 },
                 _ => "Unable to get error message".to_string(),
             },
+            Interrupted => "Execution was interrupted".to_string(),
         };
 
         if f.alternate() {
             f.write_str(&message)
-        } else {
-            let mut first_frame = true;
-            for frame in self.trace.iter() {
-                let frame_message = if first_frame {
-                    first_frame = false;
-                    Some(message.as_str())
-                } else {
-                    None
-                };
+        } else if let Some(frame) = self.trace.first() {
+            // The frame where the error was thrown is shown with an excerpt of its source
+            match self.arg_span.or_else(|| frame.span()) {
+                Some(span) => f.write_str(&format_error_with_excerpt(
+                    Some(&message),
+                    &frame.chunk.source_path,
+                    &frame.chunk.debug_info.source,
+                    span.start,
+                    span.end,
+                ))?,
+                None => write!(
+                    f,
+                    "Runtime error at instruction {}: {message}",
+                    frame.instruction,
+                )?,
+            }
 
-                match frame.chunk.debug_info.get_source_span(frame.instruction) {
-                    Some(span) => f.write_str(&format_error_with_excerpt(
-                        frame_message,
-                        &frame.chunk.source_path,
-                        &frame.chunk.debug_info.source,
-                        span.start,
-                        span.end,
-                    ))?,
-                    None => write!(
-                        f,
-                        "Runtime error at instruction {}: {message}",
-                        frame.instruction,
-                    )?,
-                };
+            // Errors thrown in nested calls are followed by a traceback
+            if self.trace.len() > 1 {
+                f.write_str("\nStack trace (most recent call first):")?;
+                for frame in self.stack_trace() {
+                    write!(f, "\n  {frame}")?;
+                }
             }
+
             Ok(())
+        } else {
+            f.write_str(&message)
         }
     }
 }
@@ -211,3 +415,15 @@ pub fn type_error_with_slice<T>(
 
     runtime_error!("Expected {expected_str}, but found {message}.")
 }
+
+/// Creates an error that describes a type mismatch with a slice of [Value]s
+///
+/// The error is associated with the argument at the given index, see
+/// [RuntimeError::with_arg_index].
+pub fn type_error_with_arg<T>(
+    expected_str: &str,
+    unexpected: &[Value],
+    arg_index: usize,
+) -> Result<T, RuntimeError> {
+    type_error_with_slice(expected_str, unexpected).map_err(|e| e.with_arg_index(arg_index))
+}
@@ -131,17 +131,34 @@ thread_local! {
     static TYPE_EXTERNAL_VALUE: ValueString = "ExternalValue".into();
 }
 
+/// The implementation of an [ExternalFunction]
+///
+/// The trait is implemented for closures that take a [Vm] and [ArgRegisters], so it usually
+/// doesn't need to be implemented directly. Implementing the trait for a type allows the type to
+/// be retrieved from the function with `downcast_ref`, which is useful when a function has
+/// associated data that should be accessible elsewhere.
+pub trait ExternalCallable: Downcast {
+    /// Calls the function with the given arguments
+    fn call(&self, vm: &mut Vm, args: &ArgRegisters) -> RuntimeResult;
+}
+
+impl_downcast!(ExternalCallable);
+
+impl<F> ExternalCallable for F
+where
+    F: Fn(&mut Vm, &ArgRegisters) -> RuntimeResult + 'static,
+{
+    fn call(&self, vm: &mut Vm, args: &ArgRegisters) -> RuntimeResult {
+        self(vm, args)
+    }
+}
+
 /// An function that's defined outside of the Koto runtime
 ///
 /// See [Value::ExternalFunction]
 pub struct ExternalFunction {
     /// The function implementation that should be called when calling the external function
-    ///
-    ///
-    // Once Trait aliases are stabilized this can be simplified a bit,
-    // see: https://github.com/rust-lang/rust/issues/55628
-    #[allow(clippy::type_complexity)]
-    pub function: Rc<dyn Fn(&mut Vm, &ArgRegisters) -> RuntimeResult + 'static>,
+    pub function: Rc<dyn ExternalCallable>,
     /// True if the function should behave as an instance function
     pub is_instance_function: bool,
 }
@@ -157,6 +174,14 @@ impl ExternalFunction {
             is_instance_function,
         }
     }
+
+    /// Creates a new external function from an implementation of [ExternalCallable]
+    pub fn with_callable(callable: impl ExternalCallable, is_instance_function: bool) -> Self {
+        Self {
+            function: Rc::new(callable),
+            is_instance_function,
+        }
+    }
 }
 
 impl Clone for ExternalFunction {
@@ -196,4 +221,8 @@ impl Hash for ExternalFunction {
 pub struct ArgRegisters {
     pub register: u8,
     pub count: u8,
+    /// The ip of the call instruction, available when the function is called from a script
+    pub(crate) call_ip: Option<usize>,
+    /// True when the call's instance value is passed as the first argument
+    pub(crate) instance_is_arg: bool,
 }
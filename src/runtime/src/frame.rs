@@ -1,9 +1,17 @@
-use {koto_bytecode::Chunk, std::rc::Rc};
+use {
+    koto_bytecode::Chunk,
+    koto_parser::Span,
+    std::{path::Path, rc::Rc},
+};
 
 #[derive(Clone, Debug)]
 pub(crate) struct Frame {
     // The chunk being interpreted in this frame
     pub chunk: Rc<Chunk>,
+    // The ip at which the frame's execution started
+    //
+    // Frames that start at the beginning of a chunk are executing a module's top level.
+    pub start_ip: usize,
     // The index in the VM value stack of the first argument register,
     // or the first local register if there are no arguments.
     pub register_base: usize,
@@ -23,9 +31,10 @@ pub(crate) struct Frame {
 }
 
 impl Frame {
-    pub fn new(chunk: Rc<Chunk>, register_base: usize) -> Self {
+    pub fn new(chunk: Rc<Chunk>, start_ip: usize, register_base: usize) -> Self {
         Self {
             chunk,
+            start_ip,
             register_base,
             return_register_and_ip: None,
             return_instruction_ip: 0,
@@ -34,3 +43,66 @@ impl Frame {
         }
     }
 }
+
+/// A read-only view of a VM's call stack, provided to the error hook
+///
+/// See [VmSettings::error_hook](crate::VmSettings::error_hook).
+pub struct CallStackInfo<'a> {
+    frames: &'a [Frame],
+    instruction_ip: usize,
+}
+
+impl<'a> CallStackInfo<'a> {
+    pub(crate) fn new(frames: &'a [Frame], instruction_ip: usize) -> Self {
+        Self {
+            frames,
+            instruction_ip,
+        }
+    }
+
+    /// Returns the number of frames in the call stack
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns true if the call stack is empty
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Returns an iterator over the frames in the call stack, starting with the innermost frame
+    pub fn frames(&self) -> impl Iterator<Item = CallFrameInfo<'a>> + 'a {
+        let instruction_ip = self.instruction_ip;
+        self.frames
+            .iter()
+            .rev()
+            .enumerate()
+            .map(move |(i, frame)| CallFrameInfo {
+                chunk: &frame.chunk,
+                // Outer frames are paused at the instruction that made the call to the next frame
+                instruction: if i == 0 {
+                    instruction_ip
+                } else {
+                    frame.return_instruction_ip
+                },
+            })
+    }
+}
+
+/// Information about a frame in a [CallStackInfo]
+pub struct CallFrameInfo<'a> {
+    chunk: &'a Chunk,
+    instruction: usize,
+}
+
+impl<'a> CallFrameInfo<'a> {
+    /// The path of the script that's being executed in the frame, if available
+    pub fn source_path(&self) -> Option<&'a Path> {
+        self.chunk.source_path.as_deref()
+    }
+
+    /// The source span of the instruction that's being executed in the frame, if available
+    pub fn span(&self) -> Option<Span> {
+        self.chunk.debug_info.get_source_span(self.instruction)
+    }
+}
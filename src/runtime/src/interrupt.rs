@@ -0,0 +1,77 @@
+use {
+    crate::{RuntimeError, ValueIterator, ValueIteratorOutput},
+    std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// A handle that allows a running script to be stopped from another thread
+///
+/// The handle can be retrieved with [Vm::interrupt_handle](crate::Vm::interrupt_handle), and can
+/// be cloned and sent to other threads.
+///
+/// After [InterruptHandle::stop] has been called, the runtime will stop executing at the next
+/// instruction boundary, returning an error for which [RuntimeError::is_interrupted] returns true.
+/// The error can't be caught by `try` / `catch` expressions in the script.
+///
+/// The stop request is cleared once the runtime's current execution has ended, so that the
+/// runtime can then be used again. If the handle is stopped when no script is running, then the
+/// next execution will be interrupted.
+#[derive(Clone, Debug, Default)]
+pub struct InterruptHandle(Arc<AtomicBool>);
+
+impl InterruptHandle {
+    /// Requests that the runtime stops executing
+    pub fn stop(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if the runtime has been requested to stop executing
+    pub fn is_stopped(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn reset(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+}
+
+// An iterator that produces an error when the runtime has been interrupted
+//
+// Iterators that are consumed by core library functions (e.g. `iterator.consume`) don't
+// necessarily execute any instructions, so the interrupt handle needs to be checked while they're
+// being iterated. See `Vm::make_interruptible_iterator`.
+pub(crate) struct InterruptibleIterator {
+    iterator: ValueIterator,
+    interrupt: InterruptHandle,
+}
+
+impl InterruptibleIterator {
+    pub(crate) fn new(iterator: ValueIterator, interrupt: InterruptHandle) -> Self {
+        Self {
+            iterator,
+            interrupt,
+        }
+    }
+
+    pub(crate) fn capacity_hint(&self) -> usize {
+        self.iterator.capacity_hint()
+    }
+}
+
+impl Iterator for InterruptibleIterator {
+    type Item = ValueIteratorOutput;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.interrupt.is_stopped() {
+            Some(ValueIteratorOutput::Error(RuntimeError::interrupted()))
+        } else {
+            self.iterator.next()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iterator.size_hint()
+    }
+}
@@ -2,12 +2,16 @@
 
 #![warn(missing_docs)]
 
+mod args;
 mod error;
 mod external;
 mod file;
 mod frame;
+mod interrupt;
+mod macros;
 mod meta_map;
 mod stdio;
+mod typed_fn;
 mod value_iterator;
 mod value_key;
 mod value_list;
@@ -25,13 +29,20 @@ pub mod prelude;
 pub mod value;
 
 pub use {
-    error::{type_error, type_error_with_slice, RuntimeError, RuntimeResult},
-    external::{ExternalData, ExternalFunction, ExternalValue},
+    args::all_iterable,
+    error::{
+        type_error, type_error_with_arg, type_error_with_slice, FrameFunction, RuntimeError,
+        RuntimeResult, StackFrame,
+    },
+    external::{ExternalCallable, ExternalData, ExternalFunction, ExternalValue},
     file::{KotoFile, KotoRead, KotoWrite},
+    frame::{CallFrameInfo, CallStackInfo},
+    interrupt::InterruptHandle,
     meta_map::{BinaryOp, MetaKey, MetaMap, MetaMapBuilder, UnaryOp},
     num2::Num2,
     num4::Num4,
     stdio::{DefaultStderr, DefaultStdin, DefaultStdout},
+    typed_fn::{FromValue, TypedFn},
     value::{FunctionInfo, IntRange, Value},
     value_iterator::{KotoIterator, ValueIterator, ValueIteratorOutput},
     value_key::ValueKey,
@@ -40,5 +51,5 @@ pub use {
     value_number::ValueNumber,
     value_string::ValueString,
     value_tuple::ValueTuple,
-    vm::{CallArgs, ModuleImportedCallback, Vm, VmSettings},
+    vm::{CallArgs, ErrorHook, ModuleImportedCallback, Vm, VmSettings},
 };
@@ -0,0 +1,81 @@
+/// Creates a [Value::List](crate::Value::List) containing the provided values
+///
+/// Each value is converted into a [Value](crate::Value) via `Value::from`.
+///
+/// # Example
+///
+/// ```
+/// use koto_runtime::prelude::*;
+///
+/// let list = koto_list![1, "two", koto_list![3.0, false]];
+/// assert_eq!(list.to_string(), "[1, 'two', [3.0, false]]");
+/// ```
+#[macro_export]
+macro_rules! koto_list {
+    ($($value:expr),* $(,)?) => {
+        $crate::Value::List($crate::ValueList::from_slice(&[$($crate::Value::from($value)),*]))
+    };
+}
+
+/// Creates a [Value::Tuple](crate::Value::Tuple) containing the provided values
+///
+/// Each value is converted into a [Value](crate::Value) via `Value::from`.
+///
+/// # Example
+///
+/// ```
+/// use koto_runtime::prelude::*;
+///
+/// let tuple = koto_tuple!["x", 42, koto_tuple![]];
+/// assert_eq!(tuple.to_string(), "('x', 42, (,))");
+/// ```
+#[macro_export]
+macro_rules! koto_tuple {
+    ($($value:expr),* $(,)?) => {
+        $crate::Value::Tuple($crate::ValueTuple::from(vec![$($crate::Value::from($value)),*]))
+    };
+}
+
+/// Creates a [Value::Map](crate::Value::Map) containing the provided entries
+///
+/// Keys can be provided as identifiers (which are converted into strings), as literals, or as
+/// parenthesized expressions. Values are converted into [Value](crate::Value)s via `Value::from`.
+///
+/// Only immutable values can be used as keys (see [Value::is_immutable](crate::Value::is_immutable)),
+/// and a panic will occur if a mutable value is used as a key.
+///
+/// # Example
+///
+/// ```
+/// use koto_runtime::prelude::*;
+///
+/// let n = 99;
+/// let map = koto_map! {
+///     foo: 42,
+///     "bar baz": koto_list![1, 2],
+///     (n): koto_map! { nested: true },
+/// };
+/// assert_eq!(map.to_string(), "{foo: 42, 'bar baz': [1, 2], 99: {nested: true}}");
+/// ```
+#[macro_export]
+macro_rules! koto_map {
+    (@key $key:ident) => {
+        $crate::ValueKey::from(stringify!($key))
+    };
+    (@key $key:literal) => {
+        $crate::ValueKey::from($crate::Value::from($key))
+    };
+    (@key ($key:expr)) => {
+        $crate::ValueKey::from($crate::Value::from($key))
+    };
+    () => {
+        $crate::Value::Map($crate::ValueMap::new())
+    };
+    ($($key:tt : $value:expr),+ $(,)?) => {{
+        let map = $crate::ValueMap::new();
+        $(
+            map.insert($crate::koto_map!(@key $key), $crate::Value::from($value));
+        )+
+        $crate::Value::Map(map)
+    }};
+}
@@ -22,12 +22,33 @@ impl Num2 {
         (self.0 * self.0 + self.1 * self.1).sqrt()
     }
 
+    pub fn dot(&self, other: &Self) -> f64 {
+        self.0 * other.0 + self.1 * other.1
+    }
+
+    /// Returns the angle in radians between the two vectors, in the range `0..=π`
+    ///
+    /// Zero is returned if either vector has a length of zero.
+    pub fn angle_between(&self, other: &Self) -> f64 {
+        angle_from_dot(self.dot(other), self.length() * other.length())
+    }
+
     #[must_use]
     pub fn normalize(&self) -> Self {
         *self / self.length()
     }
 }
 
+// Returns the angle between two vectors given their dot product and the product of their lengths
+pub(crate) fn angle_from_dot(dot: f64, length_product: f64) -> f64 {
+    if length_product == 0.0 {
+        0.0
+    } else {
+        // Rounding errors can produce values slightly outside of acos's domain
+        (dot / length_product).clamp(-1.0, 1.0).acos()
+    }
+}
+
 impl PartialEq for Num2 {
     fn eq(&self, other: &Self) -> bool {
         self.0 == other.0 && self.1 == other.1
@@ -1,7 +1,7 @@
 #![allow(missing_docs)]
 
 use {
-    crate::ValueNumber,
+    crate::{num2::angle_from_dot, ValueNumber},
     std::{
         fmt,
         hash::{Hash, Hasher},
@@ -26,6 +26,28 @@ impl Num4 {
         (x * x + y * y + z * z + w * w).sqrt()
     }
 
+    pub fn dot(&self, other: &Self) -> f64 {
+        (0..4).map(|i| self[i] as f64 * other[i] as f64).sum()
+    }
+
+    /// Returns the cross product of the `x`, `y`, and `z` components, with `w` set to zero
+    #[must_use]
+    pub fn cross(&self, other: &Self) -> Self {
+        Self(
+            self.1 * other.2 - self.2 * other.1,
+            self.2 * other.0 - self.0 * other.2,
+            self.0 * other.1 - self.1 * other.0,
+            0.0,
+        )
+    }
+
+    /// Returns the angle in radians between the two vectors, in the range `0..=π`
+    ///
+    /// Zero is returned if either vector has a length of zero.
+    pub fn angle_between(&self, other: &Self) -> f64 {
+        angle_from_dot(self.dot(other), self.length() * other.length())
+    }
+
     #[must_use]
     pub fn normalize(&self) -> Self {
         *self / self.length()
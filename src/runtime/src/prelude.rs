@@ -2,9 +2,10 @@
 
 #[doc(inline)]
 pub use crate::{
-    make_runtime_error, runtime_error, type_error, type_error_with_slice, BinaryOp, CallArgs,
-    DataMap, ExternalData, ExternalValue, IntRange, KotoFile, KotoIterator, KotoRead, KotoWrite,
-    MetaKey, MetaMap, MetaMapBuilder, Num2, Num4, RuntimeError, RuntimeResult, UnaryOp, Value,
-    ValueIterator, ValueIteratorOutput, ValueKey, ValueList, ValueMap, ValueNumber, ValueString,
-    ValueTuple, ValueVec, Vm, VmSettings,
+    all_iterable, koto_list, koto_map, koto_tuple, make_runtime_error, runtime_error, type_error,
+    type_error_with_arg, type_error_with_slice, BinaryOp, CallArgs, CallStackInfo, DataMap,
+    ExternalData, ExternalValue, FrameFunction, FromValue, IntRange, InterruptHandle, KotoFile,
+    KotoIterator, KotoRead, KotoWrite, MetaKey, MetaMap, MetaMapBuilder, Num2, Num4, RuntimeError,
+    RuntimeResult, StackFrame, TypedFn, UnaryOp, Value, ValueIterator, ValueIteratorOutput,
+    ValueKey, ValueList, ValueMap, ValueNumber, ValueString, ValueTuple, ValueVec, Vm, VmSettings,
 };
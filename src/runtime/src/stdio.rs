@@ -17,8 +17,10 @@ impl KotoWrite for DefaultStdin {}
 impl KotoRead for DefaultStdin {
     fn read_line(&self) -> Result<Option<String>, RuntimeError> {
         let mut result = String::new();
-        io::stdin().read_line(&mut result).map_err(map_io_err)?;
-        Ok(Some(result))
+        match io::stdin().read_line(&mut result).map_err(map_io_err)? {
+            0 => Ok(None),
+            _ => Ok(Some(result)),
+        }
     }
 
     fn read_to_string(&self) -> Result<String, RuntimeError> {
@@ -0,0 +1,278 @@
+use crate::{
+    external::ArgRegisters, prelude::*, ExternalFunction, Num2, Num4, ValueNumber, ValueString,
+};
+
+/// A trait for types that can be extracted from the arguments of a typed function
+///
+/// See [ValueMap::add_typed_fn].
+pub trait FromValue: Sized {
+    /// A description of the expected type, used when reporting type errors
+    fn type_description() -> String;
+
+    /// Attempts to convert the value, returning None if the value has an unexpected type
+    fn from_value(value: &Value) -> Option<Self>;
+
+    /// Returns the value that should be used when the argument is omitted
+    ///
+    /// None is returned by default, making the argument required.
+    fn from_missing_value() -> Option<Self> {
+        None
+    }
+}
+
+impl FromValue for Value {
+    fn type_description() -> String {
+        "Value".into()
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        Some(value.clone())
+    }
+}
+
+impl FromValue for bool {
+    fn type_description() -> String {
+        "Bool".into()
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for ValueNumber {
+    fn type_description() -> String {
+        "Number".into()
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for f64 {
+    fn type_description() -> String {
+        "Number".into()
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Number(n) => Some(n.into()),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for i64 {
+    fn type_description() -> String {
+        "Number".into()
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Number(n) => Some(n.into()),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for usize {
+    fn type_description() -> String {
+        "non-negative Number".into()
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Number(n) if *n >= 0.0 => Some(n.into()),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for Num2 {
+    fn type_description() -> String {
+        "Num2".into()
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Num2(n) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for Num4 {
+    fn type_description() -> String {
+        "Num4".into()
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Num4(n) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for ValueString {
+    fn type_description() -> String {
+        "String".into()
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Str(s) => Some(s.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for String {
+    fn type_description() -> String {
+        "String".into()
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Str(s) => Some(s.to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for ValueList {
+    fn type_description() -> String {
+        "List".into()
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::List(l) => Some(l.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for ValueMap {
+    fn type_description() -> String {
+        "Map".into()
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Map(m) => Some(m.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for ValueTuple {
+    fn type_description() -> String {
+        "Tuple".into()
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Tuple(t) => Some(t.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Optional arguments can be omitted when they're at the end of the function's arguments
+impl<T: FromValue> FromValue for Option<T> {
+    fn type_description() -> String {
+        format!("optional {}", T::type_description())
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        T::from_value(value).map(Some)
+    }
+
+    fn from_missing_value() -> Option<Self> {
+        Some(None)
+    }
+}
+
+/// A trait for Rust functions with typed arguments that can be called from Koto
+///
+/// The trait is implemented for functions that take up to 5 arguments that implement [FromValue],
+/// and that return a `Result` containing a value that can be converted into a [Value].
+///
+/// See [ValueMap::add_typed_fn].
+pub trait TypedFn<Args>: 'static {
+    /// Calls the function with the provided arguments
+    ///
+    /// An error is returned if the arguments don't match the function's signature.
+    fn call_with_args(&self, args: &[Value]) -> RuntimeResult;
+}
+
+macro_rules! impl_typed_fn {
+    ($($arg:ident),*) => {
+        impl<F, R, $($arg),*> TypedFn<($($arg,)*)> for F
+        where
+            F: Fn($($arg),*) -> Result<R, RuntimeError> + 'static,
+            R: Into<Value>,
+            $($arg: FromValue,)*
+        {
+            #[allow(non_snake_case, unused_mut, unused_variables)]
+            fn call_with_args(&self, args: &[Value]) -> RuntimeResult {
+                let optional_args: &[bool] = &[$($arg::from_missing_value().is_some()),*];
+                let arg_types: &[String] = &[$($arg::type_description()),*];
+
+                // Optional args are only allowed to be omitted at the end of the arguments
+                let required_arg_count = optional_args
+                    .iter()
+                    .rposition(|is_optional| !is_optional)
+                    .map_or(0, |index| index + 1);
+
+                if args.len() < required_arg_count || args.len() > arg_types.len() {
+                    return signature_error(arg_types, args);
+                }
+
+                let mut args_iter = args.iter();
+                $(
+                    let $arg = match args_iter.next() {
+                        Some(value) => $arg::from_value(value),
+                        None => $arg::from_missing_value(),
+                    };
+                    let $arg = match $arg {
+                        Some(arg) => arg,
+                        None => return signature_error(arg_types, args),
+                    };
+                )*
+
+                self($($arg),*).map(|result| result.into())
+            }
+        }
+    };
+}
+
+impl_typed_fn!();
+impl_typed_fn!(A);
+impl_typed_fn!(A, B);
+impl_typed_fn!(A, B, C);
+impl_typed_fn!(A, B, C, D);
+impl_typed_fn!(A, B, C, D, E);
+
+fn signature_error(arg_types: &[String], args: &[Value]) -> RuntimeResult {
+    if arg_types.is_empty() {
+        type_error_with_slice("no arguments", args)
+    } else {
+        type_error_with_slice(&format!("|{}| as arguments", arg_types.join(", ")), args)
+    }
+}
+
+pub(crate) fn make_typed_fn<Args>(f: impl TypedFn<Args>) -> Value {
+    Value::ExternalFunction(ExternalFunction::new(
+        move |vm: &mut Vm, args: &ArgRegisters| f.call_with_args(vm.get_args(args)),
+        false,
+    ))
+}
@@ -98,6 +98,7 @@ impl Value {
             Value::Num4(n) => ValueRef::Num4(n),
             Value::Str(s) => ValueRef::Str(s),
             Value::Range(r) => ValueRef::Range(r),
+            Value::Tuple(t) => ValueRef::Tuple(t),
             _ => unreachable!(), // Only immutable values can be used in ValueKey
         }
     }
@@ -140,13 +141,16 @@ impl Value {
 
     /// Returns true if the value doesn't have internal mutability
     ///
+    /// Tuples are immutable if all of their elements are immutable.
+    ///
     /// Only immutable values are acceptable as map keys.
     pub fn is_immutable(&self) -> bool {
         use Value::*;
-        matches!(
-            self,
-            Null | Bool(_) | Number(_) | Num2(_) | Num4(_) | Range(_) | Str(_)
-        )
+        match self {
+            Null | Bool(_) | Number(_) | Num2(_) | Num4(_) | Range(_) | Str(_) => true,
+            Tuple(t) => t.iter().all(Value::is_immutable),
+            _ => false,
+        }
     }
 
     /// Returns true if a `ValueIterator` can be made from the value
@@ -268,7 +272,7 @@ impl fmt::Display for Value {
                     write!(f, "{m}")
                 }
             }
-            Range(IntRange { start, end }) => write!(f, "{start}..{end}"),
+            Range(r) => write!(f, "{r}"),
             SimpleFunction(_) | Function(_) => write!(f, "||"),
             Generator(_) => write!(f, "Generator"),
             Iterator(_) => write!(f, "Iterator"),
@@ -384,12 +388,16 @@ pub struct FunctionInfo {
 
 /// The integer range type that's exposed to users in the runtime
 ///
+/// Inclusive ranges (e.g. `1..=5`) keep their end value, with `inclusive` set to true.
+/// Ranges that contain the same values compare as equal, so `1..=5` is equal to `1..6`.
+///
 /// See [Value::Range]
 #[allow(missing_docs)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug)]
 pub struct IntRange {
     pub start: isize,
     pub end: isize,
+    pub inclusive: bool,
 }
 
 impl IntRange {
@@ -398,14 +406,59 @@ impl IntRange {
         self.start <= self.end
     }
 
+    /// Returns the end of the range, excluding the range's last value
+    ///
+    /// For non-inclusive ranges this is the range's end,
+    /// for inclusive ranges it's one step past the end in the range's direction.
+    pub fn exclusive_end(&self) -> isize {
+        match (self.inclusive, self.is_ascending()) {
+            (false, _) => self.end,
+            (true, true) => self.end + 1,
+            (true, false) => self.end - 1,
+        }
+    }
+
+    /// Returns an equivalent non-inclusive range
+    pub fn to_exclusive(&self) -> IntRange {
+        IntRange {
+            start: self.start,
+            end: self.exclusive_end(),
+            inclusive: false,
+        }
+    }
+
+    /// Returns an equivalent range with the requested inclusivity
+    ///
+    /// Empty ranges can't be represented as inclusive ranges, so they're always returned as
+    /// non-inclusive ranges.
+    pub fn with_inclusivity(&self, inclusive: bool) -> IntRange {
+        let exclusive = self.to_exclusive();
+
+        if !inclusive || exclusive.is_empty() {
+            exclusive
+        } else {
+            IntRange {
+                start: self.start,
+                end: if exclusive.is_ascending() {
+                    exclusive.end - 1
+                } else {
+                    exclusive.end + 1
+                },
+                inclusive: true,
+            }
+        }
+    }
+
     /// Returns the size of the range
     ///
-    /// Descending ranges have a non-negative size, i.e. the size is equal to `start - end`.
+    /// Descending ranges have a non-negative size, e.g. the size of `5..1` is 4,
+    /// and the size of `5..=1` is 5.
     pub fn len(&self) -> usize {
+        let end = self.exclusive_end();
         if self.is_ascending() {
-            (self.end - self.start) as usize
+            (end - self.start) as usize
         } else {
-            (self.start - self.end) as usize
+            (self.start - end) as usize
         }
     }
 
@@ -413,18 +466,104 @@ impl IntRange {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Returns the range of values that are contained in both ranges
+    ///
+    /// The result has the same direction and inclusivity as `self`, and None is returned if the
+    /// ranges don't overlap.
+    pub fn intersection(&self, other: &IntRange) -> Option<IntRange> {
+        let (a_start, a_end) = self.ascending_bounds();
+        let (b_start, b_end) = other.ascending_bounds();
+        let start = a_start.max(b_start);
+        let end = a_end.min(b_end);
+
+        let result = if start >= end {
+            return None;
+        } else if self.is_ascending() {
+            IntRange {
+                start,
+                end,
+                inclusive: false,
+            }
+        } else {
+            IntRange {
+                start: end - 1,
+                end: start - 1,
+                inclusive: false,
+            }
+        };
+
+        Some(result.with_inclusivity(self.inclusive))
+    }
+
+    // Returns the range's bounds as an ascending half-open interval
+    fn ascending_bounds(&self) -> (isize, isize) {
+        let end = self.exclusive_end();
+        if self.is_ascending() {
+            (self.start, end)
+        } else {
+            (end + 1, self.start + 1)
+        }
+    }
+}
+
+impl PartialEq for IntRange {
+    fn eq(&self, other: &Self) -> bool {
+        self.start == other.start && self.exclusive_end() == other.exclusive_end()
+    }
+}
+
+impl Eq for IntRange {}
+
+impl fmt::Display for IntRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.inclusive {
+            write!(f, "{}..={}", self.start, self.end)
+        } else {
+            write!(f, "{}..{}", self.start, self.end)
+        }
+    }
 }
 
 /// A range type that's used in indexing expressions
 ///
 /// Index ranges have an optional end to support indexing expressions like `foo[10..]`.
 ///
+/// Negative bounds count back from the end of the indexed value, e.g. `foo[-3..]` produces the
+/// last 3 elements of `foo`, see [IndexRange::resolve].
+///
 /// See [Value::IndexRange]
 #[allow(missing_docs)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct IndexRange {
-    pub start: usize,
-    pub end: Option<usize>,
+    pub start: isize,
+    pub end: Option<isize>,
+}
+
+impl IndexRange {
+    /// Returns the range's start and end when indexing a value of the given size
+    ///
+    /// Negative bounds are resolved against the size, and a missing end resolves to the size.
+    ///
+    /// The resolved bounds aren't validated, so they can be out of bounds for the indexed value.
+    pub fn resolve(&self, size: usize) -> (isize, isize) {
+        (
+            resolve_index(self.start, size),
+            self.end
+                .map_or(size as isize, |end| resolve_index(end, size)),
+        )
+    }
+}
+
+/// Resolves an index against the size of an indexed value
+///
+/// Negative indices count back from the end of the value, so `-1` resolves to `size - 1`.
+pub(crate) fn resolve_index(index: isize, size: usize) -> isize {
+    if index < 0 {
+        index + size as isize
+    } else {
+        index
+    }
 }
 
 /// A slice of a VM's registers
@@ -447,4 +586,61 @@ mod tests {
         // total size of Value should not be greater than 40 bytes.
         assert!(std::mem::size_of::<Value>() <= 40);
     }
+
+    #[test]
+    fn int_range_intersection() {
+        let range = |start, end| IntRange {
+            start,
+            end,
+            inclusive: false,
+        };
+        let inclusive = |start, end| IntRange {
+            start,
+            end,
+            inclusive: true,
+        };
+
+        assert_eq!(range(0, 10).intersection(&range(5, 15)), Some(range(5, 10)));
+        assert_eq!(range(0, 10).intersection(&range(10, 15)), None);
+        assert_eq!(range(0, 10).intersection(&range(3, 3)), None);
+        // 10..0 contains 10 down to 1, 4..=8 is represented as 4..9
+        assert_eq!(range(10, 0).intersection(&range(4, 9)), Some(range(8, 3)));
+        assert_eq!(range(4, 9).intersection(&range(10, 0)), Some(range(4, 9)));
+        assert_eq!(range(10, 0).intersection(&range(20, 5)), Some(range(10, 5)));
+        assert_eq!(range(10, 0).intersection(&range(-5, 1)), None);
+
+        let result = inclusive(0, 10).intersection(&range(5, 15)).unwrap();
+        assert!(result.inclusive);
+        assert_eq!((result.start, result.end), (5, 10));
+        let result = inclusive(10, 0).intersection(&inclusive(-5, 5)).unwrap();
+        assert!(result.inclusive);
+        assert_eq!((result.start, result.end), (5, 0));
+    }
+
+    #[test]
+    fn inclusive_int_range() {
+        let range = |start, end, inclusive| IntRange {
+            start,
+            end,
+            inclusive,
+        };
+
+        assert_eq!(range(1, 5, true).len(), 5);
+        assert_eq!(range(5, 1, true).len(), 5);
+        assert_eq!(range(3, 3, true).len(), 1);
+        assert_eq!(range(3, 3, false).len(), 0);
+
+        assert_eq!(range(1, 5, true), range(1, 6, false));
+        assert_eq!(range(5, 1, true), range(5, 0, false));
+        assert_ne!(range(1, 5, true), range(1, 5, false));
+
+        assert_eq!(range(1, 5, true).to_string(), "1..=5");
+        assert_eq!(range(5, 1, false).to_string(), "5..1");
+
+        let converted = range(5, 0, false).with_inclusivity(true);
+        assert!(converted.inclusive);
+        assert_eq!((converted.start, converted.end), (5, 1));
+        // Empty ranges stay non-inclusive
+        assert!(!range(3, 3, false).with_inclusivity(true).inclusive);
+    }
 }
@@ -2,7 +2,13 @@ use {
     crate::{
         IntRange, Num2, Num4, RuntimeError, Value, ValueList, ValueMap, ValueString, ValueTuple, Vm,
     },
-    std::{cell::RefCell, cmp::Ordering, fmt, ops::DerefMut, rc::Rc},
+    std::{
+        cell::RefCell,
+        cmp::Ordering,
+        fmt,
+        ops::{DerefMut, Range},
+        rc::Rc,
+    },
     unicode_segmentation::GraphemeCursor,
 };
 
@@ -19,6 +25,24 @@ pub trait KotoIterator: Iterator<Item = ValueIteratorOutput> {
     /// iterator adaptors like chunks() or windows().
     fn might_have_side_effects(&self) -> bool;
 
+    /// Returns a description of the iterator, used when debugging chains of iterator adaptors
+    ///
+    /// Adaptors should include the name of the iterator that they're adapting,
+    /// e.g. the iterator produced by `(1..10).each(f).take(5)` is named `Take(5) <- Each <- Range`.
+    fn name(&self) -> String {
+        "Iterator".into()
+    }
+
+    /// Returns the number of values that should be allocated for when collecting the output
+    ///
+    /// Unlike the lower bound of the iterator's size hint, the capacity hint isn't guaranteed to be
+    /// less than or equal to the number of values that will be produced.
+    ///
+    /// The default implementation returns the lower bound of the iterator's size hint.
+    fn capacity_hint(&self) -> usize {
+        self.size_hint().0
+    }
+
     /// Returns true if the iterator supports reversed iteration via `next_back`
     fn is_bidirectional(&self) -> bool {
         false
@@ -30,6 +54,18 @@ pub trait KotoIterator: Iterator<Item = ValueIteratorOutput> {
     fn next_back(&mut self) -> Option<ValueIteratorOutput> {
         None
     }
+
+    /// Returns true if the iterator supports looking ahead via `peek`
+    fn is_peekable(&self) -> bool {
+        false
+    }
+
+    /// Returns the next item that will be produced by the iterator, without advancing it
+    ///
+    /// Returns `None` when the iterator is exhausted, or if the iterator isn't peekable.
+    fn peek(&mut self) -> Option<ValueIteratorOutput> {
+        None
+    }
 }
 
 /// The output type for iterators in Koto
@@ -63,6 +99,14 @@ impl ValueIterator {
         Self::new(RangeIterator::new(range))
     }
 
+    /// Creates a new ValueIterator that yields the characters between `start` and `end`
+    ///
+    /// The characters are produced by advancing through Unicode scalar values, in descending
+    /// order if `end` is less than `start`.
+    pub fn with_char_range(start: char, end: char, inclusive: bool) -> Self {
+        Self::new(CharRangeIterator::new(start, end, inclusive))
+    }
+
     /// Creates a new ValueIterator from a Num2
     pub fn with_num2(n: Num2) -> Self {
         Self::new(Num2Iterator::new(n))
@@ -78,6 +122,13 @@ impl ValueIterator {
         Self::new(ListIterator::new(list))
     }
 
+    /// Creates a new ValueIterator that yields the List's values within the provided index range
+    ///
+    /// The range is clamped to the List's current size.
+    pub fn with_list_range(list: ValueList, range: Range<usize>) -> Self {
+        Self::new(ListIterator::with_range(list, range))
+    }
+
     /// Creates a new ValueIterator from a Tuple
     pub fn with_tuple(tuple: ValueTuple) -> Self {
         Self::new(TupleIterator::new(tuple))
@@ -113,6 +164,20 @@ impl ValueIterator {
         self.0.borrow().might_have_side_effects()
     }
 
+    /// Returns a description of the iterator
+    ///
+    /// See [KotoIterator::name]
+    pub fn name(&self) -> String {
+        self.0.borrow().name()
+    }
+
+    /// Returns the number of values that should be allocated for when collecting the output
+    ///
+    /// See [KotoIterator::capacity_hint]
+    pub fn capacity_hint(&self) -> usize {
+        self.0.borrow().capacity_hint()
+    }
+
     /// Returns true if the iterator supports reversed iteration via `next_back`
     ///
     /// See [KotoIterator::is_bidirectional]
@@ -127,6 +192,20 @@ impl ValueIterator {
         self.0.borrow_mut().next_back()
     }
 
+    /// Returns true if the iterator supports looking ahead via `peek`
+    ///
+    /// See [KotoIterator::is_peekable]
+    pub fn is_peekable(&self) -> bool {
+        self.0.borrow().is_peekable()
+    }
+
+    /// Returns the next item that will be produced by the iterator, without advancing it
+    ///
+    /// See [KotoIterator::peek]
+    pub fn peek(&mut self) -> Option<ValueIteratorOutput> {
+        self.0.borrow_mut().peek()
+    }
+
     /// Mutably borrows the underlying iterator, allowing repeated iterations with a single borrow
     pub fn borrow_internals(
         &mut self,
@@ -187,6 +266,10 @@ impl KotoIterator for Num2Iterator {
         false
     }
 
+    fn name(&self) -> String {
+        "Num2".into()
+    }
+
     fn is_bidirectional(&self) -> bool {
         true
     }
@@ -250,6 +333,10 @@ impl KotoIterator for Num4Iterator {
         false
     }
 
+    fn name(&self) -> String {
+        "Num4".into()
+    }
+
     fn is_bidirectional(&self) -> bool {
         true
     }
@@ -290,7 +377,11 @@ struct RangeIterator {
 
 impl RangeIterator {
     fn new(range: IntRange) -> Self {
-        Self { range }
+        // The iterator steps the range's bounds towards each other until they meet,
+        // so inclusive ranges are converted into their non-inclusive equivalents.
+        Self {
+            range: range.to_exclusive(),
+        }
     }
 }
 
@@ -303,6 +394,10 @@ impl KotoIterator for RangeIterator {
         false
     }
 
+    fn name(&self) -> String {
+        "Range".into()
+    }
+
     fn is_bidirectional(&self) -> bool {
         true
     }
@@ -348,6 +443,92 @@ impl Iterator for RangeIterator {
     }
 }
 
+#[derive(Clone)]
+struct CharRangeIterator {
+    // The range is stored as a non-inclusive range of scalar values,
+    // with i64 used to allow for an inclusive range reaching below '\0' or beyond char::MAX.
+    start: i64,
+    end: i64,
+}
+
+impl CharRangeIterator {
+    fn new(start: char, end: char, inclusive: bool) -> Self {
+        let start = start as i64;
+        let end = end as i64;
+        let end = match (inclusive, start <= end) {
+            (true, true) => end + 1,
+            (true, false) => end - 1,
+            (false, _) => end,
+        };
+        Self { start, end }
+    }
+}
+
+impl KotoIterator for CharRangeIterator {
+    fn make_copy(&self) -> ValueIterator {
+        ValueIterator::new(self.clone())
+    }
+
+    fn might_have_side_effects(&self) -> bool {
+        false
+    }
+
+    fn name(&self) -> String {
+        "CharRange".into()
+    }
+
+    fn is_bidirectional(&self) -> bool {
+        true
+    }
+
+    fn next_back(&mut self) -> Option<ValueIteratorOutput> {
+        loop {
+            match self.start.cmp(&self.end) {
+                Ordering::Less => self.end -= 1,
+                Ordering::Greater => self.end += 1,
+                Ordering::Equal => return None,
+            }
+
+            // Surrogate code points aren't valid chars, and are skipped
+            if let Some(c) = u32::try_from(self.end).ok().and_then(char::from_u32) {
+                return Some(ValueIteratorOutput::Value(Value::Str(c.to_string().into())));
+            }
+        }
+    }
+}
+
+impl Iterator for CharRangeIterator {
+    type Item = Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let result = match self.start.cmp(&self.end) {
+                Ordering::Less => {
+                    self.start += 1;
+                    self.start - 1
+                }
+                Ordering::Greater => {
+                    self.start -= 1;
+                    self.start + 1
+                }
+                Ordering::Equal => return None,
+            };
+
+            // Surrogate code points aren't valid chars, and are skipped
+            if let Some(c) = u32::try_from(result).ok().and_then(char::from_u32) {
+                return Some(ValueIteratorOutput::Value(Value::Str(c.to_string().into())));
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // The upper bound includes any surrogate code points that will be skipped
+        let upper_bound = (self.end - self.start).unsigned_abs() as usize;
+        let lower_bound = upper_bound.saturating_sub(0xE000 - 0xD800);
+        (lower_bound, Some(upper_bound))
+    }
+}
+
 #[derive(Clone)]
 struct ListIterator {
     data: ValueList,
@@ -365,6 +546,15 @@ impl ListIterator {
         }
     }
 
+    fn with_range(data: ValueList, range: Range<usize>) -> Self {
+        let end = range.end.min(data.len());
+        Self {
+            data,
+            index: range.start.min(end),
+            end,
+        }
+    }
+
     fn get_output(&self, index: usize) -> Option<ValueIteratorOutput> {
         self.data
             .data()
@@ -382,6 +572,10 @@ impl KotoIterator for ListIterator {
         false
     }
 
+    fn name(&self) -> String {
+        "List".into()
+    }
+
     fn is_bidirectional(&self) -> bool {
         true
     }
@@ -410,7 +604,7 @@ impl Iterator for ListIterator {
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining = self.data.len().saturating_sub(self.index);
+        let remaining = self.end.min(self.data.len()).saturating_sub(self.index);
         (remaining, Some(remaining))
     }
 }
@@ -448,6 +642,10 @@ impl KotoIterator for TupleIterator {
         false
     }
 
+    fn name(&self) -> String {
+        "Tuple".into()
+    }
+
     fn is_bidirectional(&self) -> bool {
         true
     }
@@ -515,6 +713,10 @@ impl KotoIterator for MapIterator {
         false
     }
 
+    fn name(&self) -> String {
+        "Map".into()
+    }
+
     fn is_bidirectional(&self) -> bool {
         true
     }
@@ -580,6 +782,10 @@ impl KotoIterator for StringIterator {
         false
     }
 
+    fn name(&self) -> String {
+        "String".into()
+    }
+
     fn is_bidirectional(&self) -> bool {
         true
     }
@@ -587,13 +793,15 @@ impl KotoIterator for StringIterator {
     fn next_back(&mut self) -> Option<ValueIteratorOutput> {
         let remaining = self.as_slice();
         match GraphemeCursor::new(remaining.len(), remaining.len(), true)
-            .prev_boundary(remaining, self.index)
-            .unwrap() // Safety: self.index will be on a grapheme boundary or at the string's end
+            .prev_boundary(remaining, 0)
+            .unwrap() // Safety: the cursor is given the complete remaining string
         {
             Some(grapheme_start) => {
+                // The cursor's offsets are relative to the start of the remaining string
+                let grapheme_start = self.index + grapheme_start;
                 let result = self.data
                     .with_bounds(grapheme_start..self.end)
-                    .unwrap(); // Safety: Some(_) returned from next_boundary implies valid bounds
+                    .unwrap(); // Safety: Some(_) returned from prev_boundary implies valid bounds
                 self.end = grapheme_start;
                 Some(ValueIteratorOutput::Value(Value::Str(result)))
             }
@@ -623,7 +831,7 @@ impl Iterator for StringIterator {
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let upper_bound = self.data[self.index..].len();
+        let upper_bound = self.as_slice().len();
         let lower_bound = (upper_bound != 0) as usize;
         (lower_bound, Some(upper_bound))
     }
@@ -649,6 +857,10 @@ impl KotoIterator for GeneratorIterator {
     fn might_have_side_effects(&self) -> bool {
         true
     }
+
+    fn name(&self) -> String {
+        "Generator".into()
+    }
 }
 
 impl Iterator for GeneratorIterator {
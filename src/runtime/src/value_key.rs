@@ -1,5 +1,5 @@
 use {
-    crate::{num2, num4, value::Value, IntRange, ValueNumber, ValueString},
+    crate::{num2, num4, value::Value, IntRange, ValueNumber, ValueString, ValueTuple},
     std::{
         borrow::Borrow,
         cmp::Ordering,
@@ -23,18 +23,8 @@ impl ValueKey {
 
 impl PartialEq for ValueKey {
     fn eq(&self, other: &Self) -> bool {
-        use Value::*;
-
-        match (&self.0, &other.0) {
-            (Number(a), Number(b)) => a == b,
-            (Num2(a), Num2(b)) => a == b,
-            (Num4(a), Num4(b)) => a == b,
-            (Bool(a), Bool(b)) => a == b,
-            (Str(a), Str(b)) => a == b,
-            (Range(a), Range(b)) => a == b,
-            (Null, Null) => true,
-            _ => false,
-        }
+        // Keys are compared via ValueRef to ensure that equality agrees with hashing
+        self.0.as_ref() == other.0.as_ref()
     }
 }
 
@@ -92,6 +82,15 @@ impl PartialOrd for ValueKey {
             (Num2(a), Num2(b)) => a.partial_cmp(b),
             (Num4(a), Num4(b)) => a.partial_cmp(b),
             (Str(a), Str(b)) => a.partial_cmp(b),
+            (Tuple(a), Tuple(b)) => {
+                for (a, b) in a.iter().zip(b.iter()) {
+                    match ValueKey(a.clone()).partial_cmp(&ValueKey(b.clone())) {
+                        Some(Ordering::Equal) => {}
+                        other => return other,
+                    }
+                }
+                a.len().partial_cmp(&b.len())
+            }
             _ => Some(Ordering::Less),
         }
     }
@@ -107,6 +106,15 @@ impl Ord for ValueKey {
             (_, Null) => Ordering::Greater,
             (Number(a), Number(b)) => a.cmp(b),
             (Str(a), Str(b)) => a.cmp(b),
+            (Tuple(a), Tuple(b)) => {
+                for (a, b) in a.iter().zip(b.iter()) {
+                    match ValueKey(a.clone()).cmp(&ValueKey(b.clone())) {
+                        Ordering::Equal => {}
+                        other => return other,
+                    }
+                }
+                a.len().cmp(&b.len())
+            }
             _ => Ordering::Less,
         }
     }
@@ -124,6 +132,7 @@ pub(crate) enum ValueRef<'a> {
     Num4(&'a num4::Num4),
     Str(&'a str),
     Range(&'a IntRange),
+    Tuple(&'a ValueTuple),
 }
 
 impl<'a> From<&'a Value> for ValueRef<'a> {
@@ -136,6 +145,7 @@ impl<'a> From<&'a Value> for ValueRef<'a> {
             Value::Num4(n) => ValueRef::Num4(n),
             Value::Str(s) => ValueRef::Str(s),
             Value::Range(r) => ValueRef::Range(r),
+            Value::Tuple(t) => ValueRef::Tuple(t),
             _ => unreachable!(), // Only immutable values can be used in ValueKey
         }
     }
@@ -152,6 +162,12 @@ impl<'a> PartialEq for ValueRef<'a> {
             (Bool(a), Bool(b)) => a == b,
             (Str(a), Str(b)) => a == b,
             (Range(a), Range(b)) => a == b,
+            (Tuple(a), Tuple(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .zip(b.iter())
+                        .all(|(a, b)| a.as_ref() == b.as_ref())
+            }
             (Null, Null) => true,
             _ => false,
         }
@@ -173,9 +189,16 @@ impl<'a> Hash for ValueRef<'a> {
             Num2(n) => n.hash(state),
             Num4(n) => n.hash(state),
             Str(s) => s.hash(state),
-            Range(IntRange { start, end }) => {
-                state.write_isize(*start);
-                state.write_isize(*end);
+            Range(r) => {
+                // Equivalent inclusive and non-inclusive ranges need to produce the same hash
+                state.write_isize(r.start);
+                state.write_isize(r.exclusive_end());
+            }
+            Tuple(t) => {
+                state.write_usize(t.len());
+                for value in t.iter() {
+                    value.as_ref().hash(state);
+                }
             }
         }
     }
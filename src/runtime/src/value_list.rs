@@ -51,6 +51,11 @@ impl ValueList {
     pub fn data_mut(&self) -> RefMut<ValueVec> {
         self.0.borrow_mut()
     }
+
+    /// Returns true if the two lists share the same underlying data
+    pub fn is_same_instance(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
 }
 
 impl fmt::Display for ValueList {
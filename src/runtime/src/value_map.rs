@@ -1,10 +1,12 @@
 use {
     crate::{
         external::{ArgRegisters, ExternalFunction},
+        typed_fn::{make_typed_fn, TypedFn},
         value_key::ValueKeyRef,
         MetaKey, MetaMap, RuntimeResult, Value, ValueKey, Vm,
     },
     indexmap::IndexMap,
+    koto_lexer::{Lexer, Token},
     rustc_hash::FxHasher,
     std::{
         cell::{Ref, RefCell, RefMut},
@@ -118,6 +120,13 @@ impl ValueMap {
         self.data.borrow_mut()
     }
 
+    /// Returns true if the two maps share the same underlying data
+    ///
+    /// The maps' meta maps aren't taken into account.
+    pub fn is_same_instance(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.data, &other.data)
+    }
+
     /// Provides a reference to the ValueMaps' meta map
     ///
     /// This is returned as a reference to the meta map's Rc to allow for cloning.
@@ -157,6 +166,30 @@ impl ValueMap {
         self.add_value(id, Value::ExternalFunction(ExternalFunction::new(f, false)));
     }
 
+    /// Adds a function with typed arguments to the ValueMap's data map
+    ///
+    /// The function's arguments are checked and converted before the function is called,
+    /// with an error listing the function's expected arguments being thrown if the arguments
+    /// don't match. Arguments with `Option` types are optional when they're at the end of the
+    /// function's arguments.
+    ///
+    /// See [FromValue](crate::FromValue) for the supported argument types.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use koto_runtime::prelude::*;
+    ///
+    /// let module = ValueMap::default();
+    /// module.add_typed_fn("greet", |name: String, greeting: Option<String>| {
+    ///     let greeting = greeting.as_deref().unwrap_or("Hello");
+    ///     Ok(format!("{greeting}, {name}!"))
+    /// });
+    /// ```
+    pub fn add_typed_fn<Args>(&self, id: &str, f: impl TypedFn<Args>) {
+        self.add_value(id, make_typed_fn(f));
+    }
+
     /// Adds an instance function to the ValueMap's data map
     pub fn add_instance_fn(
         &self,
@@ -192,6 +225,8 @@ impl ValueMap {
 }
 
 impl fmt::Display for ValueMap {
+    /// Keys that aren't valid identifiers are quoted, so that maps containing data-only values
+    /// can be parsed by Koto
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{{")?;
         let mut first = true;
@@ -199,13 +234,25 @@ impl fmt::Display for ValueMap {
             if !first {
                 write!(f, ", ")?;
             }
-            write!(f, "{}: {value:#}", key.value())?;
+            match key.value() {
+                Value::Str(s) if is_unquoted_key(s) => write!(f, "{s}")?,
+                other => write!(f, "{other:#}")?,
+            }
+            write!(f, ": {value:#}")?;
             first = false;
         }
         write!(f, "}}")
     }
 }
 
+// Returns true if the key can be used in a map literal without quotes
+//
+// Keywords are excluded, e.g. `{if: 1}` isn't a valid map literal.
+fn is_unquoted_key(key: &str) -> bool {
+    let mut lexer = Lexer::new(key);
+    matches!((lexer.next(), lexer.next()), (Some(Token::Id), None))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,14 +118,24 @@ impl fmt::Debug for ValueNumber {
     }
 }
 
+/// Numbers are displayed so that parsing the output (e.g. with `string.to_number`) produces the
+/// same number
+///
+/// - Floats are displayed with the shortest representation that round-trips to the same value,
+///   with a trailing `.0` for integer values so that they're distinct from Ints.
+/// - Infinities are displayed as `inf` and `-inf`, and NaN is displayed as `nan`.
 impl fmt::Display for ValueNumber {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ValueNumber::F64(n) => {
-                if n.fract() > 0.0 {
-                    write!(f, "{n}")
-                } else {
+                if n.is_nan() {
+                    f.write_str("nan")
+                } else if n.is_infinite() {
+                    f.write_str(if *n > 0.0 { "inf" } else { "-inf" })
+                } else if n.fract() == 0.0 {
                     write!(f, "{n:.1}")
+                } else {
+                    write!(f, "{n}")
                 }
             }
             ValueNumber::I64(n) => write!(f, "{n}"),
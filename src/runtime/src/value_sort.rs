@@ -2,52 +2,100 @@
 //! `Ord` for example). So we always need to call operators to compare them. This module contains
 //! helpers for comparing and sorting [Value].
 
-use std::cmp::Ordering;
-
 use crate::{runtime_error, BinaryOp, RuntimeError, Value, Vm};
 
 /// Sorts values in a slice using Koto operators for comparison.
+///
+/// The sort is stable, with values only being reordered when the `<` operator returns true,
+/// so values that compare as equal (e.g. `2` and `2.0`) keep their relative order.
+///
+/// If any of the values can't be compared then an error is returned, and the slice is left in
+/// its original order.
 pub fn sort_values(vm: &mut Vm, arr: &mut [Value]) -> Result<(), RuntimeError> {
-    let mut error = None;
+    let order = sorted_order(vm, arr)?;
+    apply_order(arr, &order);
+    Ok(())
+}
 
-    arr.sort_by(|a, b| {
-        if error.is_some() {
-            return Ordering::Equal;
-        }
+/// Sorts items using a matching slice of sort keys, with the keys compared using Koto operators.
+///
+/// See [sort_values] for details of the sorting behaviour.
+pub fn sort_values_by_keys<T: Clone>(
+    vm: &mut Vm,
+    items: &mut [T],
+    keys: &[Value],
+) -> Result<(), RuntimeError> {
+    debug_assert_eq!(items.len(), keys.len());
+    let order = sorted_order(vm, keys)?;
+    apply_order(items, &order);
+    Ok(())
+}
+
+// Returns the indices of the values in sorted order, determined with a bottom-up merge sort
+//
+// The sort is performed on indices so that the input values are untouched if an error occurs.
+fn sorted_order(vm: &mut Vm, values: &[Value]) -> Result<Vec<usize>, RuntimeError> {
+    let len = values.len();
+    let mut order = (0..len).collect::<Vec<_>>();
+    let mut merged = Vec::with_capacity(len);
+    let mut width = 1;
+
+    while width < len {
+        merged.clear();
 
-        match compare_values(vm, a, b) {
-            Ok(ordering) => ordering,
-            Err(e) => {
-                error.get_or_insert(e);
-                Ordering::Equal
+        for start in (0..len).step_by(width * 2) {
+            let middle = (start + width).min(len);
+            let end = (start + width * 2).min(len);
+            let (mut left, mut right) = (start, middle);
+
+            while left < middle && right < end {
+                // Values from the right are only taken first when they're strictly less than
+                // values from the left, which keeps the sort stable.
+                if is_less(vm, &values[order[right]], &values[order[left]])? {
+                    merged.push(order[right]);
+                    right += 1;
+                } else {
+                    merged.push(order[left]);
+                    left += 1;
+                }
             }
+
+            merged.extend_from_slice(&order[left..middle]);
+            merged.extend_from_slice(&order[right..end]);
         }
-    });
 
-    if let Some(err) = error {
-        return Err(err);
+        std::mem::swap(&mut order, &mut merged);
+        width *= 2;
     }
 
-    Ok(())
+    Ok(order)
 }
 
-/// Compares values using Koto operators.
-pub fn compare_values(vm: &mut Vm, a: &Value, b: &Value) -> Result<Ordering, RuntimeError> {
-    match vm.run_binary_op(BinaryOp::Less, a.clone(), b.clone())? {
-        Value::Bool(true) => Ok(Ordering::Less),
-        Value::Bool(false) => match vm.run_binary_op(BinaryOp::Greater, a.clone(), b.clone())? {
-            Value::Bool(true) => Ok(Ordering::Greater),
-            Value::Bool(false) => Ok(Ordering::Equal),
-            unexpected => runtime_error!(
-                "Expected Bool from > comparison, found '{}'",
-                unexpected.type_as_string()
-            ),
-        },
-        unexpected => {
-            runtime_error!(
-                "Expected Bool from < comparison, found '{}'",
-                unexpected.type_as_string()
-            )
-        }
+fn apply_order<T: Clone>(items: &mut [T], order: &[usize]) {
+    let sorted = order.iter().map(|i| items[*i].clone()).collect::<Vec<_>>();
+    items.clone_from_slice(&sorted);
+}
+
+/// Returns true if `a` is less than `b`, using Koto operators for comparison.
+///
+/// An error is returned if the values can't be compared, or if the comparison doesn't produce a
+/// Bool.
+pub fn is_less(vm: &mut Vm, a: &Value, b: &Value) -> Result<bool, RuntimeError> {
+    use Value::{ExternalValue, Map};
+
+    match vm.run_binary_op(BinaryOp::Less, a.clone(), b.clone()) {
+        Ok(Value::Bool(result)) => Ok(result),
+        Ok(unexpected) => runtime_error!(
+            "Expected Bool from < comparison, found '{}'",
+            unexpected.type_as_string()
+        ),
+        // Errors from overloaded operators are passed along unchanged
+        Err(error) if matches!(a, Map(_) | ExternalValue(_)) => Err(error),
+        Err(error) if matches!(b, Map(_) | ExternalValue(_)) => Err(error),
+        Err(_) => runtime_error!(
+            "Unable to sort values, '{}' and '{}' can't be compared with '<'",
+            a.type_as_string(),
+            b.type_as_string()
+        ),
     }
 }
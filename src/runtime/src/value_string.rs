@@ -1,6 +1,6 @@
 use {
     std::{
-        fmt,
+        fmt::{self, Write},
         hash::{Hash, Hasher},
         ops::{Deref, Range},
         rc::Rc,
@@ -117,6 +117,14 @@ impl ValueString {
         self.graphemes(true).count()
     }
 
+    /// Returns true if the two strings share the same underlying data
+    ///
+    /// The strings' bounds aren't taken into account, so this can be used to check that a
+    /// substring was produced without allocating.
+    pub fn shares_data_with(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.string, &other.string)
+    }
+
     /// Returns the `&str` within the ValueString's bounds
     #[inline]
     pub fn as_str(&self) -> &str {
@@ -175,9 +183,23 @@ impl fmt::Debug for ValueString {
 }
 
 impl fmt::Display for ValueString {
+    /// The alternate format (`{:#}`) produces a quoted string literal that can be parsed by Koto
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if f.alternate() {
-            write!(f, "'{}'", self.as_str())
+            f.write_char('\'')?;
+            for c in self.as_str().chars() {
+                match c {
+                    '\'' => f.write_str("\\'")?,
+                    '\\' => f.write_str("\\\\")?,
+                    '$' => f.write_str("\\$")?,
+                    '\n' => f.write_str("\\n")?,
+                    '\r' => f.write_str("\\r")?,
+                    '\t' => f.write_str("\\t")?,
+                    c if c.is_control() => write!(f, "\\u{{{:x}}}", c as u32)?,
+                    c => f.write_char(c)?,
+                }
+            }
+            f.write_char('\'')
         } else {
             write!(f, "{}", self.as_str())
         }
@@ -55,6 +55,8 @@ impl Default for ValueTuple {
 
 impl fmt::Display for ValueTuple {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Empty and single-element tuples include a trailing comma so that they can be
+        // distinguished from parenthesized expressions, e.g. `(,)` or `(1,)`.
         write!(f, "(")?;
         for (i, value) in self.iter().enumerate() {
             if i > 0 {
@@ -62,6 +64,9 @@ impl fmt::Display for ValueTuple {
             }
             write!(f, "{value:#}")?;
         }
+        if self.len() < 2 {
+            write!(f, ",")?;
+        }
         write!(f, ")")
     }
 }
@@ -85,3 +90,13 @@ impl From<Vec<Value>> for ValueTuple {
         }
     }
 }
+
+impl FromIterator<Value> for ValueTuple {
+    fn from_iter<T: IntoIterator<Item = Value>>(iter: T) -> Self {
+        // Collecting directly into an Rc avoids an intermediate allocation when the iterator's
+        // size is known in advance.
+        let data: Rc<[Value]> = iter.into_iter().collect();
+        let bounds = 0..data.len();
+        Self { data, bounds }
+    }
+}
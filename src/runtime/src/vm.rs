@@ -1,22 +1,23 @@
 use {
     crate::{
         core::CoreLib,
-        error::{type_error, RuntimeErrorType},
+        error::{type_error, FrameFunction, RuntimeErrorType},
         external::{self, ArgRegisters, ExternalFunction},
-        frame::Frame,
+        frame::{CallStackInfo, Frame},
+        interrupt::{InterruptHandle, InterruptibleIterator},
         meta_map::meta_id_to_key,
         runtime_error,
-        value::{self, FunctionInfo, RegisterSlice, SimpleFunctionInfo},
+        value::{self, resolve_index, FunctionInfo, RegisterSlice, SimpleFunctionInfo},
         value_iterator::{ValueIterator, ValueIteratorOutput},
         BinaryOp, DefaultStderr, DefaultStdin, DefaultStdout, IntRange, KotoFile, MetaKey,
         RuntimeError, RuntimeResult, UnaryOp, Value, ValueKey, ValueList, ValueMap, ValueNumber,
         ValueString, ValueTuple, ValueVec,
     },
     koto_bytecode::{Chunk, Instruction, InstructionReader, Loader, TypeId},
-    koto_parser::{ConstantIndex, MetaKeyId},
+    koto_parser::{ConstantIndex, MetaKeyId, Span},
     rustc_hash::FxHasher,
     std::{
-        cell::RefCell,
+        cell::{Cell, RefCell},
         collections::HashMap,
         fmt,
         hash::BuildHasherDefault,
@@ -53,7 +54,7 @@ pub enum ControlFlow {
 // Instructions will place their results in registers, there's no Ok type
 pub type InstructionResult = Result<(), RuntimeError>;
 
-fn setup_core_lib_and_prelude() -> (CoreLib, ValueMap) {
+fn setup_core_lib_and_prelude(settings: &VmSettings) -> (CoreLib, ValueMap) {
     let core_lib = CoreLib::default();
 
     let prelude = ValueMap::default();
@@ -62,7 +63,9 @@ fn setup_core_lib_and_prelude() -> (CoreLib, ValueMap) {
     prelude.add_map("koto", core_lib.koto.clone());
     prelude.add_map("list", core_lib.list.clone());
     prelude.add_map("map", core_lib.map.clone());
-    prelude.add_map("os", core_lib.os.clone());
+    if settings.enable_os_module {
+        prelude.add_map("os", core_lib.os.clone());
+    }
     prelude.add_map("number", core_lib.number.clone());
     prelude.add_map("num2", core_lib.num2.clone());
     prelude.add_map("num4", core_lib.num4.clone());
@@ -109,6 +112,14 @@ struct VmContext {
     loader: RefCell<Loader>,
     // The cached export maps of imported modules
     imported_modules: RefCell<ModuleCache>,
+    // Stacks released by dropped VMs, available for reuse by newly spawned VMs
+    spare_stacks: RefCell<SpareStacks>,
+    // The number of nested executions that are currently active, see VmSettings::max_nesting_depth
+    nesting_depth: Cell<usize>,
+    // The next ID to be returned by Vm::next_unique_id
+    next_unique_id: Cell<i64>,
+    // The handle that's used to interrupt execution, see Vm::interrupt_handle
+    interrupt: InterruptHandle,
 }
 
 impl Default for VmContext {
@@ -119,7 +130,7 @@ impl Default for VmContext {
 
 impl VmContext {
     fn with_settings(settings: VmSettings) -> Self {
-        let (core_lib, prelude) = setup_core_lib_and_prelude();
+        let (core_lib, prelude) = setup_core_lib_and_prelude(&settings);
 
         Self {
             settings,
@@ -127,16 +138,72 @@ impl VmContext {
             core_lib,
             loader: RefCell::new(Loader::default()),
             imported_modules: RefCell::new(ModuleCache::default()),
+            spare_stacks: RefCell::new(SpareStacks::default()),
+            nesting_depth: Cell::new(0),
+            next_unique_id: Cell::new(1),
+            interrupt: InterruptHandle::default(),
         }
     }
 }
 
+// A pool of value and call stacks taken from dropped VMs
+//
+// Iterator adaptors that call functions spawn their own shared VMs, so a pipeline with several
+// stages will spawn several short-lived VMs. Recycling the stacks of dropped VMs makes spawning
+// a VM cheap, with no allocations needed once the pool has warmed up.
+#[derive(Default)]
+struct SpareStacks {
+    value_stacks: Vec<Vec<Value>>,
+    call_stacks: Vec<Vec<Frame>>,
+}
+
+impl SpareStacks {
+    // Limits for the number of stacks of each kind that will be kept in the pool, and their
+    // capacity, avoiding holding on to the memory of stacks that grew during deep recursion.
+    const MAX_SPARE_STACKS: usize = 32;
+    const MAX_SPARE_STACK_CAPACITY: usize = 256;
+
+    fn take_value_stack(&mut self) -> Vec<Value> {
+        self.value_stacks
+            .pop()
+            .unwrap_or_else(|| Vec::with_capacity(8))
+    }
+
+    fn take_call_stack(&mut self) -> Vec<Frame> {
+        self.call_stacks.pop().unwrap_or_default()
+    }
+
+    // The stacks are expected to have been cleared before being released
+    fn release(&mut self, value_stack: Vec<Value>, call_stack: Vec<Frame>) {
+        if self.value_stacks.len() < Self::MAX_SPARE_STACKS
+            && Self::is_reusable_capacity(value_stack.capacity())
+        {
+            self.value_stacks.push(value_stack);
+        }
+        if self.call_stacks.len() < Self::MAX_SPARE_STACKS
+            && Self::is_reusable_capacity(call_stack.capacity())
+        {
+            self.call_stacks.push(call_stack);
+        }
+    }
+
+    fn is_reusable_capacity(capacity: usize) -> bool {
+        capacity > 0 && capacity <= Self::MAX_SPARE_STACK_CAPACITY
+    }
+}
+
 /// The trait used by the 'module imported' callback mechanism
 pub trait ModuleImportedCallback: Fn(&Path) {}
 
 // Implement the trait for any matching function
 impl<T> ModuleImportedCallback for T where T: Fn(&Path) {}
 
+/// The trait used by the error hook mechanism, see [VmSettings::error_hook]
+pub trait ErrorHook: Fn(&RuntimeError, &CallStackInfo) {}
+
+// Implement the trait for any matching function
+impl<T> ErrorHook for T where T: Fn(&RuntimeError, &CallStackInfo) {}
+
 /// The configurable settings that should be used by the Koto runtime
 pub struct VmSettings {
     /// Whether or not tests should be run when importing modules
@@ -152,6 +219,36 @@ pub struct VmSettings {
     pub stdout: Rc<dyn KotoFile>,
     /// The runtime's stderr
     pub stderr: Rc<dyn KotoFile>,
+    /// The maximum number of nested executions that are allowed in the runtime
+    ///
+    /// Calls between Koto functions don't consume native stack space, but each time the runtime
+    /// is re-entered from Rust (e.g. an external function calling [Vm::run_function], or an
+    /// overloaded operator being called via [Vm::run_binary_op]), execution continues on the
+    /// native stack. A runtime error is returned when the limit is reached, rather than the
+    /// native stack being allowed to overflow.
+    ///
+    /// Each level of nesting needs up to around 8KB of native stack in optimized builds, and up to
+    /// around 28KB in debug builds. The default limit of 64 allows scripts to run on a thread with
+    /// a 2MB stack (the default for spawned threads), and can be increased when running on a
+    /// thread with a larger stack.
+    ///
+    /// The depth is shared between VMs that have been spawned with [Vm::spawn_shared_vm].
+    pub max_nesting_depth: usize,
+    /// An optional hook that's called whenever an error is raised while executing Koto code
+    ///
+    /// The hook is called at the point where the error is raised, before the runtime looks for a
+    /// `catch` block, so errors that get caught by the script are also passed to the hook.
+    /// The hook is called once for each error, along with a view of the call stack of the VM
+    /// where the error was raised (VMs that have been spawned with [Vm::spawn_shared_vm] have
+    /// their own call stacks).
+    ///
+    /// The hook only has read-only access to the error, and isn't able to re-enter the runtime.
+    pub error_hook: Option<Box<dyn ErrorHook>>,
+    /// Whether or not the `os` core library module should be made available in the prelude
+    ///
+    /// The `os` module provides access to the system's clock and details about the host, which
+    /// might be undesirable when running untrusted scripts in a sandboxed environment.
+    pub enable_os_module: bool,
 }
 
 impl Default for VmSettings {
@@ -162,6 +259,9 @@ impl Default for VmSettings {
             stdin: Rc::new(DefaultStdin::default()),
             stdout: Rc::new(DefaultStdout::default()),
             stderr: Rc::new(DefaultStderr::default()),
+            max_nesting_depth: 64,
+            error_hook: None,
+            enable_os_module: true,
         }
     }
 }
@@ -176,6 +276,8 @@ pub struct Vm {
     call_stack: Vec<Frame>,
     // The ip that produced the most recently read instruction, used for debug and error traces
     instruction_ip: usize,
+    // Vecs that have previously been used by sequence builders, available for reuse
+    spare_sequences: Vec<Vec<Value>>,
 }
 
 impl Default for Vm {
@@ -184,6 +286,20 @@ impl Default for Vm {
     }
 }
 
+impl Drop for Vm {
+    fn drop(&mut self) {
+        // Make the VM's stacks available for reuse by other VMs that share the same context.
+        // The stacks are cleared first given that dropping their values might drop other VMs.
+        let mut value_stack = std::mem::take(&mut self.value_stack);
+        let mut call_stack = std::mem::take(&mut self.call_stack);
+        value_stack.clear();
+        call_stack.clear();
+        if let Ok(mut spare_stacks) = self.context.spare_stacks.try_borrow_mut() {
+            spare_stacks.release(value_stack, call_stack);
+        }
+    }
+}
+
 impl Vm {
     /// Initializes a Koto VM with the provided settings
     pub fn with_settings(settings: VmSettings) -> Self {
@@ -194,6 +310,7 @@ impl Vm {
             value_stack: Vec::with_capacity(32),
             call_stack: vec![],
             instruction_ip: 0,
+            spare_sequences: vec![],
         }
     }
 
@@ -203,15 +320,26 @@ impl Vm {
     ///   - An iterator spawns a shared VM that can be used to execute functors
     ///   - A generator function spawns a shared VM to yield incremental results
     ///   - Thrown errors spawn a shared VM to display an error from a custom error type
+    ///
+    /// Spawning is cheap, the stacks of dropped VMs are reused by newly spawned VMs.
     #[must_use]
     pub fn spawn_shared_vm(&self) -> Self {
+        let (value_stack, call_stack) = {
+            let mut spare_stacks = self.context.spare_stacks.borrow_mut();
+            (
+                spare_stacks.take_value_stack(),
+                spare_stacks.take_call_stack(),
+            )
+        };
+
         Self {
             exports: self.exports.clone(),
             context: self.context.clone(),
             reader: self.reader.clone(),
-            value_stack: Vec::with_capacity(8),
-            call_stack: vec![],
+            value_stack,
+            call_stack,
             instruction_ip: 0,
+            spare_sequences: vec![],
         }
     }
 
@@ -225,6 +353,23 @@ impl Vm {
         &self.context.prelude
     }
 
+    /// Returns a handle that can be used to stop the runtime from another thread
+    ///
+    /// The handle is shared with any VMs spawned from this VM. See [InterruptHandle].
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.context.interrupt.clone()
+    }
+
+    /// Returns an ID that's unique within the runtime
+    ///
+    /// IDs are shared between the VM and any VMs spawned from it,
+    /// so there will be no repeated IDs when generated in different modules or by iterator adaptors.
+    pub fn next_unique_id(&self) -> i64 {
+        let id = self.context.next_unique_id.get();
+        self.context.next_unique_id.set(id + 1);
+        id
+    }
+
     /// The active module's exports map
     ///
     /// Note that this is the exports map of the active module, so during execution the returned
@@ -278,6 +423,10 @@ impl Vm {
 
         // Run the chunk
         let result = self.execute_instructions();
+        if result.is_err() {
+            // Clean up the chunk's frame so that the VM can be used again
+            self.pop_frame(Value::Null)?;
+        }
         // Reset the value stack back to where it was at the start of the run
         self.truncate_registers(result_register);
         result
@@ -296,6 +445,17 @@ impl Vm {
     }
 
     /// Runs a function with some given arguments
+    ///
+    /// It's safe to call `run_function` re-entrantly, e.g. from an external function that was
+    /// called by the VM, and Koto functions called in this way can in turn call external functions
+    /// that call `run_function` again. The function runs in a register window at the top of the
+    /// VM's value stack, and the stack is reset to its previous size before returning, so the
+    /// caller's registers are left intact whether or not the call succeeds.
+    ///
+    /// Each nested call consumes native stack space, so nesting is limited by
+    /// [VmSettings::max_nesting_depth], with an error being returned when the limit is reached.
+    /// External functions should avoid holding borrows of values (e.g. from `ValueList::data_mut`)
+    /// while calling `run_function`, given that the called function might access the same values.
     pub fn run_function(&mut self, function: Value, args: CallArgs) -> RuntimeResult {
         self.call_and_run_function(None, function, args)
     }
@@ -376,8 +536,7 @@ impl Vm {
                         (1, Some(args))
                     }
                     _ => {
-                        let tuple_contents = Vec::from(args);
-                        self.value_stack.push(Value::Tuple(tuple_contents.into()));
+                        self.value_stack.push(Value::Tuple(args.into()));
                         (1, None)
                     }
                 }
@@ -447,6 +606,113 @@ impl Vm {
         result
     }
 
+    /// Compares two values for equality, recursing into Lists, Tuples, and Maps
+    ///
+    /// Values are compared in the same way as with `==`, with overloaded `@==` operators in meta
+    /// maps and external values being called when present.
+    ///
+    /// Lists and Maps that contain references to themselves are supported. When a List or Map
+    /// is encountered again while its contents are being compared, then it's compared by
+    /// identity rather than recursing.
+    pub fn values_equal(&mut self, a: &Value, b: &Value) -> Result<bool, RuntimeError> {
+        let mut in_progress = Vec::new();
+        self.values_equal_recursive(a, b, &mut in_progress)
+    }
+
+    fn values_equal_recursive(
+        &mut self,
+        a: &Value,
+        b: &Value,
+        // The Lists and Maps that are currently having their contents compared
+        in_progress: &mut Vec<Value>,
+    ) -> Result<bool, RuntimeError> {
+        use Value::*;
+
+        let is_in_progress = |value: &Value, in_progress: &[Value]| {
+            in_progress.iter().any(|other| match (value, other) {
+                (List(a), List(b)) => a.is_same_instance(b),
+                (Map(a), Map(b)) => a.is_same_instance(b),
+                _ => false,
+            })
+        };
+
+        match (a, b) {
+            (List(list_a), List(list_b)) => {
+                if is_in_progress(a, in_progress) || is_in_progress(b, in_progress) {
+                    return Ok(list_a.is_same_instance(list_b));
+                }
+
+                // The lists' data is cloned so that overloaded operators are able to modify them
+                let data_a = list_a.data().clone();
+                let data_b = list_b.data().clone();
+
+                in_progress.extend([a.clone(), b.clone()]);
+                let result = self.value_slices_equal(&data_a, &data_b, in_progress);
+                in_progress.truncate(in_progress.len() - 2);
+                result
+            }
+            (Tuple(tuple_a), Tuple(tuple_b)) => {
+                // Tuples are immutable and can't contain themselves, so they're not tracked
+                self.value_slices_equal(tuple_a, tuple_b, in_progress)
+            }
+            (Map(map_a), Map(map_b))
+                if !map_a.contains_meta_key(&MetaKey::BinaryOp(BinaryOp::Equal)) =>
+            {
+                if is_in_progress(a, in_progress) || is_in_progress(b, in_progress) {
+                    return Ok(map_a.is_same_instance(map_b));
+                }
+
+                if map_a.len() != map_b.len() {
+                    return Ok(false);
+                }
+
+                let data_a = map_a.data().clone();
+                let data_b = map_b.data().clone();
+
+                in_progress.extend([a.clone(), b.clone()]);
+                let mut result = Ok(true);
+                for (key, value_a) in data_a.iter() {
+                    let equal = match data_b.get(key) {
+                        Some(value_b) => self.values_equal_recursive(value_a, value_b, in_progress),
+                        None => Ok(false),
+                    };
+                    if !matches!(equal, Ok(true)) {
+                        result = equal;
+                        break;
+                    }
+                }
+                in_progress.truncate(in_progress.len() - 2);
+                result
+            }
+            _ => match self.run_binary_op(BinaryOp::Equal, a.clone(), b.clone())? {
+                Bool(result) => Ok(result),
+                other => runtime_error!(
+                    "Expected Bool from equality comparison, found '{}'",
+                    other.type_as_string()
+                ),
+            },
+        }
+    }
+
+    fn value_slices_equal(
+        &mut self,
+        a: &[Value],
+        b: &[Value],
+        in_progress: &mut Vec<Value>,
+    ) -> Result<bool, RuntimeError> {
+        if a.len() != b.len() {
+            return Ok(false);
+        }
+
+        for (value_a, value_b) in a.iter().zip(b.iter()) {
+            if !self.values_equal_recursive(value_a, value_b, in_progress)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
     /// Provides the result of running a binary operation on a pair of Values
     pub fn run_binary_op(&mut self, op: BinaryOp, lhs: Value, rhs: Value) -> RuntimeResult {
         let old_frame_count = self.call_stack.len();
@@ -547,6 +813,21 @@ impl Vm {
         Ok(result)
     }
 
+    // Makes an iterator for core library functions that consume the value's contents
+    //
+    // The iterator produces an error when the runtime is interrupted, allowing long-running
+    // functions (e.g. `to_list` on a large range) to be stopped.
+    pub(crate) fn make_interruptible_iterator(
+        &mut self,
+        value: Value,
+    ) -> Result<InterruptibleIterator, RuntimeError> {
+        let iterator = self.make_iterator(value)?;
+        Ok(InterruptibleIterator::new(
+            iterator,
+            self.interrupt_handle(),
+        ))
+    }
+
     /// Runs any tests that are contained in the map's @tests meta entry
     ///
     /// Any test failure will be returned as an error.
@@ -651,12 +932,38 @@ impl Vm {
     }
 
     fn execute_instructions(&mut self) -> RuntimeResult {
+        let depth = self.context.nesting_depth.get();
+        let max_depth = self.context.settings.max_nesting_depth;
+        if depth >= max_depth {
+            return runtime_error!("The maximum nesting depth ({max_depth}) has been exceeded");
+        }
+
+        self.context.nesting_depth.set(depth + 1);
+        let result = self.execute_instructions_at_depth();
+        self.context.nesting_depth.set(depth);
+
+        // Any interruption request has been handled once the top-level execution has ended,
+        // allowing the runtime to be used again.
+        if depth == 0 {
+            self.context.interrupt.reset();
+        }
+
+        result
+    }
+
+    fn execute_instructions_at_depth(&mut self) -> RuntimeResult {
         let mut result = Value::Null;
 
         self.instruction_ip = self.ip();
 
         while let Some(instruction) = self.reader.next() {
-            match self.execute_instruction(instruction) {
+            let instruction_result = if self.context.interrupt.is_stopped() {
+                Err(RuntimeError::interrupted())
+            } else {
+                self.execute_instruction(instruction)
+            };
+
+            match instruction_result {
                 Ok(ControlFlow::Continue) => {}
                 Ok(ControlFlow::Return(value)) => {
                     result = value;
@@ -669,10 +976,17 @@ impl Vm {
                 Err(mut error) => {
                     let mut recover_register_and_ip = None;
 
-                    error.extend_trace(self.chunk(), self.instruction_ip);
+                    error.extend_trace(self.chunk(), self.instruction_ip, self.frame_function());
+                    self.call_error_hook(&mut error);
+
+                    // Interruptions can't be caught, so the call stack is unwound until an
+                    // execution barrier is reached.
+                    let catchable = !error.is_interrupted();
 
                     while let Some(frame) = self.call_stack.last() {
-                        if let Some((error_register, catch_ip)) = frame.catch_stack.last() {
+                        if let (true, Some((error_register, catch_ip))) =
+                            (catchable, frame.catch_stack.last())
+                        {
                             recover_register_and_ip = Some((*error_register, *catch_ip));
                             break;
                         } else {
@@ -683,17 +997,17 @@ impl Vm {
                             self.pop_frame(Value::Null)?;
 
                             if !self.call_stack.is_empty() {
-                                error.extend_trace(self.chunk(), self.instruction_ip);
+                                error.extend_trace(
+                                    self.chunk(),
+                                    self.instruction_ip,
+                                    self.frame_function(),
+                                );
                             }
                         }
                     }
 
                     if let Some((register, ip)) = recover_register_and_ip {
-                        let catch_value = match error.error {
-                            RuntimeErrorType::KotoError { thrown_value, .. } => thrown_value,
-                            _ => Value::Str(error.to_string().into()),
-                        };
-                        self.set_register(register, catch_value);
+                        self.set_register(register, error.to_catch_value());
                         self.set_ip(ip);
                     } else {
                         return Err(error);
@@ -707,6 +1021,48 @@ impl Vm {
         Ok(result)
     }
 
+    // Describes the function that's being executed in the current frame, used in error traces
+    //
+    // Functions that were called from a script are named using the source of the call's callee
+    // expression (e.g. `foo` or `x.foo`). Functions that were called externally (e.g. by an
+    // iterator adaptor) don't have a known name, and are reported as anonymous.
+    fn frame_function(&self) -> FrameFunction {
+        const MAX_NAME_CHARS: usize = 40;
+
+        let frame = self.frame();
+
+        if frame.start_ip == 0 {
+            return FrameFunction::Module;
+        }
+
+        if !frame.execution_barrier {
+            if let Some(caller) = self.call_stack.iter().rev().nth(1) {
+                let debug_info = &caller.chunk.debug_info;
+                let name = debug_info
+                    .get_call_spans(caller.return_instruction_ip)
+                    .and_then(|spans| spans.function)
+                    .and_then(|span| debug_info.source_text(span));
+                if let Some(name) = name {
+                    return FrameFunction::Named(truncated(name, MAX_NAME_CHARS));
+                }
+            }
+        }
+
+        FrameFunction::Anonymous
+    }
+
+    fn call_error_hook(&self, error: &mut RuntimeError) {
+        if let Some(hook) = &self.context.settings.error_hook {
+            if !error.hook_called {
+                error.hook_called = true;
+                hook(
+                    error,
+                    &CallStackInfo::new(&self.call_stack, self.instruction_ip),
+                );
+            }
+        }
+    }
+
     fn execute_instruction(
         &mut self,
         instruction: Instruction,
@@ -778,7 +1134,8 @@ impl Vm {
                 register,
                 size_hint,
             } => {
-                self.set_register(register, SequenceBuilder(Vec::with_capacity(size_hint)));
+                let sequence = self.take_spare_sequence(size_hint);
+                self.set_register(register, SequenceBuilder(sequence));
                 Ok(())
             }
             Instruction::SequencePush { sequence, value } => {
@@ -890,28 +1247,14 @@ impl Vm {
                 function,
                 frame_base,
                 arg_count,
-            } => self.call_callable(
-                result,
-                self.clone_register(function),
-                frame_base,
-                arg_count,
-                None,
-                None,
-            ),
+            } => self.run_call(result, function, frame_base, arg_count, None),
             Instruction::CallInstance {
                 result,
                 function,
                 frame_base,
                 arg_count,
                 instance,
-            } => self.call_callable(
-                result,
-                self.clone_register(function),
-                frame_base,
-                arg_count,
-                Some(instance),
-                None,
-            ),
+            } => self.run_call(result, function, frame_base, arg_count, Some(instance)),
             Instruction::Return { register } => {
                 if let Some(return_value) = self.pop_frame(self.clone_register(register))? {
                     // If pop_frame returns a new return_value, then execution should stop.
@@ -1046,11 +1389,21 @@ impl Vm {
                 Ok(())
             }
             Instruction::Debug { register, constant } => self.run_debug(register, constant),
-            Instruction::CheckType { register, type_id } => self.run_check_type(register, type_id),
-            Instruction::CheckSizeEqual { register, size } => {
-                self.run_check_size_equal(register, size)
-            }
-            Instruction::CheckSizeMin { register, size } => self.run_check_size_min(register, size),
+            Instruction::CheckType {
+                register,
+                type_id,
+                arg_index,
+            } => self.run_check_type(register, type_id, arg_index),
+            Instruction::CheckSizeEqual {
+                register,
+                size,
+                arg_index,
+            } => self.run_check_size_equal(register, size, arg_index),
+            Instruction::CheckSizeMin {
+                register,
+                size,
+                arg_index,
+            } => self.run_check_size_min(register, size, arg_index),
         }?;
 
         Ok(control_flow)
@@ -1104,53 +1457,31 @@ impl Vm {
         end_register: Option<u8>,
         inclusive: bool,
     ) -> InstructionResult {
-        use Value::{IndexRange, Number, Range};
+        use Value::{IndexRange, Iterator, Number, Range, Str};
 
         let start = start_register.map(|register| self.get_register(register));
         let end = end_register.map(|register| self.get_register(register));
 
         let range = match (start, end) {
-            (Some(Number(start)), Some(Number(end))) => {
-                let istart = isize::from(start);
-                let iend = isize::from(end);
-
-                let (start, end) = if inclusive {
-                    if istart <= iend {
-                        (istart, iend + 1)
-                    } else {
-                        (istart, iend - 1)
-                    }
-                } else {
-                    (istart, iend)
-                };
-
-                Range(IntRange { start, end })
-            }
+            (Some(Number(start)), Some(Number(end))) => Range(IntRange {
+                start: isize::from(start),
+                end: isize::from(end),
+                inclusive,
+            }),
             (None, Some(Number(end))) => {
-                if *end < 0.0 {
-                    return runtime_error!("RangeTo: negative numbers not allowed, found '{end}'");
-                }
-                let end = if inclusive {
-                    usize::from(end) + 1
-                } else {
-                    usize::from(end)
+                let end = isize::from(end);
+                let end = match (inclusive, end) {
+                    // `..=-1` includes the last element, so the range has no end
+                    (true, -1) => None,
+                    (true, _) => Some(end + 1),
+                    (false, _) => Some(end),
                 };
-                IndexRange(value::IndexRange {
-                    start: 0,
-                    end: Some(end),
-                })
-            }
-            (Some(Number(start)), None) => {
-                if *start < 0.0 {
-                    return runtime_error!(
-                        "RangeFrom: negative numbers not allowed, found '{start}'"
-                    );
-                }
-                IndexRange(value::IndexRange {
-                    start: usize::from(start),
-                    end: None,
-                })
+                IndexRange(value::IndexRange { start: 0, end })
             }
+            (Some(Number(start)), None) => IndexRange(value::IndexRange {
+                start: isize::from(start),
+                end: None,
+            }),
             (None, None) => {
                 // RangeFull
                 IndexRange(value::IndexRange {
@@ -1158,9 +1489,31 @@ impl Vm {
                     end: None,
                 })
             }
+            (Some(Str(start)), Some(Str(end))) => {
+                let start = match single_char(start) {
+                    Some(c) => c,
+                    None => {
+                        return runtime_error!(
+                            "Expected a single character for range start, found '{start}'"
+                        )
+                    }
+                };
+                let end = match single_char(end) {
+                    Some(c) => c,
+                    None => {
+                        return runtime_error!(
+                            "Expected a single character for range end, found '{end}'"
+                        )
+                    }
+                };
+                Iterator(ValueIterator::with_char_range(start, end, inclusive))
+            }
             (Some(Number(_)), Some(unexpected)) | (None, Some(unexpected)) => {
                 return type_error("Number for range end", unexpected);
             }
+            (Some(Str(_)), Some(unexpected)) => {
+                return type_error("String for range end", unexpected);
+            }
             (Some(unexpected), _) => {
                 return type_error("Number for range start", unexpected);
             }
@@ -1237,7 +1590,7 @@ impl Vm {
                     self.set_register(register + 1, first);
                     self.set_register(register + 2, second);
                 } else {
-                    self.set_register(register, Tuple(vec![first, second].into()));
+                    self.set_register(register, Tuple([first, second].into_iter().collect()));
                 }
             }
             (Some(ValueIteratorOutput::Error(error)), _) => {
@@ -1289,7 +1642,9 @@ impl Vm {
                     Null
                 }
             }
-            unexpected => return type_error("indexable value", unexpected),
+            unexpected => {
+                return type_error("a List, Tuple, or other container to unpack", unexpected)
+            }
         };
 
         self.set_register(register, result);
@@ -2314,29 +2669,26 @@ impl Vm {
                 let list_len = list.len();
                 match index_value {
                     Number(index) => {
-                        let u_index = usize::from(index);
-                        if index >= 0.0 && u_index < list_len {
-                            list.data_mut()[u_index] = value;
+                        let resolved = resolve_index(isize::from(index), list_len);
+                        if resolved >= 0 && (resolved as usize) < list_len {
+                            list.data_mut()[resolved as usize] = value;
+                        } else if index < 0.0 {
+                            return runtime_error!(
+                                "Index '{index}' (resolved to '{resolved}') not in List"
+                            );
                         } else {
                             return runtime_error!("Index '{index}' not in List");
                         }
                     }
-                    Range(IntRange { start, end }) => {
+                    Range(range) => {
+                        let (start, end) = resolve_int_range(&range, list_len);
                         let (ustart, uend) = self.validate_int_range(start, end, Some(list_len))?;
-
-                        let mut list_data = list.data_mut();
-                        for i in ustart..uend {
-                            list_data[i] = value.clone();
-                        }
+                        assign_to_list_range(&list, ustart..uend, value);
                     }
-                    IndexRange(value::IndexRange { start, end }) => {
-                        let end = end.unwrap_or(list_len);
-                        self.validate_index_range(start, end, list_len)?;
-
-                        let mut list_data = list.data_mut();
-                        for i in start..end {
-                            list_data[i] = value.clone();
-                        }
+                    IndexRange(range) => {
+                        let (start, end) = range.resolve(list_len);
+                        let (ustart, uend) = self.validate_int_range(start, end, Some(list_len))?;
+                        assign_to_list_range(&list, ustart..uend, value);
                     }
                     unexpected => return type_error("index", &unexpected),
                 }
@@ -2347,18 +2699,29 @@ impl Vm {
         Ok(())
     }
 
+    // Validates an index for a value of the given size, resolving negative indices
+    //
+    // The size is only needed for negative indices, with None allowing non-negative indices to be
+    // validated by the caller when the size is expensive to calculate (e.g. for strings).
     fn validate_index(&self, n: ValueNumber, size: Option<usize>) -> Result<usize, RuntimeError> {
-        let index = usize::from(n);
-
-        if n < 0.0 {
-            return runtime_error!("Negative indices aren't allowed ('{n}')");
-        } else if let Some(size) = size {
-            if index >= size {
-                return runtime_error!("Index out of bounds - index: {n}, size: {size}");
+        let index = isize::from(n);
+
+        match size {
+            Some(size) => {
+                let resolved = resolve_index(index, size);
+                if resolved >= 0 && (resolved as usize) < size {
+                    Ok(resolved as usize)
+                } else if n < 0.0 {
+                    runtime_error!(
+                        "Index out of bounds - index: {n} (resolved to {resolved}), size: {size}"
+                    )
+                } else {
+                    runtime_error!("Index out of bounds - index: {n}, size: {size}")
+                }
             }
+            None if n < 0.0 => runtime_error!("Index out of bounds - index: {n}"),
+            None => Ok(index as usize),
         }
-
-        Ok(index)
     }
 
     fn validate_int_range(
@@ -2371,9 +2734,12 @@ impl Vm {
         let uend = end as usize;
 
         if start < 0 || end < 0 {
-            return runtime_error!(
-                "Indexing with negative indices isn't supported, start: {start}, end: {end}"
-            );
+            return match size {
+                Some(size) => {
+                    runtime_error!("Index out of bounds, start: {start}, end: {end}, size: {size}")
+                }
+                None => runtime_error!("Index out of bounds, start: {start}, end: {end}"),
+            };
         } else if start > end {
             return runtime_error!(
                 "Indexing with a descending range isn't supported, start: {start}, end: {end}"
@@ -2389,18 +2755,6 @@ impl Vm {
         Ok((ustart, uend))
     }
 
-    fn validate_index_range(&self, start: usize, end: usize, size: usize) -> InstructionResult {
-        if start > end {
-            runtime_error!(
-                "Indexing with a descending range isn't supported, start: {start}, end: {end}"
-            )
-        } else if start > size || end > size {
-            runtime_error!("Index out of bounds, start: {start}, end: {end}, size: {size}")
-        } else {
-            Ok(())
-        }
-    }
-
     fn run_index(
         &mut self,
         result_register: u8,
@@ -2417,16 +2771,17 @@ impl Vm {
                 let index = self.validate_index(n, Some(l.len()))?;
                 self.set_register(result_register, l.data()[index].clone());
             }
-            (List(l), Range(IntRange { start, end })) => {
+            (List(l), Range(range)) => {
+                let (start, end) = resolve_int_range(&range, l.len());
                 let (start, end) = self.validate_int_range(start, end, Some(l.len()))?;
                 self.set_register(
                     result_register,
                     List(ValueList::from_slice(&l.data()[start..end])),
                 )
             }
-            (List(l), IndexRange(value::IndexRange { start, end })) => {
-                let end = end.unwrap_or_else(|| l.len());
-                self.validate_index_range(start, end, l.len())?;
+            (List(l), IndexRange(range)) => {
+                let (start, end) = range.resolve(l.len());
+                let (start, end) = self.validate_int_range(start, end, Some(l.len()))?;
                 self.set_register(
                     result_register,
                     List(ValueList::from_slice(&l.data()[start..end])),
@@ -2436,22 +2791,28 @@ impl Vm {
                 let index = self.validate_index(n, Some(t.len()))?;
                 self.set_register(result_register, t[index].clone());
             }
-            (Tuple(t), Range(IntRange { start, end })) => {
+            (Tuple(t), Range(range)) => {
+                let (start, end) = resolve_int_range(&range, t.len());
                 let (start, end) = self.validate_int_range(start, end, Some(t.len()))?;
                 // Safety: the tuple indices were validated in validate_int_range
                 let result = t.make_sub_tuple(start..end).unwrap();
                 self.set_register(result_register, Tuple(result))
             }
-            (Tuple(t), IndexRange(value::IndexRange { start, end })) => {
-                let size = t.len();
-                let end = end.unwrap_or(size);
-                self.validate_index_range(start, end, size)?;
-                // Safety: the tuple indices were validated in validate_index_range
+            (Tuple(t), IndexRange(range)) => {
+                let (start, end) = range.resolve(t.len());
+                let (start, end) = self.validate_int_range(start, end, Some(t.len()))?;
+                // Safety: the tuple indices were validated in validate_int_range
                 let result = t.make_sub_tuple(start..end).unwrap();
                 self.set_register(result_register, Tuple(result))
             }
             (Str(s), Number(n)) => {
-                let index = self.validate_index(n, None)?;
+                // Counting graphemes is only necessary when resolving negative indices
+                let size = if n < 0.0 {
+                    Some(s.grapheme_count())
+                } else {
+                    None
+                };
+                let index = self.validate_index(n, size)?;
 
                 if let Some(result) = s.with_grapheme_indices(index, Some(index + 1)) {
                     self.set_register(result_register, Str(result));
@@ -2462,8 +2823,16 @@ impl Vm {
                     );
                 }
             }
-            (Str(s), Range(IntRange { start, end })) => {
-                let (start, end) = self.validate_int_range(start, end, None)?;
+            (Str(s), Range(range)) => {
+                let (start, end, size) = if range.start < 0 || range.end < 0 {
+                    let size = s.grapheme_count();
+                    let (start, end) = resolve_int_range(&range, size);
+                    (start, end, Some(size))
+                } else {
+                    let IntRange { start, end, .. } = range.to_exclusive();
+                    (start, end, None)
+                };
+                let (start, end) = self.validate_int_range(start, end, size)?;
 
                 if let Some(result) = s.with_grapheme_indices(start, Some(end)) {
                     self.set_register(result_register, Str(result));
@@ -2474,7 +2843,19 @@ impl Vm {
                     );
                 }
             }
+            (Str(s), IndexRange(range))
+                if range.start < 0 || matches!(range.end, Some(end) if end < 0) =>
+            {
+                let size = s.grapheme_count();
+                let (start, end) = range.resolve(size);
+                let (start, end) = self.validate_int_range(start, end, Some(size))?;
+                // Safety: the indices were validated in validate_int_range
+                let result = s.with_grapheme_indices(start, Some(end)).unwrap();
+                self.set_register(result_register, Str(result));
+            }
             (Str(s), IndexRange(value::IndexRange { start, end })) => {
+                let start = start as usize;
+                let end = end.map(|end| end as usize);
                 if let Some(end_unwrapped) = end {
                     self.validate_int_range(start as isize, end_unwrapped as isize, None)?;
                 }
@@ -2494,16 +2875,16 @@ impl Vm {
                 }
             }
             (Num2(n), Number(i)) => {
-                let i = usize::from(i);
+                let i = resolve_index(isize::from(i), 2);
                 match i {
-                    0 | 1 => self.set_register(result_register, Number(n[i].into())),
+                    0 | 1 => self.set_register(result_register, Number(n[i as usize].into())),
                     other => return runtime_error!("Index out of bounds for Num2, {other}"),
                 }
             }
             (Num4(n), Number(i)) => {
-                let i = usize::from(i);
+                let i = resolve_index(isize::from(i), 4);
                 match i {
-                    0 | 1 | 2 | 3 => self.set_register(result_register, Number(n[i].into())),
+                    0..=3 => self.set_register(result_register, Number(n[i as usize].into())),
                     other => return runtime_error!("Index out of bounds for Num4, {other}"),
                 }
             }
@@ -2751,6 +3132,75 @@ impl Vm {
         Ok(result)
     }
 
+    // Runs a Call or CallInstance instruction
+    fn run_call(
+        &mut self,
+        result_register: u8,
+        function_register: u8,
+        frame_base: u8,
+        call_arg_count: u8,
+        instance_register: Option<u8>,
+    ) -> InstructionResult {
+        match self.clone_register(function_register) {
+            // External functions get access to the call instruction's ip,
+            // allowing the call's argument spans to be used in errors.
+            Value::ExternalFunction(external_function) => self.call_external_function(
+                result_register,
+                external_function,
+                frame_base,
+                call_arg_count,
+                instance_register,
+                Some(self.instruction_ip),
+            ),
+            function @ (Value::SimpleFunction(_) | Value::Function(_) | Value::Generator(_)) => {
+                self.call_callable(
+                    result_register,
+                    function,
+                    frame_base,
+                    call_arg_count,
+                    instance_register,
+                    None,
+                )
+            }
+            unexpected => self.not_callable_error(unexpected),
+        }
+    }
+
+    // Produces an error for an attempt to call a value that isn't callable
+    //
+    // If debug info is available, then the error includes the source of the callee expression,
+    // e.g. "`config.handler` is Null and cannot be called".
+    fn not_callable_error(&mut self, value: Value) -> InstructionResult {
+        // The maximum number of characters to include when displaying the value
+        const MAX_DISPLAY_CHARS: usize = 40;
+
+        let debug_info = &self.reader.chunk.debug_info;
+        let expression = debug_info
+            .get_call_spans(self.instruction_ip)
+            .and_then(|spans| spans.function)
+            .and_then(|span| debug_info.source_text(span))
+            .map(|expression| expression.to_string());
+
+        let type_string = value.type_as_string();
+        let description = match &value {
+            Value::Null => type_string.to_string(),
+            Value::Str(s) => format!("{type_string} ('{}')", truncated(s, MAX_DISPLAY_CHARS)),
+            _ => match self.run_unary_op(UnaryOp::Display, value.clone()) {
+                Ok(Value::Str(s)) => {
+                    format!("{type_string} ({})", truncated(&s, MAX_DISPLAY_CHARS))
+                }
+                _ => type_string.to_string(),
+            },
+        };
+
+        match expression {
+            Some(expression) => {
+                runtime_error!("`{expression}` is {description} and cannot be called")
+            }
+            None => runtime_error!("{description} cannot be called"),
+        }
+    }
+
     fn call_external_function(
         &mut self,
         result_register: u8,
@@ -2758,6 +3208,7 @@ impl Vm {
         frame_base: u8,
         call_arg_count: u8,
         instance_register: Option<u8>,
+        call_ip: Option<usize>,
     ) -> InstructionResult {
         let function = external_function.function.as_ref();
 
@@ -2778,22 +3229,30 @@ impl Vm {
             frame_base + 1
         };
 
-        let result = (*function)(
-            self,
-            &ArgRegisters {
-                register: adjusted_frame_base,
-                count: call_arg_count,
-            },
-        );
+        let args = ArgRegisters {
+            register: adjusted_frame_base,
+            count: call_arg_count,
+            call_ip,
+            instance_is_arg: external_function.is_instance_function,
+        };
 
-        match result {
+        match function.call(self, &args) {
             Ok(value) => {
                 self.set_register(result_register, value);
                 // External function calls don't use the push/pop frame mechanism,
                 // so drop the function args here now that the call has been completed.
                 self.truncate_registers(frame_base);
             }
-            Err(error) => return Err(error),
+            Err(mut error) => {
+                // The arg index is always taken here so that it doesn't get resolved against an
+                // unrelated call further up the stack.
+                if let Some(index) = error.arg_index.take() {
+                    if error.arg_span.is_none() {
+                        error.arg_span = self.arg_span(&args, index);
+                    }
+                }
+                return Err(error);
+            }
         }
 
         Ok(())
@@ -3044,6 +3503,7 @@ impl Vm {
                 frame_base,
                 call_arg_count,
                 instance_register,
+                None,
             ),
             unexpected => type_error("callable function", &unexpected),
         }
@@ -3080,41 +3540,65 @@ impl Vm {
             .write_line(&format!("{prefix}{expression_string}: {value_string}",))
     }
 
-    fn run_check_type(&self, register: u8, type_id: TypeId) -> InstructionResult {
+    fn run_check_type(&self, register: u8, type_id: TypeId, arg_index: u8) -> InstructionResult {
         let value = self.get_register(register);
-        match type_id {
-            TypeId::List => {
-                if !matches!(value, Value::List(_)) {
-                    return type_error("List", value);
-                }
-            }
-            TypeId::Tuple => {
-                if !matches!(value, Value::Tuple(_) | Value::TemporaryTuple(_)) {
-                    return type_error("Tuple", value);
-                }
+        let expected = match type_id {
+            TypeId::List if matches!(value, Value::List(_)) => return Ok(()),
+            TypeId::Tuple if matches!(value, Value::Tuple(_) | Value::TemporaryTuple(_)) => {
+                return Ok(())
             }
-        }
-        Ok(())
+            TypeId::List => "List",
+            TypeId::Tuple => "Tuple",
+        };
+
+        runtime_error!(
+            "Expected a {expected} for argument {}, but found {}.",
+            arg_index + 1,
+            value.type_as_string()
+        )
     }
 
-    fn run_check_size_equal(&self, register: u8, expected_size: usize) -> InstructionResult {
-        let value_size = self.get_register(register).size();
+    fn run_check_size_equal(
+        &self,
+        register: u8,
+        expected_size: usize,
+        arg_index: u8,
+    ) -> InstructionResult {
+        let value = self.get_register(register);
+        let value_size = value.size();
 
         if value_size == expected_size {
             Ok(())
         } else {
-            runtime_error!("Container has a size of '{value_size}', expected '{expected_size}'")
+            let value_type = value.type_as_string();
+            runtime_error!(
+                "Expected a {value_type} with {} for argument {}, but found a {value_type} with {}.",
+                element_count(expected_size),
+                arg_index + 1,
+                element_count(value_size),
+            )
         }
     }
 
-    fn run_check_size_min(&self, register: u8, expected_size: usize) -> InstructionResult {
-        let value_size = self.get_register(register).size();
+    fn run_check_size_min(
+        &self,
+        register: u8,
+        expected_size: usize,
+        arg_index: u8,
+    ) -> InstructionResult {
+        let value = self.get_register(register);
+        let value_size = value.size();
 
         if value_size >= expected_size {
             Ok(())
         } else {
+            let value_type = value.type_as_string();
             runtime_error!(
-                "Container has a size of '{value_size}', expected a minimum of '{expected_size}'"
+                "Expected a {value_type} with at least {} for argument {}, \
+                 but found a {value_type} with {}.",
+                element_count(expected_size),
+                arg_index + 1,
+                element_count(value_size),
             )
         }
     }
@@ -3142,9 +3626,17 @@ impl Vm {
     fn run_sequence_to_list(&mut self, register: u8) -> InstructionResult {
         // Move the sequence builder out of its register to avoid cloning the Vec
         match self.remove_register(register) {
-            Value::SequenceBuilder(result) => {
-                let list = ValueList::with_data(ValueVec::from_vec(result));
-                self.set_register(register, Value::List(list));
+            Value::SequenceBuilder(mut result) => {
+                let list_data = if result.len() <= ValueVec::new().inline_size() {
+                    // The list's values will be stored inline,
+                    // so the sequence's Vec can be made available for reuse.
+                    let list_data = result.drain(..).collect();
+                    self.release_spare_sequence(result);
+                    list_data
+                } else {
+                    ValueVec::from_vec(result)
+                };
+                self.set_register(register, Value::List(ValueList::with_data(list_data)));
                 Ok(())
             }
             other => type_error("SequenceBuilder", &other),
@@ -3154,14 +3646,44 @@ impl Vm {
     fn run_sequence_to_tuple(&mut self, register: u8) -> InstructionResult {
         // Move the sequence builder out of its register to avoid cloning the Vec
         match self.remove_register(register) {
-            Value::SequenceBuilder(result) => {
-                self.set_register(register, Value::Tuple(ValueTuple::from(result)));
+            Value::SequenceBuilder(mut result) => {
+                // The tuple needs its own allocation for its values,
+                // so the sequence's Vec can be made available for reuse.
+                let tuple = result.drain(..).collect();
+                self.release_spare_sequence(result);
+                self.set_register(register, Value::Tuple(tuple));
                 Ok(())
             }
             other => type_error("SequenceBuilder", &other),
         }
     }
 
+    // Provides a Vec for a sequence builder, reusing a spare Vec if one is available
+    fn take_spare_sequence(&mut self, size_hint: usize) -> Vec<Value> {
+        match self.spare_sequences.pop() {
+            Some(mut sequence) => {
+                sequence.reserve(size_hint);
+                sequence
+            }
+            None => Vec::with_capacity(size_hint),
+        }
+    }
+
+    // Makes an empty Vec that was used by a sequence builder available for reuse
+    fn release_spare_sequence(&mut self, sequence: Vec<Value>) {
+        // Limits for the number of spare sequences, and their capacity,
+        // avoiding holding on to large amounts of unused memory.
+        const MAX_SPARE_SEQUENCES: usize = 8;
+        const MAX_SPARE_SEQUENCE_CAPACITY: usize = 64;
+
+        debug_assert!(sequence.is_empty());
+        if self.spare_sequences.len() < MAX_SPARE_SEQUENCES
+            && sequence.capacity() <= MAX_SPARE_SEQUENCE_CAPACITY
+        {
+            self.spare_sequences.push(sequence);
+        }
+    }
+
     fn run_string_push(&mut self, register: u8, value_register: u8) -> InstructionResult {
         let value = self.clone_register(value_register);
         let display_result = self.run_unary_op(UnaryOp::Display, value)?;
@@ -3236,7 +3758,7 @@ impl Vm {
         let new_frame_base = previous_frame_base + frame_base as usize;
 
         self.call_stack
-            .push(Frame::new(chunk.clone(), new_frame_base));
+            .push(Frame::new(chunk.clone(), ip, new_frame_base));
         self.set_chunk_and_ip(chunk, ip);
     }
 
@@ -3348,6 +3870,25 @@ impl Vm {
         self.register_slice(args.register, args.count)
     }
 
+    /// Returns the source span of one of the arguments passed to an external function
+    ///
+    /// The index refers to the argument's position in the slice returned by [Vm::get_args].
+    ///
+    /// `None` is returned if the function wasn't called directly from a script,
+    /// or if the argument's span isn't available.
+    pub fn arg_span(&self, args: &ArgRegisters, index: usize) -> Option<Span> {
+        let spans = self.reader.chunk.debug_info.get_call_spans(args.call_ip?)?;
+
+        if args.instance_is_arg {
+            match index {
+                0 => spans.instance,
+                _ => spans.args.get(index - 1).copied(),
+            }
+        } else {
+            spans.args.get(index).copied()
+        }
+    }
+
     fn get_constant_str(&self, constant_index: ConstantIndex) -> &str {
         self.reader.chunk.constants.get_str(constant_index)
     }
@@ -3384,6 +3925,71 @@ fn signed_index_to_unsigned(index: i8, size: usize) -> usize {
     }
 }
 
+// Resolves a range's bounds against the size of an indexed value, returning exclusive bounds
+//
+// Negative bounds are resolved before the range's inclusivity is taken into account,
+// so that e.g. `x[1..=-1]` includes the last element of `x`.
+fn resolve_int_range(range: &IntRange, size: usize) -> (isize, isize) {
+    let resolved = IntRange {
+        start: resolve_index(range.start, size),
+        end: resolve_index(range.end, size),
+        inclusive: range.inclusive,
+    }
+    .to_exclusive();
+    (resolved.start, resolved.end)
+}
+
+// Assigns a value to a range of a list's entries
+//
+// Lists and Tuples replace the entries in the range with their own entries, with the list growing
+// or shrinking to fit. Any other value is assigned to each of the entries in the range.
+fn assign_to_list_range(list: &ValueList, range: std::ops::Range<usize>, value: Value) {
+    // The new entries are copied before the list is modified, given that the value might be the
+    // list that's being assigned to.
+    let new_entries: ValueVec = match &value {
+        Value::List(l) => l.data().clone(),
+        Value::Tuple(t) => t.iter().cloned().collect(),
+        _ => {
+            let mut list_data = list.data_mut();
+            for i in range {
+                list_data[i] = value.clone();
+            }
+            return;
+        }
+    };
+
+    let mut list_data = list.data_mut();
+    let start = range.start;
+    list_data.drain(range);
+    list_data.insert_many(start, new_entries);
+}
+
+// Returns the string, truncated with an ellipsis if it's longer than the given number of chars
+fn truncated(s: &str, max_chars: usize) -> String {
+    match s.char_indices().nth(max_chars) {
+        Some((byte_index, _)) => format!("{}...", &s[..byte_index]),
+        None => s.to_string(),
+    }
+}
+
+// Returns the string's char if it contains a single Unicode scalar value, used for char ranges
+fn single_char(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+// Returns a description of a number of elements, e.g. '1 element' or '3 elements'
+fn element_count(count: usize) -> String {
+    if count == 1 {
+        "1 element".into()
+    } else {
+        format!("{count} elements")
+    }
+}
+
 // Used when calling iterator.copy on a generator
 //
 // The idea here is to clone the VM, and then scan through the value stack to make copies of
@@ -0,0 +1,116 @@
+mod runtime_test_utils;
+
+mod data_display {
+    use {koto_bytecode::Loader, koto_runtime::prelude::*};
+
+    // A simple LCG, producing a deterministic stream of pseudo-random values
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            self.0 >> 33
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+    }
+
+    fn random_string(rng: &mut Rng) -> String {
+        const CHARS: &[char] = &[
+            'a', 'b', 'x', 'Z', '_', '0', '9', ' ', '\'', '"', '\\', '$', '{', '}', ':', ',', '\n',
+            '\t', '\r', '\u{1}', 'é', '🐢',
+        ];
+        const WORDS: &[&str] = &["if", "true", "null", "self", "and", "foo", "x1"];
+
+        if rng.below(4) == 0 {
+            WORDS[rng.below(WORDS.len() as u64) as usize].to_string()
+        } else {
+            (0..rng.below(8))
+                .map(|_| CHARS[rng.below(CHARS.len() as u64) as usize])
+                .collect()
+        }
+    }
+
+    fn random_value(rng: &mut Rng, depth: usize) -> Value {
+        let max_kind = if depth < 3 { 9 } else { 6 };
+        match rng.below(max_kind) {
+            0 => Value::Null,
+            1 => Value::Bool(rng.below(2) == 0),
+            2 => Value::Number((rng.below(2000) as i64 - 1000).into()),
+            3 => Value::Number(((rng.below(2000) as f64 - 1000.0) / 8.0).into()),
+            4 => Value::Number((rng.next() as f64 / 1e7).into()),
+            5 => Value::Str(random_string(rng).into()),
+            6 => {
+                let values = (0..rng.below(4))
+                    .map(|_| random_value(rng, depth + 1))
+                    .collect::<Vec<_>>();
+                Value::List(ValueList::from_slice(&values))
+            }
+            7 => Value::Tuple(
+                (0..rng.below(4))
+                    .map(|_| random_value(rng, depth + 1))
+                    .collect(),
+            ),
+            _ => {
+                let map = ValueMap::new();
+                for _ in 0..rng.below(4) {
+                    map.add_value(&random_string(rng), random_value(rng, depth + 1));
+                }
+                Value::Map(map)
+            }
+        }
+    }
+
+    fn check_round_trip(vm: &mut Vm, value: Value) {
+        let displayed = format!("{value:#}");
+
+        let mut loader = Loader::default();
+        let chunk = match loader.compile_script(&displayed, &None) {
+            Ok(chunk) => chunk,
+            Err(error) => panic!("Failed to compile '{displayed}': {error}"),
+        };
+        let result = match vm.run(chunk) {
+            Ok(result) => result,
+            Err(error) => panic!("Failed to run '{displayed}': {error}"),
+        };
+
+        match vm.run_binary_op(BinaryOp::Equal, result.clone(), value) {
+            Ok(Value::Bool(true)) => {}
+            Ok(_) => panic!("'{displayed}' was parsed as '{result:#}'"),
+            Err(error) => panic!("Error while comparing '{displayed}': {error}"),
+        }
+    }
+
+    #[test]
+    fn displayed_forms() {
+        let map = ValueMap::new();
+        map.add_value("foo", Value::Str("it's".into()));
+        map.add_value(
+            "bar baz",
+            Value::Tuple(vec![Value::Number(1.into())].into()),
+        );
+        map.add_value("if", Value::Tuple(ValueTuple::default()));
+
+        assert_eq!(
+            format!("{:#}", Value::Map(map)),
+            r"{foo: 'it\'s', 'bar baz': (1,), 'if': (,)}"
+        );
+        assert_eq!(format!("{:#}", Value::Str("$x\n\\".into())), r"'\$x\n\\'");
+    }
+
+    #[test]
+    fn random_data_round_trips() {
+        let mut vm = Vm::default();
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+
+        for _ in 0..1000 {
+            let value = random_value(&mut rng, 0);
+            check_round_trip(&mut vm, value);
+        }
+    }
+}
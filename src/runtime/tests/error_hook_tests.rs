@@ -0,0 +1,138 @@
+mod runtime_test_utils;
+
+mod error_hook {
+    use {
+        koto_bytecode::Loader,
+        koto_runtime::prelude::*,
+        std::{cell::RefCell, rc::Rc},
+    };
+
+    // An error that was passed to the hook, along with the line numbers of the call stack's frames
+    #[derive(Debug, PartialEq)]
+    struct HookedError {
+        message: String,
+        lines: Vec<u32>,
+    }
+
+    // Makes a VM with an error hook that collects the errors it receives
+    fn make_vm() -> (Vm, Rc<RefCell<Vec<HookedError>>>) {
+        let errors = Rc::new(RefCell::new(vec![]));
+
+        let vm = Vm::with_settings(VmSettings {
+            error_hook: Some(Box::new({
+                let errors = errors.clone();
+                move |error: &RuntimeError, call_stack: &CallStackInfo| {
+                    errors.borrow_mut().push(HookedError {
+                        message: format!("{error:#}"),
+                        lines: call_stack
+                            .frames()
+                            .map(|frame| frame.span().map_or(0, |span| span.start.line))
+                            .collect(),
+                    })
+                }
+            })),
+            ..Default::default()
+        });
+
+        vm.prelude()
+            .add_fn("external_error", |vm, args| match vm.get_args(args) {
+                [] => runtime_error!("external error"),
+                unexpected => type_error_with_slice("no arguments", unexpected),
+            });
+
+        (vm, errors)
+    }
+
+    fn run_script(vm: &mut Vm, script: &str) -> RuntimeResult {
+        let mut loader = Loader::default();
+        let chunk = loader.compile_script(script, &None).unwrap();
+        vm.run(chunk)
+    }
+
+    fn check_hooked_errors(script: &str, expected: &[(&str, &[u32])]) {
+        let (mut vm, errors) = make_vm();
+        let _ = run_script(&mut vm, script);
+
+        let expected = expected
+            .iter()
+            .map(|(message, lines)| HookedError {
+                message: message.to_string(),
+                lines: lines.to_vec(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(*errors.borrow(), expected);
+    }
+
+    #[test]
+    fn core_library_error() {
+        check_hooked_errors(
+            "
+x = 1
+x.sin 2
+",
+            &[("Expected a Number as argument, but found (Int, Int).", &[3])],
+        );
+    }
+
+    #[test]
+    fn thrown_error() {
+        check_hooked_errors(
+            "
+f = ||
+  throw 'oops'
+f()
+",
+            &[("oops", &[3, 4])],
+        );
+    }
+
+    #[test]
+    fn external_function_error() {
+        check_hooked_errors("external_error()", &[("external error", &[1])]);
+    }
+
+    #[test]
+    fn caught_errors_are_passed_to_the_hook() {
+        check_hooked_errors(
+            "
+try
+  throw 'oops'
+catch error
+  null
+try
+  external_error()
+catch error
+  throw 'rethrown'
+",
+            &[("oops", &[3]), ("external error", &[7]), ("rethrown", &[9])],
+        );
+    }
+
+    #[test]
+    fn errors_raised_in_nested_calls_are_passed_to_the_hook_once() {
+        // The error is raised in a VM spawned by iterator.each, and then unwinds through the
+        // outer VM.
+        check_hooked_errors(
+            "
+f = |x|
+  if x == 3
+    external_error()
+  x
+(1..5).each(f).consume()
+",
+            &[("external error", &[4])],
+        );
+    }
+
+    #[test]
+    fn the_vm_can_be_used_after_an_error_has_been_raised() {
+        let (mut vm, errors) = make_vm();
+
+        assert!(run_script(&mut vm, "throw 'oops'").is_err());
+        assert!(matches!(
+            run_script(&mut vm, "1 + 1"),
+            Ok(Value::Number(n)) if n == 2
+        ));
+        assert_eq!(errors.borrow().len(), 1);
+    }
+}
@@ -1,10 +1,20 @@
 mod runtime_test_utils;
 
-use crate::runtime_test_utils::{number_tuple, test_script, value_tuple};
+use {
+    crate::runtime_test_utils::{assert_substrings_share_data, run_script, test_script},
+    koto_runtime::prelude::*,
+};
 
 mod iterator {
     use super::*;
 
+    fn check_size_hint(script: &str, expected: (usize, Option<usize>)) {
+        match run_script(script) {
+            Value::Iterator(i) => assert_eq!(i.size_hint(), expected),
+            unexpected => panic!("Expected an Iterator, found {unexpected}"),
+        }
+    }
+
     mod chain {
         use super::*;
 
@@ -37,6 +47,28 @@ y.next()
         }
     }
 
+    mod chunks {
+        use super::*;
+
+        #[test]
+        fn make_copy() {
+            let script = "
+x = (1..10).each(|n| n).chunks 2
+x.next() # (1, 2)
+y = x.copy()
+x.next() # (3, 4)
+y.next()
+";
+            test_script(script, koto_tuple![3, 4]);
+        }
+
+        #[test]
+        fn size_hint() {
+            check_size_hint("(0..10).chunks 3", (4, Some(4)));
+            check_size_hint("(0..9).each(|n| n).chunks 3", (3, Some(3)));
+        }
+    }
+
     mod cycle {
         use super::*;
 
@@ -54,6 +86,37 @@ y.next()
         }
     }
 
+    mod dedup {
+        use super::*;
+
+        #[test]
+        fn make_copy() {
+            let script = "
+x = (1, 1, 2, 2, 3, 3, 4).dedup()
+x.next() # 1
+y = x.copy()
+x.next() # 2
+x.next() # 3
+y.next()
+";
+            test_script(script, 2);
+        }
+
+        #[test]
+        fn size_hint() {
+            check_size_hint("(1, 1, 2).dedup()", (1, Some(3)));
+            check_size_hint("(,).dedup()", (0, Some(0)));
+            check_size_hint(
+                "
+x = (1, 1, 2).dedup()
+x.next()
+x
+",
+                (0, Some(2)),
+            );
+        }
+    }
+
     mod each {
         use super::*;
 
@@ -84,7 +147,7 @@ x.next() # 1, 11
 x.next() # 2, 12
 y.next()
 ";
-            test_script(script, value_tuple(&[1.into(), 11.into()]));
+            test_script(script, koto_tuple![1, 11]);
         }
     }
 
@@ -144,7 +207,103 @@ y.next()
             let script = "
 [].skip(1).to_tuple()
 ";
-            test_script(script, value_tuple(&[]));
+            test_script(script, koto_tuple![]);
+        }
+    }
+
+    mod slice {
+        use super::*;
+
+        #[test]
+        fn make_copy() {
+            let script = "
+x = (0..10).each(|n| n * 10).slice 3..6
+x.next() # 30
+y = x.copy()
+x.next() # 40
+y.next()
+";
+            test_script(script, 40);
+        }
+
+        #[test]
+        fn size_hint() {
+            check_size_hint("(0..100).each(|n| n).slice 10..20", (10, Some(10)));
+            check_size_hint("(0..100).each(|n| n).slice 90..", (10, Some(10)));
+            check_size_hint("(0..15).each(|n| n).slice 10..20", (5, Some(5)));
+            check_size_hint("(0..5).each(|n| n).slice 10..20", (0, Some(0)));
+            check_size_hint("(1..=100).keep(|n| n > 50).slice 10..20", (0, Some(10)));
+        }
+
+        #[test]
+        fn size_hint_after_skipping() {
+            let script = "
+x = (0..100).each(|n| n).slice 10..20
+x.next()
+x
+";
+            check_size_hint(script, (9, Some(9)));
+        }
+
+        #[test]
+        fn size_hint_with_sub_views() {
+            check_size_hint("[1, 2, 3, 4, 5].slice 1..3", (2, Some(2)));
+            check_size_hint("[1, 2, 3, 4, 5].slice 3..100", (2, Some(2)));
+            check_size_hint("(1, 2, 3, 4, 5).slice 4..", (1, Some(1)));
+        }
+    }
+
+    mod step_by {
+        use super::*;
+
+        #[test]
+        fn make_copy() {
+            let script = "
+x = (0..10).step_by 3
+x.next() # 0
+y = x.copy()
+x.next() # 3
+y.next()
+";
+            test_script(script, 3);
+        }
+
+        #[test]
+        fn size_hint() {
+            check_size_hint("(0..100).step_by 10", (10, Some(10)));
+            check_size_hint("(0..101).step_by 10", (11, Some(11)));
+            check_size_hint("(0..1).step_by 10", (1, Some(1)));
+            check_size_hint("(0..0).step_by 10", (0, Some(0)));
+            check_size_hint("(0..10).keep(|n| n > 5).step_by 3", (0, Some(4)));
+        }
+
+        #[test]
+        fn size_hint_after_first_value() {
+            for (script, expected) in [
+                ("x = (0..10).step_by 3\nx.next()\nx", 3),
+                ("x = (0..9).step_by 3\nx.next()\nx", 2),
+                ("x = (0..1).step_by 3\nx.next()\nx", 0),
+            ] {
+                check_size_hint(script, (expected, Some(expected)));
+            }
+        }
+
+        #[test]
+        fn value_pairs_are_preserved() {
+            let mut iterator = match run_script("{a: 1, b: 2, c: 3}.step_by 2") {
+                Value::Iterator(i) => i,
+                unexpected => panic!("Expected an Iterator, found {unexpected}"),
+            };
+
+            for expected_key in ["a", "c"] {
+                match iterator.next() {
+                    Some(ValueIteratorOutput::ValuePair(Value::Str(key), _)) => {
+                        assert_eq!(key.as_str(), expected_key)
+                    }
+                    unexpected => panic!("Expected a value pair, found {unexpected:?}"),
+                }
+            }
+            assert!(iterator.next().is_none());
         }
     }
 
@@ -165,6 +324,180 @@ y.next()
         }
     }
 
+    mod iterable_inputs {
+        use {super::*, koto_bytecode::Loader};
+
+        // The extra arguments that are passed to each function after the iterable input
+        const FUNCTION_ARGS: &[(&str, &str)] = &[
+            ("all", "|_| true"),
+            ("any", "|_| true"),
+            ("chain", "[1]"),
+            ("chunks", "2"),
+            ("consume", ""),
+            ("copy", ""),
+            ("count", ""),
+            ("cycle", ""),
+            ("dedup", ""),
+            ("each", "|x| x"),
+            ("each_indexed", "|_, x| x"),
+            ("enumerate", ""),
+            ("find", "|_| true"),
+            ("flatten", ""),
+            ("fold", "0, |n, _| n"),
+            ("group", "|x| x"),
+            ("inspect", "|_| null"),
+            ("intersperse", "0"),
+            ("iter", ""),
+            ("keep", "|_| true"),
+            ("last", ""),
+            ("max", ""),
+            ("max_index", ""),
+            ("min", ""),
+            ("min_index", ""),
+            ("min_max", ""),
+            ("next", ""),
+            ("partition", "|_| true"),
+            ("peekable", ""),
+            ("position", "|_| true"),
+            ("product", ""),
+            ("reversed", ""),
+            ("round_robin", ""),
+            ("scan", "0, |_, x| x"),
+            ("skip", "1"),
+            ("skip_last", "1"),
+            ("skip_while", "|_| false"),
+            ("slice", "0..1"),
+            ("sorted", ""),
+            ("step_by", "1"),
+            ("sum", ""),
+            ("take", "1"),
+            ("take_last", "1"),
+            ("to_list", ""),
+            ("to_map", ""),
+            ("to_num2", ""),
+            ("to_num4", ""),
+            ("to_string", ""),
+            ("to_tuple", ""),
+            ("unique", ""),
+            ("windows", "2"),
+            ("with_size_hint", "1"),
+            ("with_state", "0, |state, x| (state, x)"),
+            ("zip", "[1]"),
+            ("zip_longest", "[1]"),
+        ];
+
+        // Functions that don't take an arbitrary iterable input
+        const NON_STREAM_FUNCTIONS: &[&str] = &["generate", "peek", "repeat", "stop"];
+
+        const INPUTS: &[&str] = &[
+            "[1, 2, 3]",
+            "1..4",
+            "{foo: 1, bar: 2}",
+            "'abc'",
+            "(1, 2, 3).iter()",
+        ];
+
+        #[test]
+        fn all_functions_are_covered() {
+            let vm = Vm::default();
+            let iterator = match vm.prelude().data().get_with_string("iterator") {
+                Some(Value::Map(iterator)) => iterator.clone(),
+                _ => panic!("Missing iterator module"),
+            };
+
+            for key in iterator.data().keys() {
+                let name = key.to_string();
+                assert!(
+                    FUNCTION_ARGS.iter().any(|(function, _)| *function == name)
+                        || NON_STREAM_FUNCTIONS.contains(&name.as_str()),
+                    "iterator.{name} is missing from the list of functions to test"
+                );
+            }
+        }
+
+        #[test]
+        fn functions_accept_any_iterable() {
+            for (function, args) in FUNCTION_ARGS {
+                for input in INPUTS {
+                    let script = if args.is_empty() {
+                        format!("iterator.{function} {input}")
+                    } else {
+                        format!("iterator.{function} {input}, {args}")
+                    };
+
+                    let mut vm = Vm::default();
+                    let mut loader = Loader::default();
+                    let chunk = loader.compile_script(&script, &None).unwrap();
+
+                    // Some inputs are invalid for some functions (e.g. `sum` with a String),
+                    // but an iterable input should never be rejected for not being an Iterator.
+                    if let Err(error) = vm.run(chunk) {
+                        let message = error.to_string();
+                        assert!(
+                            !message.contains("an Iterator"),
+                            "'{script}' failed with: {message}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    mod unique {
+        use super::*;
+
+        #[test]
+        fn make_copy() {
+            let script = "
+x = (1, 2, 1, 3, 2, 4).unique()
+x.next() # 1
+y = x.copy()
+x.next() # 2
+x.next() # 3
+y.next()
+";
+            test_script(script, 2);
+        }
+
+        #[test]
+        fn size_hint() {
+            check_size_hint("(1, 1, 2).unique()", (1, Some(3)));
+            check_size_hint("(,).unique()", (0, Some(0)));
+        }
+    }
+
+    mod windows {
+        use super::*;
+
+        #[test]
+        fn make_copy() {
+            let script = "
+x = (1..10).each(|n| n).windows 3
+x.next() # (1, 2, 3)
+y = x.copy()
+x.next() # (2, 3, 4)
+y.next()
+";
+            test_script(script, koto_tuple![2, 3, 4]);
+        }
+
+        #[test]
+        fn size_hint() {
+            check_size_hint("(0..10).windows 3", (8, Some(8)));
+            check_size_hint("(0..2).windows 3", (0, Some(0)));
+        }
+
+        #[test]
+        fn size_hint_after_first_window() {
+            let script = "
+x = (0..10).windows 3
+x.next()
+x
+";
+            check_size_hint(script, (7, Some(7)));
+        }
+    }
+
     mod zip {
         use super::*;
 
@@ -178,7 +511,42 @@ y = x.copy()
 x.next() # (3, 13)
 y.next()
 ";
-            test_script(script, number_tuple(&[3, 13]));
+            test_script(script, koto_tuple![3, 13]);
+        }
+    }
+
+    mod zip_longest {
+        use super::*;
+
+        #[test]
+        fn make_copy() {
+            let script = "
+x = (1..3).zip_longest 11..15
+x.next() # (1, 11)
+x.next() # (2, 12)
+y = x.copy()
+x.next() # (null, 13)
+y.next()
+";
+            test_script(script, koto_tuple![Value::Null, 13]);
+        }
+
+        #[test]
+        fn size_hint() {
+            check_size_hint("(0..10).zip_longest 0..20", (20, Some(20)));
+            check_size_hint("(0..10).zip_longest (0..20).keep |n| n > 5", (10, Some(20)));
+        }
+
+        #[test]
+        fn size_hint_after_exhausting_one_side() {
+            let script = "
+x = (0..2).zip_longest 0..5
+x.next()
+x.next()
+x.next()
+x
+";
+            check_size_hint(script, (2, Some(2)));
         }
     }
 }
@@ -222,6 +590,22 @@ y.next()
 mod string {
     use super::*;
 
+    // Runs a script that returns an input string along with a tuple of substrings,
+    // checking that the substrings match the expected output without allocating new strings.
+    fn check_substrings(script: &str, expected: &[&str]) {
+        let result = run_script(script);
+        let (input, substrings) = match &result {
+            Value::Tuple(t) => match t.as_ref() {
+                [input, Value::Tuple(substrings)] => (input, substrings),
+                _ => panic!("Expected a String and a Tuple, found {result}"),
+            },
+            unexpected => panic!("Expected a Tuple, found {}", unexpected.type_as_string()),
+        };
+        let substrings_as_str: Vec<String> = substrings.iter().map(|s| s.to_string()).collect();
+        assert_eq!(substrings_as_str, expected);
+        assert_substrings_share_data(input, substrings);
+    }
+
     mod bytes {
         use super::*;
 
@@ -238,6 +622,40 @@ y.next()
         }
     }
 
+    mod chunks {
+        use super::*;
+
+        #[test]
+        fn multi_byte_text() {
+            let script = "
+x = 'héllo wörld'
+x, x.chunks(3).to_tuple()
+";
+            check_substrings(script, &["hél", "lo ", "wör", "ld"]);
+        }
+
+        #[test]
+        fn graphemes() {
+            let script = "
+x = 'a👩‍👩‍👧bcé'
+x, x.chunks(2).to_tuple()
+";
+            check_substrings(script, &["a👩‍👩‍👧", "bc", "é"]);
+        }
+
+        #[test]
+        fn make_copy() {
+            let script = "
+x = 'abcdef'.chunks 2
+x.next() # ab
+y = x.copy()
+x.next() # cd
+y.next()
+";
+            test_script(script, "cd");
+        }
+    }
+
     mod lines {
         use super::*;
 
@@ -258,10 +676,29 @@ y.next()
             let script = "
 'abc\r\ndef\r\nxyz\r\n\r\n'.lines().to_tuple()
 ";
-            test_script(
-                script,
-                value_tuple(&["abc".into(), "def".into(), "xyz".into(), "".into()]),
-            );
+            test_script(script, koto_tuple!["abc", "def", "xyz", ""]);
+        }
+    }
+
+    mod slice {
+        use super::*;
+
+        #[test]
+        fn graphemes() {
+            let script = "
+x = 'a👩‍👩‍👧bcé'
+x, x.slice(1..4).to_tuple()
+";
+            check_substrings(script, &["👩‍👩‍👧", "b", "c"]);
+        }
+
+        #[test]
+        fn beyond_the_end() {
+            let script = "
+x = 'héllo'
+x, x.slice(3..10).to_tuple()
+";
+            check_substrings(script, &["l", "o"]);
         }
     }
 
@@ -292,4 +729,58 @@ y.next()
             test_script(script, "2");
         }
     }
+
+    mod windows {
+        use super::*;
+
+        #[test]
+        fn multi_byte_text() {
+            let script = "
+x = 'über'
+x, x.windows(3).to_tuple()
+";
+            check_substrings(script, &["übe", "ber"]);
+        }
+
+        #[test]
+        fn graphemes() {
+            let script = "
+x = 'a👩‍👩‍👧bé'
+x, x.windows(2).to_tuple()
+";
+            check_substrings(script, &["a👩‍👩‍👧", "👩‍👩‍👧b", "bé"]);
+        }
+
+        #[test]
+        fn input_shorter_than_window() {
+            let script = "
+x = 'ab'
+x, x.windows(3).to_tuple()
+";
+            check_substrings(script, &[]);
+        }
+
+        #[test]
+        fn iterator_of_chars_produces_windows_of_values() {
+            let script = "
+'abc'.chars().windows(2).each(iterator.to_tuple).to_tuple()
+";
+            test_script(
+                script,
+                koto_tuple![koto_tuple!["a", "b"], koto_tuple!["b", "c"]],
+            );
+        }
+
+        #[test]
+        fn make_copy() {
+            let script = "
+x = 'abcd'.windows 2
+x.next() # ab
+y = x.copy()
+x.next() # bc
+y.next()
+";
+            test_script(script, "bc");
+        }
+    }
 }
@@ -0,0 +1,214 @@
+mod runtime_test_utils;
+
+mod nested_calls {
+    use {
+        crate::runtime_test_utils::test_script_with_vm, koto_bytecode::Loader,
+        koto_runtime::prelude::*,
+    };
+
+    // Each level of nesting consumes native stack space,
+    // so the tests are run on a thread with a generous stack size.
+    fn run_with_large_stack(f: impl FnOnce() + Send + 'static) {
+        std::thread::Builder::new()
+            .stack_size(128 * 1024 * 1024)
+            .spawn(f)
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    // Runs the test on a thread with the same stack size as threads spawned with the default
+    // settings, to check that the default nesting limit is reached before the stack overflows.
+    fn run_with_default_stack(f: impl FnOnce() + Send + 'static) {
+        std::thread::Builder::new()
+            .stack_size(2 * 1024 * 1024)
+            .spawn(f)
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    // Makes a VM with functions that call back into the VM from Rust
+    fn make_vm(max_nesting_depth: usize) -> Vm {
+        let vm = Vm::with_settings(VmSettings {
+            max_nesting_depth,
+            ..Default::default()
+        });
+
+        let prelude = vm.prelude();
+
+        prelude.add_fn("call_back", |vm, args| match vm.get_args(args) {
+            [f, n] if f.is_callable() => {
+                let f = f.clone();
+                let n = n.clone();
+                vm.run_function(f, CallArgs::Single(n))
+            }
+            unexpected => type_error_with_slice("a Function and a Value", unexpected),
+        });
+
+        prelude.add_fn("add_via_vm", |vm, args| match vm.get_args(args) {
+            [a, b] => {
+                let a = a.clone();
+                let b = b.clone();
+                vm.run_binary_op(BinaryOp::Add, a, b)
+            }
+            unexpected => type_error_with_slice("two Values", unexpected),
+        });
+
+        vm
+    }
+
+    fn run_script(vm: &mut Vm, script: &str) -> RuntimeResult {
+        let mut loader = Loader::default();
+        let chunk = loader.compile_script(script, &None).unwrap();
+        vm.run(chunk)
+    }
+
+    fn recursive_callback_script(depth: usize) -> String {
+        format!(
+            "
+recurse = |n|
+  if n == 0
+    0
+  else
+    1 + call_back recurse, n - 1
+recurse {depth}
+"
+        )
+    }
+
+    #[test]
+    fn several_hundred_levels_of_nested_calls() {
+        run_with_large_stack(|| {
+            let vm = make_vm(1000);
+            test_script_with_vm(vm, &recursive_callback_script(500), 500.into());
+        });
+    }
+
+    #[test]
+    fn nested_overloaded_operators() {
+        run_with_large_stack(|| {
+            let script = "
+make_counter = |n|
+  n: n
+  @+: |self, other|
+    if other == 0
+      self.n
+    else
+      add_via_vm (make_counter self.n + 1), other - 1
+add_via_vm (make_counter 0), 300
+";
+            test_script_with_vm(make_vm(1000), script, 300.into());
+        });
+    }
+
+    #[test]
+    fn exceeding_the_nesting_limit_results_in_an_error() {
+        run_with_large_stack(|| {
+            let mut vm = make_vm(100);
+
+            match run_script(&mut vm, &recursive_callback_script(500)) {
+                Ok(result) => panic!("Expected an error, found '{result}'"),
+                Err(error) => {
+                    let message = format!("{error:#}");
+                    assert!(
+                        message.contains("The maximum nesting depth (100) has been exceeded"),
+                        "Unexpected error message: {message}"
+                    );
+                }
+            }
+
+            // The VM can continue to be used after the error
+            let result = run_script(&mut vm, &recursive_callback_script(50)).unwrap();
+            assert_eq!(result.to_string(), "50");
+        });
+    }
+
+    #[test]
+    fn nesting_errors_can_be_caught() {
+        run_with_large_stack(|| {
+            let script = "
+recurse = |n|
+  1 + call_back recurse, n + 1
+
+x = 99
+result = try
+  recurse 0
+catch error
+  'caught'
+(result, x)
+";
+            let mut vm = make_vm(100);
+            let result = run_script(&mut vm, script).unwrap();
+            assert_eq!(result.to_string(), "('caught', 99)");
+        });
+    }
+
+    #[test]
+    fn nesting_depth_is_shared_with_spawned_vms() {
+        run_with_large_stack(|| {
+            // Iterator adaptors run their functions in spawned VMs
+            let script = "
+recurse = |n|
+  if n == 0
+    0
+  else
+    (0..1)
+      .each |_| 1 + recurse n - 1
+      .next()
+recurse 200
+";
+            let mut vm = make_vm(100);
+            let error = run_script(&mut vm, script).unwrap_err();
+            assert!(format!("{error:#}").contains("maximum nesting depth"));
+
+            test_script_with_vm(make_vm(1000), script, 200.into());
+        });
+    }
+
+    #[test]
+    fn default_nesting_limit_fits_in_the_default_stack_size() {
+        run_with_default_stack(|| {
+            let default_depth = VmSettings::default().max_nesting_depth;
+            let scripts = [
+                recursive_callback_script(1000),
+                "
+make_counter = |n|
+  n: n
+  @+: |self, other|
+    add_via_vm (make_counter self.n + 1), other - 1
+add_via_vm (make_counter 0), 1000
+"
+                .to_string(),
+                "
+recurse = |n|
+  (0..1)
+    .each |_| 1 + recurse n + 1
+    .next()
+recurse 0
+"
+                .to_string(),
+                "
+recurse = |n|
+  [1, 2].sort |x| call_back recurse, n + 1
+recurse 0
+"
+                .to_string(),
+            ];
+
+            for script in scripts.iter() {
+                let mut vm = make_vm(default_depth);
+                match run_script(&mut vm, script) {
+                    Ok(result) => panic!("Expected an error, found '{result}'"),
+                    Err(error) => {
+                        let message = format!("{error:#}");
+                        assert!(
+                            message.contains("maximum nesting depth"),
+                            "Unexpected error message: {message}"
+                        );
+                    }
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,109 @@
+mod runtime_test_utils;
+
+mod number_display {
+    use koto_runtime::prelude::*;
+
+    // Displays the number, and then parses the result with string.to_number
+    fn round_trip(vm: &mut Vm, n: ValueNumber) -> ValueNumber {
+        let to_number = match vm.prelude().data().get_with_string("string") {
+            Some(Value::Map(string)) => string.data().get_with_string("to_number").cloned(),
+            _ => None,
+        }
+        .expect("Missing string.to_number");
+
+        let displayed = Value::Str(n.to_string().into());
+        match vm.run_function(to_number, CallArgs::Single(displayed)) {
+            Ok(Value::Number(result)) => result,
+            Ok(unexpected) => panic!("Expected a Number, found {unexpected}"),
+            Err(error) => panic!("Failed to parse '{n}': {error}"),
+        }
+    }
+
+    fn check_float(vm: &mut Vm, n: f64) {
+        match round_trip(vm, n.into()) {
+            ValueNumber::F64(result) if n.is_nan() => assert!(result.is_nan()),
+            ValueNumber::F64(result) => assert_eq!(
+                result.to_bits(),
+                n.to_bits(),
+                "{n:?} was displayed as '{}' and parsed as {result:?}",
+                ValueNumber::from(n)
+            ),
+            ValueNumber::I64(result) => panic!("{n:?} was parsed as an Int ({result})"),
+        }
+    }
+
+    fn check_int(vm: &mut Vm, n: i64) {
+        match round_trip(vm, n.into()) {
+            ValueNumber::I64(result) => assert_eq!(result, n),
+            ValueNumber::F64(result) => panic!("{n} was parsed as a Float ({result:?})"),
+        }
+    }
+
+    #[test]
+    fn displayed_forms() {
+        for (n, expected) in [
+            (0.0, "0.0"),
+            (-0.0, "-0.0"),
+            (1.0, "1.0"),
+            (-0.25, "-0.25"),
+            (0.1 + 0.2, "0.30000000000000004"),
+            (f64::INFINITY, "inf"),
+            (f64::NEG_INFINITY, "-inf"),
+            (f64::NAN, "nan"),
+        ] {
+            assert_eq!(ValueNumber::from(n).to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn edge_case_floats_round_trip() {
+        let mut vm = Vm::default();
+
+        for n in [
+            0.0,
+            -0.0,
+            0.1,
+            0.1 + 0.2,
+            1.0 / 3.0,
+            -1.0 / 3.0,
+            1e-7,
+            1e15,
+            1e16,
+            1e21,
+            9007199254740992.0,
+            f64::EPSILON,
+            f64::MIN_POSITIVE,
+            5e-324,
+            f64::MAX,
+            f64::MIN,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NAN,
+        ] {
+            check_float(&mut vm, n);
+        }
+    }
+
+    #[test]
+    fn arbitrary_floats_round_trip() {
+        let mut vm = Vm::default();
+
+        // A simple LCG, producing a deterministic spread of bit patterns
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        for _ in 0..1000 {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            check_float(&mut vm, f64::from_bits(state));
+        }
+    }
+
+    #[test]
+    fn ints_round_trip() {
+        let mut vm = Vm::default();
+
+        for n in [0, 1, -1, i64::MIN, i64::MIN + 1, i64::MAX, i64::MAX - 1] {
+            check_int(&mut vm, n);
+        }
+    }
+}
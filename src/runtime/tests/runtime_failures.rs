@@ -4,7 +4,8 @@ mod runtime {
         koto_runtime::Vm,
     };
 
-    fn check_script_fails(script: &str) {
+    // Runs the script, returning the resulting error message
+    fn check_script_fails(script: &str) -> String {
         let mut vm = Vm::default();
 
         let print_chunk = |script: &str, chunk| {
@@ -23,12 +24,23 @@ mod runtime {
             }
         };
 
-        if let Ok(result) = vm.run(chunk) {
-            print_chunk(script, vm.chunk());
-            panic!("Script didn't fail as expected, result: {}", result)
+        match vm.run(chunk) {
+            Ok(result) => {
+                print_chunk(script, vm.chunk());
+                panic!("Script didn't fail as expected, result: {}", result)
+            }
+            Err(error) => format!("{error:#}"),
         }
     }
 
+    fn check_script_fails_with_message(script: &str, expected_message: &str) {
+        let message = check_script_fails(script);
+        assert!(
+            message.starts_with(expected_message),
+            "Unexpected error message: {message}"
+        );
+    }
+
     mod should_fail {
         use super::*;
 
@@ -54,6 +66,84 @@ mod runtime {
             fn check_assert_near() {
                 check_script_fails("assert_near 1, 2, 0.1");
             }
+
+            #[test]
+            fn assert_eq_reports_mismatched_component() {
+                check_script_fails_with_message(
+                    "assert_eq (1, 2, 3), (1, 2, 4)",
+                    "Assertion failed, '(1, 2, 3)' is not equal to '(1, 2, 4)' \
+                     (mismatch at component 2)",
+                );
+            }
+
+            mod assert_near {
+                use super::*;
+
+                #[test]
+                fn num2() {
+                    check_script_fails_with_message(
+                        "assert_near (make_num2 1, 2), (make_num2 1, 3), 0.1",
+                        "Assertion failed, 'num2(1, 2)' and 'num2(1, 3)' are not within 0.1 of \
+                         each other (component 1 differs by 1)",
+                    );
+                }
+
+                #[test]
+                fn num4() {
+                    check_script_fails_with_message(
+                        "assert_near (make_num4 1, 2, 3, 4), (make_num4 1, 2, 3.5, 4), 0.1",
+                        "Assertion failed, 'num4(1, 2, 3, 4)' and 'num4(1, 2, 3.5, 4)' are not \
+                         within 0.1 of each other (component 2 differs by 0.5)",
+                    );
+                }
+
+                #[test]
+                fn tuple() {
+                    check_script_fails_with_message(
+                        "assert_near (1, 2, 3), (1.5, 2, 3), 0.1",
+                        "Assertion failed, '(1, 2, 3)' and '(1.5, 2, 3)' are not within 0.1 of \
+                         each other (component 0 differs by 0.5)",
+                    );
+                }
+
+                #[test]
+                fn list() {
+                    check_script_fails_with_message(
+                        "assert_near [1, 2], [1, 2.5], 0.1",
+                        "Assertion failed, '[1, 2]' and '[1, 2.5]' are not within 0.1 of \
+                         each other (component 1 differs by 0.5)",
+                    );
+                }
+
+                #[test]
+                fn mismatched_sizes() {
+                    check_script_fails_with_message(
+                        "assert_near (1, 2), (1, 2, 3)",
+                        "assert_near: Expected values with matching sizes, \
+                         found '(1, 2)' (size 2) and '(1, 2, 3)' (size 3)",
+                    );
+                }
+
+                #[test]
+                fn mismatched_types() {
+                    check_script_fails_with_message(
+                        "assert_near (1, 2), [1, 2]",
+                        "Expected two Numbers, Num2s, Num4s, or same-length Tuples or Lists",
+                    );
+                    check_script_fails_with_message(
+                        "assert_near (make_num2 1, 2), (1, 2)",
+                        "Expected two Numbers, Num2s, Num4s, or same-length Tuples or Lists",
+                    );
+                }
+
+                #[test]
+                fn non_number_in_tuple() {
+                    check_script_fails_with_message(
+                        "assert_near (1, 'x'), (1, 2)",
+                        "Expected two Numbers, Num2s, Num4s, or same-length Tuples or Lists",
+                    );
+                }
+            }
         }
 
         mod missing_values {
@@ -103,7 +193,10 @@ x
 f = |(a, b)| a + b
 f "O_o"
 "#;
-                check_script_fails(script);
+                check_script_fails_with_message(
+                    script,
+                    "Expected a Tuple for argument 1, but found String.",
+                );
             }
 
             #[test]
@@ -112,7 +205,11 @@ f "O_o"
 f = |(a, b)| a + b
 f (1, 2, 3)
 "#;
-                check_script_fails(script);
+                check_script_fails_with_message(
+                    script,
+                    "Expected a Tuple with 2 elements for argument 1, \
+                     but found a Tuple with 3 elements.",
+                );
             }
 
             #[test]
@@ -121,7 +218,10 @@ f (1, 2, 3)
 f = |[a, b]| a + b
 f (1, 2)
 "#;
-                check_script_fails(script);
+                check_script_fails_with_message(
+                    script,
+                    "Expected a List for argument 1, but found Tuple.",
+                );
             }
 
             #[test]
@@ -130,7 +230,50 @@ f (1, 2)
 f = |[a, b]| a + b
 f [1, 2, 3]
 "#;
-                check_script_fails(script);
+                check_script_fails_with_message(
+                    script,
+                    "Expected a List with 2 elements for argument 1, \
+                     but found a List with 3 elements.",
+                );
+            }
+
+            #[test]
+            fn unpacking_of_later_argument_with_wrong_size() {
+                let script = r#"
+f = |x, (a, b)| x + a + b
+f 1, (2,)
+"#;
+                check_script_fails_with_message(
+                    script,
+                    "Expected a Tuple with 2 elements for argument 2, \
+                     but found a Tuple with 1 element.",
+                );
+            }
+
+            #[test]
+            fn nested_unpacking_with_wrong_size() {
+                let script = r#"
+f = |(x, [y, z])| x + y + z
+f (1, [2])
+"#;
+                check_script_fails_with_message(
+                    script,
+                    "Expected a List with 2 elements for argument 1, \
+                     but found a List with 1 element.",
+                );
+            }
+
+            #[test]
+            fn unpacking_with_ellipsis_and_too_few_elements() {
+                let script = r#"
+f = |(a, b, others...)| a + b
+f (1,)
+"#;
+                check_script_fails_with_message(
+                    script,
+                    "Expected a Tuple with at least 2 elements for argument 1, \
+                     but found a Tuple with 1 element.",
+                );
             }
 
             #[test]
@@ -162,6 +305,1119 @@ x[0] = -1
 ";
                 check_script_fails(script);
             }
+
+            #[test]
+            fn list_with_out_of_range_negative_index() {
+                check_script_fails_with_message(
+                    "[1, 2, 3][-4]",
+                    "Index out of bounds - index: -4 (resolved to -1), size: 3",
+                );
+            }
+
+            #[test]
+            fn tuple_with_out_of_range_negative_range() {
+                check_script_fails_with_message(
+                    "(1, 2, 3)[-5..]",
+                    "Index out of bounds, start: -2, end: 3, size: 3",
+                );
+            }
+
+            #[test]
+            fn string_with_out_of_range_negative_index() {
+                check_script_fails_with_message(
+                    "'héllo'[-6]",
+                    "Index out of bounds - index: -6 (resolved to -1), size: 5",
+                );
+            }
+
+            #[test]
+            fn string_with_out_of_range_negative_range() {
+                check_script_fails_with_message(
+                    "'héllo'[..-6]",
+                    "Index out of bounds, start: 0, end: -1, size: 5",
+                );
+            }
+
+            #[test]
+            fn num4_with_out_of_range_negative_index() {
+                check_script_fails_with_message(
+                    "(make_num4 1, 2, 3, 4)[-5]",
+                    "Index out of bounds for Num4, -1",
+                );
+            }
+
+            #[test]
+            fn list_assignment_with_out_of_range_negative_index() {
+                let script = "
+x = [1, 2]
+x[-3] = 0
+";
+                check_script_fails_with_message(
+                    script,
+                    "Index '-3' (resolved to '-1') not in List",
+                );
+            }
+        }
+
+        mod num2_num4 {
+            use super::*;
+
+            #[test]
+            fn num2_with_invalid_index() {
+                check_script_fails_with_message(
+                    "(make_num2 1, 2).with 2, 99",
+                    "num2.with: invalid index '2'",
+                );
+            }
+
+            #[test]
+            fn num2_with_negative_index() {
+                check_script_fails_with_message(
+                    "(make_num2 1, 2).with -1, 99",
+                    "num2.with: invalid index '-1'",
+                );
+            }
+
+            #[test]
+            fn num4_with_invalid_index() {
+                check_script_fails_with_message(
+                    "(make_num4 1, 2, 3, 4).with 4, 99",
+                    "num4.with: invalid index '4'",
+                );
+            }
+
+            #[test]
+            fn num2_dot_with_num4() {
+                check_script_fails_with_message(
+                    "num2.dot (make_num2 1), (make_num4 1)",
+                    "Expected two Num2s as arguments, but found (Num2, Num4).",
+                );
+            }
+
+            #[test]
+            fn num4_cross_with_num2() {
+                check_script_fails_with_message(
+                    "num4.cross (make_num4 1), (make_num2 1)",
+                    "Expected two Num4s as arguments, but found (Num4, Num2).",
+                );
+            }
+        }
+
+        mod char_ranges {
+            use super::*;
+
+            #[test]
+            fn multiple_chars_in_range_start() {
+                check_script_fails("'ab'..'z'");
+            }
+
+            #[test]
+            fn multiple_chars_in_range_end() {
+                check_script_fails("'a'..='yz'");
+            }
+
+            #[test]
+            fn empty_string_in_range() {
+                check_script_fails("''..'z'");
+            }
+
+            #[test]
+            fn mismatched_range_types() {
+                check_script_fails("'a'..10");
+            }
+        }
+
+        mod count {
+            use super::*;
+
+            #[test]
+            fn iterator_count_with_non_bool_predicate_result() {
+                check_script_fails_with_message(
+                    "(1..10).count |n| n",
+                    "Expected a Bool to be returned from the predicate, but found Int.",
+                );
+            }
+
+            #[test]
+            fn iterator_count_with_throwing_predicate() {
+                check_script_fails_with_message(
+                    "(1..10).count |n| if n == 5 then throw 'oops' else true",
+                    "oops",
+                );
+            }
+
+            #[test]
+            fn list_count_with_non_bool_predicate_result() {
+                check_script_fails_with_message(
+                    "[1, 2, 3].count |n| n",
+                    "Expected a Bool to be returned from the predicate, but found Int.",
+                );
+            }
+
+            #[test]
+            fn list_count_with_throwing_predicate() {
+                check_script_fails_with_message(
+                    "[1, 2, 3].count |n| if n == 2 then throw 'oops' else true",
+                    "oops",
+                );
+            }
+
+            #[test]
+            fn list_count_with_non_bool_equality_result() {
+                let script = "
+foo = |x|
+  x: x
+  @==: |self, other| self.x
+[foo(1), foo(2)].count foo(1)
+";
+                check_script_fails_with_message(
+                    script,
+                    "list.count: Expected Bool from comparison, found 'Int'",
+                );
+            }
+
+            #[test]
+            fn list_count_with_throwing_equality_op() {
+                let script = "
+foo = |x|
+  x: x
+  @==: |self, other| throw 'oops'
+[foo(1), foo(2)].count foo(1)
+";
+                check_script_fails_with_message(script, "oops");
+            }
+        }
+
+        mod number_formatting {
+            use super::*;
+
+            #[test]
+            fn to_fixed_with_negative_decimals() {
+                check_script_fails_with_message(
+                    "1.5.to_fixed -1",
+                    "number.to_fixed: Expected a non-negative number of decimal places (found -1)",
+                );
+            }
+
+            #[test]
+            fn to_precision_with_zero_digits() {
+                check_script_fails_with_message(
+                    "1.5.to_precision 0",
+                    "number.to_precision: Expected a positive number of significant digits \
+                     (found 0)",
+                );
+            }
+
+            #[test]
+            fn format_with_negative_decimals() {
+                check_script_fails_with_message(
+                    "1.5.format {decimals: -2}",
+                    "number.format: Expected a non-negative number of decimal places (found -2)",
+                );
+            }
+
+            #[test]
+            fn format_with_non_string_separator() {
+                check_script_fails_with_message(
+                    "1.5.format {separator: 1}",
+                    "number.format: Expected a String for 'separator', found 'Int'",
+                );
+            }
+        }
+
+        mod number_ops {
+            use super::*;
+
+            #[test]
+            fn clamp_with_min_greater_than_max() {
+                check_script_fails_with_message(
+                    "5.clamp 10, 1",
+                    "number.clamp: Expected a min that's less than or equal to max \
+                     (found min: 10, max: 1)",
+                );
+            }
+        }
+
+        mod string_matches {
+            use super::*;
+
+            #[test]
+            fn empty_pattern() {
+                check_script_fails_with_message(
+                    "'abc'.matches ''",
+                    "string.matches: Empty patterns aren't allowed",
+                );
+            }
+
+            #[test]
+            fn non_bool_predicate_result() {
+                check_script_fails_with_message(
+                    "'abc'.matches(|c| c).to_list()",
+                    "string.matches: Expected a Bool from the match function, found 'String'",
+                );
+            }
+        }
+
+        mod string_encoding {
+            use super::*;
+
+            #[test]
+            fn from_hex_with_odd_length() {
+                check_script_fails_with_message(
+                    "'abc'.from_hex()",
+                    "string.from_hex: Expected an even number of hex digits, found 3",
+                );
+            }
+
+            #[test]
+            fn from_hex_with_invalid_digits() {
+                check_script_fails_with_message(
+                    "'00zz'.from_hex()",
+                    "string.from_hex: Invalid hex digits 'zz' at position 2",
+                );
+            }
+
+            #[test]
+            fn from_base64_with_invalid_padding() {
+                check_script_fails_with_message(
+                    "'Z==='.from_base64()",
+                    "string.from_base64: Invalid padding",
+                );
+                check_script_fails_with_message(
+                    "'Zg='.from_base64()",
+                    "string.from_base64: Expected the input's length to be a multiple of 4, found 3",
+                );
+            }
+
+            #[test]
+            fn from_base64_with_invalid_character() {
+                check_script_fails_with_message(
+                    "'Zm9*'.from_base64()",
+                    "string.from_base64: Invalid character '*' at position 3",
+                );
+            }
+
+            #[test]
+            fn decoded_bytes_are_invalid_utf8() {
+                check_script_fails_with_message(
+                    "'ff'.from_hex()",
+                    "string.from_hex: The decoded bytes aren't valid UTF-8",
+                );
+                check_script_fails_with_message(
+                    "'/w=='.from_base64()",
+                    "string.from_base64: The decoded bytes aren't valid UTF-8",
+                );
+            }
+        }
+
+        mod iterator_group {
+            use super::*;
+
+            #[test]
+            fn mutable_key() {
+                check_script_fails_with_message(
+                    "(1..5).group |n| [n]",
+                    "iterator.group: Only immutable Values can be used as keys (found 'List')",
+                );
+            }
+
+            #[test]
+            fn throwing_key_function() {
+                check_script_fails_with_message(
+                    "(1..5).group |n| if n == 3 then throw 'oops' else n",
+                    "oops",
+                );
+            }
+
+            #[test]
+            fn error_in_the_adapted_iterator() {
+                let script = "
+(1..10)
+  .each |n| if n == 4 then throw 'oops' else n
+  .group |n| n % 2
+";
+                check_script_fails_with_message(script, "oops");
+            }
+        }
+
+        mod dedup {
+            use super::*;
+
+            #[test]
+            fn non_bool_equality_result() {
+                let script = "
+foo = |x|
+  x: x
+  @==: |self, other| self.x
+(foo(1), foo(2)).dedup().to_tuple()
+";
+                check_script_fails_with_message(
+                    script,
+                    "iterator.dedup: Expected a Bool to be returned from the equality comparison, \
+                     found 'Int'",
+                );
+            }
+
+            #[test]
+            fn non_bool_comparison_function_result() {
+                check_script_fails_with_message(
+                    "(1, 2).dedup(|a, b| a + b).to_tuple()",
+                    "iterator.dedup: Expected a Bool to be returned from the comparison function, \
+                     found 'Int'",
+                );
+            }
+
+            #[test]
+            fn throwing_comparison_function() {
+                check_script_fails_with_message(
+                    "(1, 2, 3).dedup(|a, b| if b == 3 then throw 'oops' else false).to_tuple()",
+                    "oops",
+                );
+            }
+
+            #[test]
+            fn error_in_the_adapted_iterator() {
+                let script = "
+(1..10)
+  .each |n| if n == 4 then throw 'oops' else n
+  .dedup()
+  .to_list()
+";
+                check_script_fails_with_message(script, "oops");
+            }
+        }
+
+        mod partition {
+            use super::*;
+
+            #[test]
+            fn non_bool_predicate_result() {
+                check_script_fails_with_message(
+                    "(1..10).partition |n| n",
+                    "Expected a Bool to be returned from the predicate, but found Int.",
+                );
+            }
+
+            #[test]
+            fn throwing_predicate() {
+                check_script_fails_with_message(
+                    "(1..10).partition |n| if n == 3 then throw 'oops' else true",
+                    "oops",
+                );
+            }
+
+            #[test]
+            fn error_in_the_adapted_iterator() {
+                let script = "
+(1..10)
+  .each |n| if n == 4 then throw 'oops' else n
+  .partition |n| n < 2
+";
+                check_script_fails_with_message(script, "oops");
+            }
+        }
+
+        mod peek {
+            use super::*;
+
+            #[test]
+            fn iterator_isnt_peekable() {
+                check_script_fails_with_message(
+                    "(1..10).iter().peek()",
+                    "iterator.peek: the iterator isn't peekable, see iterator.peekable",
+                );
+            }
+
+            #[test]
+            fn non_iterator_input() {
+                check_script_fails_with_message("iterator.peek [1, 2, 3]", "Expected an Iterator");
+            }
+        }
+
+        mod unique {
+            use super::*;
+
+            #[test]
+            fn non_bool_equality_result() {
+                let script = "
+foo = |x|
+  x: x
+  @==: |self, other| self.x
+(foo(1), foo(2)).unique().to_tuple()
+";
+                check_script_fails_with_message(
+                    script,
+                    "iterator.unique: Expected a Bool to be returned from the equality \
+                     comparison, found 'Int'",
+                );
+            }
+
+            #[test]
+            fn error_in_the_adapted_iterator() {
+                let script = "
+(1..10)
+  .each |n| if n == 4 then throw 'oops' else n
+  .unique()
+  .to_list()
+";
+                check_script_fails_with_message(script, "oops");
+            }
+        }
+
+        mod scan {
+            use super::*;
+
+            #[test]
+            fn throwing_function() {
+                check_script_fails_with_message(
+                    "(1..10).scan(0, |sum, n| if n == 3 then throw 'oops' else sum + n).to_list()",
+                    "oops",
+                );
+            }
+
+            #[test]
+            fn error_in_the_adapted_iterator() {
+                let script = "
+(1..10)
+  .each |n| if n == 4 then throw 'oops' else n
+  .scan 0, |sum, n| sum + n
+  .to_list()
+";
+                check_script_fails_with_message(script, "oops");
+            }
+        }
+
+        mod sort {
+            use super::*;
+
+            #[test]
+            fn incomparable_values_in_iterator() {
+                check_script_fails_with_message(
+                    "(1, 'foo').each(|x| x).sorted()",
+                    "Unable to sort values, 'String' and 'Int' can't be compared with '<'",
+                );
+            }
+
+            #[test]
+            fn non_bool_comparison_result() {
+                let script = "
+foo = |x|
+  x: x
+  @<: |self, other| self.x
+(foo(1), foo(2)).sorted()
+";
+                check_script_fails_with_message(
+                    script,
+                    "Expected Bool from < comparison, found 'Int'",
+                );
+            }
+
+            #[test]
+            fn throwing_key_function() {
+                check_script_fails_with_message(
+                    "(1, 2, 3).sorted |n| if n == 2 then throw 'oops' else n",
+                    "oops",
+                );
+            }
+
+            #[test]
+            fn incomparable_values_in_list() {
+                check_script_fails_with_message(
+                    "[3, 'foo', 1].sort()",
+                    "Unable to sort values, 'String' and 'Int' can't be compared with '<'",
+                );
+            }
+
+            #[test]
+            fn incomparable_keys_in_map() {
+                let script = "
+m = {foo: 1}
+m.insert 2, 'two'
+m.sort()
+";
+                check_script_fails_with_message(
+                    script,
+                    "Unable to sort values, 'Int' and 'String' can't be compared with '<'",
+                );
+            }
+
+            #[test]
+            fn incomparable_sort_keys_in_map() {
+                check_script_fails_with_message(
+                    "{a: 1, b: 2}.sort |key, value| if key == 'a' then value else [value]",
+                    "Unable to sort values, 'List' and 'Int' can't be compared with '<'",
+                );
+            }
+
+            #[test]
+            fn non_bool_comparison_result_in_list() {
+                let script = "
+foo = |x|
+  x: x
+  @<: |self, other| 'maybe'
+[foo(1), foo(2)].sort()
+";
+                check_script_fails_with_message(
+                    script,
+                    "Expected Bool from < comparison, found 'String'",
+                );
+            }
+
+            #[test]
+            fn throwing_key_function_in_list() {
+                check_script_fails_with_message(
+                    "[1, 2, 3].sort |n| if n == 2 then throw 'oops' else n",
+                    "oops",
+                );
+            }
+
+            #[test]
+            fn incomparable_values_in_binary_search() {
+                check_script_fails_with_message(
+                    "[1, 2, 3].binary_search 'foo'",
+                    "Unable to sort values, 'Int' and 'String' can't be compared with '<'",
+                );
+            }
+
+            #[test]
+            fn throwing_comparison_operator_in_insert_sorted() {
+                let script = "
+foo = |x|
+  x: x
+  @<: |self, other| if other.x == 2 then throw 'oops' else self.x < other.x
+[foo(1), foo(2), foo(3)].insert_sorted foo(0)
+";
+                check_script_fails_with_message(script, "oops");
+            }
+
+            #[test]
+            fn throwing_comparison_operator() {
+                let script = "
+foo = |x|
+  x: x
+  @<: |self, other| throw 'oops'
+[foo(1), foo(2)].sort()
+";
+                check_script_fails_with_message(script, "oops");
+            }
+        }
+
+        mod skip_while {
+            use super::*;
+
+            #[test]
+            fn non_bool_predicate_result() {
+                check_script_fails_with_message(
+                    "(1..10).skip_while(|n| n).to_list()",
+                    "iterator.skip_while: Expected a Bool to be returned from the predicate, \
+                     found 'Int'",
+                );
+            }
+
+            #[test]
+            fn throwing_predicate() {
+                check_script_fails_with_message(
+                    "(1..10).skip_while(|n| if n == 3 then throw 'oops' else true).to_list()",
+                    "oops",
+                );
+            }
+
+            #[test]
+            fn error_in_the_adapted_iterator() {
+                let script = "
+(1..10)
+  .each |n| if n == 4 then throw 'oops' else n
+  .skip_while |n| n < 2
+  .to_list()
+";
+                check_script_fails_with_message(script, "oops");
+            }
+        }
+
+        mod step_by {
+            use super::*;
+
+            #[test]
+            fn zero_step() {
+                check_script_fails_with_message(
+                    "(1..10).step_by 0",
+                    "iterator.step_by: Expected a positive step (found 0)",
+                );
+            }
+
+            #[test]
+            fn negative_step() {
+                check_script_fails_with_message(
+                    "(1..10).step_by -2",
+                    "iterator.step_by: Expected a positive step (found -2)",
+                );
+            }
+
+            #[test]
+            fn error_in_the_adapted_iterator() {
+                let script = "
+(1..10)
+  .each |n| if n == 4 then throw 'oops' else n
+  .step_by 2
+  .to_list()
+";
+                check_script_fails_with_message(script, "oops");
+            }
+        }
+
+        mod take {
+            use super::*;
+
+            #[test]
+            fn non_bool_predicate_result() {
+                check_script_fails_with_message(
+                    "(1..10).take(|n| n).to_list()",
+                    "iterator.take: Expected a Bool to be returned from the predicate, found 'Int'",
+                );
+            }
+
+            #[test]
+            fn throwing_predicate() {
+                check_script_fails_with_message(
+                    "(1..10).take(|n| if n == 3 then throw 'oops' else true).to_list()",
+                    "oops",
+                );
+            }
+
+            #[test]
+            fn unexpected_argument() {
+                check_script_fails_with_message(
+                    "(1..10).take 'x'",
+                    "Expected an iterable value and either a non-negative number or a predicate \
+                     Function as arguments, but found (Range, String).",
+                );
+            }
+        }
+
+        mod zip_longest {
+            use super::*;
+
+            #[test]
+            fn error_in_the_longer_iterator() {
+                let script = "
+gen = ||
+  yield 1
+  yield 2
+  throw 'oops'
+(1..=2).zip_longest(gen()).to_list()
+";
+                check_script_fails_with_message(script, "oops");
+            }
+
+            #[test]
+            fn error_in_the_shorter_iterator() {
+                let script = "
+gen = ||
+  yield 1
+  throw 'oops'
+gen().zip_longest(1..10).to_list()
+";
+                check_script_fails_with_message(script, "oops");
+            }
+
+            #[test]
+            fn non_iterable_argument() {
+                check_script_fails_with_message(
+                    "(1..10).zip_longest 42",
+                    "Expected two iterable values and an optional fill value as arguments, \
+                     but found (Range, Int).",
+                );
+            }
+        }
+
+        mod chunks_and_windows {
+            use super::*;
+
+            #[test]
+            fn error_in_the_chunked_iterator() {
+                let script = "
+gen = ||
+  yield 1
+  yield 2
+  throw 'oops'
+gen().chunks(2).to_list()
+";
+                check_script_fails_with_message(script, "oops");
+            }
+
+            #[test]
+            fn error_in_the_windowed_iterator() {
+                let script = "
+(1..10)
+  .each |n| if n == 4 then throw 'oops' else n
+  .windows 2
+  .to_list()
+";
+                check_script_fails_with_message(script, "oops");
+            }
+
+            #[test]
+            fn zero_chunk_size() {
+                check_script_fails_with_message(
+                    "[1, 2, 3].chunks 0",
+                    "iterator.chunks: the chunk size must be at least 1",
+                );
+            }
+        }
+
+        mod multi_assignment {
+            use super::*;
+
+            #[test]
+            fn unpacking_a_non_container() {
+                check_script_fails_with_message(
+                    "a, b = 42",
+                    "Expected a List, Tuple, or other container to unpack, but found Int.",
+                );
+            }
+        }
+
+        mod map_pairs {
+            use super::*;
+
+            #[test]
+            fn invert_with_mutable_value() {
+                check_script_fails_with_message(
+                    "{a: 1, b: [2]}.invert()",
+                    "map.invert: Only immutable Values can be used as keys (found 'List')",
+                );
+            }
+
+            #[test]
+            fn from_pairs_with_mutable_key() {
+                check_script_fails_with_message(
+                    "map.from_pairs [([1], 2)]",
+                    "Only immutable Values can be used as keys (found 'List')",
+                );
+            }
+        }
+
+        mod io {
+            use super::*;
+
+            #[test]
+            fn open_with_invalid_mode() {
+                check_script_fails_with_message(
+                    "io.open 'foo.txt', 'x'",
+                    "io.open: Invalid mode 'x', expected 'r', 'w', or 'a'",
+                );
+            }
+
+            #[test]
+            fn open_missing_file() {
+                check_script_fails_with_message(
+                    "io.open 'this/file/does/not/exist.txt'",
+                    "io.open: Error while opening 'this/file/does/not/exist.txt'",
+                );
+            }
+        }
+
+        mod deep_equals {
+            use super::*;
+
+            #[test]
+            fn overloaded_equality_with_non_bool_result() {
+                let script = "
+foo = {@==: |self, other| 42}
+koto.deep_equals [foo], [foo]
+";
+                check_script_fails_with_message(
+                    script,
+                    "Expected Bool from equality comparison, found 'Int'",
+                );
+            }
+        }
+
+        mod hash {
+            use super::*;
+
+            #[test]
+            fn tuple_containing_a_list() {
+                check_script_fails_with_message(
+                    "koto.hash (1, [2, 3])",
+                    "koto.hash: Only immutable Values can be hashed (found 'Tuple')",
+                );
+            }
+        }
+
+        mod map_keys {
+            use super::*;
+
+            #[test]
+            fn tuple_containing_a_list() {
+                check_script_fails_with_message(
+                    "
+m = {}
+m.insert (1, [2, 3]), 42
+",
+                    "Expected a Map and key (with optional Value to insert) as arguments",
+                );
+            }
+        }
+
+        mod list_insert_remove {
+            use super::*;
+
+            #[test]
+            fn remove_with_out_of_range_index() {
+                check_script_fails_with_message(
+                    "[1, 2].remove 2",
+                    "list.remove: Index out of bounds - the index is 2 but the List only has 2 elements",
+                );
+            }
+
+            #[test]
+            fn remove_with_out_of_range_negative_index() {
+                check_script_fails_with_message(
+                    "[1, 2].remove -3",
+                    "list.remove: Index out of bounds - \
+                     the index is -3 (resolved to -1) but the List only has 2 elements",
+                );
+            }
+
+            #[test]
+            fn insert_with_out_of_range_index() {
+                check_script_fails_with_message(
+                    "[1, 2].insert 3, 99",
+                    "list.insert: Index out of bounds - the index is 3 but the List only has 2 elements",
+                );
+            }
+
+            #[test]
+            fn insert_all_with_out_of_range_negative_index() {
+                check_script_fails_with_message(
+                    "[1, 2].insert_all -3, [99]",
+                    "list.insert_all: Index out of bounds - \
+                     the index is -3 (resolved to -1) but the List only has 2 elements",
+                );
+            }
+        }
+
+        mod os {
+            use super::*;
+
+            #[test]
+            fn time_string_with_invalid_format() {
+                check_script_fails_with_message(
+                    "os.time_string '%Y-%Q'",
+                    "os.time_string: Invalid format string '%Y-%Q'",
+                );
+            }
+        }
+    }
+
+    mod arg_spans {
+        use {
+            super::*,
+            koto_runtime::{prelude::*, ExternalFunction},
+        };
+
+        // Runs a script that's expected to fail, and then returns the source text highlighted by
+        // the error's excerpt
+        fn highlighted_source(vm: &mut Vm, script: &str) -> String {
+            let mut loader = Loader::default();
+            let chunk = loader
+                .compile_script(script, &None)
+                .expect("Error while compiling script");
+
+            let error = match vm.run(chunk) {
+                Ok(result) => panic!("Script didn't fail as expected, result: {result}"),
+                Err(error) => error.to_string(),
+            };
+
+            let lines = error.lines().collect::<Vec<_>>();
+            let caret_line = lines
+                .iter()
+                .position(|line| line.trim_end().ends_with('^'))
+                .unwrap_or_else(|| panic!("Missing highlighted source in error:\n{error}"));
+            let excerpt = lines[caret_line - 1];
+            let highlight = lines[caret_line];
+            let start = highlight.find('^').unwrap();
+            let end = highlight.rfind('^').unwrap() + 1;
+
+            excerpt[start..end].to_string()
+        }
+
+        fn check_highlighted_arg(script: &str, expected: &str) {
+            let mut vm = Vm::default();
+            assert_eq!(highlighted_source(&mut vm, script), expected);
+        }
+
+        fn vm_with_test_functions() -> Vm {
+            let vm = Vm::default();
+            let prelude = vm.prelude();
+
+            prelude.add_fn("apply", |vm, args| match vm.get_args(args) {
+                [f, f_args @ ..] if f.is_callable() => {
+                    let f = f.clone();
+                    let f_args = f_args.to_vec();
+                    vm.run_function(f, CallArgs::Separate(&f_args))
+                }
+                unexpected => {
+                    type_error_with_arg("a callable value as first argument", unexpected, 0)
+                }
+            });
+
+            let checker = ValueMap::new();
+            checker.add_value(
+                "check_self",
+                Value::ExternalFunction(ExternalFunction::new(
+                    |vm, args| type_error_with_arg("a String as self", vm.get_args(args), 0),
+                    true,
+                )),
+            );
+            prelude.add_map("checker", checker);
+
+            vm
+        }
+
+        #[test]
+        fn chain_with_non_iterable_arg() {
+            check_highlighted_arg("iterator.chain [1], [2], 42, [3]", "42");
+        }
+
+        #[test]
+        fn chain_with_non_iterable_arg_in_instance_call() {
+            let script = "
+x = [1, 2]
+y = 99
+x.chain [3], y, [4]
+";
+            check_highlighted_arg(script, "y");
+        }
+
+        #[test]
+        fn zip_with_non_iterable_first_arg() {
+            check_highlighted_arg("iterator.zip 99, [1, 2]", "99");
+        }
+
+        #[test]
+        fn format_with_non_string_arg() {
+            let script = "
+x = 123
+string.format x, 1, 2
+";
+            check_highlighted_arg(script, "x");
+        }
+
+        #[test]
+        fn apply_with_non_callable_first_arg() {
+            let mut vm = vm_with_test_functions();
+            assert_eq!(highlighted_source(&mut vm, "apply 1, 2, 3"), "1");
+        }
+
+        #[test]
+        fn instance_arg() {
+            let mut vm = vm_with_test_functions();
+            assert_eq!(
+                highlighted_source(&mut vm, "checker.check_self()"),
+                "checker"
+            );
+        }
+
+        #[test]
+        fn nested_external_call_doesnt_use_outer_arg_spans() {
+            // string.format is called via run_function, so the error's arg index shouldn't be
+            // resolved using the spans of the outer call.
+            let mut vm = vm_with_test_functions();
+            assert_ne!(
+                highlighted_source(&mut vm, "apply string.format, 1"),
+                "string.format"
+            );
+        }
+    }
+
+    mod not_callable {
+        use super::*;
+
+        // Runs a script that's expected to fail, and then returns the error message
+        fn error_message(script: &str) -> String {
+            let mut loader = Loader::default();
+            let chunk = loader
+                .compile_script(script, &None)
+                .expect("Error while compiling script");
+
+            match Vm::default().run(chunk) {
+                Ok(result) => panic!("Script didn't fail as expected, result: {result}"),
+                Err(error) => format!("{error:#}"),
+            }
+        }
+
+        #[test]
+        fn null_map_value() {
+            let script = "
+config = {handler: null}
+config.handler 42
+";
+            assert_eq!(
+                error_message(script),
+                "`config.handler` is Null and cannot be called"
+            );
+        }
+
+        #[test]
+        fn misspelled_map_key() {
+            let script = "
+handlers = {on_click: null, on_clik: || 'clicked'}
+handlers.on_click()
+";
+            assert_eq!(
+                error_message(script),
+                "`handlers.on_click` is Null and cannot be called"
+            );
+        }
+
+        #[test]
+        fn list_element() {
+            let script = "
+x = [1, 2, 3]
+x[1] 42
+";
+            assert_eq!(
+                error_message(script),
+                "`x[1]` is Int (2) and cannot be called"
+            );
+        }
+
+        #[test]
+        fn named_value() {
+            let script = "
+foo = 'hello'
+foo()
+";
+            assert_eq!(
+                error_message(script),
+                "`foo` is String ('hello') and cannot be called"
+            );
+        }
+
+        #[test]
+        fn call_result() {
+            let script = "
+f = || 42
+f()()
+";
+            assert_eq!(
+                error_message(script),
+                "`f()` is Int (42) and cannot be called"
+            );
+        }
+
+        #[test]
+        fn long_values_are_truncated() {
+            let script = "
+x = (1..=100).to_list()
+x 1
+";
+            assert_eq!(
+                error_message(script),
+                "`x` is List ([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, ...) and cannot be called"
+            );
         }
     }
 }
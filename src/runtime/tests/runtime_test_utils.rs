@@ -48,6 +48,27 @@ pub fn test_script_with_vm(mut vm: Vm, script: &str, expected_output: Value) {
     }
 }
 
+pub fn run_script(script: &str) -> Value {
+    let mut vm = Vm::default();
+    let mut loader = Loader::default();
+    let chunk = loader.compile_script(script, &None).unwrap();
+    vm.run(chunk).unwrap()
+}
+
+/// Checks that each of the strings in `substrings` shares its data with `input`
+pub fn assert_substrings_share_data(input: &Value, substrings: &[Value]) {
+    let input = match input {
+        Str(s) => s,
+        unexpected => panic!("Expected a String, found {}", unexpected.type_as_string()),
+    };
+    for substring in substrings {
+        match substring {
+            Str(s) => assert!(s.shares_data_with(input), "'{s}' doesn't share data"),
+            unexpected => panic!("Expected a String, found {}", unexpected.type_as_string()),
+        }
+    }
+}
+
 pub fn print_chunk(script: &str, chunk: Rc<Chunk>) {
     println!("{}\n", script);
     let script_lines = script.lines().collect::<Vec<_>>();
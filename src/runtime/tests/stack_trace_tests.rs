@@ -0,0 +1,108 @@
+mod stack_trace {
+    use {koto_bytecode::Loader, koto_runtime::prelude::*};
+
+    fn run_script_with_error(script: &str) -> RuntimeError {
+        let mut vm = Vm::default();
+        let mut loader = Loader::default();
+        let chunk = loader.compile_script(script, &None).unwrap();
+        match vm.run(chunk) {
+            Ok(result) => panic!("Expected an error, found '{result}'"),
+            Err(error) => error,
+        }
+    }
+
+    // Checks the functions and line numbers of the frames in an error's stack trace
+    fn check_stack_trace(script: &str, expected: &[(FrameFunction, u32)]) {
+        let error = run_script_with_error(script);
+        let frames = error
+            .stack_trace()
+            .into_iter()
+            .map(|frame| (frame.function, frame.span.map_or(0, |span| span.start.line)))
+            .collect::<Vec<_>>();
+        assert_eq!(frames, expected);
+    }
+
+    fn named(name: &str) -> FrameFunction {
+        FrameFunction::Named(name.into())
+    }
+
+    #[test]
+    fn three_deep_call_chain() {
+        let script = "
+b = |x|
+  x + null
+a = |x| b x
+a 42
+";
+        check_stack_trace(
+            script,
+            &[(named("b"), 3), (named("a"), 4), (FrameFunction::Module, 5)],
+        );
+    }
+
+    #[test]
+    fn instance_function_calls() {
+        let script = "
+x =
+  foo: |self| self.bar 1
+  bar: |self, n| throw 'oops'
+x.foo()
+";
+        check_stack_trace(
+            script,
+            &[
+                (named("self.bar"), 4),
+                (named("x.foo"), 3),
+                (FrameFunction::Module, 5),
+            ],
+        );
+    }
+
+    #[test]
+    fn frames_from_iterator_adaptors_are_included() {
+        let script = "
+f = |x|
+  [1, 2]
+    .each |n| n + x
+    .to_list()
+f null
+";
+        check_stack_trace(
+            script,
+            &[
+                (FrameFunction::Anonymous, 4),
+                (named("f"), 5),
+                (FrameFunction::Module, 6),
+            ],
+        );
+    }
+
+    #[test]
+    fn display_includes_the_stack_trace() {
+        let script = "
+b = |x|
+  x + null
+a = |x| b x
+a 42
+";
+        let error = run_script_with_error(script);
+        let message = error.to_string();
+        assert!(
+            message.ends_with(
+                "
+Stack trace (most recent call first):
+  b at 3:5
+  a at 4:9
+  <module> at 5:1"
+            ),
+            "Unexpected error message: {message}"
+        );
+    }
+
+    #[test]
+    fn errors_in_the_top_level_have_a_single_frame() {
+        let error = run_script_with_error("1 + null");
+        assert_eq!(error.stack_trace().len(), 1);
+        assert!(!error.to_string().contains("Stack trace"));
+    }
+}
@@ -35,6 +35,40 @@ impl KotoWrite for TestStdout {
     }
 }
 
+// A stdin that provides a fixed series of lines
+#[derive(Debug)]
+struct TestStdin {
+    lines: RefCell<Vec<String>>,
+}
+
+impl TestStdin {
+    fn new(input: &str) -> Self {
+        Self {
+            lines: RefCell::new(
+                input
+                    .split_inclusive('\n')
+                    .rev()
+                    .map(String::from)
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl KotoFile for TestStdin {
+    fn id(&self) -> ValueString {
+        "_teststdin_".into()
+    }
+}
+
+impl KotoRead for TestStdin {
+    fn read_line(&self) -> Result<Option<String>, RuntimeError> {
+        Ok(self.lines.borrow_mut().pop())
+    }
+}
+
+impl KotoWrite for TestStdin {}
+
 mod vm {
     use super::*;
 
@@ -42,6 +76,7 @@ mod vm {
         let output = Rc::new(RefCell::new(String::new()));
 
         let mut vm = Vm::with_settings(VmSettings {
+            stdin: Rc::new(TestStdin::new("one\ntwo\r\nthree")),
             stdout: Rc::new(TestStdout {
                 output: output.clone(),
             }),
@@ -146,4 +181,40 @@ stderr.write_line '789'
 
         check_logged_output(script, "123456789\n");
     }
+
+    #[test]
+    fn read_lines_from_stdin() {
+        let script = "
+for line in io.stdin().lines()
+  print '<{}>', line
+";
+
+        check_logged_output(script, "<one>\n<two>\n<three>\n");
+    }
+
+    #[test]
+    fn read_line_from_stdin_returns_null_at_the_end_of_the_input() {
+        let script = "
+stdin = io.stdin()
+print stdin.read_line()
+print stdin.read_line()
+print stdin.read_line()
+print stdin.read_line()
+print stdin.read_line()
+";
+
+        check_logged_output(script, "one\ntwo\nthree\nnull\nnull\n");
+    }
+
+    #[test]
+    fn stdin_lines_after_the_end_of_the_input() {
+        let script = "
+stdin = io.stdin()
+print stdin.lines().to_tuple()
+print stdin.lines().count()
+print stdin.read_line()
+";
+
+        check_logged_output(script, "('one', 'two', 'three')\n0\nnull\n");
+    }
 }
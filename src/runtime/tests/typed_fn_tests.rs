@@ -0,0 +1,158 @@
+mod runtime_test_utils;
+
+mod typed_fns {
+    use {
+        crate::runtime_test_utils::test_script_with_vm, koto_bytecode::Loader,
+        koto_runtime::prelude::*,
+    };
+
+    fn make_vm() -> Vm {
+        let vm = Vm::default();
+        let prelude = vm.prelude();
+
+        prelude.add_typed_fn("no_args", || Ok(42));
+        prelude.add_typed_fn("add", |a: f64, b: f64| Ok(a + b));
+        prelude.add_typed_fn("repeat", |s: String, n: usize| Ok(s.repeat(n)));
+        prelude.add_typed_fn("greet", |name: String, greeting: Option<String>| {
+            let greeting = greeting.unwrap_or_else(|| "Hello".into());
+            Ok(format!("{greeting}, {name}!"))
+        });
+        prelude.add_typed_fn("sum_list", |list: ValueList| {
+            let mut result = 0.0;
+            for value in list.data().iter() {
+                match value {
+                    Value::Number(n) => result += f64::from(n),
+                    unexpected => return type_error("Number", unexpected),
+                }
+            }
+            Ok(result)
+        });
+
+        vm
+    }
+
+    fn check_script(script: &str, expected_output: impl Into<Value>) {
+        test_script_with_vm(make_vm(), script, expected_output.into());
+    }
+
+    fn check_error(script: &str, expected_error: &str) {
+        let mut vm = make_vm();
+        let mut loader = Loader::default();
+        let chunk = loader.compile_script(script, &None).unwrap();
+
+        match vm.run(chunk) {
+            Ok(result) => panic!("Expected an error, found '{result}'"),
+            Err(error) => {
+                let message = format!("{error:#}");
+                assert!(
+                    message.starts_with(expected_error),
+                    "Unexpected error message: {message}"
+                );
+            }
+        }
+    }
+
+    mod calls {
+        use super::*;
+
+        #[test]
+        fn no_args() {
+            check_script("no_args()", 42);
+        }
+
+        #[test]
+        fn two_numbers() {
+            check_script("add 1, 2.5", 3.5);
+        }
+
+        #[test]
+        fn string_and_count() {
+            check_script("repeat 'ab', 3", "ababab");
+        }
+
+        #[test]
+        fn optional_arg_provided() {
+            check_script("greet 'Koto', 'Hi'", "Hi, Koto!");
+        }
+
+        #[test]
+        fn optional_arg_omitted() {
+            check_script("greet 'Koto'", "Hello, Koto!");
+        }
+
+        #[test]
+        fn container_arg() {
+            check_script("sum_list [1, 2, 3]", 6);
+        }
+
+        #[test]
+        fn errors_from_the_function_are_propagated() {
+            check_error("sum_list [1, 'two']", "Expected Number, but found String.");
+        }
+    }
+
+    mod signature_errors {
+        use super::*;
+
+        #[test]
+        fn too_few_args() {
+            check_error(
+                "add 1",
+                "Expected |Number, Number| as arguments, but found Int.",
+            );
+        }
+
+        #[test]
+        fn too_many_args() {
+            check_error(
+                "add 1, 2, 3",
+                "Expected |Number, Number| as arguments, but found (Int, Int, Int).",
+            );
+        }
+
+        #[test]
+        fn unexpected_arg_type() {
+            check_error(
+                "add 1, true",
+                "Expected |Number, Number| as arguments, but found (Int, Bool).",
+            );
+        }
+
+        #[test]
+        fn negative_count() {
+            check_error(
+                "repeat 'x', -1",
+                "Expected |String, non-negative Number| as arguments, but found (String, Int).",
+            );
+        }
+
+        #[test]
+        fn unexpected_optional_arg_type() {
+            check_error(
+                "greet 'Koto', 99",
+                "Expected |String, optional String| as arguments, but found (String, Int).",
+            );
+        }
+
+        #[test]
+        fn missing_required_arg_before_optional_arg() {
+            check_error(
+                "greet()",
+                "Expected |String, optional String| as arguments, but found no args.",
+            );
+        }
+
+        #[test]
+        fn args_for_a_function_without_args() {
+            check_error("no_args 1", "Expected no arguments, but found Int.");
+        }
+
+        #[test]
+        fn ported_core_function() {
+            check_error(
+                "number.clamp 1, 2",
+                "Expected |Number, Number, Number| as arguments, but found (Int, Int).",
+            );
+        }
+    }
+}
@@ -0,0 +1,89 @@
+mod runtime_test_utils;
+
+mod unique_ids {
+    use {koto_bytecode::Loader, koto_runtime::prelude::*, std::collections::HashSet};
+
+    fn run_script(vm: &mut Vm, script: &str) -> Value {
+        let mut loader = Loader::default();
+        let chunk = loader.compile_script(script, &None).unwrap();
+        vm.run(chunk).unwrap()
+    }
+
+    fn collect_ids(value: &Value) -> Vec<i64> {
+        match value {
+            Value::List(l) => l
+                .data()
+                .iter()
+                .map(|id| match id {
+                    Value::Number(n) => n.into(),
+                    unexpected => panic!("Expected a Number, found {unexpected}"),
+                })
+                .collect(),
+            unexpected => panic!("Expected a List, found {unexpected}"),
+        }
+    }
+
+    #[test]
+    fn ids_are_unique_across_scripts_and_spawned_vms() {
+        let script = "
+(0..3)
+  .each |_|
+    (0..3)
+      .each |_| koto.unique_id()
+      .to_tuple()
+  .flatten()
+  .chain [koto.unique_id()]
+  .to_list()
+";
+        let mut vm = Vm::default();
+        let mut ids = collect_ids(&run_script(&mut vm, script));
+        ids.extend(collect_ids(&run_script(&mut vm, script)));
+
+        assert_eq!(ids.len(), 20);
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 20);
+    }
+
+    #[test]
+    fn each_runtime_has_its_own_ids() {
+        let mut vm_a = Vm::default();
+        let mut vm_b = Vm::default();
+
+        let a = run_script(&mut vm_a, "[koto.unique_id(), koto.unique_id()]");
+        let b = run_script(&mut vm_b, "[koto.unique_id()]");
+
+        // Each runtime has its own counter
+        assert_eq!(collect_ids(&a), [1, 2]);
+        assert_eq!(collect_ids(&b), [1]);
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn uuids() {
+        let mut vm = Vm::default();
+        let result = run_script(&mut vm, "(0..100).each(|_| koto.uuid()).to_list()");
+        let uuids: Vec<String> = match result {
+            Value::List(l) => l.data().iter().map(|uuid| uuid.to_string()).collect(),
+            unexpected => panic!("Expected a List, found {unexpected}"),
+        };
+
+        for uuid in uuids.iter() {
+            let groups: Vec<&str> = uuid.split('-').collect();
+            assert_eq!(
+                groups.iter().map(|group| group.len()).collect::<Vec<_>>(),
+                [8, 4, 4, 4, 12],
+                "unexpected format: {uuid}"
+            );
+            assert!(uuid
+                .chars()
+                .all(|c| c == '-' || c.is_ascii_digit() || ('a'..='f').contains(&c)));
+            // Version 4, with the RFC 4122 variant
+            assert!(groups[2].starts_with('4'), "unexpected version: {uuid}");
+            assert!(
+                matches!(groups[3].chars().next(), Some('8' | '9' | 'a' | 'b')),
+                "unexpected variant: {uuid}"
+            );
+        }
+
+        assert_eq!(uuids.iter().collect::<HashSet<_>>().len(), uuids.len());
+    }
+}
@@ -188,18 +188,41 @@ a %= 5
     mod ranges {
         use super::*;
 
+        fn make_range(start: isize, end: isize) -> Value {
+            Range(IntRange {
+                start,
+                end,
+                inclusive: false,
+            })
+        }
+
+        fn make_range_inclusive(start: isize, end: isize) -> Value {
+            Range(IntRange {
+                start,
+                end,
+                inclusive: true,
+            })
+        }
+
         #[test]
         fn range() {
-            test_script("0..10", Range(IntRange { start: 0, end: 10 }));
-            test_script("0..-10", Range(IntRange { start: 0, end: -10 }));
-            test_script("1 + 1..2 + 2", Range(IntRange { start: 2, end: 4 }));
+            test_script("0..10", make_range(0, 10));
+            test_script("0..-10", make_range(0, -10));
+            test_script("1 + 1..2 + 2", make_range(2, 4));
         }
 
         #[test]
         fn range_inclusive() {
-            test_script("10..=20", Range(IntRange { start: 10, end: 21 }));
-            test_script("4..=0", Range(IntRange { start: 4, end: -1 }));
-            test_script("2 * 2..=3 * 3", Range(IntRange { start: 4, end: 10 }));
+            test_script("10..=20", make_range_inclusive(10, 20));
+            test_script("4..=0", make_range_inclusive(4, 0));
+            test_script("2 * 2..=3 * 3", make_range_inclusive(4, 9));
+        }
+
+        #[test]
+        fn inclusive_ranges_are_equal_to_equivalent_ranges() {
+            test_script("(10..=20) == (10..21)", Bool(true));
+            test_script("(4..=0) == (4..-1)", Bool(true));
+            test_script("(10..=20) == (10..20)", Bool(false));
         }
     }
 
@@ -1049,6 +1072,15 @@ f (1, 2, 3, 4, 5)
             test_script(script, 6);
         }
 
+        #[test]
+        fn arg_unpacking_ellipsis_with_id_matching_no_values() {
+            let script = "
+f = |(a, b, others...)| a + b + others.size()
+f (1, 2)
+";
+            test_script(script, 3);
+        }
+
         #[test]
         fn arg_unpacking_ellipsis_at_start() {
             let script = "
@@ -1725,16 +1757,36 @@ result";
 
         #[test]
         fn empty() {
-            test_script("{}", Map(ValueMap::new()));
+            test_script("{}", koto_map! {});
         }
 
         #[test]
         fn from_literals() {
-            let expected = ValueMap::default();
-            expected.add_value("foo", 42.into());
-            expected.add_value("bar", "baz".into());
+            test_script("{foo: 42, bar: 'baz'}", koto_map! {foo: 42, bar: "baz"});
+        }
+
+        #[test]
+        fn nested_containers() {
+            let script = "
+x = 1
+m = {foo: [x, (2, 'three')], 'bar baz': {nested: null}}
+m.insert x, true
+m
+";
+            test_script(
+                script,
+                koto_map! {
+                    foo: koto_list![1, koto_tuple![2, "three"]],
+                    "bar baz": koto_map! {nested: Null},
+                    (1): true,
+                },
+            );
+        }
 
-            test_script("{foo: 42, bar: 'baz'}", Map(expected));
+        #[test]
+        #[should_panic(expected = "Only immutable Value types can be used as a ValueKey")]
+        fn mutable_value_as_key() {
+            let _ = koto_map! {(koto_list![1, 2]): 42};
         }
 
         #[test]
@@ -2714,6 +2766,24 @@ catch _
 ";
             test_script(script, 4);
         }
+
+        #[test]
+        fn try_catch_finally_with_rethrow() {
+            let script = "
+x = 0
+try
+  try
+    throw 'error'
+  catch e
+    throw e
+  finally
+    x = 42
+catch _
+  x += 1
+x
+";
+            test_script(script, 43);
+        }
     }
 
     mod operator_overloading {
@@ -3068,4 +3138,75 @@ f()";
             test_script(script, 42);
         }
     }
+
+    mod settings {
+        use super::*;
+
+        #[test]
+        fn os_module_enabled_by_default() {
+            test_script("koto.modules().contains_key 'os'", true);
+        }
+
+        #[test]
+        fn os_module_disabled() {
+            let vm = Vm::with_settings(VmSettings {
+                enable_os_module: false,
+                ..Default::default()
+            });
+            test_script_with_vm(vm, "koto.modules().contains_key 'os'", false.into());
+        }
+    }
+
+    mod values_equal {
+        use {super::*, koto_bytecode::Loader};
+
+        #[test]
+        fn nested_values() {
+            let mut vm = Vm::default();
+            let a = value_tuple(&[number_list(&[1, 2]), string("foo")]);
+            let b = value_tuple(&[number_list(&[1, 2]), string("foo")]);
+            let c = value_tuple(&[number_list(&[1, 3]), string("foo")]);
+            assert!(vm.values_equal(&a, &b).unwrap());
+            assert!(!vm.values_equal(&a, &c).unwrap());
+        }
+
+        #[test]
+        fn self_referential_list() {
+            let mut vm = Vm::default();
+            let list = ValueList::from_slice(&[1.into()]);
+            list.data_mut().push(List(list.clone()));
+            let other = ValueList::from_slice(&[1.into()]);
+            other.data_mut().push(List(other.clone()));
+
+            assert!(vm
+                .values_equal(&List(list.clone()), &List(list.clone()))
+                .unwrap());
+            assert!(!vm
+                .values_equal(&List(list.clone()), &List(other.clone()))
+                .unwrap());
+
+            // Break the cycles to allow the lists to be dropped
+            list.data_mut().clear();
+            other.data_mut().clear();
+        }
+
+        #[test]
+        fn overloaded_equality() {
+            let script = "
+foo = |x|
+  x: x
+  @==: |self, other| self.x.floor() == other.x.floor()
+[foo(1.2), foo(1.8), foo(2.5)]
+";
+            let mut vm = Vm::default();
+            let chunk = Loader::default().compile_script(script, &None).unwrap();
+            let result = match vm.run(chunk).unwrap() {
+                List(result) => result.data().clone(),
+                other => panic!("Unexpected result: {other}"),
+            };
+
+            assert!(vm.values_equal(&result[0], &result[1]).unwrap());
+            assert!(!vm.values_equal(&result[0], &result[2]).unwrap());
+        }
+    }
 }